@@ -1,3 +1,5 @@
+#[path = "commands/alias.rs"]
+pub mod rad_alias;
 #[path = "commands/auth.rs"]
 pub mod rad_auth;
 #[path = "commands/block.rs"]
@@ -10,6 +12,8 @@ pub mod rad_clean;
 pub mod rad_clone;
 #[path = "commands/cob.rs"]
 pub mod rad_cob;
+#[path = "commands/completions.rs"]
+pub mod rad_completions;
 #[path = "commands/config.rs"]
 pub mod rad_config;
 #[path = "commands/debug.rs"]
@@ -34,6 +38,8 @@ pub mod rad_inspect;
 pub mod rad_issue;
 #[path = "commands/job.rs"]
 pub mod rad_job;
+#[path = "commands/label.rs"]
+pub mod rad_label;
 #[path = "commands/ls.rs"]
 pub mod rad_ls;
 #[path = "commands/node.rs"]
@@ -60,5 +66,7 @@ pub mod rad_unblock;
 pub mod rad_unfollow;
 #[path = "commands/unseed.rs"]
 pub mod rad_unseed;
+#[path = "commands/use.rs"]
+pub mod rad_use;
 #[path = "commands/watch.rs"]
 pub mod rad_watch;
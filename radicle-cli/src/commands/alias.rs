@@ -0,0 +1,136 @@
+use std::ffi::OsString;
+
+use anyhow::{anyhow, Context as _};
+
+use radicle::identity::alias::{self, Alias};
+use radicle::prelude::RepoId;
+use radicle::storage::WriteStorage;
+
+use crate::terminal as term;
+use crate::terminal::args::{Arg, Args, Error, Help, Kind};
+use crate::terminal::Element as _;
+
+pub const HELP: Help = Help {
+    name: "alias",
+    description: "Manage your alias in a repository",
+    version: env!("RADICLE_VERSION"),
+    usage: r#"
+Usage
+
+    rad alias [<option>...]
+    rad alias <name> [--avatar <url>] [<option>...]
+
+    Without a name, lists the aliases published by the contributors of
+    the repository. With a name, publishes your alias in the repository,
+    ie. the name you go by in that repository.
+
+    Aliases are published under your namespace and are part of your
+    signed references. Use `rad sync` to announce them to the network.
+
+Options
+
+    --repo <rid>       Operate on the given repository (default: cwd)
+    --avatar <url>     Avatar URL to publish along with the name
+    --help             Print help
+"#,
+    options: &[
+        Arg::Opt("repo", None, Kind::Rid),
+        Arg::Opt("avatar", None, Kind::Any),
+        Arg::Flag("help", Some('h')),
+    ],
+};
+
+#[derive(Default, Debug)]
+pub struct Options {
+    pub rid: Option<RepoId>,
+    pub name: Option<String>,
+    pub avatar: Option<String>,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut rid = None;
+        let mut name = None;
+        let mut avatar = None;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("help") | Short('h') => {
+                    return Err(Error::Help.into());
+                }
+                Long("repo") => {
+                    let val = parser.value()?;
+                    rid = Some(term::args::rid(&val)?);
+                }
+                Long("avatar") => {
+                    let val = parser.value()?;
+                    avatar = Some(term::args::string(&val));
+                }
+                Value(val) if name.is_none() => {
+                    name = Some(term::args::string(&val));
+                }
+                arg => {
+                    return Err(anyhow!(arg.unexpected()));
+                }
+            }
+        }
+        if avatar.is_some() && name.is_none() {
+            anyhow::bail!("an alias name must be specified along with `--avatar`");
+        }
+
+        Ok((Options { rid, name, avatar }, vec![]))
+    }
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let profile = ctx.profile()?;
+    let rid = match options.rid {
+        Some(rid) => rid,
+        None => radicle::rad::cwd()
+            .map(|(_, rid)| rid)
+            .context("Current directory is not a Radicle repository")?,
+    };
+    let repo = profile.storage.repository_mut(rid)?;
+
+    if let Some(name) = options.name {
+        let signer = term::signer(&profile)?;
+        let alias = Alias::new(profile.id(), name, options.avatar)?;
+
+        alias.publish(&repo, &signer)?;
+        term::success!(
+            "Alias set to {} in {}",
+            term::format::tertiary(&alias.name),
+            term::format::tertiary(rid)
+        );
+        return Ok(());
+    }
+
+    let aliases = alias::aliases(&repo)?;
+    if aliases.is_empty() {
+        term::print(term::format::italic("Nothing to show."));
+        return Ok(());
+    }
+    let mut table = term::Table::new(term::table::TableOptions::bordered());
+    table.header([
+        term::format::bold("Alias").into(),
+        term::format::bold("DID").into(),
+        term::format::bold("Avatar").into(),
+    ]);
+    table.divider();
+
+    for (nid, alias) in aliases {
+        let (_, you) = term::format::Author::new(&nid, &profile).labels();
+        let avatar = alias
+            .avatar
+            .map(|a| term::format::dim(a).into())
+            .unwrap_or_else(term::Label::blank);
+
+        table.push([term::format::primary(alias.name).into(), you, avatar]);
+    }
+    table.print();
+
+    Ok(())
+}
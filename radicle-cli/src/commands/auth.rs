@@ -8,11 +8,12 @@ use anyhow::anyhow;
 use radicle::crypto::ssh;
 use radicle::crypto::ssh::Passphrase;
 use radicle::node::Alias;
-use radicle::profile::env;
+use radicle::profile::{env, Home, ProfileName, Profiles};
 use radicle::{profile, Profile};
 
+use crate::commands::rad_use;
 use crate::terminal as term;
-use crate::terminal::args::{Args, Error, Help};
+use crate::terminal::args::{Arg, Args, Error, Help, Kind};
 
 pub const HELP: Help = Help {
     name: "auth",
@@ -27,18 +28,33 @@ Usage
     via the standard input stream if `--stdin` is used. Using either of these
     methods disables the passphrase prompt.
 
+    With `--profile`, the named profile is initialized or authenticated instead
+    of the current one. Named profiles live under `$RAD_HOME/profiles`, and the
+    active profile can be changed with `--switch` or `rad use`.
+
 Options
 
     --alias                 When initializing an identity, sets the node alias
+    --profile <name>        Initialize or authenticate the named profile
+    --switch <name>         Make the named profile the active profile
     --stdin                 Read passphrase from stdin (default: false)
     --help                  Print help
 "#,
+    options: &[
+        Arg::Opt("alias", None, Kind::Any),
+        Arg::Opt("profile", None, Kind::Any),
+        Arg::Flag("switch", None),
+        Arg::Flag("stdin", None),
+        Arg::Flag("help", Some('h')),
+    ],
 };
 
 #[derive(Debug)]
 pub struct Options {
     pub stdin: bool,
     pub alias: Option<Alias>,
+    pub profile: Option<ProfileName>,
+    pub switch: Option<ProfileName>,
 }
 
 impl Args for Options {
@@ -47,6 +63,8 @@ impl Args for Options {
 
         let mut stdin = false;
         let mut alias = None;
+        let mut profile = None;
+        let mut switch = None;
         let mut parser = lexopt::Parser::from_args(args);
 
         while let Some(arg) = parser.next()? {
@@ -57,6 +75,18 @@ impl Args for Options {
 
                     alias = Some(val);
                 }
+                Long("profile") => {
+                    let val = parser.value()?;
+                    let val = term::args::profile_name(&val)?;
+
+                    profile = Some(val);
+                }
+                Long("switch") => {
+                    let val = parser.value()?;
+                    let val = term::args::profile_name(&val)?;
+
+                    switch = Some(val);
+                }
                 Long("stdin") => {
                     stdin = true;
                 }
@@ -67,18 +97,53 @@ impl Args for Options {
             }
         }
 
-        Ok((Options { alias, stdin }, vec![]))
+        Ok((
+            Options {
+                alias,
+                stdin,
+                profile,
+                switch,
+            },
+            vec![],
+        ))
     }
 }
 
 pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    if let Some(name) = &options.switch {
+        if options.profile.is_some() {
+            anyhow::bail!("`--profile` and `--switch` cannot be used together");
+        }
+        return rad_use::switch(name);
+    }
+    if let Some(name) = options.profile.clone() {
+        let profiles = Profiles::open()?;
+        let home = Home::new(profiles.path(&name))?;
+
+        return match Profile::open(home.clone()) {
+            Ok(profile) => authenticate(options, &profile),
+            Err(profile::Error::NotFound(_)) => {
+                init(options, home)?;
+
+                if profiles.current()? != name {
+                    term::blank();
+                    term::info!(
+                        "To make this your active profile, run {}.",
+                        term::format::command(format!("rad use {name}"))
+                    );
+                }
+                Ok(())
+            }
+            Err(e) => Err(e.into()),
+        };
+    }
     match ctx.profile() {
         Ok(profile) => authenticate(options, &profile),
-        Err(_) => init(options),
+        Err(_) => init(options, ctx.home()?),
     }
 }
 
-pub fn init(options: Options) -> anyhow::Result<()> {
+pub fn init(options: Options, home: Home) -> anyhow::Result<()> {
     term::headline("Initializing your radicle 👾 identity");
 
     if let Ok(version) = radicle::git::version() {
@@ -103,7 +168,6 @@ pub fn init(options: Options) -> anyhow::Result<()> {
             Some("This is your node alias. You can always change it later"),
         )?
     };
-    let home = profile::home()?;
     let passphrase = if options.stdin {
         term::passphrase_stdin()
     } else {
@@ -5,7 +5,7 @@ use radicle::prelude::{NodeId, RepoId};
 
 use crate::terminal as term;
 use crate::terminal::args;
-use crate::terminal::args::{Args, Error, Help};
+use crate::terminal::args::{Arg, Args, Error, Help, Kind};
 
 pub const HELP: Help = Help {
     name: "block",
@@ -23,6 +23,11 @@ Options
 
     --help          Print help
 "#,
+    options: &[
+        Arg::Positional(Kind::Rid),
+        Arg::Positional(Kind::Nid),
+        Arg::Flag("help", Some('h')),
+    ],
 };
 
 enum Target {
@@ -12,7 +12,7 @@ use radicle::storage::git::transport;
 
 use crate::project;
 use crate::terminal as term;
-use crate::terminal::args::{Args, Error, Help};
+use crate::terminal::args::{Arg, Args, Error, Help, Kind};
 
 pub const HELP: Help = Help {
     name: "checkout",
@@ -31,6 +31,12 @@ Options
     --no-confirm    Don't ask for confirmation during checkout
     --help          Print help
 "#,
+    options: &[
+        Arg::Positional(Kind::Rid),
+        Arg::Opt("remote", None, Kind::Any),
+        Arg::Flag("no-confirm", None),
+        Arg::Flag("help", Some('h')),
+    ],
 };
 
 pub struct Options {
@@ -1,38 +1,64 @@
+use std::collections::BTreeSet;
 use std::ffi::OsString;
 
-use anyhow::anyhow;
-
 use radicle::identity::RepoId;
+use radicle::node::policy::Policy;
+use radicle::node::Handle as _;
 use radicle::storage;
-use radicle::storage::WriteStorage;
+use radicle::storage::refs::SignedRefsAt;
+use radicle::storage::{ReadStorage as _, WriteRepository as _, WriteStorage};
+use radicle::Profile;
 
 use crate::terminal as term;
-use crate::terminal::args::{Args, Error, Help};
+use crate::terminal::args::{Arg, Args, Error, Help, Kind};
 
 pub const HELP: Help = Help {
     name: "clean",
-    description: "Remove all remotes from a repository",
+    description: "Clean up repositories in storage",
     version: env!("RADICLE_VERSION"),
     usage: r#"
 Usage
 
     rad clean <rid> [<option>...]
+    rad clean --all [<option>...]
+
+    Cleans up a repository, or all repositories in storage with `--all`.
 
     Removes all remotes from a repository, as long as they are not the
-    local operator or a delegate of the repository.
+    local operator or a delegate of the repository. With `--prune-untracked`,
+    only the remotes that aren't followed are removed.
+
+    The repository's objects are then repacked and unreachable objects are
+    removed, reporting the size of the object database before and after.
 
     Note that remotes will still be fetched as long as they are
     followed and/or the follow scope is "all".
 
+    Repositories that are being fetched by the node are not cleaned.
+
 Options
 
+    --all               Clean all repositories in storage
+    --prune-untracked   Only remove the remotes that aren't followed
+    --dry-run           Show what would be removed, without removing anything
     --no-confirm        Do not ask for confirmation before removal (default: false)
     --help              Print help
 "#,
+    options: &[
+        Arg::Positional(Kind::Rid),
+        Arg::Flag("all", None),
+        Arg::Flag("prune-untracked", None),
+        Arg::Flag("dry-run", None),
+        Arg::Flag("no-confirm", None),
+        Arg::Flag("help", Some('h')),
+    ],
 };
 
 pub struct Options {
-    rid: RepoId,
+    rid: Option<RepoId>,
+    all: bool,
+    prune_untracked: bool,
+    dry_run: bool,
     confirm: bool,
 }
 
@@ -42,10 +68,22 @@ impl Args for Options {
 
         let mut parser = lexopt::Parser::from_args(args);
         let mut id: Option<RepoId> = None;
+        let mut all = false;
+        let mut prune_untracked = false;
+        let mut dry_run = false;
         let mut confirm = true;
 
         while let Some(arg) = parser.next()? {
             match arg {
+                Long("all") => {
+                    all = true;
+                }
+                Long("prune-untracked") => {
+                    prune_untracked = true;
+                }
+                Long("dry-run") => {
+                    dry_run = true;
+                }
                 Long("no-confirm") => {
                     confirm = false;
                 }
@@ -58,11 +96,19 @@ impl Args for Options {
                 _ => return Err(anyhow::anyhow!(arg.unexpected())),
             }
         }
+        if id.is_some() && all {
+            anyhow::bail!("`--all` cannot be used with an RID; see `rad clean --help`");
+        }
+        if id.is_none() && !all {
+            anyhow::bail!("an RID or `--all` must be provided; see `rad clean --help`");
+        }
 
         Ok((
             Options {
-                rid: id
-                    .ok_or_else(|| anyhow!("an RID must be provided; see `rad clean --help`"))?,
+                rid: id,
+                all,
+                prune_untracked,
+                dry_run,
                 confirm,
             },
             vec![],
@@ -73,20 +119,120 @@ impl Args for Options {
 pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
     let profile = ctx.profile()?;
     let storage = &profile.storage;
-    let rid = options.rid;
-    let path = storage::git::paths::repository(storage, &rid);
+    let (rids, prompt) = match options.rid {
+        Some(rid) => {
+            let path = storage::git::paths::repository(storage, &rid);
 
-    if !path.exists() {
-        anyhow::bail!("repository {rid} was not found");
+            if !path.exists() {
+                anyhow::bail!("repository {rid} was not found");
+            }
+            (vec![rid], format!("Clean {rid}?"))
+        }
+        None => {
+            let rids = storage
+                .repositories()?
+                .into_iter()
+                .map(|info| info.rid)
+                .collect::<Vec<_>>();
+
+            (rids, String::from("Clean all repositories?"))
+        }
+    };
+    let fetching = fetching(&profile)?;
+
+    if options.dry_run || !options.confirm || term::confirm(prompt) {
+        for rid in rids {
+            if fetching.contains(&rid) {
+                if options.all {
+                    term::warning(format!("Skipping {rid}: repository is being fetched"));
+                    continue;
+                }
+                anyhow::bail!("repository {rid} is being fetched by the node, try again later");
+            }
+            clean(rid, &profile, &options)?;
+        }
     }
 
-    if !options.confirm || term::confirm(format!("Clean {rid}?")) {
-        let cleaned = storage.clean(rid)?;
-        for remote in cleaned {
-            term::info!("Removed {remote}");
+    Ok(())
+}
+
+/// Clean a single repository.
+fn clean(rid: RepoId, profile: &Profile, options: &Options) -> anyhow::Result<()> {
+    let storage = &profile.storage;
+    let removed = if options.dry_run {
+        "Would remove"
+    } else {
+        "Removed"
+    };
+
+    if options.prune_untracked {
+        let repo = storage.repository_mut(rid)?;
+        let followed = profile
+            .policies()?
+            .follow_policies()?
+            .filter_map(|f| (f.policy == Policy::Allow).then_some(f.nid))
+            .collect::<BTreeSet<_>>();
+
+        for remote in repo.untracked(profile.id(), &followed)? {
+            if !options.dry_run {
+                repo.prune_namespace(&remote)?;
+            }
+            term::info!("{removed} {remote}");
         }
+    } else {
+        let remotes = if options.dry_run {
+            let repo = storage.repository(rid)?;
+
+            if SignedRefsAt::load(*profile.id(), &repo)?.is_some() {
+                repo.untracked(profile.id(), &BTreeSet::new())?
+            } else {
+                repo.remote_ids()?.collect::<Result<_, _>>()?
+            }
+        } else {
+            storage.clean(rid)?
+        };
+        for remote in remotes {
+            term::info!("{removed} {remote}");
+        }
+    }
+
+    // N.b. the repository is removed entirely when we have no refs of our own in it.
+    if !storage::git::paths::repository(storage, &rid).exists() {
         term::success!("Successfully cleaned {rid}");
+        return Ok(());
     }
+    let repo = storage.repository(rid)?;
+    let before = repo.size()?;
+
+    if options.dry_run {
+        term::info!("Size {}", term::format::bytes(before as usize));
+        return Ok(());
+    }
+    repo.gc()?;
+
+    let after = repo.size()?;
+    term::info!(
+        "Size {} → {}",
+        term::format::bytes(before as usize),
+        term::format::bytes(after as usize)
+    );
+    term::success!("Successfully cleaned {rid}");
 
     Ok(())
 }
+
+/// Get the repositories currently being fetched by the node, if it is running.
+fn fetching(profile: &Profile) -> anyhow::Result<BTreeSet<RepoId>> {
+    let node = radicle::Node::new(profile.socket());
+    if !node.is_running() {
+        return Ok(BTreeSet::new());
+    }
+    match node.fetching() {
+        Ok(fetching) => Ok(fetching.into_iter().collect()),
+        Err(radicle::node::Error::UnknownCommand { .. }) => {
+            term::warning("the running node can't report its fetches, consider upgrading it");
+            Ok(BTreeSet::new())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
@@ -25,7 +25,7 @@ use crate::commands::rad_sync as sync;
 use crate::node::SyncSettings;
 use crate::project;
 use crate::terminal as term;
-use crate::terminal::args::{Args, Error, Help};
+use crate::terminal::args::{Arg, Args, Error, Help, Kind};
 use crate::terminal::Element as _;
 
 pub const HELP: Help = Help {
@@ -51,6 +51,15 @@ Options
         --help              Print help
 
 "#,
+    options: &[
+        Arg::Positional(Kind::Rid),
+        Arg::Positional(Kind::Path),
+        Arg::Opt("scope", None, Kind::OneOf(&["followed", "all"])),
+        Arg::Opt("seed", Some('s'), Kind::Nid),
+        Arg::Opt("timeout", None, Kind::Any),
+        Arg::Flag("no-confirm", None),
+        Arg::Flag("help", Some('h')),
+    ],
 };
 
 #[derive(Debug)]
@@ -20,7 +20,7 @@ use serde_json::json;
 
 use crate::git::Rev;
 use crate::terminal as term;
-use crate::terminal::args::{Args, Error, Help};
+use crate::terminal::args::{Arg, Args, Error, Help, Kind};
 
 pub const HELP: Help = Help {
     name: "cob",
@@ -47,12 +47,25 @@ Log options
 
 Show options
 
-    --format json              Desired output format (default: json)
+    --format (json | log)      Desired output format (default: json)
+
+    Objects of unknown types are shown as the list of their raw operations.
 
 Other options
 
     --help                     Print help
 "#,
+    options: &[
+        Arg::Command("list"),
+        Arg::Command("log"),
+        Arg::Command("show"),
+        Arg::Command("migrate"),
+        Arg::Opt("repo", None, Kind::Rid),
+        Arg::Opt("type", Some('t'), Kind::Any),
+        Arg::Opt("object", None, Kind::Any),
+        Arg::Opt("format", None, Kind::OneOf(&["pretty", "json", "log"])),
+        Arg::Flag("help", Some('h')),
+    ],
 };
 
 #[derive(PartialEq)]
@@ -139,6 +152,7 @@ impl Args for Options {
                     let v: String = term::args::string(&parser.value()?);
                     match v.as_ref() {
                         "pretty" if op == Some(OperationName::Log) => format = Some(Format::Pretty),
+                        "log" if op == Some(OperationName::Show) => format = Some(Format::Pretty),
                         "json" => format = Some(Format::Json),
                         unknown => anyhow::bail!("unknown format '{unknown}'"),
                     }
@@ -149,6 +163,10 @@ impl Args for Options {
         let repo = rid.ok_or_else(|| anyhow!("a repository id must be specified with `--repo`"));
         let type_name =
             type_name.ok_or_else(|| anyhow!("an object type must be specified with `--type`"));
+        let format = format.unwrap_or(match op {
+            Some(OperationName::Show) => Format::Json,
+            _ => Format::Pretty,
+        });
 
         Ok((
             Options {
@@ -178,7 +196,7 @@ impl Args for Options {
                         }
                     }
                 },
-                format: format.unwrap_or(Format::Pretty),
+                format,
             },
             vec![],
         ))
@@ -231,7 +249,7 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
         } => {
             let repo = storage.repository(repo)?;
 
-            if let Err(e) = show(revs, &repo, type_name, &profile) {
+            if let Err(e) = show(revs, &repo, type_name, &options.format, &profile) {
                 if let Some(err) = e.downcast_ref::<io::Error>() {
                     if err.kind() == io::ErrorKind::BrokenPipe {
                         return Ok(());
@@ -249,10 +267,23 @@ fn show(
     revs: Vec<Rev>,
     repo: &git::Repository,
     type_name: cob::TypeName,
+    format: &Format,
     profile: &Profile,
 ) -> Result<(), anyhow::Error> {
     let mut stdout = std::io::stdout();
 
+    if let Format::Pretty = format {
+        for oid in revs {
+            let oid = &oid.resolve(&repo.backend)?;
+            let ops = cob::store::ops(oid, &type_name, repo)?;
+
+            for op in ops.into_iter().rev() {
+                print_op_pretty(op)?;
+            }
+        }
+        return Ok(());
+    }
+
     if type_name == cob::patch::TYPENAME.clone() {
         let patches = term::cob::patches(profile, repo)?;
         for oid in revs {
@@ -283,7 +314,17 @@ fn show(
             stdout.write_all(b"\n")?;
         }
     } else {
-        anyhow::bail!("the type name '{type_name}' is unknown");
+        // Without an evaluator for this type, the best we can do is to show the raw operations.
+        for oid in revs {
+            let oid = &oid.resolve(&repo.backend)?;
+            let ops = cob::store::ops(oid, &type_name, repo)?
+                .into_iter()
+                .map(op_json)
+                .collect::<anyhow::Result<Vec<_>>>()?;
+
+            serde_json::to_writer(&stdout, &ops)?;
+            stdout.write_all(b"\n")?;
+        }
     }
     Ok(())
 }
@@ -305,6 +346,11 @@ fn print_op_pretty(op: Op<Vec<u8>>) -> anyhow::Result<()> {
     }
     term::print(format!("author   {}", op.author));
     term::print(format!("date     {}", time));
+    term::print(format!(
+        "manifest {} (version {})",
+        op.manifest.type_name,
+        usize::from(op.manifest.version)
+    ));
     term::blank();
     for action in op.actions {
         let obj: serde_json::Value = serde_json::from_slice(&action)?;
@@ -318,6 +364,11 @@ fn print_op_pretty(op: Op<Vec<u8>>) -> anyhow::Result<()> {
 }
 
 fn print_op_json(op: Op<Vec<u8>>) -> anyhow::Result<()> {
+    term::print(op_json(op)?);
+    Ok(())
+}
+
+fn op_json(op: Op<Vec<u8>>) -> anyhow::Result<serde_json::Value> {
     let mut ser = json!(op);
     ser.as_object_mut().unwrap().insert(
         "actions".to_string(),
@@ -329,6 +380,5 @@ fn print_op_json(op: Op<Vec<u8>>) -> anyhow::Result<()> {
             })
             .collect::<Result<Vec<serde_json::Value>, _>>()?),
     );
-    term::print(ser);
-    Ok(())
+    Ok(ser)
 }
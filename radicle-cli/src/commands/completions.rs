@@ -0,0 +1,648 @@
+use std::ffi::OsString;
+use std::fmt::Write as _;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+
+use radicle::issue::cache::Issues as _;
+use radicle::node::address::Store as _;
+use radicle::patch::cache::Patches as _;
+use radicle::storage::ReadStorage as _;
+
+use crate::terminal as term;
+use crate::terminal::args::{Arg, Args, Error, Help, Kind};
+
+use super::*;
+
+pub const HELP: Help = Help {
+    name: "completions",
+    description: "Generate shell completions",
+    version: env!("RADICLE_VERSION"),
+    usage: r#"
+Usage
+
+    rad completions <shell> [<option>...]
+
+    Prints a completion script for the given shell, one of `bash`, `zsh`
+    or `fish`. Repository, node, patch and issue identifiers are completed
+    from your storage, address book and current working copy.
+
+    To enable completions, add one of the following to your shell
+    configuration:
+
+        eval "$(rad completions bash)"                  # ~/.bashrc
+        source <(rad completions zsh)                   # ~/.zshrc
+        rad completions fish | source                   # ~/.config/fish/config.fish
+
+Options
+
+    --help    Print help
+"#,
+    options: &[
+        Arg::Positional(Kind::OneOf(&["bash", "zsh", "fish"])),
+        Arg::Flag("help", Some('h')),
+    ],
+};
+
+/// Commands that can be completed, with the name they are invoked with.
+///
+/// Keep in sync with the commands matched in `main.rs`.
+pub const COMMANDS: &[(&str, Help)] = &[
+    ("alias", rad_alias::HELP),
+    ("auth", rad_auth::HELP),
+    ("block", rad_block::HELP),
+    ("checkout", rad_checkout::HELP),
+    ("clean", rad_clean::HELP),
+    ("clone", rad_clone::HELP),
+    ("cob", rad_cob::HELP),
+    ("completions", HELP),
+    ("config", rad_config::HELP),
+    ("debug", rad_debug::HELP),
+    ("diff", rad_diff::HELP),
+    ("follow", rad_follow::HELP),
+    ("fork", rad_fork::HELP),
+    ("help", rad_help::HELP),
+    ("id", rad_id::HELP),
+    ("inbox", rad_inbox::HELP),
+    ("init", rad_init::HELP),
+    ("inspect", rad_inspect::HELP),
+    ("issue", rad_issue::HELP),
+    ("job", rad_job::HELP),
+    ("label", rad_label::HELP),
+    ("ls", rad_ls::HELP),
+    ("node", rad_node::HELP),
+    ("patch", rad_patch::HELP),
+    ("path", rad_path::HELP),
+    ("publish", rad_publish::HELP),
+    ("remote", rad_remote::HELP),
+    ("seed", rad_seed::HELP),
+    ("self", rad_self::HELP),
+    ("stats", rad_stats::HELP),
+    ("sync", rad_sync::HELP),
+    ("unblock", rad_unblock::HELP),
+    ("unfollow", rad_unfollow::HELP),
+    ("unseed", rad_unseed::HELP),
+    ("use", rad_use::HELP),
+    ("watch", rad_watch::HELP),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl FromStr for Shell {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bash" => Ok(Self::Bash),
+            "zsh" => Ok(Self::Zsh),
+            "fish" => Ok(Self::Fish),
+            _ => Err(anyhow!(
+                "unsupported shell '{s}', expected one of: bash, zsh, fish"
+            )),
+        }
+    }
+}
+
+/// Identifiers listed by the completion scripts, via `rad completions --list`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum List {
+    Rids,
+    Nids,
+    Patches,
+    Issues,
+}
+
+impl FromStr for List {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rids" => Ok(Self::Rids),
+            "nids" => Ok(Self::Nids),
+            "patches" => Ok(Self::Patches),
+            "issues" => Ok(Self::Issues),
+            _ => Err(anyhow!(
+                "unknown list '{s}', expected one of: rids, nids, patches, issues"
+            )),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Operation {
+    Script(Shell),
+    List(List),
+}
+
+#[derive(Debug)]
+pub struct Options {
+    op: Operation,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut op: Option<Operation> = None;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                // Plumbing used by the generated scripts, hence not documented.
+                Long("list") if op.is_none() => {
+                    let list = term::args::string(&parser.value()?).parse()?;
+
+                    op = Some(Operation::List(list));
+                }
+                Value(val) if op.is_none() => {
+                    let shell = term::args::string(&val).parse()?;
+
+                    op = Some(Operation::Script(shell));
+                }
+                Long("help") | Short('h') => {
+                    return Err(Error::Help.into());
+                }
+                _ => return Err(anyhow!(arg.unexpected())),
+            }
+        }
+
+        Ok((
+            Options {
+                op: op.ok_or_else(|| {
+                    anyhow!("a shell must be specified, eg. `rad completions bash`")
+                })?,
+            },
+            vec![],
+        ))
+    }
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    match options.op {
+        Operation::Script(shell) => {
+            let script = match shell {
+                Shell::Bash => bash(),
+                Shell::Zsh => zsh(),
+                Shell::Fish => fish(),
+            };
+            print!("{script}");
+        }
+        Operation::List(list) => {
+            for id in self::list(list, &ctx.profile()?)? {
+                term::print(id);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// List the identifiers to complete. Patches and issues are only listed when inside
+/// a working copy of a repository.
+fn list(list: List, profile: &radicle::Profile) -> anyhow::Result<Vec<String>> {
+    let ids = match list {
+        List::Rids => profile
+            .storage
+            .repositories()?
+            .into_iter()
+            .map(|info| info.rid.to_string())
+            .collect(),
+        List::Nids => profile
+            .database()?
+            .entries()?
+            .map(|entry| entry.node.to_string())
+            .collect(),
+        List::Patches => {
+            let Ok((_, rid)) = radicle::rad::cwd() else {
+                return Ok(vec![]);
+            };
+            let repo = profile.storage.repository(rid)?;
+            let patches = term::cob::patches(profile, &repo)?;
+
+            return Ok(patches
+                .list()?
+                .filter_map(|result| result.ok())
+                .map(|(id, _)| id.to_string())
+                .collect());
+        }
+        List::Issues => {
+            let Ok((_, rid)) = radicle::rad::cwd() else {
+                return Ok(vec![]);
+            };
+            let repo = profile.storage.repository(rid)?;
+            let issues = term::cob::issues(profile, &repo)?;
+
+            return Ok(issues
+                .list()?
+                .filter_map(|result| result.ok())
+                .map(|(id, _)| id.to_string())
+                .collect());
+        }
+    };
+    Ok(ids)
+}
+
+/// The `rad completions --list` invocation for a kind of value, if it is listed.
+fn lister(kind: Kind) -> Option<&'static str> {
+    match kind {
+        Kind::Rid => Some("rad completions --list rids 2>/dev/null"),
+        Kind::Nid => Some("rad completions --list nids 2>/dev/null"),
+        Kind::Patch => Some("rad completions --list patches 2>/dev/null"),
+        Kind::Issue => Some("rad completions --list issues 2>/dev/null"),
+        Kind::Any | Kind::Path | Kind::OneOf(_) => None,
+    }
+}
+
+/// Flags and options of a command, in their long and short forms.
+fn flags(help: &Help) -> Vec<String> {
+    let mut flags = Vec::new();
+
+    for arg in help.options {
+        if let Arg::Flag(long, short) | Arg::Opt(long, short, _) = arg {
+            flags.push(format!("--{long}"));
+            if let Some(short) = short {
+                flags.push(format!("-{short}"));
+            }
+        }
+    }
+    flags
+}
+
+/// Sub-commands of a command.
+fn commands(help: &Help) -> Vec<&'static str> {
+    help.options
+        .iter()
+        .filter_map(|arg| match arg {
+            Arg::Command(name) => Some(*name),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Kinds of the positional arguments of a command.
+fn positionals(help: &Help) -> Vec<Kind> {
+    help.options
+        .iter()
+        .filter_map(|arg| match arg {
+            Arg::Positional(kind) => Some(*kind),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Options that take a value, along with their value kind.
+fn values(help: &Help) -> Vec<(Vec<String>, Kind)> {
+    help.options
+        .iter()
+        .filter_map(|arg| match arg {
+            Arg::Opt(long, short, kind) => {
+                let mut names = vec![format!("--{long}")];
+                if let Some(short) = short {
+                    names.push(format!("-{short}"));
+                }
+                Some((names, *kind))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Generate the bash completion script.
+pub fn bash() -> String {
+    let mut s = String::new();
+    let names = COMMANDS.iter().map(|(n, _)| *n).collect::<Vec<_>>();
+
+    writeln!(s, "# bash completion for rad").ok();
+    writeln!(s, "_rad() {{").ok();
+    writeln!(s, "    local cur=\"${{COMP_WORDS[COMP_CWORD]}}\"").ok();
+    writeln!(s, "    local prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"").ok();
+    writeln!(s, "    local opts=\"\" words=\"\" files=0").ok();
+    writeln!(s).ok();
+    writeln!(s, "    if [[ $COMP_CWORD -eq 1 ]]; then").ok();
+    writeln!(
+        s,
+        "        COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))",
+        names.join(" ")
+    )
+    .ok();
+    writeln!(s, "        return").ok();
+    writeln!(s, "    fi").ok();
+    writeln!(s).ok();
+    writeln!(s, "    case \"${{COMP_WORDS[1]}}\" in").ok();
+
+    for (name, help) in COMMANDS {
+        writeln!(s, "        {name})").ok();
+
+        let values = values(help);
+        if !values.is_empty() {
+            writeln!(s, "            case \"$prev\" in").ok();
+            for (names, kind) in values {
+                let reply = match kind {
+                    Kind::Any => String::new(),
+                    Kind::Path => "COMPREPLY=($(compgen -f -- \"$cur\")); ".to_owned(),
+                    Kind::OneOf(vals) => {
+                        format!(
+                            "COMPREPLY=($(compgen -W \"{}\" -- \"$cur\")); ",
+                            vals.join(" ")
+                        )
+                    }
+                    kind => format!(
+                        "COMPREPLY=($(compgen -W \"$({})\" -- \"$cur\")); ",
+                        lister(kind).unwrap_or_default()
+                    ),
+                };
+                writeln!(s, "                {}) {reply}return ;;", names.join("|")).ok();
+            }
+            writeln!(s, "            esac").ok();
+        }
+        writeln!(s, "            opts=\"{}\"", flags(help).join(" ")).ok();
+
+        let mut words = commands(help)
+            .into_iter()
+            .map(|c| c.to_owned())
+            .collect::<Vec<_>>();
+        for kind in positionals(help) {
+            match kind {
+                Kind::Path => {
+                    writeln!(s, "            files=1").ok();
+                }
+                Kind::OneOf(vals) => words.extend(vals.iter().map(|v| v.to_string())),
+                Kind::Any => {}
+                kind => words.extend(lister(kind).map(|l| format!("$({l})"))),
+            }
+        }
+        writeln!(s, "            words=\"{}\"", words.join(" ")).ok();
+        writeln!(s, "            ;;").ok();
+    }
+    writeln!(s, "    esac").ok();
+    writeln!(s).ok();
+    writeln!(s, "    if [[ \"$cur\" == -* ]]; then").ok();
+    writeln!(s, "        COMPREPLY=($(compgen -W \"$opts\" -- \"$cur\"))").ok();
+    writeln!(s, "    else").ok();
+    writeln!(
+        s,
+        "        COMPREPLY=($(compgen -W \"$words\" -- \"$cur\"))"
+    )
+    .ok();
+    writeln!(s, "        if [[ $files -eq 1 ]]; then").ok();
+    writeln!(s, "            COMPREPLY+=($(compgen -f -- \"$cur\"))").ok();
+    writeln!(s, "        fi").ok();
+    writeln!(s, "    fi").ok();
+    // Repository identifiers contain a colon, which bash treats as a word separator.
+    writeln!(
+        s,
+        "    if declare -F __ltrim_colon_completions >/dev/null; then"
+    )
+    .ok();
+    writeln!(s, "        __ltrim_colon_completions \"$cur\"").ok();
+    writeln!(s, "    fi").ok();
+    writeln!(s, "}}").ok();
+    writeln!(s).ok();
+    writeln!(s, "complete -F _rad rad").ok();
+
+    s
+}
+
+/// Generate the zsh completion script.
+pub fn zsh() -> String {
+    let mut s = String::new();
+    let names = COMMANDS.iter().map(|(n, _)| *n).collect::<Vec<_>>();
+    let list = |kind| {
+        lister(kind)
+            .map(|l| format!("${{(f)\"$({l})\"}}"))
+            .unwrap_or_default()
+    };
+
+    writeln!(s, "#compdef rad").ok();
+    writeln!(s).ok();
+    writeln!(s, "_rad() {{").ok();
+    writeln!(
+        s,
+        "    local cur=${{words[CURRENT]}} prev=${{words[CURRENT-1]}}"
+    )
+    .ok();
+    writeln!(s).ok();
+    writeln!(s, "    if (( CURRENT == 2 )); then").ok();
+    writeln!(s, "        compadd -- {}", names.join(" ")).ok();
+    writeln!(s, "        return").ok();
+    writeln!(s, "    fi").ok();
+    writeln!(s).ok();
+    writeln!(s, "    case ${{words[2]}} in").ok();
+
+    for (name, help) in COMMANDS {
+        writeln!(s, "        {name})").ok();
+
+        let values = values(help);
+        if !values.is_empty() {
+            writeln!(s, "            case $prev in").ok();
+            for (names, kind) in values {
+                let reply = match kind {
+                    Kind::Any => String::new(),
+                    Kind::Path => "_files; ".to_owned(),
+                    Kind::OneOf(vals) => format!("compadd -- {}; ", vals.join(" ")),
+                    kind => format!("compadd -- {}; ", list(kind)),
+                };
+                writeln!(s, "                {}) {reply}return ;;", names.join("|")).ok();
+            }
+            writeln!(s, "            esac").ok();
+        }
+        writeln!(s, "            if [[ $cur == -* ]]; then").ok();
+        writeln!(s, "                compadd -- {}", flags(help).join(" ")).ok();
+        writeln!(s, "            else").ok();
+
+        let mut words = commands(help)
+            .into_iter()
+            .map(|c| c.to_owned())
+            .collect::<Vec<_>>();
+        let mut files = false;
+        for kind in positionals(help) {
+            match kind {
+                Kind::Path => files = true,
+                Kind::OneOf(vals) => words.extend(vals.iter().map(|v| v.to_string())),
+                Kind::Any => {}
+                kind => words.push(list(kind)),
+            }
+        }
+        writeln!(s, "                compadd -- {}", words.join(" ")).ok();
+        if files {
+            writeln!(s, "                _files").ok();
+        }
+        writeln!(s, "            fi").ok();
+        writeln!(s, "            ;;").ok();
+    }
+    writeln!(s, "    esac").ok();
+    writeln!(s, "}}").ok();
+    writeln!(s).ok();
+    writeln!(s, "compdef _rad rad").ok();
+
+    s
+}
+
+/// Generate the fish completion script.
+pub fn fish() -> String {
+    let mut s = String::new();
+    let quote = |s: &str| format!("'{}'", s.replace('\\', "\\\\").replace('\'', "\\'"));
+    let list = |kind| lister(kind).map(|l| quote(&format!("({l})")));
+
+    writeln!(s, "# fish completion for rad").ok();
+    writeln!(s, "complete -c rad -f").ok();
+
+    for (name, help) in COMMANDS {
+        writeln!(
+            s,
+            "complete -c rad -n __fish_use_subcommand -a {name} -d {}",
+            quote(help.description)
+        )
+        .ok();
+    }
+    for (name, help) in COMMANDS {
+        let cond = format!("-n '__fish_seen_subcommand_from {name}'");
+
+        let commands = commands(help);
+        if !commands.is_empty() {
+            writeln!(
+                s,
+                "complete -c rad {cond} -a {}",
+                quote(&commands.join(" "))
+            )
+            .ok();
+        }
+        for kind in positionals(help) {
+            match kind {
+                Kind::Path => {
+                    writeln!(s, "complete -c rad {cond} -F").ok();
+                }
+                Kind::OneOf(vals) => {
+                    writeln!(s, "complete -c rad {cond} -a {}", quote(&vals.join(" "))).ok();
+                }
+                Kind::Any => {}
+                kind => {
+                    if let Some(list) = list(kind) {
+                        writeln!(s, "complete -c rad {cond} -a {list}").ok();
+                    }
+                }
+            }
+        }
+        for arg in help.options {
+            let (long, short, kind) = match arg {
+                Arg::Flag(long, short) => (long, short, None),
+                Arg::Opt(long, short, kind) => (long, short, Some(*kind)),
+                Arg::Command(_) | Arg::Positional(_) => continue,
+            };
+            let mut line = format!("complete -c rad {cond} -l {long}");
+            if let Some(short) = short {
+                write!(line, " -s {short}").ok();
+            }
+            match kind {
+                None => {}
+                Some(Kind::Path) => line.push_str(" -r -F"),
+                Some(Kind::OneOf(vals)) => {
+                    write!(line, " -x -a {}", quote(&vals.join(" "))).ok();
+                }
+                Some(Kind::Any) => line.push_str(" -x"),
+                Some(kind) => {
+                    write!(line, " -x -a {}", list(kind).unwrap_or_default()).ok();
+                }
+            }
+            writeln!(s, "{line}").ok();
+        }
+    }
+    s
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Long flags mentioned in the usage, on the lines listing commands and options.
+    fn usage_flags(usage: &str) -> Vec<String> {
+        let mut flags = Vec::new();
+
+        for line in usage.lines().map(str::trim) {
+            if !(line.starts_with('-') || line.starts_with('[') || line.starts_with("rad ")) {
+                continue;
+            }
+            for (i, _) in line.match_indices("--") {
+                let rest = &line[i + 2..];
+                let (negated, rest) = match rest.strip_prefix("[no-]") {
+                    Some(rest) => (true, rest),
+                    None => (false, rest),
+                };
+                let name = rest
+                    .split(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+                    .next()
+                    .unwrap_or_default();
+                if name.is_empty() {
+                    continue;
+                }
+                flags.push(name.to_owned());
+                if negated {
+                    flags.push(format!("no-{name}"));
+                }
+            }
+        }
+        flags
+    }
+
+    #[test]
+    fn test_all_commands_completed() {
+        let modules = include_str!("../commands.rs");
+
+        for line in modules.lines() {
+            let Some(module) = line
+                .strip_prefix("pub mod rad_")
+                .and_then(|m| m.strip_suffix(';'))
+            else {
+                continue;
+            };
+            assert!(
+                COMMANDS.iter().any(|(name, _)| *name == module),
+                "`rad {module}` is missing from the completions"
+            );
+        }
+    }
+
+    #[test]
+    fn test_usage_flags_completed() {
+        for (name, help) in COMMANDS {
+            let longs = help
+                .options
+                .iter()
+                .filter_map(|arg| arg.long())
+                .collect::<Vec<_>>();
+
+            for flag in usage_flags(help.usage) {
+                assert!(
+                    longs.contains(&flag.as_str()),
+                    "`rad {name} --{flag}` is in the usage but not in the options"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_scripts() {
+        let bash = bash();
+        assert!(bash.contains("complete -F _rad rad"));
+        assert!(bash.contains("        patch)\n"));
+        assert!(bash.contains("--repo) COMPREPLY=($(compgen -W \"$(rad completions --list rids"));
+        assert!(bash.contains("$(rad completions --list patches 2>/dev/null)"));
+
+        let zsh = zsh();
+        assert!(zsh.starts_with("#compdef rad"));
+        assert!(zsh.contains("--format) compadd -- full json; return ;;"));
+        assert!(zsh.contains("${(f)\"$(rad completions --list issues 2>/dev/null)\"}"));
+
+        let fish = fish();
+        assert!(
+            fish.contains("complete -c rad -n __fish_use_subcommand -a patch -d 'Manage patches'")
+        );
+        assert!(fish.contains(
+            "complete -c rad -n '__fish_seen_subcommand_from seed' -l from -x -a '(rad completions --list nids 2>/dev/null)'"
+        ));
+    }
+}
@@ -8,7 +8,7 @@ use radicle::node::Alias;
 use radicle::profile::{Config, ConfigError, ConfigPath, RawConfig};
 
 use crate::terminal as term;
-use crate::terminal::args::{Args, Error, Help};
+use crate::terminal::args::{Arg, Args, Error, Help, Kind};
 use crate::terminal::Element as _;
 
 pub const HELP: Help = Help {
@@ -36,6 +36,18 @@ Options
     --help    Print help
 
 "#,
+    options: &[
+        Arg::Command("show"),
+        Arg::Command("init"),
+        Arg::Command("edit"),
+        Arg::Command("get"),
+        Arg::Command("set"),
+        Arg::Command("unset"),
+        Arg::Command("push"),
+        Arg::Command("remove"),
+        Arg::Opt("alias", None, Kind::Any),
+        Arg::Flag("help", Some('h')),
+    ],
 };
 
 #[derive(Default)]
@@ -33,6 +33,7 @@ Usage
     please check it, and then forward to the Radicle developers.
 
 "#,
+    options: &[],
 };
 
 #[derive(Debug)]
@@ -1,15 +1,22 @@
+use std::borrow::Cow;
 use std::ffi::OsString;
+use std::str::FromStr as _;
 
 use anyhow::anyhow;
 
 use radicle::git;
+use radicle::patch::cache::Patches as _;
+use radicle::patch::{PatchId, RevisionId};
+use radicle::prelude::{NodeId, RepoId};
 use radicle::rad;
+use radicle::storage::ReadStorage as _;
+use radicle::Profile;
 use radicle_surf as surf;
 
 use crate::git::pretty_diff::ToPretty as _;
 use crate::git::Rev;
 use crate::terminal as term;
-use crate::terminal::args::{Args, Error, Help};
+use crate::terminal::args::{Arg, Args, Error, Help, Kind};
 use crate::terminal::highlight::Highlighter;
 
 pub const HELP: Help = Help {
@@ -21,23 +28,53 @@ Usage
 
     rad diff [<commit>] [--staged] [<option>...]
     rad diff <commit> [<commit>] [<option>...]
+    rad diff --repo <rid> <commit> <commit> [<option>...]
 
     This command is meant to operate as closely as possible to `git diff`,
     except its output is optimized for human-readability.
 
+    Commits may also be given as patch revision IDs, in which case the head
+    of the revision is used. IDs that match both a commit and a revision, or
+    more than one revision, are rejected as ambiguous. Commits that are the
+    head of a patch revision are shown along with the revision and patch
+    they belong to.
+
+    Renamed and copied files are detected by their similarity. Only files
+    that were modified are considered as the source of a copy.
+
+    With `--repo`, the diff is computed directly on the stored repository.
+    Commits may then be given as canonical references, eg. `refs/heads/master`,
+    or as references of a peer, eg. `<nid>/refs/heads/master`.
+
 Options
 
     --unified, -U   Context lines to show (default: 5)
     --staged        View staged changes
+    --stat          Only show a summary of the changes of each file
+    --name-only     Only show the names of the changed files
+    --repo <rid>    Diff commits of the given stored repository
     --color         Force color output
     --help          Print help
 "#,
+    options: &[
+        Arg::Opt("unified", Some('U'), Kind::Any),
+        Arg::Flag("staged", None),
+        Arg::Flag("cached", None),
+        Arg::Flag("stat", None),
+        Arg::Flag("name-only", None),
+        Arg::Opt("repo", None, Kind::Rid),
+        Arg::Flag("color", None),
+        Arg::Flag("help", Some('h')),
+    ],
 };
 
 pub struct Options {
     pub commits: Vec<Rev>,
     pub staged: bool,
     pub unified: usize,
+    pub stat: bool,
+    pub name_only: bool,
+    pub repo: Option<RepoId>,
     pub color: bool,
 }
 
@@ -49,6 +86,9 @@ impl Args for Options {
         let mut commits = Vec::new();
         let mut staged = false;
         let mut unified = 5;
+        let mut stat = false;
+        let mut name_only = false;
+        let mut repo = None;
         let mut color = false;
 
         while let Some(arg) = parser.next()? {
@@ -58,6 +98,12 @@ impl Args for Options {
                     unified = term::args::number(&val)?;
                 }
                 Long("staged") | Long("cached") => staged = true,
+                Long("stat") => stat = true,
+                Long("name-only") => name_only = true,
+                Long("repo") => {
+                    let val = parser.value()?;
+                    repo = Some(term::args::rid(&val)?);
+                }
                 Long("color") => color = true,
                 Long("help") | Short('h') => return Err(Error::Help.into()),
                 Value(val) => {
@@ -69,11 +115,26 @@ impl Args for Options {
             }
         }
 
+        if stat && name_only {
+            anyhow::bail!("`--stat` and `--name-only` cannot be used together");
+        }
+        if repo.is_some() {
+            if staged {
+                anyhow::bail!("`--staged` cannot be used with `--repo`");
+            }
+            if commits.len() != 2 {
+                anyhow::bail!("two commits must be given when using `--repo`");
+            }
+        }
+
         Ok((
             Options {
                 commits,
                 staged,
                 unified,
+                stat,
+                name_only,
+                repo,
                 color,
             },
             vec![],
@@ -81,29 +142,86 @@ impl Args for Options {
     }
 }
 
-pub fn run(options: Options, _ctx: impl term::Context) -> anyhow::Result<()> {
-    let repo = rad::repo()?;
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let (repo, rid) = if let Some(rid) = options.repo {
+        let profile = ctx.profile()?;
+
+        (profile.storage.repository(rid)?.backend, Some(rid))
+    } else {
+        match rad::cwd() {
+            Ok((repo, rid)) => (repo, Some(rid)),
+            // Outside of a Radicle repository, there are no patches to consider.
+            Err(_) => (rad::repo()?, None),
+        }
+    };
+    // Patch revisions are only needed to resolve and annotate the given commits.
+    let revisions = match rid {
+        Some(rid) if !options.commits.is_empty() => match ctx.profile() {
+            Ok(profile) => revisions(&profile, rid)?,
+            Err(_) => Vec::new(),
+        },
+        _ => Vec::new(),
+    };
     let oids = options
         .commits
         .into_iter()
         .map(|rev| {
-            repo.revparse_single(rev.as_str())
-                .map_err(|e| anyhow!("unknown object {rev}: {e}"))
-                .and_then(|o| {
-                    o.into_commit()
-                        .map_err(|_| anyhow!("object {rev} is not a commit"))
-                })
+            let spec = if options.repo.is_some() {
+                namespaced(rev.as_str())
+            } else {
+                Cow::Borrowed(rev.as_str())
+            };
+            let mut matching = revisions.iter().filter(|r| r.matches(rev.as_str()));
+            let revision = match (matching.next(), matching.next()) {
+                (Some(_), Some(_)) => {
+                    anyhow::bail!("{rev} is ambiguous, it matches more than one patch revision");
+                }
+                (revision, _) => revision,
+            };
+
+            match (repo.revparse_single(&spec), revision) {
+                // In storage, the revision ID is itself the commit of the patch change.
+                (Ok(object), Some(revision))
+                    if git::Oid::from(object.id()) == git::Oid::from(revision.id) =>
+                {
+                    repo.find_commit(revision.head.into())
+                        .map_err(|e| anyhow!("head of patch revision {rev} not found: {e}"))
+                }
+                (Ok(_), Some(_)) => {
+                    anyhow::bail!("{rev} is ambiguous, it matches a commit and a patch revision");
+                }
+                (Ok(object), None) => object
+                    .into_commit()
+                    .map_err(|_| anyhow!("object {rev} is not a commit")),
+                (Err(_), Some(revision)) => repo
+                    .find_commit(revision.head.into())
+                    .map_err(|e| anyhow!("head of patch revision {rev} not found: {e}")),
+                (Err(e), None) => Err(anyhow!("unknown object {rev}: {e}")),
+            }
         })
         .collect::<Result<Vec<_>, _>>()?;
 
+    for commit in &oids {
+        let head = git::Oid::from(commit.id());
+        for revision in revisions.iter().filter(|r| r.head == head) {
+            term::info!(
+                "{} is revision {} of patch {} {}",
+                term::format::secondary(term::format::oid(head)),
+                term::format::tertiary(term::format::oid(revision.id)),
+                term::format::tertiary(term::format::cob(&revision.patch)),
+                term::format::dim(format!("\"{}\"", revision.title)),
+            );
+        }
+    }
+
     let mut opts = git::raw::DiffOptions::new();
     opts.patience(true)
         .minimal(true)
         .context_lines(options.unified as u32);
 
+    // Like `git diff -C`, only modified files are considered as copy sources.
     let mut find_opts = git::raw::DiffFindOptions::new();
-    find_opts.exact_match_only(true);
-    find_opts.all(true);
+    find_opts.renames(true).copies(true);
 
     let mut diff = match oids.as_slice() {
         [] => {
@@ -139,6 +257,22 @@ pub fn run(options: Options, _ctx: impl term::Context) -> anyhow::Result<()> {
     }?;
     diff.find_similar(Some(&mut find_opts))?;
 
+    if options.name_only {
+        for delta in diff.deltas() {
+            if let Some(path) = delta.new_file().path().or(delta.old_file().path()) {
+                println!("{}", path.display());
+            }
+        }
+        return Ok(());
+    }
+    if options.stat {
+        let stats = diff.stats()?;
+        let buf = stats.to_buf(git::raw::DiffStatsFormat::FULL, 80)?;
+
+        print!("{}", buf.as_str().unwrap_or_default());
+        return Ok(());
+    }
+
     term::Paint::force(options.color);
 
     let diff = surf::diff::Diff::try_from(diff)?;
@@ -149,3 +283,60 @@ pub fn run(options: Options, _ctx: impl term::Context) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// A patch revision, used to resolve and annotate the commits being compared.
+struct PatchRevision {
+    /// The patch the revision belongs to.
+    patch: PatchId,
+    /// The patch title.
+    title: String,
+    /// The revision identifier.
+    id: RevisionId,
+    /// The head commit of the revision.
+    head: git::Oid,
+}
+
+impl PatchRevision {
+    /// Check whether the given revision string is a (possibly abbreviated) ID of this revision.
+    fn matches(&self, rev: &str) -> bool {
+        rev.len() >= 7
+            && rev.chars().all(|c| c.is_ascii_hexdigit())
+            && self.id.to_string().starts_with(rev)
+    }
+}
+
+/// Get the revisions of all patches of the given repository.
+fn revisions(profile: &Profile, rid: RepoId) -> anyhow::Result<Vec<PatchRevision>> {
+    let repo = profile.storage.repository(rid)?;
+    let patches = term::cob::patches(profile, &repo)?;
+    let mut revisions = Vec::new();
+
+    for result in patches.list()? {
+        let (patch, p) = match result {
+            Ok(patch) => patch,
+            Err(e) => {
+                // Skip patches that failed to load.
+                log::error!(target: "cli", "Patch load error: {e}");
+                continue;
+            }
+        };
+
+        revisions.extend(p.revisions().map(|(id, revision)| PatchRevision {
+            patch,
+            title: p.title().to_owned(),
+            id,
+            head: revision.head(),
+        }));
+    }
+    Ok(revisions)
+}
+
+/// Expand a `<nid>/<reference>` revision to the reference in the namespace of `nid`.
+fn namespaced(rev: &str) -> Cow<str> {
+    match rev.split_once('/') {
+        Some((nid, reference)) if NodeId::from_str(nid).is_ok() => {
+            Cow::Owned(format!("refs/namespaces/{nid}/{reference}"))
+        }
+        _ => Cow::Borrowed(rev),
+    }
+}
@@ -7,7 +7,7 @@ use radicle::{prelude::*, Node};
 use radicle_term::{Element as _, Paint, Table};
 
 use crate::terminal as term;
-use crate::terminal::args::{Args, Error, Help};
+use crate::terminal::args::{Arg, Args, Error, Help, Kind};
 
 pub const HELP: Help = Help {
     name: "follow",
@@ -29,6 +29,12 @@ Options
     --verbose, -v          Verbose output
     --help                 Print help
 "#,
+    options: &[
+        Arg::Positional(Kind::Nid),
+        Arg::Opt("alias", None, Kind::Any),
+        Arg::Flag("verbose", Some('v')),
+        Arg::Flag("help", Some('h')),
+    ],
 };
 
 #[derive(Debug)]
@@ -2,12 +2,13 @@ use std::ffi::OsString;
 
 use anyhow::Context as _;
 
-use radicle::prelude::RepoId;
+use radicle::prelude::{NodeId, RepoId};
 use radicle::rad;
+use radicle::storage::BranchName;
 
 use crate::terminal as term;
 use crate::terminal::args;
-use crate::terminal::args::{Args, Error, Help};
+use crate::terminal::args::{Arg, Args, Error, Help, Kind};
 
 pub const HELP: Help = Help {
     name: "fork",
@@ -18,14 +19,32 @@ Usage
 
     rad fork [<rid>] [<option>...]
 
+    Creates our namespace in the repository from the canonical default branch.
+    Use the options below to fork other branches, or to fork a peer's view
+    of the repository instead.
+
 Options
 
-    --help          Print help
+    --branch <name>     Fork the given branch (may be specified multiple times,
+                        default: the default branch)
+    --from <nid>        Fork the refs of the given peer instead of the canonical refs
+    --no-cobs           Don't fork the peer's COB refs (only applies with `--from`)
+    --help              Print help
 "#,
+    options: &[
+        Arg::Positional(Kind::Rid),
+        Arg::Opt("branch", None, Kind::Any),
+        Arg::Opt("from", None, Kind::Nid),
+        Arg::Flag("no-cobs", None),
+        Arg::Flag("help", Some('h')),
+    ],
 };
 
 pub struct Options {
     rid: Option<RepoId>,
+    branches: Vec<BranchName>,
+    from: Option<NodeId>,
+    cobs: bool,
 }
 
 impl Args for Options {
@@ -34,12 +53,26 @@ impl Args for Options {
 
         let mut parser = lexopt::Parser::from_args(args);
         let mut rid = None;
+        let mut branches = Vec::new();
+        let mut from = None;
+        let mut cobs = true;
 
-        if let Some(arg) = parser.next()? {
+        while let Some(arg) = parser.next()? {
             match arg {
                 Long("help") | Short('h') => {
                     return Err(Error::Help.into());
                 }
+                Long("branch") => {
+                    let val = parser.value()?;
+                    branches.push(args::refstring("branch", val)?);
+                }
+                Long("from") => {
+                    let val = parser.value()?;
+                    from = Some(args::nid(&val)?);
+                }
+                Long("no-cobs") => {
+                    cobs = false;
+                }
                 Value(val) if rid.is_none() => {
                     rid = Some(args::rid(&val)?);
                 }
@@ -47,7 +80,15 @@ impl Args for Options {
             }
         }
 
-        Ok((Options { rid }, vec![]))
+        Ok((
+            Options {
+                rid,
+                branches,
+                from,
+                cobs,
+            },
+            vec![],
+        ))
     }
 }
 
@@ -66,8 +107,25 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
         }
     };
 
-    rad::fork(rid, &signer, &storage)?;
+    let created = rad::fork_with(
+        rid,
+        rad::ForkOptions {
+            from: options.from,
+            branches: options.branches,
+            cobs: options.cobs,
+        },
+        &signer,
+        &storage,
+    )?;
     term::success!("Forked repository {rid} for {}", profile.id());
 
+    for (name, oid) in created {
+        term::info!(
+            "Created {} at {}",
+            term::format::tertiary(name),
+            term::format::oid(oid)
+        );
+    }
+
     Ok(())
 }
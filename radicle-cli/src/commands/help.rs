@@ -1,7 +1,7 @@
 use std::ffi::OsString;
 
 use crate::terminal as term;
-use crate::terminal::args::{Args, Error, Help};
+use crate::terminal::args::{Arg, Args, Error, Help};
 
 use super::*;
 
@@ -10,9 +10,11 @@ pub const HELP: Help = Help {
     description: "CLI help",
     version: env!("RADICLE_VERSION"),
     usage: "Usage: rad help [--help]",
+    options: &[Arg::Flag("help", None)],
 };
 
 const COMMANDS: &[Help] = &[
+    rad_alias::HELP,
     rad_auth::HELP,
     rad_block::HELP,
     rad_checkout::HELP,
@@ -26,6 +28,7 @@ const COMMANDS: &[Help] = &[
     rad_inspect::HELP,
     rad_issue::HELP,
     rad_job::HELP,
+    rad_label::HELP,
     rad_ls::HELP,
     rad_node::HELP,
     rad_patch::HELP,
@@ -37,6 +40,7 @@ const COMMANDS: &[Help] = &[
     rad_unblock::HELP,
     rad_unfollow::HELP,
     rad_unseed::HELP,
+    rad_use::HELP,
     rad_remote::HELP,
     rad_stats::HELP,
     rad_sync::HELP,
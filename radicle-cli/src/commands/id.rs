@@ -5,7 +5,7 @@ use std::{ffi::OsString, io};
 use anyhow::{anyhow, Context};
 
 use radicle::cob::identity::{self, IdentityMut, Revision, RevisionId};
-use radicle::identity::{doc, Doc, Identity, PayloadError, RawDoc, Visibility};
+use radicle::identity::{doc, Doc, Identity, PayloadError, RawDoc, Validators, Visibility};
 use radicle::prelude::{Did, RepoId, Signer};
 use radicle::storage::refs;
 use radicle::storage::{ReadRepository, ReadStorage as _, WriteRepository};
@@ -17,7 +17,7 @@ use serde_json as json;
 use crate::git::unified_diff::Encode as _;
 use crate::git::Rev;
 use crate::terminal as term;
-use crate::terminal::args::{Args, Error, Help};
+use crate::terminal::args::{Arg, Args, Error, Help, Kind};
 use crate::terminal::patch::Message;
 use crate::terminal::Interactive;
 
@@ -33,7 +33,8 @@ Usage
                   [--delegate <did>] [--rescind <did>]
                   [--threshold <num>] [--visibility <private | public>]
                   [--allow <did>] [--disallow <did>]
-                  [--no-confirm] [--payload <id> <key> <val>...] [--edit] [<option>...]
+                  [--no-confirm] [--payload <id> <key> <val>...] [--edit] [--strict]
+                  [<option>...]
     rad id edit <revision-id> [--title <string>] [--description <string>] [<option>...]
     rad id show <revision-id> [<option>...]
     rad id <accept | reject | redact> <revision-id> [<option>...]
@@ -43,12 +44,40 @@ Usage
 
     See the rad-id(1) man page for more information.
 
+Update options
+
+    --strict               Warn about payloads that can't be validated
+
 Options
 
     --repo <rid>           Repository (defaults to the current repository)
     --quiet, -q            Don't print anything
     --help                 Print help
 "#,
+    options: &[
+        Arg::Command("list"),
+        Arg::Command("update"),
+        Arg::Command("edit"),
+        Arg::Command("show"),
+        Arg::Command("accept"),
+        Arg::Command("reject"),
+        Arg::Command("redact"),
+        Arg::Opt("title", None, Kind::Any),
+        Arg::Opt("description", None, Kind::Any),
+        Arg::Opt("delegate", None, Kind::Any),
+        Arg::Opt("rescind", None, Kind::Any),
+        Arg::Opt("threshold", None, Kind::Any),
+        Arg::Opt("visibility", None, Kind::OneOf(&["private", "public"])),
+        Arg::Opt("allow", None, Kind::Any),
+        Arg::Opt("disallow", None, Kind::Any),
+        Arg::Opt("payload", None, Kind::Any),
+        Arg::Flag("edit", None),
+        Arg::Flag("strict", None),
+        Arg::Flag("no-confirm", None),
+        Arg::Opt("repo", None, Kind::Rid),
+        Arg::Flag("quiet", Some('q')),
+        Arg::Flag("help", Some('h')),
+    ],
 };
 
 #[derive(Clone, Debug, Default)]
@@ -64,6 +93,7 @@ pub enum Operation {
         disallow: BTreeSet<Did>,
         payload: Vec<(doc::PayloadId, String, json::Value)>,
         edit: bool,
+        strict: bool,
     },
     AcceptRevision {
         revision: Rev,
@@ -147,6 +177,7 @@ impl Args for Options {
         let mut interactive = Interactive::new(io::stdout());
         let mut payload = Vec::new();
         let mut edit = false;
+        let mut strict = false;
         let mut quiet = false;
 
         while let Some(arg) = parser.next()? {
@@ -241,6 +272,9 @@ impl Args for Options {
                 Long("edit") => {
                     edit = true;
                 }
+                Long("strict") if op == Some(OperationName::Update) => {
+                    strict = true;
+                }
                 Value(val) => {
                     let val = term::args::rev(&val)?;
                     revision = Some(val);
@@ -281,6 +315,7 @@ impl Args for Options {
                 disallow,
                 payload,
                 edit,
+                strict,
             },
         };
         Ok((
@@ -391,6 +426,7 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
             disallow,
             payload,
             edit,
+            strict,
         } => {
             let proposal = {
                 let mut proposal = current.doc.clone().edit();
@@ -438,17 +474,6 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
                     .chain(delegates)
                     .filter(|d| !rescind.contains(d))
                     .collect::<Vec<_>>();
-                if let Some(errs) = verify_delegates(&proposal, &repo)? {
-                    term::error(format!("failed to verify delegates for {rid}"));
-                    term::error(format!(
-                        "the threshold of {} delegates cannot be met..",
-                        proposal.threshold
-                    ));
-                    for e in errs {
-                        e.print();
-                    }
-                    anyhow::bail!("fatal: refusing to update identity document");
-                }
 
                 for (id, key, val) in payload {
                     if let Some(ref mut payload) = proposal.payload.get_mut(&id) {
@@ -487,11 +512,44 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
                 proposal
             };
 
+            // Nb. Duplicate delegates are only counted once in the final document.
+            let count = proposal.delegates.iter().collect::<BTreeSet<_>>().len();
+            if count == 0 {
+                anyhow::bail!("an identity must have at least one delegate; use `--rescind` on fewer delegates");
+            }
+            if proposal.threshold > count {
+                anyhow::bail!(
+                    "threshold of {} exceeds the number of delegates ({count}); use `--threshold {count}` or lower",
+                    proposal.threshold
+                );
+            }
+            if let Some(errs) = verify_delegates(&proposal, &repo)? {
+                term::error(format!("failed to verify delegates for {rid}"));
+                term::error(format!(
+                    "the threshold of {} delegates cannot be met..",
+                    proposal.threshold
+                ));
+                for e in errs {
+                    e.print();
+                }
+                anyhow::bail!("fatal: refusing to update identity document");
+            }
+
             // Verify that the project payload can still be parsed into the `Project` type.
             if let Err(PayloadError::Json(e)) = proposal.project() {
                 anyhow::bail!("failed to verify `xyz.radicle.project`, {e}");
             }
-            let proposal = proposal.verified()?;
+            let validators = Validators::default();
+            if strict {
+                for id in proposal.payload.keys() {
+                    if !validators.is_known(id) {
+                        term::warning(format!(
+                            "payload `{id}` is not known and will not be validated"
+                        ));
+                    }
+                }
+            }
+            let proposal = proposal.validated(&validators)?;
             if proposal == current.doc {
                 if !options.quiet {
                     term::print(term::format::italic(
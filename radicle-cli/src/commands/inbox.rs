@@ -20,7 +20,7 @@ use term::Element as _;
 
 use crate::terminal as term;
 use crate::terminal::args;
-use crate::terminal::args::{Args, Error, Help};
+use crate::terminal::args::{Arg, Args, Error, Help, Kind};
 
 pub const HELP: Help = Help {
     name: "inbox",
@@ -53,6 +53,17 @@ Options
     --show-unknown       Show any updates that were not recognized
     --help               Print help
 "#,
+    options: &[
+        Arg::Command("list"),
+        Arg::Command("show"),
+        Arg::Command("clear"),
+        Arg::Flag("all", Some('a')),
+        Arg::Opt("repo", None, Kind::Rid),
+        Arg::Opt("sort-by", None, Kind::OneOf(&["id", "timestamp"])),
+        Arg::Flag("reverse", Some('r')),
+        Arg::Flag("show-unknown", None),
+        Arg::Flag("help", Some('h')),
+    ],
 };
 
 #[derive(Debug, Default, PartialEq, Eq)]
@@ -14,17 +14,17 @@ use radicle::crypto::ssh;
 use radicle::explorer::ExplorerUrl;
 use radicle::git::RefString;
 use radicle::identity::project::ProjectName;
-use radicle::identity::{Doc, RepoId, Visibility};
+use radicle::identity::{Doc, RepoId, Validators, Visibility};
 use radicle::node::events::UploadPack;
 use radicle::node::policy::Scope;
 use radicle::node::{Event, Handle, NodeId, DEFAULT_SUBSCRIBE_TIMEOUT};
-use radicle::storage::ReadStorage as _;
+use radicle::storage::{ReadRepository as _, ReadStorage as _};
 use radicle::{profile, Node};
 
 use crate::commands;
 use crate::git;
 use crate::terminal as term;
-use crate::terminal::args::{Args, Error, Help};
+use crate::terminal::args::{Arg, Args, Error, Help, Kind};
 use crate::terminal::Interactive;
 
 pub const HELP: Help = Help {
@@ -49,9 +49,27 @@ Options
         --setup-signing            Setup the radicle key as a signing key for this repository
         --no-confirm               Don't ask for confirmation during setup
         --no-seed                  Don't seed this repository after initializing it
+        --strict                   Warn about payloads that can't be validated
     -v, --verbose                  Verbose mode
         --help                     Print help
 "#,
+    options: &[
+        Arg::Positional(Kind::Path),
+        Arg::Opt("name", None, Kind::Any),
+        Arg::Opt("description", None, Kind::Any),
+        Arg::Opt("default-branch", None, Kind::Any),
+        Arg::Opt("scope", None, Kind::OneOf(&["followed", "all"])),
+        Arg::Flag("private", None),
+        Arg::Flag("public", None),
+        Arg::Opt("existing", None, Kind::Rid),
+        Arg::Flag("set-upstream", Some('u')),
+        Arg::Flag("setup-signing", None),
+        Arg::Flag("no-confirm", None),
+        Arg::Flag("no-seed", None),
+        Arg::Flag("strict", None),
+        Arg::Flag("verbose", Some('v')),
+        Arg::Flag("help", Some('h')),
+    ],
 };
 
 #[derive(Default)]
@@ -68,6 +86,7 @@ pub struct Options {
     pub set_upstream: bool,
     pub verbose: bool,
     pub seed: bool,
+    pub strict: bool,
 }
 
 impl Args for Options {
@@ -86,6 +105,7 @@ impl Args for Options {
         let mut scope = Scope::All;
         let mut existing = None;
         let mut seed = true;
+        let mut strict = false;
         let mut verbose = false;
         let mut visibility = None;
 
@@ -137,6 +157,9 @@ impl Args for Options {
                 Long("no-seed") => {
                     seed = false;
                 }
+                Long("strict") => {
+                    strict = true;
+                }
                 Long("private") => {
                     visibility = Some(Visibility::private([]));
                 }
@@ -174,6 +197,7 @@ impl Args for Options {
                 set_upstream,
                 setup_signing,
                 seed,
+                strict,
                 visibility,
                 verbose,
             },
@@ -293,6 +317,9 @@ pub fn init(
             ));
             spinner.finish();
 
+            if options.strict {
+                warn_unknown_payloads(&doc);
+            }
             if options.verbose {
                 term::blob(json::to_string_pretty(&proj)?);
             }
@@ -368,6 +395,10 @@ pub fn init_existing(
 ) -> anyhow::Result<()> {
     let stored = profile.storage.repository(rid)?;
     let project = stored.project()?;
+
+    if options.strict {
+        warn_unknown_payloads(&stored.identity_doc()?.doc);
+    }
     let url = radicle::git::Url::from(rid);
 
     radicle::git::configure_repository(&working)?;
@@ -402,6 +433,17 @@ pub fn init_existing(
     Ok(())
 }
 
+/// Warn about payloads in the identity document that have no validator.
+fn warn_unknown_payloads(doc: &Doc) {
+    let validators = Validators::default();
+
+    for id in doc.payload().keys() {
+        if !validators.is_known(id) {
+            term::warning(format!("payload `{id}` is not known and was not validated"));
+        }
+    }
+}
+
 #[derive(Debug)]
 enum SyncResult<T> {
     NodeStopped,
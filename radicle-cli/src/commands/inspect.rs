@@ -7,16 +7,19 @@ use std::str::FromStr;
 use anyhow::{anyhow, Context as _};
 use chrono::prelude::*;
 
+use radicle::cob;
+use radicle::git::canonical::Position;
+use radicle::git::RefString;
 use radicle::identity::RepoId;
 use radicle::identity::{DocAt, Identity};
 use radicle::node::policy::SeedingPolicy;
-use radicle::node::AliasStore as _;
+use radicle::node::{AliasStore as _, NodeId};
 use radicle::storage::git::{Repository, Storage};
-use radicle::storage::refs::RefsAt;
+use radicle::storage::refs::{RefsAt, SignedRefsAt};
 use radicle::storage::{ReadRepository, ReadStorage};
 
 use crate::terminal as term;
-use crate::terminal::args::{Args, Error, Help};
+use crate::terminal::args::{Arg, Args, Error, Help, Kind};
 use crate::terminal::json;
 use crate::terminal::Element;
 
@@ -40,13 +43,36 @@ Options
     --payload    Inspect the repository's identity payload
     --refs       Inspect the repository's refs on the local device
     --sigrefs    Inspect the values of `rad/sigrefs` for all remotes of this repository
+    --sigref     Inspect the refs signed by a remote, eg. `--sigref <nid>` (default: local key)
     --identity   Inspect the identity document
     --visibility Inspect the repository's visibility
     --delegates  Inspect the repository's delegates
+    --canonical  Inspect the canonical tip of a branch, eg. `--canonical master`,
+                 and the position of each delegate's tip relative to it
     --policy     Inspect the repository's seeding policy
     --history    Show the history of the repository identity document
+    --patches    Inspect the raw refs of the repository's patches, with their tips
+    --issues     Inspect the raw refs of the repository's issues, with their tips
     --help       Print help
 "#,
+    options: &[
+        Arg::Positional(Kind::Rid),
+        Arg::Positional(Kind::Path),
+        Arg::Flag("rid", None),
+        Arg::Flag("payload", None),
+        Arg::Flag("refs", None),
+        Arg::Flag("sigrefs", None),
+        Arg::Flag("sigref", None),
+        Arg::Flag("identity", None),
+        Arg::Flag("visibility", None),
+        Arg::Flag("delegates", None),
+        Arg::Flag("policy", None),
+        Arg::Flag("history", None),
+        Arg::Flag("patches", None),
+        Arg::Flag("issues", None),
+        Arg::Opt("canonical", None, Kind::Any),
+        Arg::Flag("help", Some('h')),
+    ],
 };
 
 #[derive(Default, Debug, Eq, PartialEq)]
@@ -54,11 +80,15 @@ pub enum Target {
     Refs,
     Payload,
     Delegates,
+    Canonical(RefString),
     Identity,
     Visibility,
     Sigrefs,
+    Sigref(Option<NodeId>),
     Policy,
     History,
+    Patches,
+    Issues,
     #[default]
     RepoId,
 }
@@ -94,21 +124,43 @@ impl Args for Options {
                 Long("delegates") => {
                     target = Target::Delegates;
                 }
+                Long("canonical") => {
+                    let val = parser.value()?;
+                    target = Target::Canonical(term::args::refstring("canonical", val)?);
+                }
                 Long("history") => {
                     target = Target::History;
                 }
                 Long("identity") => {
                     target = Target::Identity;
                 }
+                Long("patches") => {
+                    target = Target::Patches;
+                }
+                Long("issues") => {
+                    target = Target::Issues;
+                }
                 Long("sigrefs") => {
                     target = Target::Sigrefs;
                 }
+                Long("sigref") => {
+                    let remote = parser
+                        .optional_value()
+                        .map(|v| term::args::pubkey(&v))
+                        .transpose()?;
+                    target = Target::Sigref(remote);
+                }
                 Long("rid") => {
                     target = Target::RepoId;
                 }
                 Long("visibility") => {
                     target = Target::Visibility;
                 }
+                Value(val)
+                    if target == Target::Sigref(None) && term::args::pubkey(&val).is_ok() =>
+                {
+                    target = Target::Sigref(Some(term::args::pubkey(&val)?));
+                }
                 Value(val) if rid.is_none() => {
                     let val = val.to_string_lossy();
 
@@ -171,6 +223,22 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
                 );
             }
         }
+        Target::Sigref(remote) => {
+            let (repo, _) = repo(rid, storage)?;
+            let remote = remote.unwrap_or(*profile.id());
+            let Some(sigrefs) = SignedRefsAt::load(remote, &repo)? else {
+                anyhow::bail!("no signed refs found for remote {remote} in {rid}");
+            };
+            json::to_pretty(&sigrefs.refs, Path::new("sigrefs.json"))?.print();
+        }
+        Target::Patches => {
+            let (repo, _) = repo(rid, storage)?;
+            cobs(&repo, &cob::patch::TYPENAME)?;
+        }
+        Target::Issues => {
+            let (repo, _) = repo(rid, storage)?;
+            cobs(&repo, &cob::issue::TYPENAME)?;
+        }
         Target::Policy => {
             let policies = profile.policies()?;
             let seed = policies.seed_policy(&rid)?;
@@ -207,6 +275,33 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
                 }
             }
         }
+        Target::Canonical(branch) => {
+            let (repo, _) = repo(rid, storage)?;
+            let aliases = profile.aliases();
+            let canonical = repo.canonical_branch(&branch)?;
+
+            println!(
+                "{} {}",
+                term::format::secondary(canonical.head),
+                term::format::tertiary(&canonical.name)
+            );
+            for (did, position) in canonical.positions {
+                let position = match position {
+                    Position::Even => term::format::positive(position),
+                    Position::Diverged { .. } => term::format::negative(position),
+                    _ => term::format::yellow(position),
+                };
+                if let Some(alias) = aliases.alias(&did) {
+                    println!(
+                        "{} {} {position}",
+                        term::format::tertiary(&did),
+                        term::format::parens(term::format::dim(alias)),
+                    );
+                } else {
+                    println!("{} {position}", term::format::tertiary(&did));
+                }
+            }
+        }
         Target::Visibility => {
             let (_, doc) = repo(rid, storage)?;
             println!("{}", term::format::visibility(doc.visibility()));
@@ -302,6 +397,32 @@ fn refs(repo: &radicle::storage::git::Repository) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Print the raw refs of all the COBs of the given type, across all remotes, along with
+/// their tips.
+fn cobs(repo: &Repository, typename: &cob::TypeName) -> anyhow::Result<()> {
+    let prefix = format!("refs/cobs/{typename}/");
+    let mut refs = Vec::new();
+
+    for r in repo.references()? {
+        let r = r?;
+        if let Some(namespace) = r.namespace {
+            if r.name.as_str().starts_with(&prefix) {
+                refs.push((format!("{}/{}", namespace, r.name), r.oid));
+            }
+        }
+    }
+    refs.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    for (name, oid) in refs {
+        println!(
+            "{} {}",
+            term::format::tertiary(name),
+            term::format::secondary(oid)
+        );
+    }
+    Ok(())
+}
+
 /// Show the list of given git references as a newline terminated tree `String` similar to the tree command.
 fn tree(mut refs: Vec<String>) -> String {
     refs.sort();
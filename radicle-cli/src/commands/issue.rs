@@ -1,7 +1,7 @@
 #[path = "issue/cache.rs"]
 mod cache;
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::ffi::OsString;
 use std::str::FromStr;
 
@@ -11,8 +11,9 @@ use radicle::cob::common::{Label, Reaction};
 use radicle::cob::issue::{CloseReason, State};
 use radicle::cob::{issue, thread};
 use radicle::crypto::Signer;
+use radicle::identity::alias;
 use radicle::issue::cache::Issues as _;
-use radicle::prelude::{Did, RepoId};
+use radicle::prelude::{Did, NodeId, RepoId};
 use radicle::profile;
 use radicle::storage;
 use radicle::storage::{ReadRepository, WriteRepository, WriteStorage};
@@ -22,7 +23,7 @@ use radicle::{cob, Node};
 use crate::git::Rev;
 use crate::node;
 use crate::terminal as term;
-use crate::terminal::args::{Args, Error, Help};
+use crate::terminal::args::{Arg, Args, Error, Help, Kind};
 use crate::terminal::format::Author;
 use crate::terminal::issue::Format;
 use crate::terminal::patch::Message;
@@ -38,14 +39,19 @@ Usage
     rad issue [<option>...]
     rad issue delete <issue-id> [<option>...]
     rad issue edit <issue-id> [<option>...]
-    rad issue list [--assigned <did>] [--all | --closed | --open | --solved] [<option>...]
-    rad issue open [--title <title>] [--description <text>] [--label <label>] [<option>...]
+    rad issue list [--assigned <did>] [--milestone <name>] [--overdue] [--sort <field>[:<order>]]
+                   [--all | --closed | --open | --solved] [<option>...]
+    rad issue open [--title <title>] [--description <text>] [--label <label>]
+                   [--due <date>] [--milestone <name>] [<option>...]
     rad issue react <issue-id> [--emoji <char>] [--to <comment>] [<option>...]
     rad issue assign <issue-id> [--add <did>] [--delete <did>] [<option>...]
     rad issue label <issue-id> [--add <label>] [--delete <label>] [<option>...]
     rad issue comment <issue-id> [--message <message>] [--reply-to <comment-id>] [<option>...]
-    rad issue show <issue-id> [<option>...]
+    rad issue show <issue-id> [--format <format>] [<option>...]
     rad issue state <issue-id> [--closed | --open | --solved] [<option>...]
+    rad issue close <issue-id> [--reason <reason>] [--duplicate-of <issue-id>] [<option>...]
+    rad issue set-due <issue-id> (<date> | --clear) [<option>...]
+    rad issue set-milestone <issue-id> (<name> | --clear) [<option>...]
     rad issue cache [<issue-id>] [--storage] [<option>...]
 
 Assign options
@@ -62,18 +68,95 @@ Label options
 
     Note: --add takes precedence over --delete
 
+Open options
+
+        --due <date>           Set the date the issue is due, as YYYY-MM-DD
+        --milestone <name>     Set the milestone the issue is part of
+
+List options
+
+    -a, --assigned [<did>]     Only show issues assigned to the given DID (default: you)
+        --assignee <did>       Only show issues assigned to the given DID
+        --milestone <name>     Only show issues that are part of the given milestone
+        --overdue              Only show open issues that are past their due date
+        --sort <field>[:<order>]
+                               Sort issues by `created`, `updated` or `title`, in `asc`
+                               or `desc` order (default: created:desc)
+
+    Note: filters can be combined, and only issues matching all of them are shown
+
+Close options
+
+        --reason <reason>      Reason for closing the issue: `solved` or `other` (default: other)
+        --duplicate-of <issue-id>
+                               Close the issue as a duplicate of the given issue
+
+    Note: issues are also closed as solved when a patch is merged with commits that
+    carry a `Closes: <issue-id>` or `Fixes: <issue-id>` trailer
+
+Set-due and set-milestone options
+
+        --clear                Clear the due date or milestone
+
 Show options
 
+        --format <format>      Output format: `full`, `header` or `json` (default: full)
         --debug                Show the issue as Rust debug output
 
 Options
 
         --repo <rid>       Operate on the given repository (default: cwd)
         --no-announce      Don't announce issue to peers
-        --header           Show only the issue header, hiding the comments
     -q, --quiet            Don't print anything
         --help             Print help
 "#,
+    options: &[
+        Arg::Command("delete"),
+        Arg::Command("edit"),
+        Arg::Command("list"),
+        Arg::Command("open"),
+        Arg::Command("react"),
+        Arg::Command("assign"),
+        Arg::Command("label"),
+        Arg::Command("comment"),
+        Arg::Command("show"),
+        Arg::Command("state"),
+        Arg::Command("close"),
+        Arg::Command("set-due"),
+        Arg::Command("set-milestone"),
+        Arg::Command("cache"),
+        Arg::Positional(Kind::Issue),
+        Arg::Opt("add", Some('a'), Kind::Any),
+        Arg::Opt("delete", Some('d'), Kind::Any),
+        Arg::Opt("due", None, Kind::Any),
+        Arg::Opt("milestone", None, Kind::Any),
+        Arg::Opt("assigned", Some('a'), Kind::Any),
+        Arg::Opt("assignee", None, Kind::Any),
+        Arg::Flag("overdue", None),
+        Arg::Opt("sort", None, Kind::Any),
+        Arg::Flag("all", None),
+        Arg::Flag("closed", None),
+        Arg::Flag("open", None),
+        Arg::Flag("solved", None),
+        Arg::Opt("reason", None, Kind::OneOf(&["solved", "other"])),
+        Arg::Opt("duplicate-of", None, Kind::Issue),
+        Arg::Opt("title", None, Kind::Any),
+        Arg::Opt("description", None, Kind::Any),
+        Arg::Opt("label", Some('l'), Kind::Any),
+        Arg::Opt("assign", None, Kind::Any),
+        Arg::Opt("emoji", None, Kind::Any),
+        Arg::Opt("to", None, Kind::Any),
+        Arg::Opt("message", Some('m'), Kind::Any),
+        Arg::Opt("reply-to", None, Kind::Any),
+        Arg::Flag("clear", None),
+        Arg::Opt("format", None, Kind::OneOf(&["full", "header", "json"])),
+        Arg::Flag("debug", None),
+        Arg::Flag("storage", None),
+        Arg::Opt("repo", None, Kind::Rid),
+        Arg::Flag("no-announce", None),
+        Arg::Flag("quiet", Some('q')),
+        Arg::Flag("help", Some('h')),
+    ],
 };
 
 #[derive(Default, Debug, PartialEq, Eq)]
@@ -89,6 +172,9 @@ pub enum OperationName {
     React,
     Show,
     State,
+    Close,
+    SetDue,
+    SetMilestone,
     Cache,
 }
 
@@ -100,6 +186,56 @@ pub enum Assigned {
     Peer(Did),
 }
 
+/// Field to sort listed issues by.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortBy {
+    #[default]
+    Created,
+    Updated,
+    Title,
+}
+
+/// Order to list issues in.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+/// How to sort listed issues, eg. `updated:desc`.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sort {
+    pub by: SortBy,
+    pub order: SortOrder,
+}
+
+impl FromStr for Sort {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (by, order) = match s.split_once(':') {
+            Some((by, order)) => (by, Some(order)),
+            None => (s, None),
+        };
+        let by = match by {
+            "created" => SortBy::Created,
+            "updated" => SortBy::Updated,
+            "title" => SortBy::Title,
+            _ => anyhow::bail!("unknown sort field '{by}'"),
+        };
+        let order = match order {
+            Some("asc") => SortOrder::Asc,
+            Some("desc") => SortOrder::Desc,
+            Some(order) => anyhow::bail!("unknown sort order '{order}'"),
+            // Titles read best alphabetically, times most recent first.
+            None if by == SortBy::Title => SortOrder::Asc,
+            None => SortOrder::Desc,
+        };
+        Ok(Self { by, order })
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum Operation {
     Edit {
@@ -112,6 +248,8 @@ pub enum Operation {
         description: Option<String>,
         labels: Vec<Label>,
         assignees: Vec<Did>,
+        due: Option<cob::Timestamp>,
+        milestone: Option<String>,
     },
     Show {
         id: Rev,
@@ -127,6 +265,19 @@ pub enum Operation {
         id: Rev,
         state: State,
     },
+    Close {
+        id: Rev,
+        reason: CloseReason,
+        duplicate_of: Option<Rev>,
+    },
+    SetDue {
+        id: Rev,
+        due: Option<cob::Timestamp>,
+    },
+    SetMilestone {
+        id: Rev,
+        milestone: Option<String>,
+    },
     Delete {
         id: Rev,
     },
@@ -146,6 +297,9 @@ pub enum Operation {
     List {
         assigned: Option<Assigned>,
         state: Option<State>,
+        milestone: Option<String>,
+        overdue: bool,
+        sort: Sort,
     },
     Cache {
         id: Option<Rev>,
@@ -198,6 +352,13 @@ impl Args for Options {
         let mut label_opts = LabelOptions::default();
         let mut repo = None;
         let mut cache_storage = false;
+        let mut due = None;
+        let mut milestone = None;
+        let mut overdue = false;
+        let mut sort = Sort::default();
+        let mut clear = false;
+        let mut reason = CloseReason::Other;
+        let mut duplicate_of = None;
 
         while let Some(arg) = parser.next()? {
             match arg {
@@ -222,6 +383,22 @@ impl Args for Options {
                         reason: CloseReason::Solved,
                     });
                 }
+                Long("overdue") if op.is_none() || op == Some(OperationName::List) => {
+                    overdue = true;
+                }
+                Long("sort") if op.is_none() || op == Some(OperationName::List) => {
+                    let val = parser.value()?;
+                    sort = term::args::string(&val).parse()?;
+                }
+                Long("milestone")
+                    if matches!(
+                        op,
+                        None | Some(OperationName::List) | Some(OperationName::Open)
+                    ) =>
+                {
+                    let val = parser.value()?;
+                    milestone = Some(term::args::string(&val));
+                }
 
                 // Open options.
                 Long("title") if op == Some(OperationName::Open) => {
@@ -243,6 +420,20 @@ impl Args for Options {
                 Long("description") if op == Some(OperationName::Open) => {
                     description = Some(parser.value()?.to_string_lossy().into());
                 }
+                Long("due") if op == Some(OperationName::Open) => {
+                    let val = parser.value()?;
+                    due = Some(term::args::date(&val)?);
+                }
+
+                // Set-due and set-milestone options.
+                Long("clear")
+                    if matches!(
+                        op,
+                        Some(OperationName::SetDue) | Some(OperationName::SetMilestone)
+                    ) =>
+                {
+                    clear = true;
+                }
 
                 // State options.
                 Long("closed") if op == Some(OperationName::State) => {
@@ -259,6 +450,23 @@ impl Args for Options {
                     });
                 }
 
+                // Close options.
+                Long("reason") if op == Some(OperationName::Close) => {
+                    let val = parser.value()?;
+
+                    reason = match term::args::string(&val).as_str() {
+                        "solved" => CloseReason::Solved,
+                        "other" => CloseReason::Other,
+                        other => anyhow::bail!(
+                            "invalid close reason '{other}', expected 'solved' or 'other'"
+                        ),
+                    };
+                }
+                Long("duplicate-of") if op == Some(OperationName::Close) => {
+                    let val = parser.value()?;
+                    duplicate_of = Some(term::args::rev(&val)?);
+                }
+
                 // React options.
                 Long("emoji") if op == Some(OperationName::React) => {
                     if let Some(emoji) = parser.value()?.to_str() {
@@ -279,6 +487,7 @@ impl Args for Options {
                     match val.as_str() {
                         "header" => format = Format::Header,
                         "full" => format = Format::Full,
+                        "json" => format = Format::Json,
                         _ => anyhow::bail!("unknown format '{val}'"),
                     }
                 }
@@ -317,6 +526,11 @@ impl Args for Options {
                         assigned = Some(Assigned::Me);
                     }
                 }
+                Long("assignee") if assigned.is_none() => {
+                    let val = parser.value()?;
+                    let peer = term::args::did(&val)?;
+                    assigned = Some(Assigned::Peer(peer));
+                }
 
                 // Label options
                 Short('a') | Long("add") if matches!(op, Some(OperationName::Label)) => {
@@ -362,12 +576,21 @@ impl Args for Options {
                     "o" | "open" => op = Some(OperationName::Open),
                     "r" | "react" => op = Some(OperationName::React),
                     "s" | "state" => op = Some(OperationName::State),
+                    "close" => op = Some(OperationName::Close),
                     "assign" => op = Some(OperationName::Assign),
                     "label" => op = Some(OperationName::Label),
                     "cache" => op = Some(OperationName::Cache),
+                    "set-due" => op = Some(OperationName::SetDue),
+                    "set-milestone" => op = Some(OperationName::SetMilestone),
 
                     unknown => anyhow::bail!("unknown operation '{}'", unknown),
                 },
+                Value(val) if op == Some(OperationName::SetDue) && id.is_some() => {
+                    due = Some(term::args::date(&val)?);
+                }
+                Value(val) if op == Some(OperationName::SetMilestone) && id.is_some() => {
+                    milestone = Some(term::args::string(&val));
+                }
                 Value(val) if op.is_some() => {
                     let val = term::args::rev(&val)?;
                     id = Some(val);
@@ -389,6 +612,8 @@ impl Args for Options {
                 description,
                 labels,
                 assignees,
+                due,
+                milestone,
             },
             OperationName::Comment => Operation::Comment {
                 id: id.ok_or_else(|| anyhow!("an issue must be provided"))?,
@@ -404,6 +629,11 @@ impl Args for Options {
                 id: id.ok_or_else(|| anyhow!("an issue must be provided"))?,
                 state: state.ok_or_else(|| anyhow!("a state operation must be provided"))?,
             },
+            OperationName::Close => Operation::Close {
+                id: id.ok_or_else(|| anyhow!("an issue must be provided"))?,
+                reason,
+                duplicate_of,
+            },
             OperationName::React => Operation::React {
                 id: id.ok_or_else(|| anyhow!("an issue must be provided"))?,
                 reaction: reaction.ok_or_else(|| anyhow!("a reaction emoji must be provided"))?,
@@ -420,7 +650,29 @@ impl Args for Options {
                 id: id.ok_or_else(|| anyhow!("an issue to label must be provided"))?,
                 opts: label_opts,
             },
-            OperationName::List => Operation::List { assigned, state },
+            OperationName::SetDue => Operation::SetDue {
+                id: id.ok_or_else(|| anyhow!("an issue must be provided"))?,
+                due: match (due, clear) {
+                    (Some(_), true) => anyhow::bail!("a due date cannot be set and cleared"),
+                    (None, false) => anyhow::bail!("a due date or `--clear` must be provided"),
+                    (due, _) => due,
+                },
+            },
+            OperationName::SetMilestone => Operation::SetMilestone {
+                id: id.ok_or_else(|| anyhow!("an issue must be provided"))?,
+                milestone: match (milestone, clear) {
+                    (Some(_), true) => anyhow::bail!("a milestone cannot be set and cleared"),
+                    (None, false) => anyhow::bail!("a milestone or `--clear` must be provided"),
+                    (milestone, _) => milestone,
+                },
+            },
+            OperationName::List => Operation::List {
+                assigned,
+                state,
+                milestone,
+                overdue,
+                sort,
+            },
             OperationName::Cache => Operation::Cache {
                 id,
                 storage: cache_storage,
@@ -453,6 +705,9 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
             Operation::Open { .. }
                 | Operation::React { .. }
                 | Operation::State { .. }
+                | Operation::Close { .. }
+                | Operation::SetDue { .. }
+                | Operation::SetMilestone { .. }
                 | Operation::Delete { .. }
                 | Operation::Assign { .. }
                 | Operation::Label { .. }
@@ -473,18 +728,6 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
                 term::issue::show(&issue, issue.id(), Format::Header, &profile)?;
             }
         }
-        Operation::Open {
-            title: Some(title),
-            description: Some(description),
-            labels,
-            assignees,
-        } => {
-            let signer = term::signer(&profile)?;
-            let issue = issues.create(title, description, &labels, &assignees, [], &signer)?;
-            if !options.quiet {
-                term::issue::show(&issue, issue.id(), Format::Header, &profile)?;
-            }
-        }
         Operation::Comment {
             id,
             message,
@@ -524,6 +767,41 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
             let mut issue = issues.get_mut(&id)?;
             issue.lifecycle(state, &signer)?;
         }
+        Operation::Close {
+            id,
+            reason,
+            duplicate_of,
+        } => {
+            let signer = term::signer(&profile)?;
+            let id = id.resolve(&repo.backend)?;
+            let duplicate_of = match duplicate_of {
+                Some(rev) => {
+                    let original = rev.resolve(&repo.backend)?;
+                    if original == id {
+                        anyhow::bail!("an issue cannot be a duplicate of itself");
+                    }
+                    if issues.get(&original)?.is_none() {
+                        anyhow::bail!("issue {original} not found");
+                    }
+                    Some(original)
+                }
+                None => None,
+            };
+            let mut issue = issues.get_mut(&id)?;
+            issue.close(reason, duplicate_of, &signer)?;
+        }
+        Operation::SetDue { id, due } => {
+            let signer = term::signer(&profile)?;
+            let id = id.resolve(&repo.backend)?;
+            let mut issue = issues.get_mut(&id)?;
+            issue.set_due(due, &signer)?;
+        }
+        Operation::SetMilestone { id, milestone } => {
+            let signer = term::signer(&profile)?;
+            let id = id.resolve(&repo.backend)?;
+            let mut issue = issues.get_mut(&id)?;
+            issue.set_milestone(milestone, &signer)?;
+        }
         Operation::React {
             id,
             reaction,
@@ -544,6 +822,8 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
             ref description,
             ref labels,
             ref assignees,
+            due,
+            ref milestone,
         } => {
             let signer = term::signer(&profile)?;
             open(
@@ -551,6 +831,8 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
                 description.clone(),
                 labels.to_vec(),
                 assignees.to_vec(),
+                due,
+                milestone.clone(),
                 &options,
                 &mut issues,
                 &signer,
@@ -591,8 +873,25 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
                 .collect::<Vec<_>>();
             issue.label(labels, &signer)?;
         }
-        Operation::List { assigned, state } => {
-            list(issues, &assigned, &state, &profile)?;
+        Operation::List {
+            assigned,
+            state,
+            milestone,
+            overdue,
+            sort,
+        } => {
+            let aliases = alias::aliases(&repo)?;
+
+            list(
+                issues,
+                &assigned,
+                &state,
+                milestone.as_deref(),
+                overdue,
+                sort,
+                &aliases,
+                &profile,
+            )?;
         }
         Operation::Delete { id } => {
             let signer = term::signer(&profile)?;
@@ -633,6 +932,10 @@ fn list<C>(
     cache: C,
     assigned: &Option<Assigned>,
     state: &Option<State>,
+    milestone: Option<&str>,
+    overdue: bool,
+    sort: Sort,
+    aliases: &BTreeMap<NodeId, alias::Alias>,
     profile: &profile::Profile,
 ) -> anyhow::Result<()>
 where
@@ -649,6 +952,7 @@ where
         None => None,
     };
 
+    let now = cob::Timestamp::from(profile::env::local_time());
     let mut all = Vec::new();
     let issues = cache.list()?;
     for result in issues {
@@ -671,14 +975,28 @@ where
                 continue;
             }
         }
+        if milestone.is_some() && issue.milestone() != milestone {
+            continue;
+        }
+        if overdue && !issue.is_overdue(now) {
+            continue;
+        }
         all.push((id, issue))
     }
 
     all.sort_by(|(id1, i1), (id2, i2)| {
-        let by_timestamp = i2.timestamp().cmp(&i1.timestamp());
+        let by_field = match sort.by {
+            SortBy::Created => i1.created_at().cmp(&i2.created_at()),
+            SortBy::Updated => i1.updated_at().cmp(&i2.updated_at()),
+            SortBy::Title => i1.title().cmp(i2.title()),
+        };
+        let by_field = match sort.order {
+            SortOrder::Asc => by_field,
+            SortOrder::Desc => by_field.reverse(),
+        };
         let by_id = id1.cmp(id2);
 
-        by_timestamp.then(by_id)
+        by_field.then(by_id)
     });
 
     let mut table = term::Table::new(term::table::TableOptions::bordered());
@@ -698,7 +1016,9 @@ where
         let assigned: String = issue
             .assignees()
             .map(|did| {
-                let (alias, _) = Author::new(did.as_key(), profile).labels();
+                let (alias, _) = Author::new(did.as_key(), profile)
+                    .or_repo_alias(aliases)
+                    .labels();
 
                 alias.content().to_owned()
             })
@@ -709,7 +1029,9 @@ where
         labels.sort();
 
         let author = issue.author().id;
-        let (alias, did) = Author::new(&author, profile).labels();
+        let (alias, did) = Author::new(&author, profile)
+            .or_repo_alias(aliases)
+            .labels();
 
         table.push([
             match issue.state() {
@@ -744,6 +1066,8 @@ fn open<R, G>(
     description: Option<String>,
     labels: Vec<Label>,
     assignees: Vec<Did>,
+    due: Option<cob::Timestamp>,
+    milestone: Option<String>,
     options: &Options,
     cache: &mut issue::Cache<issue::Issues<'_, R>, cob::cache::StoreWriter>,
     signer: &G,
@@ -760,7 +1084,7 @@ where
     } else {
         anyhow::bail!("aborting issue creation due to empty title or description");
     };
-    let issue = cache.create(
+    let mut issue = cache.create(
         &title,
         description,
         labels.as_slice(),
@@ -768,6 +1092,12 @@ where
         [],
         signer,
     )?;
+    if due.is_some() {
+        issue.set_due(due, signer)?;
+    }
+    if milestone.is_some() {
+        issue.set_milestone(milestone, signer)?;
+    }
 
     if !options.quiet {
         term::issue::show(&issue, issue.id(), Format::Header, profile)?;
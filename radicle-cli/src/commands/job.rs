@@ -11,7 +11,7 @@ use radicle::{cob, Node};
 
 use crate::git::Rev;
 use crate::terminal as term;
-use crate::terminal::args::{Args, Error, Help};
+use crate::terminal::args::{Arg, Args, Error, Help};
 use crate::terminal::Element;
 
 pub const HELP: Help = Help {
@@ -35,6 +35,19 @@ Options
     --quiet, -q       Don't print anything
     --help            Print help
 "#,
+    options: &[
+        Arg::Command("trigger"),
+        Arg::Command("start"),
+        Arg::Command("list"),
+        Arg::Command("show"),
+        Arg::Command("finish"),
+        Arg::Command("delete"),
+        Arg::Flag("success", Some('s')),
+        Arg::Flag("failed", Some('f')),
+        Arg::Flag("no-announce", None),
+        Arg::Flag("quiet", Some('q')),
+        Arg::Flag("help", Some('h')),
+    ],
 };
 
 #[derive(Default, Debug, PartialEq, Eq)]
@@ -0,0 +1,348 @@
+use std::ffi::OsString;
+
+use anyhow::anyhow;
+
+use radicle::cob;
+use radicle::cob::common::Label;
+use radicle::issue::cache::Issues as _;
+use radicle::patch::cache::Patches as _;
+use radicle::prelude::RepoId;
+use radicle::storage::git::Repository;
+use radicle::storage::WriteStorage as _;
+use radicle::{Node, Profile};
+
+use crate::node;
+use crate::terminal as term;
+use crate::terminal::args::{Arg, Args, Error, Help};
+
+pub const HELP: Help = Help {
+    name: "label",
+    description: "Manage labels across issues and patches",
+    version: env!("RADICLE_VERSION"),
+    usage: r#"
+Usage
+
+    rad label rename <old> <new> [<option>...]
+    rad label delete <label> [<option>...]
+
+    Rename or delete a label on every issue and patch of a repository that
+    carries it. Objects that fail to update are skipped, and reported once
+    all other objects were updated.
+
+Options
+
+    --repo <rid>            Operate on the given repository (default: cwd)
+    --type <issue|patch>    Only update issues, or only patches
+    --dry-run               List the objects that would be updated, without
+                            updating them
+    --no-announce           Don't announce changes to the network
+    --help                  Print help
+"#,
+    options: &[
+        Arg::Command("rename"),
+        Arg::Command("delete"),
+        Arg::Opt("repo", None, term::args::Kind::Rid),
+        Arg::Opt("type", None, term::args::Kind::OneOf(&["issue", "patch"])),
+        Arg::Flag("dry-run", None),
+        Arg::Flag("no-announce", None),
+        Arg::Flag("help", Some('h')),
+    ],
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Issue,
+    Patch,
+}
+
+impl std::fmt::Display for Kind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Issue => write!(f, "issue"),
+            Self::Patch => write!(f, "patch"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Operation {
+    Rename { from: Label, to: Label },
+    Delete { label: Label },
+}
+
+impl Operation {
+    /// The label being renamed or deleted.
+    fn label(&self) -> &Label {
+        match self {
+            Self::Rename { from, .. } => from,
+            Self::Delete { label } => label,
+        }
+    }
+
+    /// Apply the operation to a set of labels.
+    fn apply<'a>(&self, labels: impl Iterator<Item = &'a Label>) -> Vec<Label> {
+        match self {
+            Self::Rename { from, to } => labels
+                .map(|l| if l == from { to } else { l })
+                .cloned()
+                .collect(),
+            Self::Delete { label } => labels.filter(|l| *l != label).cloned().collect(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum OperationName {
+    Rename,
+    Delete,
+}
+
+#[derive(Debug)]
+pub struct Options {
+    pub op: Operation,
+    pub repo: Option<RepoId>,
+    pub kind: Option<Kind>,
+    pub dry_run: bool,
+    pub announce: bool,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut op: Option<OperationName> = None;
+        let mut labels: Vec<Label> = Vec::new();
+        let mut repo = None;
+        let mut kind = None;
+        let mut dry_run = false;
+        let mut announce = true;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("help") | Short('h') => {
+                    return Err(Error::Help.into());
+                }
+                Long("repo") => {
+                    let val = parser.value()?;
+                    let rid = term::args::rid(&val)?;
+
+                    repo = Some(rid);
+                }
+                Long("type") => {
+                    let val = parser.value()?;
+
+                    kind = match val.to_string_lossy().as_ref() {
+                        "issue" => Some(Kind::Issue),
+                        "patch" => Some(Kind::Patch),
+                        other => anyhow::bail!(
+                            "invalid object type '{other}', expected 'issue' or 'patch'"
+                        ),
+                    };
+                }
+                Long("dry-run") => {
+                    dry_run = true;
+                }
+                Long("no-announce") => {
+                    announce = false;
+                }
+                Value(val) if op.is_none() => match val.to_string_lossy().as_ref() {
+                    "rename" => op = Some(OperationName::Rename),
+                    "delete" => op = Some(OperationName::Delete),
+
+                    unknown => anyhow::bail!("unknown operation '{}'", unknown),
+                },
+                Value(val) => {
+                    let name = term::args::string(&val);
+                    let label = Label::new(name)?;
+
+                    labels.push(label);
+                }
+                _ => return Err(anyhow!(arg.unexpected())),
+            }
+        }
+
+        let mut labels = labels.into_iter();
+        let op = match op {
+            Some(OperationName::Rename) => {
+                let from = labels
+                    .next()
+                    .ok_or_else(|| anyhow!("a label to rename must be provided"))?;
+                let to = labels
+                    .next()
+                    .ok_or_else(|| anyhow!("a new label name must be provided"))?;
+
+                Operation::Rename { from, to }
+            }
+            Some(OperationName::Delete) => Operation::Delete {
+                label: labels
+                    .next()
+                    .ok_or_else(|| anyhow!("a label to delete must be provided"))?,
+            },
+            None => anyhow::bail!("an operation must be provided, see `rad label --help`"),
+        };
+        if let Some(label) = labels.next() {
+            anyhow::bail!("unexpected argument '{label}'");
+        }
+
+        Ok((
+            Options {
+                op,
+                repo,
+                kind,
+                dry_run,
+                announce,
+            },
+            vec![],
+        ))
+    }
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let profile = ctx.profile()?;
+    let rid = if let Some(rid) = options.repo {
+        rid
+    } else {
+        radicle::rad::cwd().map(|(_, rid)| rid)?
+    };
+    let repo = profile.storage.repository_mut(rid)?;
+    let label = options.op.label();
+
+    let mut targets = Vec::new();
+    if options.kind != Some(Kind::Patch) {
+        targets.extend(
+            issues(&profile, &repo, label)?
+                .into_iter()
+                .map(|id| (Kind::Issue, id)),
+        );
+    }
+    if options.kind != Some(Kind::Issue) {
+        targets.extend(
+            patches(&profile, &repo, label)?
+                .into_iter()
+                .map(|id| (Kind::Patch, id)),
+        );
+    }
+
+    if options.dry_run {
+        for (kind, id) in &targets {
+            term::info!("{kind} {}", term::format::tertiary(id));
+        }
+        term::info!(
+            "{} object(s) carry the label `{label}`, nothing was changed",
+            targets.len()
+        );
+        return Ok(());
+    }
+
+    let signer = term::signer(&profile)?;
+    let mut issues = term::cob::issues_mut(&profile, &repo)?;
+    let mut patches = term::cob::patches_mut(&profile, &repo)?;
+    let mut failures = Vec::new();
+    let mut updated = 0;
+
+    for (kind, id) in &targets {
+        let result = match kind {
+            Kind::Issue => issues
+                .get_mut(id)
+                .map_err(anyhow::Error::from)
+                .and_then(|mut issue| {
+                    let labels = options.op.apply(issue.labels());
+                    issue.label(labels, &signer).map_err(anyhow::Error::from)
+                }),
+            Kind::Patch => {
+                patches
+                    .get_mut(id)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|mut patch| {
+                        let labels = options.op.apply(patch.labels());
+                        patch.label(labels, &signer).map_err(anyhow::Error::from)
+                    })
+            }
+        };
+        match result {
+            Ok(_) => updated += 1,
+            Err(e) => failures.push((*kind, *id, e)),
+        }
+    }
+
+    match &options.op {
+        Operation::Rename { from, to } => {
+            term::success!("Renamed label `{from}` to `{to}` on {updated} object(s)");
+        }
+        Operation::Delete { label } => {
+            term::success!("Deleted label `{label}` from {updated} object(s)");
+        }
+    }
+
+    if options.announce && updated > 0 {
+        let mut node = Node::new(profile.socket());
+        node::announce(
+            &repo,
+            node::SyncSettings::default(),
+            node::SyncReporting::default(),
+            &mut node,
+            &profile,
+        )?;
+    }
+
+    if !failures.is_empty() {
+        for (kind, id, e) in &failures {
+            term::error(format!("failed to update {kind} {id}: {e}"));
+        }
+        anyhow::bail!("{} object(s) could not be updated", failures.len());
+    }
+    Ok(())
+}
+
+/// Issues carrying the given label.
+fn issues(
+    profile: &Profile,
+    repo: &Repository,
+    label: &Label,
+) -> anyhow::Result<Vec<cob::ObjectId>> {
+    let issues = term::cob::issues(profile, repo)?;
+    let mut ids = Vec::new();
+
+    for result in issues.list()? {
+        let (id, issue) = match result {
+            Ok(result) => result,
+            Err(e) => {
+                // A corrupt issue shouldn't prevent updating the others.
+                log::warn!(target: "cli", "Failed to load issue: {e}");
+                term::warning(format!("skipping issue that failed to load: {e}"));
+                continue;
+            }
+        };
+        if issue.labels().any(|l| l == label) {
+            ids.push(id);
+        }
+    }
+    Ok(ids)
+}
+
+/// Patches carrying the given label.
+fn patches(
+    profile: &Profile,
+    repo: &Repository,
+    label: &Label,
+) -> anyhow::Result<Vec<cob::ObjectId>> {
+    let patches = term::cob::patches(profile, repo)?;
+    let mut ids = Vec::new();
+
+    for result in patches.list()? {
+        let (id, patch) = match result {
+            Ok(result) => result,
+            Err(e) => {
+                // A corrupt patch shouldn't prevent updating the others.
+                log::warn!(target: "cli", "Failed to load patch: {e}");
+                term::warning(format!("skipping patch that failed to load: {e}"));
+                continue;
+            }
+        };
+        if patch.labels().any(|l| l == label) {
+            ids.push(id);
+        }
+    }
+    Ok(ids)
+}
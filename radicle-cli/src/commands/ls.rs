@@ -3,8 +3,9 @@ use std::ffi::OsString;
 use radicle::storage::{ReadStorage, RepositoryInfo};
 
 use crate::terminal as term;
-use crate::terminal::args::{Args, Error, Help};
+use crate::terminal::args::{Arg, Args, Error, Help};
 
+use term::table::Column;
 use term::Element;
 
 pub const HELP: Help = Help {
@@ -28,6 +29,14 @@ Options
     --verbose, -v   Verbose output
     --help          Print help
 "#,
+    options: &[
+        Arg::Flag("private", None),
+        Arg::Flag("public", None),
+        Arg::Flag("seeded", Some('s')),
+        Arg::Flag("all", Some('a')),
+        Arg::Flag("verbose", Some('v')),
+        Arg::Flag("help", Some('h')),
+    ],
 };
 
 pub struct Options {
@@ -90,7 +99,17 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
     let storage = &profile.storage;
     let repos = storage.repositories()?;
     let policy = profile.policies()?;
-    let mut table = term::Table::new(term::TableOptions::bordered());
+    let mut table = term::Table::new(term::TableOptions {
+        plain_if_piped: true,
+        ..term::TableOptions::bordered()
+    })
+    .with_columns([
+        Column::new(1).min(8),
+        Column::new(3),
+        Column::new(2),
+        Column::new(2),
+        Column::new(0),
+    ]);
     let mut rows = Vec::new();
 
     if repos.is_empty() {
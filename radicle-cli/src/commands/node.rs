@@ -1,21 +1,26 @@
 use std::ffi::OsString;
 use std::path::PathBuf;
+use std::str::FromStr;
 use std::time;
 
 use anyhow::anyhow;
+use localtime::LocalTime;
 
+use radicle::node::ban::{Ban, Target};
 use radicle::node::config::ConnectAddress;
 use radicle::node::routing::Store;
 use radicle::node::Handle as _;
-use radicle::node::{Address, Node, NodeId, PeerAddr};
+use radicle::node::{Address, Node, NodeId, PeerAddr, Timestamp};
 use radicle::prelude::RepoId;
 
 use crate::terminal as term;
-use crate::terminal::args::{Args, Error, Help};
+use crate::terminal::args::{Arg, Args, Error, Help, Kind};
 use crate::terminal::Element as _;
 
 #[path = "node/commands.rs"]
 mod commands;
+#[path = "node/config.rs"]
+mod config;
 #[path = "node/control.rs"]
 pub mod control;
 #[path = "node/events.rs"]
@@ -30,26 +35,46 @@ pub const HELP: Help = Help {
     usage: r#"
 Usage
 
-    rad node status [<option>...]
+    rad node status [--verbose] [<option>...]
     rad node start [--foreground] [--verbose] [<option>...] [-- <node-option>...]
     rad node stop [<option>...]
     rad node logs [-n <lines>]
     rad node debug [<option>...]
     rad node connect <nid>@<addr> [<option>...]
+    rad node ban <nid | cidr> [--expires <duration>] [--reason <text>] [<option>...]
+    rad node unban <nid | cidr> [<option>...]
+    rad node bans [<option>...]
+    rad node addresses <nid> [<option>...]
     rad node routing [--rid <rid>] [--nid <nid>] [--json] [<option>...]
     rad node inventory [<option>...]
     rad node events [--timeout <secs>] [-n <count>] [<option>...]
     rad node config [--addresses]
+    rad node config get <path>
+    rad node config set <path> <value>
     rad node db <command> [<option>..]
 
     For `<node-option>` see `radicle-node --help`.
 
+    `rad node config` prints the node configuration, including defaults.
+    Values are looked up and set by their dotted path, eg.
+    `limits.connection.inbound`. Values are parsed as JSON, and the
+    configuration is checked before it is saved.
+
+Status options
+
+    --verbose, -v        Also show the fetch statistics used to rank peers
+
 Start options
 
     --foreground         Start the node in the foreground
     --path <path>        Start node binary at path (default: radicle-node)
     --verbose, -v        Verbose output
 
+Ban options
+
+    --expires <duration> Lift the ban after the given duration, eg. `30m`, `12h` or `7d`
+    --reason <text>      Why the node or address range is banned
+
 Routing options
 
     --rid <rid>          Show the routing table entries for the given RID
@@ -65,19 +90,65 @@ General options
 
     --help               Print help
 "#,
+    options: &[
+        Arg::Command("status"),
+        Arg::Command("start"),
+        Arg::Command("stop"),
+        Arg::Command("logs"),
+        Arg::Command("debug"),
+        Arg::Command("addresses"),
+        Arg::Command("connect"),
+        Arg::Command("ban"),
+        Arg::Command("unban"),
+        Arg::Command("bans"),
+        Arg::Command("routing"),
+        Arg::Command("inventory"),
+        Arg::Command("events"),
+        Arg::Command("config"),
+        Arg::Command("sessions"),
+        Arg::Command("db"),
+        Arg::Positional(Kind::Nid),
+        Arg::Flag("foreground", None),
+        Arg::Opt("path", None, Kind::Path),
+        Arg::Flag("verbose", Some('v')),
+        Arg::Opt("expires", None, Kind::Any),
+        Arg::Opt("reason", None, Kind::Any),
+        Arg::Opt("rid", None, Kind::Rid),
+        Arg::Opt("nid", None, Kind::Nid),
+        Arg::Flag("json", None),
+        Arg::Opt("timeout", None, Kind::Any),
+        Arg::Opt("count", Some('n'), Kind::Any),
+        Arg::Flag("addresses", None),
+        Arg::Flag("help", Some('h')),
+    ],
 };
 
 pub struct Options {
     op: Operation,
 }
 
+pub enum ConfigOperation {
+    Get { path: String },
+    Set { path: String, value: String },
+}
+
 pub enum Operation {
+    Addresses {
+        nid: NodeId,
+    },
     Connect {
         addr: PeerAddr<NodeId, Address>,
         timeout: time::Duration,
     },
+    Ban {
+        ban: Ban,
+    },
+    Unban {
+        target: Target,
+    },
     Config {
         addresses: bool,
+        op: Option<ConfigOperation>,
     },
     Db {
         args: Vec<OsString>,
@@ -100,7 +171,10 @@ pub enum Operation {
     Logs {
         lines: usize,
     },
-    Status,
+    Status {
+        verbose: bool,
+    },
+    Bans,
     Inventory,
     Debug,
     Sessions,
@@ -109,6 +183,9 @@ pub enum Operation {
 
 #[derive(Default, PartialEq, Eq)]
 pub enum OperationName {
+    Addresses,
+    Ban,
+    Bans,
     Connect,
     Config,
     Db,
@@ -116,6 +193,7 @@ pub enum OperationName {
     Routing,
     Logs,
     Start,
+    Unban,
     #[default]
     Status,
     Inventory,
@@ -140,8 +218,12 @@ impl Args for Options {
         let mut count: usize = usize::MAX;
         let mut timeout = time::Duration::MAX;
         let mut addresses = false;
+        let mut config_op = None;
         let mut path = None;
         let mut verbose = false;
+        let mut target: Option<Target> = None;
+        let mut expires: Option<time::Duration> = None;
+        let mut reason: Option<String> = None;
 
         while let Some(arg) = parser.next()? {
             match arg {
@@ -149,6 +231,9 @@ impl Args for Options {
                     return Err(Error::Help.into());
                 }
                 Value(val) if op.is_none() => match val.to_string_lossy().as_ref() {
+                    "addresses" => op = Some(OperationName::Addresses),
+                    "ban" => op = Some(OperationName::Ban),
+                    "bans" => op = Some(OperationName::Bans),
                     "connect" => op = Some(OperationName::Connect),
                     "db" => op = Some(OperationName::Db),
                     "events" => op = Some(OperationName::Events),
@@ -159,14 +244,43 @@ impl Args for Options {
                     "start" => op = Some(OperationName::Start),
                     "status" => op = Some(OperationName::Status),
                     "stop" => op = Some(OperationName::Stop),
+                    "unban" => op = Some(OperationName::Unban),
                     "sessions" => op = Some(OperationName::Sessions),
                     "debug" => op = Some(OperationName::Debug),
 
                     unknown => anyhow::bail!("unknown operation '{}'", unknown),
                 },
+                Value(val) if op == Some(OperationName::Config) && config_op.is_none() => {
+                    let path = term::args::string(&parser.value()?);
+
+                    config_op = match val.to_string_lossy().as_ref() {
+                        "get" => Some(ConfigOperation::Get { path }),
+                        "set" => {
+                            let value = term::args::string(&parser.value()?);
+                            Some(ConfigOperation::Set { path, value })
+                        }
+                        unknown => anyhow::bail!("unknown operation '{unknown}'"),
+                    };
+                }
+                Value(val) if matches!(op, Some(OperationName::Addresses)) && nid.is_none() => {
+                    nid = Some(term::args::nid(&val)?);
+                }
                 Value(val) if matches!(op, Some(OperationName::Connect)) => {
                     addr = Some(val.parse()?);
                 }
+                Value(val)
+                    if matches!(op, Some(OperationName::Ban) | Some(OperationName::Unban)) =>
+                {
+                    target = Some(Target::from_str(&val.to_string_lossy())?);
+                }
+                Long("expires") if matches!(op, Some(OperationName::Ban)) => {
+                    let val = parser.value()?;
+                    expires = Some(term::args::duration(&val)?);
+                }
+                Long("reason") if matches!(op, Some(OperationName::Ban)) => {
+                    let val = parser.value()?;
+                    reason = Some(term::args::string(&val));
+                }
                 Long("rid") if matches!(op, Some(OperationName::Routing)) => {
                     let val = parser.value()?;
                     rid = term::args::rid(&val).ok();
@@ -192,7 +306,9 @@ impl Args for Options {
                 Long("addresses") if matches!(op, Some(OperationName::Config)) => {
                     addresses = true;
                 }
-                Long("verbose") | Short('v') if matches!(op, Some(OperationName::Start)) => {
+                Long("verbose") | Short('v')
+                    if matches!(op, Some(OperationName::Start) | Some(OperationName::Status)) =>
+                {
                     verbose = true;
                 }
                 Long("path") if matches!(op, Some(OperationName::Start)) => {
@@ -213,13 +329,40 @@ impl Args for Options {
         }
 
         let op = match op.unwrap_or_default() {
+            OperationName::Addresses => Operation::Addresses {
+                nid: nid.ok_or_else(|| anyhow!("a Node ID must be provided"))?,
+            },
             OperationName::Connect => Operation::Connect {
                 addr: addr.ok_or_else(|| {
                     anyhow!("an address of the form `<nid>@<host>:<port>` must be provided")
                 })?,
                 timeout,
             },
-            OperationName::Config => Operation::Config { addresses },
+            OperationName::Ban => {
+                let target = target.ok_or_else(|| {
+                    anyhow!("a Node ID or an address range of the form `<ip>/<prefix>` must be provided")
+                })?;
+                let expires = expires
+                    .map(|d| Timestamp::from(LocalTime::now()) + d.as_millis() as u64);
+
+                Operation::Ban {
+                    ban: Ban {
+                        target,
+                        reason,
+                        expires,
+                    },
+                }
+            }
+            OperationName::Unban => Operation::Unban {
+                target: target.ok_or_else(|| {
+                    anyhow!("a Node ID or an address range of the form `<ip>/<prefix>` must be provided")
+                })?,
+            },
+            OperationName::Bans => Operation::Bans,
+            OperationName::Config => Operation::Config {
+                addresses,
+                op: config_op,
+            },
             OperationName::Db => Operation::Db { args: options },
             OperationName::Events => Operation::Events { timeout, count },
             OperationName::Routing => Operation::Routing { rid, nid, json },
@@ -231,7 +374,7 @@ impl Args for Options {
                 path: path.unwrap_or(PathBuf::from("radicle-node")),
             },
             OperationName::Inventory => Operation::Inventory,
-            OperationName::Status => Operation::Status,
+            OperationName::Status => Operation::Status { verbose },
             OperationName::Debug => Operation::Debug,
             OperationName::Sessions => Operation::Sessions,
             OperationName::Stop => Operation::Stop,
@@ -245,19 +388,50 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
     let mut node = Node::new(profile.socket());
 
     match options.op {
+        Operation::Addresses { nid } => {
+            if let Some(table) = control::addresses(&profile, &nid)? {
+                table.print();
+            } else {
+                term::info!("No addresses found for {nid}");
+            }
+        }
         Operation::Connect { addr, timeout } => {
             control::connect(&mut node, addr.id, addr.addr, timeout)?
         }
-        Operation::Config { addresses } => {
-            if addresses {
-                let cfg = node.config()?;
-                for addr in cfg.external_addresses {
-                    term::print(ConnectAddress::from((*profile.id(), addr)).to_string());
-                }
-            } else {
-                control::config(&node)?;
+        Operation::Ban { ban } => {
+            control::ban(&mut node, ban)?;
+        }
+        Operation::Unban { target } => {
+            control::unban(&mut node, target)?;
+        }
+        Operation::Bans => {
+            if let Some(table) = control::bans(&node)? {
+                table.print();
+            }
+        }
+        Operation::Config {
+            addresses: true, ..
+        } => {
+            let cfg = node.config()?;
+            for addr in cfg.external_addresses {
+                term::print(ConnectAddress::from((*profile.id(), addr)).to_string());
             }
         }
+        Operation::Config { op: None, .. } => {
+            config::show(&node, &profile)?;
+        }
+        Operation::Config {
+            op: Some(ConfigOperation::Get { path }),
+            ..
+        } => {
+            config::get(&profile, &path)?;
+        }
+        Operation::Config {
+            op: Some(ConfigOperation::Set { path, value }),
+            ..
+        } => {
+            config::set(&node, &profile, &path, &value)?;
+        }
         Operation::Db { args } => {
             commands::db(&profile, args)?;
         }
@@ -291,8 +465,8 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
                 println!("{}", term::format::tertiary(rid));
             }
         }
-        Operation::Status => {
-            control::status(&node, &profile)?;
+        Operation::Status { verbose } => {
+            control::status(&node, verbose, &profile)?;
         }
         Operation::Stop => {
             control::stop(node)?;
@@ -0,0 +1,163 @@
+use anyhow::anyhow;
+use serde_json as json;
+
+use radicle::node::Handle as _;
+use radicle::profile::{Config, RawConfig};
+use radicle::{Node, Profile};
+
+use crate::terminal as term;
+
+/// Print the effective node configuration, including defaults.
+///
+/// If the node is running, this is the configuration it is running with.
+pub fn show(node: &Node, profile: &Profile) -> anyhow::Result<()> {
+    let config = if node.is_running() {
+        node.config()?
+    } else {
+        profile.config.node.clone()
+    };
+    print(&json::to_value(config)?)
+}
+
+/// Print the value of the configuration at the given dotted path, eg. `limits.connection`.
+pub fn get(profile: &Profile, path: &str) -> anyhow::Result<()> {
+    let config = json::to_value(&profile.config.node)?;
+    let value = lookup(&config, path)?;
+
+    print(value)
+}
+
+/// Set the value of the configuration at the given dotted path.
+///
+/// The value is parsed as JSON, or used as a string if it isn't valid JSON. The resulting
+/// configuration is validated before it is written.
+pub fn set(node: &Node, profile: &Profile, path: &str, value: &str) -> anyhow::Result<()> {
+    let file = profile.home.config();
+    let value = json::from_str(value).unwrap_or_else(|_| json::Value::String(value.to_owned()));
+    let mut raw = RawConfig::from_file(&file)?;
+
+    raw.set_json(&format!("node.{path}").into(), value)?;
+
+    let config: Config = raw
+        .clone()
+        .try_into()
+        .map_err(|e| anyhow!("invalid value for `{path}`: {e}"))?;
+    // Unknown keys are ignored when the configuration is parsed, so we check that the value
+    // is still there afterwards.
+    let updated = json::to_value(&config.node)?;
+    let value = lookup(&updated, path)?;
+
+    raw.write(&file)?;
+    term::success!("Updated {} to {}", term::format::tertiary(path), value);
+
+    if node.is_running() {
+        let running = json::to_value(node.config()?)?;
+
+        if lookup(&running, path).ok() != Some(value) {
+            term::warning(
+                "the node must be restarted for this change to take effect, \
+                with `rad node stop` and `rad node start`",
+            );
+        } else {
+            term::info!("The node is already running with this value");
+        }
+    }
+    Ok(())
+}
+
+/// Look up a value by its dotted path. Array elements are looked up by index.
+fn lookup<'a>(config: &'a json::Value, path: &str) -> anyhow::Result<&'a json::Value> {
+    let mut current = config;
+    let mut parent = Vec::new();
+
+    for key in path.split('.') {
+        let next = match current {
+            json::Value::Object(map) => map.get(key),
+            json::Value::Array(array) => key.parse::<usize>().ok().and_then(|i| array.get(i)),
+            _ => {
+                anyhow::bail!("`{}` has no key `{key}`", parent.join("."));
+            }
+        };
+        let Some(next) = next else {
+            return Err(unknown(current, path, &parent));
+        };
+        parent.push(key);
+        current = next;
+    }
+    Ok(current)
+}
+
+/// Error for an unknown path, listing the valid keys next to it.
+fn unknown(value: &json::Value, path: &str, parent: &[&str]) -> anyhow::Error {
+    let keys = match value {
+        json::Value::Object(map) => map
+            .keys()
+            .map(|k| {
+                if parent.is_empty() {
+                    k.to_owned()
+                } else {
+                    format!("{}.{k}", parent.join("."))
+                }
+            })
+            .collect::<Vec<_>>(),
+        json::Value::Array(array) => (0..array.len())
+            .map(|i| format!("{}.{i}", parent.join(".")))
+            .collect(),
+        _ => vec![],
+    };
+    if keys.is_empty() {
+        anyhow!("unknown configuration key `{path}`")
+    } else {
+        anyhow!(
+            "unknown configuration key `{path}`, valid keys are: {}",
+            keys.join(", ")
+        )
+    }
+}
+
+/// Print a JSON value.
+fn print(value: &json::Value) -> anyhow::Result<()> {
+    term::print(json::to_string_pretty(value)?);
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_lookup() {
+        let config = json::json!({
+            "alias": "alice",
+            "externalAddresses": ["seed.radicle.example:8776"],
+            "limits": {
+                "connection": { "inbound": 128, "outbound": 16 },
+            },
+        });
+
+        assert_eq!(lookup(&config, "alias").unwrap(), "alice");
+        assert_eq!(lookup(&config, "limits.connection.inbound").unwrap(), 128);
+        assert_eq!(
+            lookup(&config, "externalAddresses.0").unwrap(),
+            "seed.radicle.example:8776"
+        );
+        assert_eq!(
+            lookup(&config, "limits.conection").unwrap_err().to_string(),
+            "unknown configuration key `limits.conection`, valid keys are: limits.connection"
+        );
+        assert_eq!(
+            lookup(&config, "limits.connection.inbound.max")
+                .unwrap_err()
+                .to_string(),
+            "`limits.connection.inbound` has no key `max`"
+        );
+        assert_eq!(
+            lookup(&config, "externalAddresses.1")
+                .unwrap_err()
+                .to_string(),
+            "unknown configuration key `externalAddresses.1`, valid keys are: externalAddresses.0"
+        );
+    }
+}
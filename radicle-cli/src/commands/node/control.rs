@@ -7,6 +7,9 @@ use anyhow::{anyhow, Context};
 use localtime::LocalTime;
 
 use radicle::node;
+use radicle::node::address::Store as _;
+use radicle::node::ban::{Ban, Target};
+use radicle::node::reputation::{PeerStats, Rank, Store as _};
 use radicle::node::{Address, ConnectResult, Handle as _, NodeId};
 use radicle::Node;
 use radicle::{profile, Profile};
@@ -111,7 +114,10 @@ pub fn start(
 
 pub fn stop(node: Node) -> anyhow::Result<()> {
     let mut spinner = term::spinner("Stopping node...");
-    if node.shutdown().is_err() {
+    let result = node.shutdown_with_progress(&mut |phase| {
+        spinner.message(format!("Stopping node ({phase})..."));
+    });
+    if result.is_err() {
         spinner.error("node is not running");
     } else {
         spinner.message("Node stopped");
@@ -205,7 +211,98 @@ pub fn connect(
     Ok(())
 }
 
-pub fn status(node: &Node, profile: &Profile) -> anyhow::Result<()> {
+pub fn ban(node: &mut Node, ban: Ban) -> anyhow::Result<()> {
+    let target = ban.target;
+
+    if node.ban(ban)? {
+        term::success!("Banned {}", term::format::tertiary(target));
+    } else {
+        term::info!("{} is already banned", term::format::tertiary(target));
+    }
+    Ok(())
+}
+
+pub fn unban(node: &mut Node, target: Target) -> anyhow::Result<()> {
+    if node.unban(target)? {
+        term::success!("Unbanned {}", term::format::tertiary(target));
+    } else {
+        term::info!("{} is not banned", term::format::tertiary(target));
+    }
+    Ok(())
+}
+
+pub fn bans(node: &Node) -> Result<Option<term::Table<3, term::Label>>, node::Error> {
+    let bans = node.bans()?;
+    if bans.is_empty() {
+        return Ok(None);
+    }
+    let mut table = term::Table::new(term::table::TableOptions::bordered());
+    let now = LocalTime::now();
+
+    table.header([
+        term::format::bold("Target").into(),
+        term::format::bold("Reason").into(),
+        term::format::bold("Expires").into(),
+    ]);
+    table.divider();
+
+    for ban in bans {
+        let reason = ban
+            .reason
+            .map(term::Label::from)
+            .unwrap_or_else(term::Label::blank);
+        let expires = match ban.expires.map(LocalTime::from) {
+            Some(t) if t > now => term::format::dim(format!("in {}", t - now)).into(),
+            Some(_) => term::format::dim("expired").into(),
+            None => term::format::dim("never").into(),
+        };
+        table.push([term::format::tertiary(ban.target).into(), reason, expires]);
+    }
+    Ok(Some(table))
+}
+
+pub fn addresses(
+    profile: &Profile,
+    nid: &NodeId,
+) -> anyhow::Result<Option<term::Table<4, term::Label>>> {
+    let addrs = profile.database()?.addresses_of(nid)?;
+    if addrs.is_empty() {
+        return Ok(None);
+    }
+    let mut table = term::Table::new(term::table::TableOptions::bordered());
+    let now = LocalTime::now();
+    let ago = |t: Option<LocalTime>| -> term::Label {
+        match t {
+            Some(t) => term::format::dim(format!("{} ago", now - t)).into(),
+            None => term::format::dim("never").into(),
+        }
+    };
+
+    table.header([
+        term::format::bold("Address").into(),
+        term::format::bold("Source").into(),
+        term::format::bold("Last success").into(),
+        term::format::bold("Last attempt").into(),
+    ]);
+    table.divider();
+
+    for ka in addrs {
+        let addr = if ka.banned {
+            term::format::negative(format!("{} (banned)", ka.addr)).into()
+        } else {
+            term::format::tertiary(ka.addr).into()
+        };
+        table.push([
+            addr,
+            term::format::default(ka.source).into(),
+            ago(ka.last_success),
+            ago(ka.last_attempt),
+        ]);
+    }
+    Ok(Some(table))
+}
+
+pub fn status(node: &Node, verbose: bool, profile: &Profile) -> anyhow::Result<()> {
     if node.is_running() {
         let listen = node
             .listen_addrs()?
@@ -222,6 +319,15 @@ pub fn status(node: &Node, profile: &Profile) -> anyhow::Result<()> {
                 listen.join(", ")
             );
         }
+        if let Some(next) = node.next_sync()? {
+            let mut fmt = timeago::Formatter::new();
+            fmt.ago("");
+
+            term::info!(
+                "Next scheduled sync in {}.",
+                term::format::dim(fmt.convert((LocalTime::from(next) - LocalTime::now()).into()))
+            );
+        }
     } else {
         term::info!("Node is {}.", term::format::negative("stopped"));
         term::info!(
@@ -237,6 +343,13 @@ pub fn status(node: &Node, profile: &Profile) -> anyhow::Result<()> {
         table.print();
     }
 
+    if verbose {
+        if let Some(table) = ranking(node, profile)? {
+            term::blank();
+            table.print();
+        }
+    }
+
     if profile.home.node().join("node.log").exists() {
         term::blank();
         // If we're running the node via `systemd` for example, there won't be a log file
@@ -291,13 +404,85 @@ pub fn sessions(node: &Node) -> Result<Option<term::Table<4, term::Label>>, node
     Ok(Some(table))
 }
 
-pub fn config(node: &Node) -> anyhow::Result<()> {
-    let cfg = node.config()?;
-    let cfg = serde_json::to_string_pretty(&cfg)?;
+/// Fetch statistics of peers, in the order they are ranked as fetch targets.
+pub fn ranking(
+    node: &Node,
+    profile: &Profile,
+) -> anyhow::Result<Option<term::Table<6, term::Label>>> {
+    let preferred = node.config()?.preferred_seeds;
+    let db = profile.database()?;
+    let mut peers = db
+        .peer_stats()?
+        .map(|r| r.map(|(nid, stats)| (nid, Some(stats))))
+        .collect::<Result<Vec<(NodeId, Option<PeerStats>)>, _>>()?;
+
+    // Preferred seeds are ranked first, even if we never fetched from them.
+    for nid in &preferred {
+        if !peers.iter().any(|(n, _)| n == nid) {
+            peers.push((*nid, None));
+        }
+    }
+    if peers.is_empty() {
+        return Ok(None);
+    }
+    peers.sort_by_key(|(nid, stats)| Rank::new(preferred.contains(nid), stats.as_ref()));
+
+    let mut table = term::Table::new(term::table::TableOptions::bordered());
+    let now = LocalTime::now();
+
+    table.header([
+        term::format::bold("Peer").into(),
+        term::format::bold("Preferred").into(),
+        term::format::bold("Fetches").into(),
+        term::format::bold("Success").into(),
+        term::format::bold("Latency").into(),
+        term::format::bold("Last seen").into(),
+    ]);
+    table.divider();
 
-    println!("{cfg}");
+    for (nid, stats) in peers {
+        let preferred = if preferred.contains(&nid) {
+            term::format::positive("yes").into()
+        } else {
+            term::Label::blank()
+        };
+        let Some(stats) = stats else {
+            table.push([
+                term::format::tertiary(nid).into(),
+                preferred,
+                term::format::dim("0").into(),
+                term::Label::blank(),
+                term::Label::blank(),
+                term::Label::blank(),
+            ]);
+            continue;
+        };
+        let success = stats
+            .success_rate()
+            .map(|r| format!("{:.0}%", r * 100.))
+            .unwrap_or_default();
+        // Statistics of peers we haven't fetched from enough aren't used for ranking yet.
+        let success = if stats.is_exploring() {
+            term::format::dim(success).into()
+        } else {
+            term::Label::from(success)
+        };
+        let latency = if stats.fetches_ok > 0 {
+            term::Label::from(format!("{}ms", stats.latency.as_millis()))
+        } else {
+            term::Label::blank()
+        };
 
-    Ok(())
+        table.push([
+            term::format::tertiary(nid).into(),
+            preferred,
+            format!("{}/{}", stats.fetches_ok, stats.fetches()).into(),
+            success,
+            latency,
+            term::format::dim(now - stats.last_seen).into(),
+        ]);
+    }
+    Ok(Some(table))
 }
 
 fn log_rotate(profile: &Profile) -> io::Result<File> {
@@ -18,10 +18,14 @@ mod edit;
 mod label;
 #[path = "patch/list.rs"]
 mod list;
+#[path = "patch/merge.rs"]
+mod merge;
 #[path = "patch/ready.rs"]
 mod ready;
 #[path = "patch/redact.rs"]
 mod redact;
+#[path = "patch/request_review.rs"]
+mod request_review;
 #[path = "patch/resolve.rs"]
 mod resolve;
 #[path = "patch/review.rs"]
@@ -33,6 +37,7 @@ mod update;
 
 use std::collections::BTreeSet;
 use std::ffi::OsString;
+use std::path::PathBuf;
 
 use anyhow::anyhow;
 
@@ -46,7 +51,7 @@ use radicle::{prelude::*, Node};
 use crate::git::Rev;
 use crate::node;
 use crate::terminal as term;
-use crate::terminal::args::{string, Args, Error, Help};
+use crate::terminal::args::{string, Arg, Args, Error, Help, Kind};
 use crate::terminal::patch::Message;
 
 pub const HELP: Help = Help {
@@ -57,28 +62,32 @@ pub const HELP: Help = Help {
 Usage
 
     rad patch [<option>...]
-    rad patch list [--all|--merged|--open|--archived|--draft|--authored] [--author <did>]... [<option>...]
+    rad patch list [--all|--merged|--open|--archived|--draft|--authored] [--author <did>]... [--reviewer <did>]... [<option>...]
     rad patch show <patch-id> [<option>...]
     rad patch diff <patch-id> [<option>...]
     rad patch archive <patch-id> [--undo] [<option>...]
     rad patch update <patch-id> [<option>...]
     rad patch checkout <patch-id> [<option>...]
+    rad patch merge <patch-id> [--checkout] [--via <branch>] [--push] [<option>...]
     rad patch review <patch-id> [--accept | --reject] [-m [<string>]] [-d | --delete] [<option>...]
-    rad patch resolve <patch-id> [--review <review-id>] [--comment <comment-id>] [--unresolve] [<option>...]
+    rad patch resolve <patch-id> [--review <review-id>] [--comment <comment-id>] [--undo] [<option>...]
     rad patch delete <patch-id> [<option>...]
     rad patch redact <revision-id> [<option>...]
     rad patch assign <revision-id> [--add <did>] [--delete <did>] [<option>...]
+    rad patch request-review <patch-id> [--revision <id>] [--add <did>] [--delete <did>] [<option>...]
     rad patch label <revision-id> [--add <label>] [--delete <label>] [<option>...]
     rad patch ready <patch-id> [--undo] [<option>...]
     rad patch edit <patch-id> [<option>...]
     rad patch set <patch-id> [<option>...]
-    rad patch comment <patch-id | revision-id> [<option>...]
-    rad patch cache [<patch-id>] [--storage] [<option>...]
+    rad patch comment <patch-id | revision-id> [--path <path> --line <n[:m]>] [<option>...]
+    rad patch cache [<patch-id>] [--storage] [--invalidate <patch-id>] [<option>...]
 
 Show options
 
     -p, --patch                Show the actual patch diff
     -v, --verbose              Show additional information about the patch
+        --timeline             Show the history of the patch, in chronological order
+        --format <format>      Output format: `full` or `json` (default: full)
         --debug                Show the patch as Rust debug output
 
 Diff options
@@ -89,6 +98,10 @@ Comment options
 
     -m, --message <string>     Provide a comment message via the command-line
         --reply-to <comment>   The comment to reply to
+    -r, --revision <id>        The revision to comment on (default: the given revision or patch)
+        --path <path>          Anchor the comment to a file changed by the revision
+        --line <n[:m]>         Anchor the comment to a line, or range of lines, of the file
+        --side <old|new>       The side of the diff the lines are on (default: new)
 
 Edit options
 
@@ -119,6 +132,16 @@ Assign options
     -d, --delete <did>         Delete an assignee from the patch (may be specified multiple times).
                                Note: --add will take precedence over --delete
 
+Request review options
+
+    -r, --revision <id>        Request reviews of the given revision (default: latest)
+    -a, --add    <did>         Request a review from the given DID (may be specified multiple times).
+                               Note: --add will take precedence over --delete
+
+    -d, --delete <did>         Withdraw the review request from the given DID (may be specified
+                               multiple times).
+                               Note: --add will take precedence over --delete
+
 Archive options
 
         --undo                 Unarchive a patch
@@ -147,6 +170,8 @@ List options
         --authored             Show only patches that you have authored
         --author <did>         Show only patched where the given user is an author
                                (may be specified multiple times)
+        --reviewer <did>       Show only patches reviewed by the given user
+                               (may be specified multiple times)
 
 Ready options
 
@@ -159,10 +184,26 @@ Checkout options
         --remote <string>      Provide the git remote to use as the upstream
     -f, --force                Checkout the head of the revision, even if the branch already exists
 
+Merge options
+
+        --revision <id>        Merge the given revision of the patch (default: latest)
+        --checkout             Merge the patch in the working copy, even if the default branch
+                               can be fast-forwarded in storage
+        --via <branch>         The branch to merge the patch into (default: the default branch),
+                               implies `--checkout`
+        --push                 Push the branch to the `rad` remote after merging, implies
+                               `--checkout`
+
 Set options
 
         --remote <string>      Provide the git remote to use as the upstream
 
+Cache options
+
+        --storage              Re-cache the patches of all repositories in storage
+        --invalidate <id>      Remove the cached patch and re-cache it from storage.
+                               Patches no longer in storage are only removed
+
 Other options
 
         --repo <rid>           Operate on the given repository (default: cwd)
@@ -170,6 +211,71 @@ Other options
     -q, --quiet                Quiet output
         --help                 Print help
 "#,
+    options: &[
+        Arg::Command("list"),
+        Arg::Command("show"),
+        Arg::Command("diff"),
+        Arg::Command("archive"),
+        Arg::Command("update"),
+        Arg::Command("checkout"),
+        Arg::Command("merge"),
+        Arg::Command("review"),
+        Arg::Command("resolve"),
+        Arg::Command("delete"),
+        Arg::Command("redact"),
+        Arg::Command("assign"),
+        Arg::Command("request-review"),
+        Arg::Command("label"),
+        Arg::Command("ready"),
+        Arg::Command("edit"),
+        Arg::Command("set"),
+        Arg::Command("comment"),
+        Arg::Command("cache"),
+        Arg::Positional(Kind::Patch),
+        Arg::Flag("patch", Some('p')),
+        Arg::Flag("verbose", Some('v')),
+        Arg::Opt("format", None, Kind::OneOf(&["full", "json"])),
+        Arg::Flag("debug", None),
+        Arg::Flag("timeline", None),
+        Arg::Opt("revision", Some('r'), Kind::Any),
+        Arg::Opt("message", Some('m'), Kind::Any),
+        Arg::Flag("no-message", None),
+        Arg::Opt("reply-to", None, Kind::Any),
+        Arg::Opt("path", None, Kind::Path),
+        Arg::Opt("line", None, Kind::Any),
+        Arg::Opt("side", None, Kind::OneOf(&["old", "new"])),
+        Arg::Opt("hunk", None, Kind::Any),
+        Arg::Flag("accept", None),
+        Arg::Flag("reject", None),
+        Arg::Opt("unified", Some('U'), Kind::Any),
+        Arg::Opt("review", None, Kind::Any),
+        Arg::Opt("comment", None, Kind::Any),
+        Arg::Flag("undo", None),
+        Arg::Opt("add", Some('a'), Kind::Any),
+        Arg::Opt("delete", Some('d'), Kind::Any),
+        Arg::Opt("base", Some('b'), Kind::Any),
+        Arg::Flag("all", None),
+        Arg::Flag("archived", None),
+        Arg::Flag("merged", None),
+        Arg::Flag("open", None),
+        Arg::Flag("draft", None),
+        Arg::Flag("authored", None),
+        Arg::Opt("author", None, Kind::Any),
+        Arg::Opt("reviewer", None, Kind::Any),
+        Arg::Opt("name", None, Kind::Any),
+        Arg::Opt("remote", None, Kind::Any),
+        Arg::Flag("force", Some('f')),
+        Arg::Opt("via", None, Kind::Any),
+        Arg::Flag("push", None),
+        Arg::Flag("checkout", None),
+        Arg::Flag("storage", None),
+        Arg::Opt("invalidate", None, Kind::Patch),
+        Arg::Opt("repo", None, Kind::Rid),
+        Arg::Flag("announce", None),
+        Arg::Flag("no-announce", None),
+        Arg::Flag("quiet", Some('q')),
+        Arg::Flag("help", Some('h')),
+    ],
 };
 
 #[derive(Debug, Default, PartialEq, Eq)]
@@ -190,8 +296,10 @@ pub enum OperationName {
     List,
     Edit,
     Redact,
+    RequestReview,
     Set,
     Cache,
+    Merge,
 }
 
 #[derive(Debug, Default, PartialEq, Eq)]
@@ -200,6 +308,12 @@ pub struct AssignOptions {
     pub delete: BTreeSet<Did>,
 }
 
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RequestReviewOptions {
+    pub add: BTreeSet<Did>,
+    pub delete: BTreeSet<Did>,
+}
+
 #[derive(Debug, Default, PartialEq, Eq)]
 pub struct LabelOptions {
     pub add: BTreeSet<Label>,
@@ -211,7 +325,9 @@ pub enum Operation {
     Show {
         patch_id: Rev,
         diff: bool,
+        json: bool,
         debug: bool,
+        timeline: bool,
     },
     Diff {
         patch_id: Rev,
@@ -238,10 +354,16 @@ pub enum Operation {
         revision_id: Option<Rev>,
         opts: checkout::Options,
     },
+    Merge {
+        patch_id: Rev,
+        revision_id: Option<Rev>,
+        opts: merge::Options,
+    },
     Comment {
         revision_id: Rev,
         message: Message,
         reply_to: Option<Rev>,
+        location: Option<comment::Location>,
     },
     Review {
         patch_id: Rev,
@@ -258,6 +380,11 @@ pub enum Operation {
         patch_id: Rev,
         opts: AssignOptions,
     },
+    RequestReview {
+        patch_id: Rev,
+        revision_id: Option<Rev>,
+        opts: RequestReviewOptions,
+    },
     Label {
         patch_id: Rev,
         opts: LabelOptions,
@@ -280,6 +407,7 @@ pub enum Operation {
     Cache {
         patch_id: Option<Rev>,
         storage: bool,
+        invalidate: bool,
     },
 }
 
@@ -294,10 +422,12 @@ impl Operation {
             | Operation::Review { .. }
             | Operation::Resolve { .. }
             | Operation::Assign { .. }
+            | Operation::RequestReview { .. }
             | Operation::Label { .. }
             | Operation::Edit { .. }
             | Operation::Redact { .. }
-            | Operation::Set { .. } => true,
+            | Operation::Set { .. }
+            | Operation::Merge { .. } => true,
             Operation::Show { .. }
             | Operation::Diff { .. }
             | Operation::Checkout { .. }
@@ -316,6 +446,7 @@ pub struct Options {
     pub quiet: bool,
     pub authored: bool,
     pub authors: Vec<Did>,
+    pub reviewers: Vec<Did>,
 }
 
 impl Args for Options {
@@ -328,6 +459,7 @@ impl Args for Options {
         let mut quiet = false;
         let mut authored = false;
         let mut authors = vec![];
+        let mut reviewers = vec![];
         let mut announce = true;
         let mut patch_id = None;
         let mut revision_id = None;
@@ -337,16 +469,24 @@ impl Args for Options {
         let mut filter = Some(patch::Status::Open);
         let mut diff = false;
         let mut debug = false;
+        let mut timeline = false;
+        let mut json = false;
         let mut undo = false;
         let mut reply_to: Option<Rev> = None;
+        let mut path: Option<PathBuf> = None;
+        let mut lines = None;
+        let mut side = None;
         let mut checkout_opts = checkout::Options::default();
+        let mut merge_opts = merge::Options::default();
         let mut remote: Option<RefString> = None;
         let mut assign_opts = AssignOptions::default();
+        let mut request_review_opts = RequestReviewOptions::default();
         let mut label_opts = LabelOptions::default();
         let mut review_op = review::Operation::default();
         let mut base_id = None;
         let mut repo = None;
         let mut cache_storage = false;
+        let mut cache_invalidate = false;
 
         while let Some(arg) = parser.next()? {
             match arg {
@@ -375,6 +515,19 @@ impl Args for Options {
                 Long("debug") if op == Some(OperationName::Show) => {
                     debug = true;
                 }
+                Long("timeline") if op == Some(OperationName::Show) => {
+                    timeline = true;
+                }
+                Long("format") if op == Some(OperationName::Show) => {
+                    let val = parser.value()?;
+                    let val = term::args::string(&val);
+
+                    match val.as_str() {
+                        "full" => json = false,
+                        "json" => json = true,
+                        _ => anyhow::bail!("unknown format '{val}'"),
+                    }
+                }
 
                 // Ready options.
                 Long("undo") if op == Some(OperationName::Ready) => {
@@ -401,9 +554,23 @@ impl Args for Options {
 
                     reply_to = Some(rev);
                 }
+                Long("path") if op == Some(OperationName::Comment) => {
+                    let val = parser.value()?;
+                    path = Some(PathBuf::from(val));
+                }
+                Long("line") if op == Some(OperationName::Comment) => {
+                    let val = parser.value()?;
+                    lines = Some(comment::lines(&val)?);
+                }
+                Long("side") if op == Some(OperationName::Comment) => {
+                    let val = parser.value()?;
+                    side = Some(term::args::string(&val).parse::<comment::Side>()?);
+                }
 
                 // Edit options.
-                Long("revision") | Short('r') if op == Some(OperationName::Edit) => {
+                Long("revision") | Short('r')
+                    if op == Some(OperationName::Edit) || op == Some(OperationName::Comment) =>
+                {
                     let val = parser.value()?;
                     let rev = term::args::rev(&val)?;
 
@@ -412,7 +579,9 @@ impl Args for Options {
 
                 // Review/diff options.
                 Long("revision") | Short('r')
-                    if op == Some(OperationName::Review) || op == Some(OperationName::Diff) =>
+                    if op == Some(OperationName::Review)
+                        || op == Some(OperationName::Diff)
+                        || op == Some(OperationName::RequestReview) =>
                 {
                     let val = parser.value()?;
                     let rev = term::args::rev(&val)?;
@@ -510,6 +679,27 @@ impl Args for Options {
                     checkout_opts.remote = Some(term::args::refstring("remote", val)?);
                 }
 
+                // Merge options.
+                Long("revision") if op == Some(OperationName::Merge) => {
+                    let val = parser.value()?;
+                    let rev = term::args::rev(&val)?;
+
+                    revision_id = Some(rev);
+                }
+
+                Long("checkout") if op == Some(OperationName::Merge) => {
+                    merge_opts.checkout = true;
+                }
+
+                Long("via") if op == Some(OperationName::Merge) => {
+                    let val = parser.value()?;
+                    merge_opts.via = Some(term::args::refstring("via", val)?);
+                }
+
+                Long("push") if op == Some(OperationName::Merge) => {
+                    merge_opts.push = true;
+                }
+
                 // Assign options.
                 Short('a') | Long("add") if matches!(op, Some(OperationName::Assign)) => {
                     assign_opts.add.insert(term::args::did(&parser.value()?)?);
@@ -521,6 +711,19 @@ impl Args for Options {
                         .insert(term::args::did(&parser.value()?)?);
                 }
 
+                // Request review options.
+                Short('a') | Long("add") if matches!(op, Some(OperationName::RequestReview)) => {
+                    request_review_opts
+                        .add
+                        .insert(term::args::did(&parser.value()?)?);
+                }
+
+                Short('d') | Long("delete") if matches!(op, Some(OperationName::RequestReview)) => {
+                    request_review_opts
+                        .delete
+                        .insert(term::args::did(&parser.value()?)?);
+                }
+
                 // Label options.
                 Short('a') | Long("add") if matches!(op, Some(OperationName::Label)) => {
                     let val = parser.value()?;
@@ -566,11 +769,21 @@ impl Args for Options {
                 Long("author") if op == Some(OperationName::List) => {
                     authors.push(term::args::did(&parser.value()?)?);
                 }
+                Long("reviewer") if op == Some(OperationName::List) => {
+                    reviewers.push(term::args::did(&parser.value()?)?);
+                }
 
                 // Cache options.
                 Long("storage") if op == Some(OperationName::Cache) => {
                     cache_storage = true;
                 }
+                Long("invalidate") if op == Some(OperationName::Cache) => {
+                    let val = parser.value()?;
+                    let val = term::args::string(&val);
+
+                    patch_id = Some(Rev::from(val));
+                    cache_invalidate = true;
+                }
 
                 // Common.
                 Long("verbose") | Short('v') => {
@@ -604,12 +817,14 @@ impl Args for Options {
                     "r" | "redact" => op = Some(OperationName::Redact),
                     "diff" => op = Some(OperationName::Diff),
                     "assign" => op = Some(OperationName::Assign),
+                    "request-review" => op = Some(OperationName::RequestReview),
                     "label" => op = Some(OperationName::Label),
                     "comment" => op = Some(OperationName::Comment),
                     "review" => op = Some(OperationName::Review),
                     "resolve" => op = Some(OperationName::Resolve),
                     "set" => op = Some(OperationName::Set),
                     "cache" => op = Some(OperationName::Cache),
+                    "merge" => op = Some(OperationName::Merge),
                     unknown => anyhow::bail!("unknown operation '{}'", unknown),
                 },
                 Value(val) if op == Some(OperationName::Redact) => {
@@ -632,8 +847,10 @@ impl Args for Options {
                             Some(OperationName::Edit),
                             Some(OperationName::Set),
                             Some(OperationName::Assign),
+                            Some(OperationName::RequestReview),
                             Some(OperationName::Label),
                             Some(OperationName::Cache),
+                            Some(OperationName::Merge),
                         ]
                         .contains(&op) =>
                 {
@@ -649,7 +866,9 @@ impl Args for Options {
             OperationName::Show => Operation::Show {
                 patch_id: patch_id.ok_or_else(|| anyhow!("a patch must be provided"))?,
                 diff,
+                json,
                 debug,
+                timeline,
             },
             OperationName::Diff => Operation::Diff {
                 patch_id: patch_id.ok_or_else(|| anyhow!("a patch must be provided"))?,
@@ -672,11 +891,26 @@ impl Args for Options {
                 revision_id,
                 opts: checkout_opts,
             },
+            OperationName::Merge => Operation::Merge {
+                patch_id: patch_id.ok_or_else(|| anyhow!("a patch must be provided"))?,
+                revision_id,
+                opts: merge_opts,
+            },
             OperationName::Comment => Operation::Comment {
-                revision_id: patch_id
+                revision_id: revision_id
+                    .or(patch_id)
                     .ok_or_else(|| anyhow!("a patch or revision must be provided"))?,
                 message,
                 reply_to,
+                location: match (path, lines) {
+                    (Some(path), Some(lines)) => Some(comment::Location {
+                        path,
+                        lines,
+                        side: side.unwrap_or_default(),
+                    }),
+                    (None, None) if side.is_none() => None,
+                    _ => anyhow::bail!("`--path` and `--line` must be used together"),
+                },
             },
             OperationName::Review => Operation::Review {
                 patch_id: patch_id
@@ -710,6 +944,11 @@ impl Args for Options {
                 patch_id: patch_id.ok_or_else(|| anyhow!("a patch must be provided"))?,
                 opts: assign_opts,
             },
+            OperationName::RequestReview => Operation::RequestReview {
+                patch_id: patch_id.ok_or_else(|| anyhow!("a patch must be provided"))?,
+                revision_id,
+                opts: request_review_opts,
+            },
             OperationName::Label => Operation::Label {
                 patch_id: patch_id.ok_or_else(|| anyhow!("a patch must be provided"))?,
                 opts: label_opts,
@@ -718,10 +957,16 @@ impl Args for Options {
                 patch_id: patch_id.ok_or_else(|| anyhow!("a patch must be provided"))?,
                 remote,
             },
-            OperationName::Cache => Operation::Cache {
-                patch_id,
-                storage: cache_storage,
-            },
+            OperationName::Cache => {
+                if cache_invalidate && cache_storage {
+                    anyhow::bail!("`--invalidate` cannot be used with `--storage`");
+                }
+                Operation::Cache {
+                    patch_id,
+                    storage: cache_storage,
+                    invalidate: cache_invalidate,
+                }
+            }
         };
 
         Ok((
@@ -733,6 +978,7 @@ impl Args for Options {
                 announce,
                 authored,
                 authors,
+                reviewers,
             },
             vec![],
         ))
@@ -760,18 +1006,23 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
             if options.authored {
                 authors.insert(profile.did());
             }
-            list::run(filter.as_ref(), authors, &repository, &profile)?;
+            let reviewers: BTreeSet<Did> = options.reviewers.iter().cloned().collect();
+            list::run(filter.as_ref(), authors, reviewers, &repository, &profile)?;
         }
         Operation::Show {
             patch_id,
             diff,
+            json,
             debug,
+            timeline,
         } => {
             let patch_id = patch_id.resolve(&repository.backend)?;
             show::run(
                 &patch_id,
                 diff,
+                json,
                 debug,
+                timeline,
                 options.verbose,
                 &profile,
                 &repository,
@@ -819,13 +1070,7 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
         Operation::Ready { ref patch_id, undo } => {
             let patch_id = patch_id.resolve::<PatchId>(&repository.backend)?;
 
-            if !ready::run(&patch_id, undo, &profile, &repository)? {
-                if undo {
-                    anyhow::bail!("the patch must be open to be put in draft state");
-                } else {
-                    anyhow::bail!("this patch must be in draft state to be put in open state");
-                }
-            }
+            ready::run(&patch_id, undo, &profile, &repository)?;
         }
         Operation::Delete { patch_id } => {
             let patch_id = patch_id.resolve::<PatchId>(&repository.backend)?;
@@ -853,15 +1098,36 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
                 opts,
             )?;
         }
+        Operation::Merge {
+            patch_id,
+            revision_id,
+            opts,
+        } => {
+            let patch_id = patch_id.resolve::<radicle::git::Oid>(&repository.backend)?;
+            let revision_id = revision_id
+                .map(|rev| rev.resolve::<radicle::git::Oid>(&repository.backend))
+                .transpose()?
+                .map(patch::RevisionId::from);
+            merge::run(
+                &patch::PatchId::from(patch_id),
+                revision_id,
+                &repository,
+                workdir.as_ref(),
+                &profile,
+                opts,
+            )?;
+        }
         Operation::Comment {
             revision_id,
             message,
             reply_to,
+            location,
         } => {
             comment::run(
                 revision_id,
                 message,
                 reply_to,
+                location,
                 options.quiet,
                 &repository,
                 &profile,
@@ -920,6 +1186,18 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
             let patch_id = patch_id.resolve(&repository.backend)?;
             assign::run(&patch_id, add, delete, &profile, &repository)?;
         }
+        Operation::RequestReview {
+            patch_id,
+            revision_id,
+            opts: RequestReviewOptions { add, delete },
+        } => {
+            let patch_id = patch_id.resolve(&repository.backend)?;
+            let revision_id = revision_id
+                .map(|rev| rev.resolve::<radicle::git::Oid>(&repository.backend))
+                .transpose()?
+                .map(patch::RevisionId::from);
+            request_review::run(&patch_id, revision_id, add, delete, &profile, &repository)?;
+        }
         Operation::Label {
             patch_id,
             opts: LabelOptions { add, delete },
@@ -944,7 +1222,11 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
                 true,
             )?;
         }
-        Operation::Cache { patch_id, storage } => {
+        Operation::Cache {
+            patch_id,
+            storage,
+            invalidate,
+        } => {
             let mode = if storage {
                 cache::CacheMode::Storage
             } else {
@@ -958,6 +1240,7 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
                     |id| cache::CacheMode::Patch {
                         id,
                         repository: &repository,
+                        invalidate,
                     },
                 )
             };
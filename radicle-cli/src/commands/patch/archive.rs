@@ -16,8 +16,14 @@ pub fn run(
     };
 
     if undo {
+        if !patch.is_archived() {
+            anyhow::bail!("Patch `{patch_id}` is not archived");
+        }
         patch.unarchive(&signer)?;
     } else {
+        if patch.is_archived() {
+            anyhow::bail!("Patch `{patch_id}` is already archived");
+        }
         patch.archive(&signer)?;
     }
 
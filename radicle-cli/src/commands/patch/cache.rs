@@ -1,5 +1,7 @@
 use std::ops::ControlFlow;
 
+use radicle::cob::cache::Remove;
+use radicle::patch::cache::Patches as _;
 use radicle::patch::PatchId;
 use radicle::storage::git::Repository;
 use radicle::storage::ReadStorage as _;
@@ -15,6 +17,7 @@ pub enum CacheMode<'a> {
     Patch {
         id: PatchId,
         repository: &'a Repository,
+        invalidate: bool,
     },
 }
 
@@ -31,7 +34,28 @@ pub fn run(mode: CacheMode, profile: &Profile) -> anyhow::Result<()> {
         CacheMode::Patch {
             id,
             repository: repo,
+            invalidate: false,
         } => cache(Some(id), repo, profile)?,
+        CacheMode::Patch {
+            id,
+            repository: repo,
+            invalidate: true,
+        } => invalidate(id, repo, profile)?,
+    }
+    Ok(())
+}
+
+/// Remove the cached patch, and re-derive it from storage, if it still exists.
+fn invalidate(id: PatchId, repository: &Repository, profile: &Profile) -> anyhow::Result<()> {
+    let mut patches = term::cob::patches_mut(profile, repository)?;
+
+    Remove::remove(&mut patches, &id)?;
+    patches.write_changed(&[id])?;
+
+    if patches.get(&id)?.is_some() {
+        term::success!("Successfully invalidated and re-cached patch `{id}`");
+    } else {
+        term::success!("Successfully removed patch `{id}` from the cache");
     }
     Ok(())
 }
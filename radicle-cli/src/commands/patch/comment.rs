@@ -1,8 +1,15 @@
 use super::*;
 
+use std::ffi::OsString;
+use std::fmt;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::str::FromStr;
+
 use radicle::cob;
 use radicle::cob::patch;
 use radicle::cob::thread::CommentId;
+use radicle::cob::{CodeLocation, CodeRange};
 use radicle::patch::ByRevision;
 use radicle::prelude::*;
 use radicle::storage::git::Repository;
@@ -11,10 +18,69 @@ use crate::git;
 use crate::terminal as term;
 use crate::terminal::Element as _;
 
+/// The side of a diff that a line range refers to.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    /// The file before the change.
+    Old,
+    /// The file after the change.
+    #[default]
+    New,
+}
+
+impl fmt::Display for Side {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Old => write!(f, "old"),
+            Self::New => write!(f, "new"),
+        }
+    }
+}
+
+impl FromStr for Side {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "old" => Ok(Self::Old),
+            "new" => Ok(Self::New),
+            _ => Err(anyhow!("invalid side '{s}', expected `old` or `new`")),
+        }
+    }
+}
+
+/// A location in a revision's diff that a comment is anchored to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    /// Path of the file, relative to the repository root.
+    pub path: PathBuf,
+    /// Line range, starting at `1`.
+    pub lines: Range<usize>,
+    /// The side of the diff the lines are on.
+    pub side: Side,
+}
+
+/// Parse a line range of the form `<n>` or `<n>:<m>`, where both lines are included.
+pub fn lines(val: &OsString) -> anyhow::Result<Range<usize>> {
+    let val = val.to_string_lossy();
+    let (start, end) = match val.split_once(':') {
+        Some((start, end)) => (start.parse::<usize>()?, end.parse::<usize>()?),
+        None => {
+            let line = val.parse::<usize>()?;
+            (line, line)
+        }
+    };
+    if start == 0 || end < start {
+        anyhow::bail!("invalid line range '{val}', expected `<n>` or `<n>:<m>`, with 0 < n <= m");
+    }
+    Ok(start..end + 1)
+}
+
 pub fn run(
     revision_id: git::Rev,
     message: term::patch::Message,
     reply_to: Option<git::Rev>,
+    location: Option<Location>,
     quiet: bool,
     repo: &Repository,
     profile: &Profile,
@@ -31,9 +97,12 @@ pub fn run(
     } = patches
         .find_by_revision(&patch::RevisionId::from(revision_id))?
         .ok_or_else(|| anyhow!("Patch revision `{revision_id}` not found"))?;
+    let location = location
+        .map(|l| code_location(&l, revision.base(), &revision.head(), &repo.backend))
+        .transpose()?;
     let mut patch = patch::PatchMut::new(patch_id, patch, &mut patches);
     let (body, reply_to) = prompt(message, reply_to, &revision, repo)?;
-    let comment_id = patch.comment(revision_id, body, reply_to, None, vec![], &signer)?;
+    let comment_id = patch.comment(revision_id, body, reply_to, location, vec![], &signer)?;
     let comment = patch
         .revision(&revision_id)
         .ok_or(anyhow!("error retrieving revision `{revision_id}`"))?
@@ -75,3 +144,180 @@ pub fn prompt<R: WriteRepository + radicle::cob::Store>(
     }
     Ok((body, reply_to))
 }
+
+/// Check that the given location is part of the diff between `base` and `head`, and
+/// return the code location to anchor a comment to.
+fn code_location(
+    location: &Location,
+    base: &radicle::git::Oid,
+    head: &radicle::git::Oid,
+    repo: &radicle::git::raw::Repository,
+) -> anyhow::Result<CodeLocation> {
+    let Location { path, lines, side } = location;
+    let old = repo.find_commit(**base)?.tree()?;
+    let new = repo.find_commit(**head)?.tree()?;
+    let diff = repo.diff_tree_to_tree(Some(&old), Some(&new), None)?;
+
+    let mut changed = Vec::new();
+    let mut ranges = None;
+    for (ix, delta) in diff.deltas().enumerate() {
+        let paths = [delta.old_file().path(), delta.new_file().path()];
+        if !paths.contains(&Some(path.as_path())) {
+            if let Some(p) = delta.new_file().path().or(delta.old_file().path()) {
+                changed.push(p.display().to_string());
+            }
+            continue;
+        }
+        let Some(patch) = radicle::git::raw::Patch::from_diff(&diff, ix)? else {
+            anyhow::bail!(
+                "`{}` is a binary file, it can't be commented on",
+                path.display()
+            );
+        };
+        let mut hunks = Vec::new();
+        for i in 0..patch.num_hunks() {
+            let (hunk, _) = patch.hunk(i)?;
+            let (start, len) = match side {
+                Side::Old => (hunk.old_start(), hunk.old_lines()),
+                Side::New => (hunk.new_start(), hunk.new_lines()),
+            };
+            if len > 0 {
+                hunks.push(start as usize..(start + len) as usize);
+            }
+        }
+        ranges = Some(hunks);
+        break;
+    }
+
+    let Some(ranges) = ranges else {
+        anyhow::bail!(
+            "`{}` is not changed by this revision; changed files are: {}",
+            path.display(),
+            changed.join(", ")
+        );
+    };
+    if ranges.is_empty() {
+        anyhow::bail!(
+            "there are no lines of `{}` in the diff on the {side} side",
+            path.display()
+        );
+    }
+    if !ranges
+        .iter()
+        .any(|r| r.start <= lines.start && lines.end <= r.end)
+    {
+        anyhow::bail!(
+            "line(s) {} of `{}` are not part of the diff on the {side} side; valid lines are: {}",
+            format_lines(lines),
+            path.display(),
+            ranges
+                .iter()
+                .map(format_lines)
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+    let range = Some(CodeRange::Lines {
+        range: lines.clone(),
+    });
+    let (old, new) = match side {
+        Side::Old => (range, None),
+        Side::New => (None, range),
+    };
+
+    Ok(CodeLocation {
+        commit: *head,
+        path: path.to_path_buf(),
+        old,
+        new,
+    })
+}
+
+/// Format a line range the way it's given on the command line.
+fn format_lines(range: &Range<usize>) -> String {
+    if range.len() == 1 {
+        range.start.to_string()
+    } else {
+        format!("{}:{}", range.start, range.end - 1)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use std::fmt::Write as _;
+    use std::path::Path;
+
+    use super::*;
+    use radicle::git::refname;
+    use radicle::test::fixtures;
+
+    fn location(path: &str, lines: Range<usize>, side: Side) -> Location {
+        Location {
+            path: PathBuf::from(path),
+            lines,
+            side,
+        }
+    }
+
+    #[test]
+    fn test_lines() {
+        assert_eq!(lines(&OsString::from("3")).unwrap(), 3..4);
+        assert_eq!(lines(&OsString::from("3:5")).unwrap(), 3..6);
+        assert!(lines(&OsString::from("0")).is_err());
+        assert!(lines(&OsString::from("5:3")).is_err());
+        assert!(lines(&OsString::from("three")).is_err());
+    }
+
+    #[test]
+    fn test_code_location() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (repo, base) = fixtures::repository(tmp.path());
+        let base = radicle::git::Oid::from(base);
+        let sig = radicle::git::raw::Signature::now("anonymous", "anonymous@radicle.xyz").unwrap();
+        let content = (1..=10).fold(String::new(), |mut content, i| {
+            writeln!(content, "Line {i}").unwrap();
+            content
+        });
+        let tree =
+            radicle::git::write_tree(Path::new("README"), content.as_bytes(), &repo).unwrap();
+        let parent = repo.find_commit(*base).unwrap();
+        let head = radicle::git::commit(
+            &repo,
+            &parent,
+            &radicle::git::refs::branch(&refname!("feature")),
+            "Update README",
+            &sig,
+            &tree,
+        )
+        .unwrap()
+        .id()
+        .into();
+
+        let loc = code_location(&location("README", 2..5, Side::New), &base, &head, &repo).unwrap();
+        assert_eq!(loc.commit, head);
+        assert_eq!(loc.path, Path::new("README"));
+        assert_eq!(loc.old, None);
+        assert_eq!(loc.new, Some(CodeRange::Lines { range: 2..5 }));
+
+        let loc = code_location(&location("README", 1..2, Side::Old), &base, &head, &repo).unwrap();
+        assert_eq!(loc.old, Some(CodeRange::Lines { range: 1..2 }));
+        assert_eq!(loc.new, None);
+
+        let err = code_location(&location("README", 9..12, Side::New), &base, &head, &repo)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("line(s) 9:11 of `README`"), "{err}");
+        assert!(err.contains("valid lines are: 1:10"), "{err}");
+
+        let err = code_location(&location("README", 2..3, Side::Old), &base, &head, &repo)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("on the old side; valid lines are: 1"), "{err}");
+
+        let err = code_location(&location("LICENSE", 1..2, Side::New), &base, &head, &repo)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("changed files are: README"), "{err}");
+    }
+}
@@ -2,13 +2,14 @@ use std::collections::{BTreeMap, BTreeSet};
 
 use radicle::cob::patch;
 use radicle::cob::patch::{Patch, PatchId};
+use radicle::identity::alias;
 use radicle::patch::cache::Patches as _;
 use radicle::prelude::*;
 use radicle::profile::Profile;
 use radicle::storage::git::Repository;
 
 use term::format::Author;
-use term::table::{Table, TableOptions};
+use term::table::{Column, Table, TableOptions};
 use term::Element as _;
 
 use crate::terminal as term;
@@ -18,6 +19,7 @@ use crate::terminal::patch as common;
 pub fn run(
     filter: Option<&patch::Status>,
     authors: BTreeSet<Did>,
+    reviewers: BTreeSet<Did>,
     repository: &Repository,
     profile: &Profile,
 ) -> anyhow::Result<()> {
@@ -42,6 +44,14 @@ pub fn run(
                 continue;
             }
         }
+        if !reviewers.is_empty() {
+            let reviewed = patch
+                .revisions()
+                .any(|(_, r)| reviewers.iter().any(|did| r.review_by(did).is_some()));
+            if !reviewed {
+                continue;
+            }
+        }
         all.push((id, patch));
     }
 
@@ -53,8 +63,21 @@ pub fn run(
     let mut table = Table::<10, term::Line>::new(TableOptions {
         spacing: 2,
         border: Some(term::colors::FAINT),
+        plain_if_piped: true,
         ..TableOptions::default()
-    });
+    })
+    .with_columns([
+        Column::new(3),
+        Column::new(3),
+        Column::new(0).min(12),
+        Column::new(1).min(6),
+        Column::new(1),
+        Column::new(2),
+        Column::new(3),
+        Column::new(2),
+        Column::new(2),
+        Column::new(2),
+    ]);
 
     table.header([
         term::format::dim(String::from("●")).into(),
@@ -79,9 +102,10 @@ pub fn run(
         is_me.then(by_rev_time).then(by_id)
     });
 
+    let aliases = alias::aliases(repository)?;
     let mut errors = Vec::new();
     for (id, patch) in &mut all {
-        match row(id, patch, repository, profile) {
+        match row(id, patch, repository, &aliases, profile) {
             Ok(r) => table.push(r),
             Err(e) => errors.push((patch.title(), id, e.to_string())),
         }
@@ -102,6 +126,7 @@ pub fn row(
     id: &PatchId,
     patch: &Patch,
     repository: &Repository,
+    aliases: &BTreeMap<NodeId, alias::Alias>,
     profile: &Profile,
 ) -> anyhow::Result<[term::Line; 10]> {
     let state = patch.state();
@@ -109,7 +134,9 @@ pub fn row(
     let (from, to) = revision.range();
     let stats = common::diff_stats(repository.raw(), &from, &to)?;
     let author = patch.author().id;
-    let (alias, did) = Author::new(&author, profile).labels();
+    let (alias, did) = Author::new(&author, profile)
+        .or_repo_alias(aliases)
+        .labels();
     let mut delegates = repository
         .delegates()?
         .into_iter()
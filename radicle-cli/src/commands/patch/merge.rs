@@ -0,0 +1,321 @@
+use std::process;
+
+use anyhow::{anyhow, Context as _};
+
+use radicle::cob::patch::{Patch, RevisionId};
+use radicle::git;
+use radicle::git::RefString;
+use radicle::patch::PatchId;
+use radicle::prelude::Signer;
+use radicle::storage::git::Repository;
+use radicle::storage::{ReadRepository as _, SignRepository as _, WriteRepository as _};
+use radicle::Profile;
+
+use crate::terminal as term;
+use crate::terminal::args::Error;
+
+#[derive(Debug, Default)]
+pub struct Options {
+    /// Merge the patch in the working copy, instead of fast-forwarding the default branch
+    /// in storage.
+    pub checkout: bool,
+    /// Branch to merge the patch into. Defaults to the project's default branch.
+    /// Implies `checkout`.
+    pub via: Option<RefString>,
+    /// Whether to push the branch to the `rad` remote after merging.
+    /// Implies `checkout`.
+    pub push: bool,
+}
+
+impl Options {
+    /// Whether the patch must be merged in the working copy.
+    fn checkout(&self) -> bool {
+        self.checkout || self.via.is_some() || self.push
+    }
+}
+
+/// Outcome of merging a patch by fast-forwarding.
+enum FastForward {
+    /// The default branch was fast-forwarded to the revision head.
+    Merged,
+    /// The revision doesn't contain the given commit, so it can't be fast-forwarded onto it.
+    Diverged {
+        revision_id: RevisionId,
+        branch: RefString,
+        target: git::Oid,
+    },
+}
+
+pub fn run(
+    patch_id: &PatchId,
+    revision_id: Option<RevisionId>,
+    stored: &Repository,
+    working: Option<&git::raw::Repository>,
+    profile: &Profile,
+    opts: Options,
+) -> anyhow::Result<()> {
+    if opts.checkout() {
+        let working = working
+            .ok_or_else(|| anyhow!("this command must be run from a repository checkout"))?;
+        return checkout(patch_id, revision_id, stored, working, profile, opts);
+    }
+    match (
+        fast_forward(patch_id, revision_id, stored, working, profile)?,
+        working,
+    ) {
+        (FastForward::Merged, _) => Ok(()),
+        // Merging requires a merge commit, which can only be created in a working copy.
+        (FastForward::Diverged { .. }, Some(working)) => {
+            checkout(patch_id, revision_id, stored, working, profile, opts)
+        }
+        (
+            FastForward::Diverged {
+                revision_id,
+                branch,
+                target,
+            },
+            None,
+        ) => Err(Error::WithHint {
+            err: anyhow!(
+                "patch revision `{revision_id}` cannot be fast-forwarded onto `{branch}` at {}",
+                term::format::oid(target),
+            ),
+            hint: "to merge the patch, run `rad patch merge` from a working copy",
+        }
+        .into()),
+    }
+}
+
+/// Merge a patch by fast-forwarding our default branch in storage to the revision head,
+/// and updating the canonical head.
+fn fast_forward(
+    patch_id: &PatchId,
+    revision_id: Option<RevisionId>,
+    stored: &Repository,
+    working: Option<&git::raw::Repository>,
+    profile: &Profile,
+) -> anyhow::Result<FastForward> {
+    let signer = term::signer(profile)?;
+    let doc = stored.identity_doc()?;
+
+    if !doc.is_delegate(&signer.public_key().into()) {
+        anyhow::bail!("only delegates can merge patches");
+    }
+    let project = doc.project()?;
+    let mut patches = term::cob::patches_mut(profile, stored)?;
+    let Ok(mut patch) = patches.get_mut(patch_id) else {
+        anyhow::bail!("Patch `{patch_id}` not found");
+    };
+    if patch.is_merged() {
+        anyhow::bail!("Patch `{patch_id}` is already merged");
+    }
+    let (revision_id, base, head) = revision(&patch, revision_id)?;
+    let branch = git::refs::storage::branch_of(signer.public_key(), project.default_branch());
+    let (_, canonical) = stored.canonical_head()?;
+    let current = stored
+        .reference_oid(
+            signer.public_key(),
+            &git::refs::branch(project.default_branch()),
+        )
+        .ok();
+
+    // Both the canonical head, and our own copy of the default branch, must be contained
+    // in the revision, so that nothing is lost by moving them to it.
+    for target in [Some(canonical), current].into_iter().flatten() {
+        if target != head && !stored.raw().graph_descendant_of(*head, *target)? {
+            return Ok(FastForward::Diverged {
+                revision_id,
+                branch: project.default_branch().clone(),
+                target,
+            });
+        }
+    }
+    stored.raw().reference(
+        branch.as_str(),
+        *head,
+        true,
+        &format!("merge patch {patch_id} (radicle)"),
+    )?;
+
+    let merged = patch.merge(revision_id, head, &signer)?;
+    match working {
+        Some(working) => merged.cleanup(working, &signer)?,
+        None => {
+            stored.sign_refs(&signer)?;
+        }
+    }
+    let canonical = stored.set_head()?;
+
+    term::success!(
+        "Patch {} merged into {} at {}",
+        term::format::tertiary(patch_id),
+        term::format::highlight(project.default_branch()),
+        term::format::secondary(term::format::oid(head)),
+    );
+    if canonical.is_updated() {
+        term::success!(
+            "Canonical head updated to {}",
+            term::format::secondary(canonical.new)
+        );
+    }
+    close_issues(base, head, stored, profile, &signer)?;
+
+    Ok(FastForward::Merged)
+}
+
+/// Merge a patch by running `git merge` in the working copy, and optionally pushing the
+/// result to the `rad` remote.
+fn checkout(
+    patch_id: &PatchId,
+    revision_id: Option<RevisionId>,
+    stored: &Repository,
+    working: &git::raw::Repository,
+    profile: &Profile,
+    opts: Options,
+) -> anyhow::Result<()> {
+    let signer = term::signer(profile)?;
+    let workdir = working
+        .workdir()
+        .ok_or_else(|| anyhow!("cannot merge a patch in a bare repository"))?;
+    let mut patches = term::cob::patches_mut(profile, stored)?;
+    let Ok(patch) = patches.get_mut(patch_id) else {
+        anyhow::bail!("Patch `{patch_id}` not found");
+    };
+    if patch.is_merged() {
+        anyhow::bail!("Patch `{patch_id}` is already merged");
+    }
+    let (revision_id, base, head) = revision(&patch, revision_id)?;
+    let branch = match opts.via {
+        Some(branch) => branch,
+        None => stored.project()?.default_branch().clone(),
+    };
+
+    // The revision head may only be in storage, eg. if the patch was opened by someone else.
+    if working.find_commit(*head).is_err() {
+        git::process::fetch_local(workdir, stored, [head])?;
+    }
+    crate::git::git(workdir, ["checkout", "-q", branch.as_str()])
+        .with_context(|| format!("failed to checkout branch `{branch}`"))?;
+
+    let message = format!("Merge patch {patch_id}\n\n{}", patch.title());
+    if let Err(err) = crate::git::git(
+        workdir,
+        [
+            "merge",
+            "-q",
+            "--no-edit",
+            "-m",
+            &message,
+            &head.to_string(),
+        ],
+    ) {
+        let mut index = working.index()?;
+        index.read(true)?;
+
+        if index.has_conflicts() {
+            crate::git::git(workdir, ["merge", "--abort"]).ok();
+
+            anyhow::bail!(
+                "merging patch `{patch_id}` into `{branch}` resulted in conflicts, the merge was aborted"
+            );
+        }
+        return Err(anyhow!(err).context(format!("failed to merge patch `{patch_id}`")));
+    }
+    let commit = git::Oid::from(working.head()?.peel_to_commit()?.id());
+
+    // The merge commit only exists in the working copy until it is pushed, so the merge
+    // is only recorded once it is published.
+    if !opts.push {
+        term::success!(
+            "Patch {} merged into {} at {} in the working copy",
+            term::format::tertiary(patch_id),
+            term::format::highlight(&branch),
+            term::format::secondary(term::format::oid(commit)),
+        );
+        term::hint(format!(
+            "to publish and record the merge, run `git push {} {branch}`",
+            *radicle::rad::REMOTE_NAME
+        ));
+        return Ok(());
+    }
+
+    let status = process::Command::new("git")
+        .current_dir(workdir)
+        .args(["push", &radicle::rad::REMOTE_NAME, branch.as_str()])
+        .stdout(process::Stdio::inherit())
+        .stderr(process::Stdio::inherit())
+        .status()?;
+
+    if !status.success() {
+        anyhow::bail!(
+            "failed to push `{branch}` to the `{}` remote",
+            *radicle::rad::REMOTE_NAME
+        );
+    }
+
+    // Pushing the default branch records the merge, so we only record it if that didn't
+    // already happen.
+    let Ok(mut patch) = patches.get_mut(patch_id) else {
+        anyhow::bail!("Patch `{patch_id}` not found");
+    };
+    let merged = patch
+        .merges()
+        .any(|(author, merge)| author == signer.public_key() && merge.revision == revision_id);
+
+    if !merged {
+        patch
+            .merge(revision_id, commit, &signer)?
+            .cleanup(working, &signer)?;
+
+        term::success!(
+            "Patch {} merged into {} at {}",
+            term::format::tertiary(patch_id),
+            term::format::highlight(&branch),
+            term::format::secondary(term::format::oid(commit)),
+        );
+        close_issues(base, head, stored, profile, &signer)?;
+    }
+    Ok(())
+}
+
+/// Get the given revision of the patch, or its latest revision, along with its base and head.
+fn revision(
+    patch: &Patch,
+    revision_id: Option<RevisionId>,
+) -> anyhow::Result<(RevisionId, git::Oid, git::Oid)> {
+    match revision_id {
+        Some(id) => {
+            let revision = patch
+                .revision(&id)
+                .ok_or_else(|| anyhow!("Patch revision `{id}` not found"))?;
+            Ok((id, *revision.base(), revision.head()))
+        }
+        None => {
+            let (id, revision) = patch.latest();
+            Ok((id, *revision.base(), revision.head()))
+        }
+    }
+}
+
+/// Close the issues referenced by the commits of a merged patch revision.
+fn close_issues<G: Signer>(
+    base: git::Oid,
+    head: git::Oid,
+    stored: &Repository,
+    profile: &Profile,
+    signer: &G,
+) -> anyhow::Result<()> {
+    let mut issues = term::cob::issues_mut(profile, stored)?;
+    let closed = term::issue::close_referenced(base, head, stored, &mut issues, signer)?;
+
+    for id in closed.issues {
+        term::success!(
+            "Issue {} closed",
+            term::format::tertiary(term::format::cob(&id))
+        );
+    }
+    for warning in closed.warnings {
+        term::warning(warning);
+    }
+    Ok(())
+}
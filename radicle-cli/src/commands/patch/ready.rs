@@ -1,3 +1,4 @@
+use radicle::cob::patch::RevisionId;
 use radicle::prelude::*;
 use radicle::storage::git::Repository;
 
@@ -8,7 +9,7 @@ pub fn run(
     undo: bool,
     profile: &Profile,
     repository: &Repository,
-) -> anyhow::Result<bool> {
+) -> anyhow::Result<(PatchId, RevisionId)> {
     let signer = term::signer(profile)?;
     let mut patches = term::cob::patches_mut(profile, repository)?;
     let Ok(mut patch) = patches.get_mut(patch_id) else {
@@ -16,9 +17,16 @@ pub fn run(
     };
 
     if undo {
-        patch.unready(&signer)
-    } else {
-        patch.ready(&signer)
+        if !patch.unready(&signer)? {
+            anyhow::bail!("the patch must be open to be put in draft state");
+        }
+    } else if !patch.ready(&signer)? {
+        anyhow::bail!("this patch must be in draft state to be put in open state");
     }
-    .map_err(anyhow::Error::from)
+    let (revision_id, _) = patch.latest();
+    let state = if undo { "draft" } else { "ready" };
+
+    term::success!("Patch {patch_id} marked as {state} (revision {revision_id})");
+
+    Ok((*patch_id, revision_id))
 }
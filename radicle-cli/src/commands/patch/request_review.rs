@@ -0,0 +1,44 @@
+use std::collections::BTreeSet;
+
+use super::*;
+
+use radicle::cob::patch::RevisionId;
+use radicle::prelude::Did;
+use radicle::storage::git::Repository;
+
+use crate::terminal as term;
+
+pub fn run(
+    patch_id: &PatchId,
+    revision_id: Option<RevisionId>,
+    add: BTreeSet<Did>,
+    delete: BTreeSet<Did>,
+    profile: &Profile,
+    repository: &Repository,
+) -> anyhow::Result<()> {
+    let signer = term::signer(profile)?;
+    let doc = repository.identity_doc()?;
+    let mut patches = term::cob::patches_mut(profile, repository)?;
+    let Ok(mut patch) = patches.get_mut(patch_id) else {
+        anyhow::bail!("Patch `{patch_id}` not found");
+    };
+    let revision_id = match revision_id {
+        Some(id) => {
+            if patch.revision(&id).is_none() {
+                anyhow::bail!("Patch revision `{id}` not found");
+            }
+            id
+        }
+        None => patch.latest().0,
+    };
+    // Anyone can review a patch, so requesting a review from a non-delegate is allowed.
+    for did in add.iter().filter(|did| !doc.is_delegate(did)) {
+        term::warning(format!(
+            "{} is not a delegate of this repository",
+            term::format::did(did)
+        ));
+    }
+    patch.request_review(revision_id, add, delete, &signer)?;
+
+    Ok(())
+}
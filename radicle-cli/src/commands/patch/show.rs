@@ -5,6 +5,7 @@ use radicle::git;
 use radicle::storage::git::Repository;
 
 use crate::terminal as term;
+use crate::terminal::Element as _;
 
 use super::*;
 
@@ -26,7 +27,9 @@ fn show_patch_diff(patch: &patch::Patch, stored: &Repository) -> anyhow::Result<
 pub fn run(
     patch_id: &PatchId,
     diff: bool,
+    json: bool,
     debug: bool,
+    timeline: bool,
     verbose: bool,
     profile: &Profile,
     stored: &Repository,
@@ -45,6 +48,27 @@ pub fn run(
         println!("{:#?}", patch);
         return Ok(());
     }
+    if timeline {
+        let events = patch::Patches::open(stored)?.timeline(patch_id)?;
+
+        if json {
+            println!("{}", serde_json::to_string_pretty(&events)?);
+        } else {
+            let mut widget = term::VStack::default().border(Some(term::colors::FAINT));
+            for line in term::patch::timeline::events(profile, patch_id, &events) {
+                widget.push(line);
+            }
+            widget.print();
+        }
+        return Ok(());
+    }
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&term::patch::json(&patch, patch_id))?
+        );
+        return Ok(());
+    }
     term::patch::show(&patch, patch_id, verbose, stored, workdir, profile)?;
 
     if diff {
@@ -6,7 +6,7 @@ use anyhow::anyhow;
 use radicle::profile;
 
 use crate::terminal as term;
-use crate::terminal::args::{Args, Error, Help};
+use crate::terminal::args::{Arg, Args, Error, Help};
 
 pub const HELP: Help = Help {
     name: "path",
@@ -24,6 +24,7 @@ Options
     --help    Print help
 
 "#,
+    options: &[Arg::Flag("help", Some('h'))],
 };
 
 pub struct Options {}
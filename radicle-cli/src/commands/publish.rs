@@ -8,7 +8,7 @@ use radicle::prelude::RepoId;
 use radicle::storage::{SignRepository, ValidateRepository, WriteRepository, WriteStorage};
 
 use crate::terminal as term;
-use crate::terminal::args::{Args, Error, Help};
+use crate::terminal::args::{Arg, Args, Error, Help, Kind};
 
 pub const HELP: Help = Help {
     name: "publish",
@@ -34,6 +34,7 @@ Options
 
     --help                    Print help
 "#,
+    options: &[Arg::Positional(Kind::Rid), Arg::Flag("help", Some('h'))],
 };
 
 #[derive(Default, Debug)]
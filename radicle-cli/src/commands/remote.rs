@@ -16,6 +16,7 @@ use radicle::storage::ReadStorage;
 
 use crate::terminal as term;
 use crate::terminal::args;
+use crate::terminal::args::{Arg, Kind};
 use crate::terminal::{Args, Context, Help};
 
 pub const HELP: Help = Help {
@@ -26,7 +27,7 @@ pub const HELP: Help = Help {
 Usage
 
     rad remote [<option>...]
-    rad remote list [--tracked | --untracked | --all] [<option>...]
+    rad remote list [--tracked | --untracked | --all] [--json] [<option>...]
     rad remote add (<did> | <nid>) [--name <string>] [<option>...]
     rad remote rm <name> [<option>...]
 
@@ -35,6 +36,7 @@ List options
     --tracked     Show all remotes that are listed in the working copy
     --untracked   Show all remotes that are listed in the Radicle storage
     --all         Show all remotes in both the Radicle storage and the working copy
+    --json        Output the remotes as JSON
 
 Add options
 
@@ -46,6 +48,22 @@ Options
 
     --help        Print help
 "#,
+    options: &[
+        Arg::Command("list"),
+        Arg::Command("add"),
+        Arg::Command("rm"),
+        Arg::Positional(Kind::Nid),
+        Arg::Flag("tracked", None),
+        Arg::Flag("untracked", None),
+        Arg::Flag("json", None),
+        Arg::Flag("all", None),
+        Arg::Opt("name", Some('n'), Kind::Any),
+        Arg::Flag("fetch", None),
+        Arg::Flag("no-fetch", None),
+        Arg::Flag("sync", None),
+        Arg::Flag("no-sync", None),
+        Arg::Flag("help", Some('h')),
+    ],
 };
 
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
@@ -69,6 +87,7 @@ pub enum Operation {
     },
     List {
         option: ListOption,
+        json: bool,
     },
 }
 
@@ -96,6 +115,7 @@ impl Args for Options {
         let mut list_op: ListOption = ListOption::default();
         let mut fetch = true;
         let mut sync = true;
+        let mut json = false;
 
         while let Some(arg) = parser.next()? {
             match arg {
@@ -125,6 +145,9 @@ impl Args for Options {
                 Long("untracked") if op.unwrap_or_default() == OperationName::List => {
                     list_op = ListOption::Untracked;
                 }
+                Long("json") if op.unwrap_or_default() == OperationName::List => {
+                    json = true;
+                }
 
                 // Add options
                 Long("sync") if op == Some(OperationName::Add) => {
@@ -165,7 +188,10 @@ impl Args for Options {
                 fetch,
                 sync,
             },
-            OperationName::List => Operation::List { option: list_op },
+            OperationName::List => Operation::List {
+                option: list_op,
+                json,
+            },
             OperationName::Rm => Operation::Rm {
                 name: name.ok_or(anyhow!("name required, see `rad remote`"))?,
             },
@@ -202,7 +228,22 @@ pub fn run(options: Options, ctx: impl Context) -> anyhow::Result<()> {
             )?
         }
         Operation::Rm { ref name } => self::rm::run(name, &working)?,
-        Operation::List { option } => match option {
+        Operation::List { option, json: true } => {
+            let tracked = list::tracked(&working)?;
+            let untracked = list::untracked(rid, &profile, tracked.iter())?;
+
+            match option {
+                ListOption::All => list::print_json(rid, tracked.iter(), untracked.iter())?,
+                ListOption::Tracked => list::print_json(rid, tracked.iter(), std::iter::empty())?,
+                ListOption::Untracked => {
+                    list::print_json(rid, std::iter::empty(), untracked.iter())?
+                }
+            }
+        }
+        Operation::List {
+            option,
+            json: false,
+        } => match option {
             ListOption::All => {
                 let tracked = list::tracked(&working)?;
                 let untracked = list::untracked(rid, &profile, tracked.iter())?;
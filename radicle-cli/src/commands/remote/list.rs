@@ -6,6 +6,7 @@ use radicle::node::{Alias, AliasStore as _, NodeId};
 use radicle::storage::ReadStorage as _;
 use radicle::Profile;
 use radicle_term::{Element, Table};
+use serde::Serialize;
 
 use crate::git;
 use crate::terminal as term;
@@ -37,6 +38,20 @@ pub struct Untracked {
     alias: Option<Alias>,
 }
 
+/// A remote, as printed by `rad remote list --json`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Remote {
+    /// Name of the remote in the working copy, or alias of the peer if it isn't tracked.
+    alias: Option<String>,
+    /// The peer's namespace, or `None` for the canonical upstream.
+    nid: Option<NodeId>,
+    /// Fetch URL.
+    url: String,
+    /// Whether the remote is set up in the working copy.
+    tracking: bool,
+}
+
 pub fn tracked(working: &git::Repository) -> anyhow::Result<Vec<Tracked>> {
     Ok(git::rad_remotes(working)?
         .into_iter()
@@ -117,3 +132,30 @@ pub fn print_untracked<'a>(untracked: impl Iterator<Item = &'a Untracked>) {
     }
     t.print();
 }
+
+pub fn print_json<'a>(
+    rid: RepoId,
+    tracked: impl Iterator<Item = &'a Tracked>,
+    untracked: impl Iterator<Item = &'a Untracked>,
+) -> anyhow::Result<()> {
+    let tracked = tracked.filter_map(|Tracked { name, direction }| match direction {
+        Some(Direction::Fetch(url)) => Some(Remote {
+            alias: Some(name.clone()),
+            nid: url.namespace,
+            url: url.to_string(),
+            tracking: true,
+        }),
+        _ => None,
+    });
+    let untracked = untracked.map(|Untracked { remote, alias }| Remote {
+        alias: alias.as_ref().map(|a| a.to_string()),
+        nid: Some(*remote),
+        url: Url::from(rid).with_namespace(*remote).to_string(),
+        tracking: false,
+    });
+    let remotes = tracked.chain(untracked).collect::<Vec<_>>();
+
+    println!("{}", serde_json::to_string_pretty(&remotes)?);
+
+    Ok(())
+}
@@ -13,7 +13,7 @@ use radicle_term::Element as _;
 use crate::commands::rad_sync as sync;
 use crate::node::SyncSettings;
 use crate::terminal as term;
-use crate::terminal::args::{Args, Error, Help};
+use crate::terminal::args::{Arg, Args, Error, Help, Kind};
 
 pub const HELP: Help = Help {
     name: "seed",
@@ -23,9 +23,10 @@ pub const HELP: Help = Help {
 Usage
 
     rad seed [<rid>] [--[no-]fetch] [--from <nid>] [--scope <scope>] [<option>...]
+    rad seed [--json] [<option>...]
 
     The `seed` command, when no Repository ID (<rid>) is provided, will list the
-    repositories being seeded.
+    repositories being seeded. With `--json`, the list is output as JSON.
 
     When a Repository ID (<rid>) is provided it updates or creates the seeding policy for
     that repository. To delete a seeding policy, use the `rad unseed` command.
@@ -41,9 +42,21 @@ Options
     --from <nid>           Fetch from the given node (may be specified multiple times)
     --timeout <secs>       Fetch timeout in seconds (default: 9)
     --scope <scope>        Peer follow scope for this repository
+    --json                 Output the seeding policies as JSON, when listing
     --verbose, -v          Verbose output
     --help                 Print help
 "#,
+    options: &[
+        Arg::Positional(Kind::Rid),
+        Arg::Flag("fetch", None),
+        Arg::Flag("no-fetch", None),
+        Arg::Opt("from", None, Kind::Nid),
+        Arg::Opt("timeout", Some('t'), Kind::Any),
+        Arg::Opt("scope", None, Kind::OneOf(&["followed", "all"])),
+        Arg::Flag("json", None),
+        Arg::Flag("verbose", Some('v')),
+        Arg::Flag("help", Some('h')),
+    ],
 };
 
 #[derive(Debug)]
@@ -55,7 +68,9 @@ pub enum Operation {
         timeout: time::Duration,
         scope: Scope,
     },
-    List,
+    List {
+        json: bool,
+    },
 }
 
 #[derive(Debug)]
@@ -75,6 +90,7 @@ impl Args for Options {
         let mut timeout = time::Duration::from_secs(9);
         let mut seeds: BTreeSet<NodeId> = BTreeSet::new();
         let mut verbose = false;
+        let mut json = false;
 
         while let Some(arg) = parser.next()? {
             match &arg {
@@ -103,6 +119,7 @@ impl Args for Options {
 
                     timeout = time::Duration::from_secs(secs);
                 }
+                Long("json") => json = true,
                 Long("verbose") | Short('v') => verbose = true,
                 Long("help") | Short('h') => {
                     return Err(Error::Help.into());
@@ -114,6 +131,9 @@ impl Args for Options {
         }
 
         let op = match rid {
+            Some(_) if json => {
+                anyhow::bail!("`--json` can only be used when listing seeding policies");
+            }
             Some(rid) => Operation::Seed {
                 rid,
                 fetch: fetch.unwrap_or(true),
@@ -121,7 +141,7 @@ impl Args for Options {
                 timeout,
                 seeds,
             },
-            None => Operation::List,
+            None => Operation::List { json },
         };
 
         Ok((Options { op, verbose }, vec![]))
@@ -154,7 +174,8 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
                 )?;
             }
         }
-        Operation::List => seeding(&profile)?,
+        Operation::List { json: false } => seeding(&profile)?,
+        Operation::List { json: true } => seeding_json(&profile)?,
     }
 
     Ok(())
@@ -223,3 +244,21 @@ pub fn seeding(profile: &Profile) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+pub fn seeding_json(profile: &Profile) -> anyhow::Result<()> {
+    let store = profile.policies()?;
+    let policies = store
+        .seed_policies()?
+        .map(|policy::SeedPolicy { rid, policy }| {
+            serde_json::json!({
+                "rid": rid,
+                "scope": policy.scope(),
+                "policy": Policy::from(policy),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    println!("{}", serde_json::to_string_pretty(&policies)?);
+
+    Ok(())
+}
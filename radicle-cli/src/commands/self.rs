@@ -1,10 +1,11 @@
 use std::ffi::OsString;
 
 use radicle::crypto::ssh;
+use radicle::profile::{Home, Profiles};
 use radicle::Profile;
 
 use crate::terminal as term;
-use crate::terminal::args::{Args, Error, Help};
+use crate::terminal::args::{Arg, Args, Error, Help};
 use crate::terminal::Element as _;
 
 pub const HELP: Help = Help {
@@ -25,8 +26,20 @@ Options
     --config             Show the location of your configuration file
     --ssh-key            Show your public key in OpenSSH format
     --ssh-fingerprint    Show your public key fingerprint in OpenSSH format
+    --list-profiles      List your profiles, marking the current one
     --help               Show help
 "#,
+    options: &[
+        Arg::Flag("did", None),
+        Arg::Flag("alias", None),
+        Arg::Flag("nid", None),
+        Arg::Flag("home", None),
+        Arg::Flag("config", None),
+        Arg::Flag("ssh-key", None),
+        Arg::Flag("ssh-fingerprint", None),
+        Arg::Flag("list-profiles", None),
+        Arg::Flag("help", Some('h')),
+    ],
 };
 
 #[derive(Debug)]
@@ -38,6 +51,7 @@ enum Show {
     Config,
     SshKey,
     SshFingerprint,
+    Profiles,
     All,
 }
 
@@ -76,6 +90,9 @@ impl Args for Options {
                 Long("ssh-fingerprint") if show.is_none() => {
                     show = Some(Show::SshFingerprint);
                 }
+                Long("list-profiles") if show.is_none() => {
+                    show = Some(Show::Profiles);
+                }
                 Long("help") | Short('h') => {
                     return Err(Error::Help.into());
                 }
@@ -93,6 +110,9 @@ impl Args for Options {
 }
 
 pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    if let Show::Profiles = options.show {
+        return profiles();
+    }
     let profile = ctx.profile()?;
 
     match options.show {
@@ -117,15 +137,48 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
         Show::SshFingerprint => {
             term::print(ssh::fmt::fingerprint(profile.id()));
         }
+        Show::Profiles => unreachable!("profiles are listed without loading a profile"),
         Show::All => all(&profile)?,
     }
 
     Ok(())
 }
 
+fn profiles() -> anyhow::Result<()> {
+    let profiles = Profiles::open()?;
+    let current = profiles.current()?;
+    let mut table = term::Table::<4, term::Label>::default();
+
+    for name in profiles.list()? {
+        let profile = Profile::open(Home::new(profiles.path(&name))?)?;
+        let (marker, label) = if name == current {
+            ("*", term::format::positive(name.to_string()))
+        } else {
+            (" ", term::format::default(name.to_string()))
+        };
+        table.push([
+            term::format::positive(marker).into(),
+            label.into(),
+            term::format::primary(profile.config.alias()).into(),
+            term::format::tertiary(profile.did()).into(),
+        ]);
+    }
+    table.print();
+
+    Ok(())
+}
+
 fn all(profile: &Profile) -> anyhow::Result<()> {
     let mut table = term::Table::<2, term::Label>::default();
+    let profiles = Profiles::open()?;
 
+    // Only show the profile when there's more than one to choose from.
+    if profiles.list()?.len() > 1 {
+        table.push([
+            term::format::style("Profile").into(),
+            term::format::positive(profiles.current()?).into(),
+        ]);
+    }
     table.push([
         term::format::style("Alias").into(),
         term::format::primary(profile.config.alias()).into(),
@@ -13,7 +13,7 @@ use radicle_term::Element;
 use serde::Serialize;
 
 use crate::terminal as term;
-use crate::terminal::args::{Args, Error, Help};
+use crate::terminal::args::{Arg, Args, Error, Help};
 
 pub const HELP: Help = Help {
     name: "stats",
@@ -28,6 +28,7 @@ Options
 
     --help       Print help
 "#,
+    options: &[Arg::Flag("help", Some('h'))],
 };
 
 #[derive(Default, Serialize)]
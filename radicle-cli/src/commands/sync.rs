@@ -11,7 +11,9 @@ use radicle::node;
 use radicle::node::address::Store;
 use radicle::node::AliasStore;
 use radicle::node::Seed;
-use radicle::node::{FetchResult, FetchResults, Handle as _, Node, SyncStatus};
+use radicle::node::{
+    FetchProgress, FetchResult, FetchResults, Handle as _, Node, RefClass, SyncStatus,
+};
 use radicle::prelude::{NodeId, Profile, RepoId};
 use radicle::storage::{ReadStorage, RemoteRepository};
 use radicle_term::Element;
@@ -19,7 +21,7 @@ use radicle_term::Element;
 use crate::node::SyncReporting;
 use crate::node::SyncSettings;
 use crate::terminal as term;
-use crate::terminal::args::{Args, Error, Help};
+use crate::terminal::args::{Arg, Args, Error, Help, Kind};
 use crate::terminal::format::Author;
 use crate::terminal::{Table, TableOptions};
 
@@ -50,6 +52,10 @@ Usage
     When `--fetch` or `--announce` are specified on their own, this command
     will only fetch or announce.
 
+    When fetching, `--code-only` restricts the fetch to branches and tags,
+    while `--cobs-only` restricts it to collaborative objects, eg. issues
+    and patches. Identity and signed references are always fetched.
+
     If `--inventory` is specified, the node's inventory is announced to
     the network. This mode does not take an `<rid>`.
 
@@ -63,6 +69,8 @@ Options
     -f, --fetch               Turn on fetching (default: true)
     -a, --announce            Turn on ref announcing (default: true)
     -i, --inventory           Turn on inventory announcing (default: false)
+        --code-only           Only fetch branches and tags
+        --cobs-only           Only fetch collaborative objects
         --timeout   <secs>    How many seconds to wait while syncing
         --seed      <nid>     Sync with the given node (may be specified multiple times)
     -r, --replicas  <count>   Sync with a specific number of seeds
@@ -70,6 +78,22 @@ Options
         --debug               Print debug information afer sync
         --help                Print help
 "#,
+    options: &[
+        Arg::Command("status"),
+        Arg::Positional(Kind::Rid),
+        Arg::Opt("sort-by", None, Kind::OneOf(&["nid", "alias", "status"])),
+        Arg::Flag("fetch", Some('f')),
+        Arg::Flag("announce", Some('a')),
+        Arg::Flag("inventory", Some('i')),
+        Arg::Flag("code-only", None),
+        Arg::Flag("cobs-only", None),
+        Arg::Opt("timeout", Some('t'), Kind::Any),
+        Arg::Opt("seed", None, Kind::Nid),
+        Arg::Opt("replicas", Some('r'), Kind::Any),
+        Arg::Flag("verbose", Some('v')),
+        Arg::Flag("debug", None),
+        Arg::Flag("help", Some('h')),
+    ],
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
@@ -151,6 +175,7 @@ impl Args for Options {
         let mut seeds = BTreeSet::new();
         let mut sort_by = SortBy::default();
         let mut op: Option<Operation> = None;
+        let mut refs = RefClass::default();
 
         while let Some(arg) = parser.next()? {
             match arg {
@@ -178,6 +203,12 @@ impl Args for Options {
 
                     seeds.insert(nid);
                 }
+                Long("code-only") => {
+                    refs = RefClass::CodeOnly;
+                }
+                Long("cobs-only") => {
+                    refs = RefClass::CobsOnly;
+                }
                 Long("announce") | Short('a') => {
                     announce = true;
                 }
@@ -213,6 +244,8 @@ impl Args for Options {
 
         let sync = if inventory && fetch {
             anyhow::bail!("`--inventory` cannot be used with `--fetch`");
+        } else if refs != RefClass::All && !fetch {
+            anyhow::bail!("`--code-only` and `--cobs-only` can only be used with `--fetch`");
         } else if inventory {
             SyncMode::Inventory
         } else {
@@ -221,7 +254,7 @@ impl Args for Options {
                 (true, false) => SyncDirection::Fetch,
                 (false, true) => SyncDirection::Announce,
             };
-            let mut settings = SyncSettings::default().timeout(timeout);
+            let mut settings = SyncSettings::default().timeout(timeout).refs(refs);
 
             if let Some(r) = replicas {
                 settings.replicas = r;
@@ -462,7 +495,7 @@ pub fn fetch(
     // Fetch from specified seeds, connecting to them if necessary.
     for nid in &settings.seeds {
         if node.session(*nid)?.is_some_and(|s| s.is_connected()) {
-            fetch_from(rid, nid, settings.timeout, &mut results, node)?;
+            fetch_from(rid, nid, &settings, &mut results, node)?;
         } else {
             let addrs = db.addresses_of(nid)?;
             if addrs.is_empty() {
@@ -479,7 +512,7 @@ pub fn fetch(
                 settings.timeout,
                 node,
             ) {
-                fetch_from(rid, nid, settings.timeout, &mut results, node)?;
+                fetch_from(rid, nid, &settings, &mut results, node)?;
             }
         }
         // We are done when we either hit our replica count,
@@ -512,7 +545,7 @@ pub fn fetch(
         let Some(nid) = connected.pop_front() else {
             break;
         };
-        fetch_from(rid, &nid, settings.timeout, &mut results, node)?;
+        fetch_from(rid, &nid, &settings, &mut results, node)?;
     }
 
     // Try to connect to disconnected seeds and fetch from them.
@@ -530,7 +563,7 @@ pub fn fetch(
             settings.timeout,
             node,
         ) {
-            fetch_from(rid, &seed.nid, settings.timeout, &mut results, node)?;
+            fetch_from(rid, &seed.nid, &settings, &mut results, node)?;
         }
     }
 
@@ -580,16 +613,36 @@ fn connect(
 fn fetch_from(
     rid: RepoId,
     seed: &NodeId,
-    timeout: time::Duration,
+    settings: &SyncSettings,
     results: &mut FetchResults,
     node: &mut Node,
 ) -> Result<(), node::Error> {
-    let spinner = term::spinner(format!(
+    let message = format!(
         "Fetching {} from {}..",
         term::format::tertiary(rid),
         term::format::tertiary(term::format::node(seed))
-    ));
-    let result = node.fetch(rid, *seed, timeout)?;
+    );
+    let timeout = settings.timeout;
+    let mut spinner = term::spinner(&message);
+    let mut progress = |progress: FetchProgress| {
+        if let FetchProgress::Receiving { objects, bytes, .. } = progress {
+            spinner.message(format!(
+                "{message} ({objects} object(s), {})",
+                term::format::bytes(bytes)
+            ));
+        }
+    };
+    let result = if settings.refs == RefClass::All {
+        match node.fetch_with_progress(rid, *seed, timeout, &mut progress) {
+            // Older nodes can't report progress, so fetch without it.
+            Err(node::Error::Unsupported { .. }) => node.fetch(rid, *seed, timeout)?,
+            result => result?,
+        }
+    } else {
+        node.fetch_refs(rid, *seed, timeout, settings.refs, &mut progress)?
+    };
+    // Only show progress while the fetch is ongoing.
+    spinner.message(message);
 
     match &result {
         FetchResult::Success { .. } => {
@@ -4,7 +4,7 @@ use radicle::prelude::{NodeId, RepoId};
 
 use crate::terminal as term;
 use crate::terminal::args;
-use crate::terminal::args::{Args, Error, Help};
+use crate::terminal::args::{Arg, Args, Error, Help, Kind};
 
 pub const HELP: Help = Help {
     name: "unblock",
@@ -22,6 +22,11 @@ Options
 
     --help          Print help
 "#,
+    options: &[
+        Arg::Positional(Kind::Rid),
+        Arg::Positional(Kind::Nid),
+        Arg::Flag("help", Some('h')),
+    ],
 };
 
 enum Target {
@@ -3,9 +3,13 @@ use std::ffi::OsString;
 use anyhow::anyhow;
 
 use radicle::node::{Handle, NodeId};
+use radicle::storage::{
+    ReadStorage as _, RepositoryError, WriteRepository as _, WriteStorage as _,
+};
+use radicle::Profile;
 
 use crate::terminal as term;
-use crate::terminal::args::{Args, Error, Help};
+use crate::terminal::args::{Arg, Args, Error, Help, Kind};
 
 pub const HELP: Help = Help {
     name: "unfollow",
@@ -19,16 +23,29 @@ Usage
     The `unfollow` command takes a Node ID (<nid>), optionally in DID format,
     and removes the follow policy for that peer.
 
+    The peer's namespaces are pruned from storage by the node once the
+    `node.limits.namespaceMaxAge` period has passed. To prune them right
+    away, use `--prune`. The namespaces of repository delegates are never
+    pruned.
+
 Options
 
+    --prune                Prune the peer's namespaces from all repositories
     --verbose, -v          Verbose output
     --help                 Print help
 "#,
+    options: &[
+        Arg::Positional(Kind::Nid),
+        Arg::Flag("verbose", Some('v')),
+        Arg::Flag("prune", None),
+        Arg::Flag("help", Some('h')),
+    ],
 };
 
 #[derive(Debug)]
 pub struct Options {
     pub nid: NodeId,
+    pub prune: bool,
     pub verbose: bool,
 }
 
@@ -38,6 +55,7 @@ impl Args for Options {
 
         let mut parser = lexopt::Parser::from_args(args);
         let mut nid: Option<NodeId> = None;
+        let mut prune = false;
         let mut verbose = false;
 
         while let Some(arg) = parser.next()? {
@@ -51,6 +69,7 @@ impl Args for Options {
                         anyhow::bail!("invalid Node ID `{}` specified", val.to_string_lossy());
                     }
                 }
+                Long("prune") => prune = true,
                 Long("verbose") | Short('v') => verbose = true,
                 Long("help") | Short('h') => {
                     return Err(Error::Help.into());
@@ -64,6 +83,7 @@ impl Args for Options {
         Ok((
             Options {
                 nid: nid.ok_or_else(|| anyhow!("a Node ID must be specified"))?,
+                prune,
                 verbose,
             },
             vec![],
@@ -87,5 +107,39 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
     if unfollowed {
         term::success!("Follow policy for {} removed", term::format::tertiary(nid),);
     }
+    if options.prune {
+        prune(&profile, &nid)?;
+    }
+    Ok(())
+}
+
+/// Prune the namespaces of a peer from all repositories in storage.
+fn prune(profile: &Profile, nid: &NodeId) -> anyhow::Result<()> {
+    let mut pruned = 0;
+
+    for info in profile.storage.repositories()? {
+        let repo = profile.storage.repository_mut(info.rid)?;
+
+        match repo.prune_namespace(nid) {
+            Ok(0) => {}
+            Ok(_) => {
+                repo.gc()?;
+                pruned += 1;
+            }
+            Err(RepositoryError::DelegateNamespace(_)) => {
+                term::warning(format!(
+                    "Not pruning {} from {}: peer is a delegate",
+                    term::format::tertiary(nid),
+                    term::format::tertiary(info.rid),
+                ));
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+    term::success!(
+        "Pruned namespace of {} from {pruned} repository(s)",
+        term::format::tertiary(nid)
+    );
+
     Ok(())
 }
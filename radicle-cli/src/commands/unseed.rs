@@ -5,7 +5,7 @@ use anyhow::anyhow;
 use radicle::{prelude::*, Node};
 
 use crate::terminal as term;
-use crate::terminal::args::{Args, Error, Help};
+use crate::terminal::args::{Arg, Args, Error, Help, Kind};
 
 pub const HELP: Help = Help {
     name: "unseed",
@@ -15,19 +15,30 @@ pub const HELP: Help = Help {
 Usage
 
     rad unseed <rid> [<option>...]
+    rad unseed --all [<option>...]
 
     The `unseed` command removes the seeding policy, if found,
-    for the given repository.
+    for the given repository, or for all seeded repositories with `--all`.
+    Blocked repositories are left untouched.
 
 Options
 
-    --help      Print help
+    --all           Remove the seeding policies of all seeded repositories
+    --no-confirm    Do not ask for confirmation when using `--all` (default: false)
+    --help          Print help
 "#,
+    options: &[
+        Arg::Positional(Kind::Rid),
+        Arg::Flag("all", None),
+        Arg::Flag("no-confirm", None),
+        Arg::Flag("help", Some('h')),
+    ],
 };
 
 #[derive(Debug)]
 pub struct Options {
-    rid: RepoId,
+    rid: Option<RepoId>,
+    confirm: bool,
 }
 
 impl Args for Options {
@@ -36,12 +47,20 @@ impl Args for Options {
 
         let mut parser = lexopt::Parser::from_args(args);
         let mut rid: Option<RepoId> = None;
+        let mut all = false;
+        let mut confirm = true;
 
         while let Some(arg) = parser.next()? {
             match &arg {
                 Value(val) => {
                     rid = Some(term::args::rid(val)?);
                 }
+                Long("all") => {
+                    all = true;
+                }
+                Long("no-confirm") => {
+                    confirm = false;
+                }
                 Long("help") | Short('h') => {
                     return Err(Error::Help.into());
                 }
@@ -51,14 +70,14 @@ impl Args for Options {
             }
         }
 
-        Ok((
-            Options {
-                rid: rid.ok_or(anyhow!(
-                    "A Repository ID must be provided; see `rad unseed --help`"
-                ))?,
-            },
-            vec![],
-        ))
+        if rid.is_some() && all {
+            anyhow::bail!("`--all` cannot be used with a Repository ID; see `rad unseed --help`");
+        }
+        if rid.is_none() && !all {
+            anyhow::bail!("A Repository ID or `--all` must be provided; see `rad unseed --help`");
+        }
+
+        Ok((Options { rid, confirm }, vec![]))
     }
 }
 
@@ -66,8 +85,37 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
     let profile = ctx.profile()?;
     let mut node = radicle::Node::new(profile.socket());
 
-    delete(options.rid, &mut node, &profile)?;
+    match options.rid {
+        Some(rid) => delete(rid, &mut node, &profile)?,
+        None => delete_all(options.confirm, &mut node, &profile)?,
+    }
+
+    Ok(())
+}
 
+/// Remove the seeding policies of all seeded repositories.
+pub fn delete_all(confirm: bool, node: &mut Node, profile: &Profile) -> anyhow::Result<()> {
+    let rids = profile
+        .policies()?
+        .seed_policies()?
+        .filter_map(|p| p.is_allow().then_some(p.rid))
+        .collect::<Vec<_>>();
+
+    if rids.is_empty() {
+        term::print(term::format::dim("No seeding policies to remove."));
+        return Ok(());
+    }
+    if confirm
+        && !term::confirm(format!(
+            "Remove the seeding policies of {} repositories?",
+            rids.len()
+        ))
+    {
+        return Ok(());
+    }
+    for rid in rids {
+        delete(rid, node, profile)?;
+    }
     Ok(())
 }
 
@@ -0,0 +1,91 @@
+use std::ffi::OsString;
+
+use anyhow::anyhow;
+
+use radicle::profile::{env, ProfileName, Profiles};
+
+use crate::terminal as term;
+use crate::terminal::args::{Arg, Args, Error, Help, Kind};
+
+pub const HELP: Help = Help {
+    name: "use",
+    description: "Switch the active profile",
+    version: env!("RADICLE_VERSION"),
+    usage: r#"
+Usage
+
+    rad use <name> [<option>...]
+
+    Makes the named profile the active profile, which all commands then use.
+    The default profile is named `default`. The `RAD_PROFILE` environment
+    variable takes precedence over the active profile.
+
+    To create a new profile, run `rad auth --profile <name>`. To list all
+    profiles, run `rad self --list-profiles`.
+
+Options
+
+    --help    Print help
+"#,
+    options: &[Arg::Positional(Kind::Any), Arg::Flag("help", Some('h'))],
+};
+
+pub struct Options {
+    name: ProfileName,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut name = None;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("help") | Short('h') => {
+                    return Err(Error::Help.into());
+                }
+                Value(val) if name.is_none() => {
+                    name = Some(term::args::profile_name(&val)?);
+                }
+                _ => return Err(anyhow!(arg.unexpected())),
+            }
+        }
+
+        Ok((
+            Options {
+                name: name.ok_or_else(|| anyhow!("a profile name must be specified"))?,
+            },
+            vec![],
+        ))
+    }
+}
+
+pub fn run(options: Options, _ctx: impl term::Context) -> anyhow::Result<()> {
+    switch(&options.name)
+}
+
+/// Make the given profile the active profile.
+pub fn switch(name: &ProfileName) -> anyhow::Result<()> {
+    let profiles = Profiles::open()?;
+
+    if !profiles.exists(name) {
+        return Err(Error::WithHint {
+            err: anyhow!("profile '{name}' not found"),
+            hint: "to create it, run `rad auth --profile <name>`.",
+        }
+        .into());
+    }
+    profiles.set_active(name)?;
+
+    term::success!("Switched to profile {}", term::format::highlight(name));
+
+    if let Ok(var) = env::var(env::RAD_PROFILE) {
+        term::warning(format!(
+            "`{}` is set to '{var}', and takes precedence over the active profile",
+            env::RAD_PROFILE
+        ));
+    }
+    Ok(())
+}
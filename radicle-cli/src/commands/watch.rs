@@ -4,11 +4,13 @@ use std::{thread, time};
 use anyhow::{anyhow, Context as _};
 
 use radicle::git;
+use radicle::node::{self, seed, Event, Handle as _, Node};
 use radicle::prelude::{NodeId, RepoId};
+use radicle::storage::refs::RefsAt;
 use radicle::storage::{ReadRepository, ReadStorage};
 
 use crate::terminal as term;
-use crate::terminal::args::{Args, Error, Help};
+use crate::terminal::args::{Arg, Args, Error, Help, Kind};
 
 pub const HELP: Help = Help {
     name: "wait",
@@ -17,25 +19,43 @@ pub const HELP: Help = Help {
     usage: r#"
 Usage
 
-    rad watch -r <ref> [-t <oid>] [--repo <rid>] [<option>...]
+    rad watch -r <ref> [-t <oid>] [--repo <rid>] [--seed <nid>] [<option>...]
 
     Watches a Git reference, and optionally exits when it reaches a target value.
     If no target value is passed, exits when the target changes.
 
+    With `--seed`, watches the reference as last synced by the given seed, instead
+    of the local copy. This only works for references under your own namespace.
+
+    Changes are picked up through node events when the node is running, and by
+    checking the reference every interval otherwise.
+
 Options
 
-        --repo      <rid>       The repository to watch (default: `rad .`)
+        --repo, --rid <rid>     The repository to watch (default: `rad .`)
         --node      <nid>       The namespace under which this reference exists
                                 (default: `rad self --nid`)
     -r, --ref       <ref>       The fully-qualified Git reference (branch, tag, etc.) to watch,
                                 eg. 'refs/heads/master'
     -t, --target    <oid>       The target OID (commit hash) that when reached,
                                 will cause the command to exit
+        --seed      <nid>       Watch the reference on the given seed
     -i, --interval  <millis>    How often, in milliseconds, to check the reference target
                                 (default: 1000)
         --timeout   <millis>    Timeout, in milliseconds (default: none)
     -h, --help                  Print help
 "#,
+    options: &[
+        Arg::Opt("repo", None, Kind::Rid),
+        Arg::Opt("rid", None, Kind::Rid),
+        Arg::Opt("node", None, Kind::Nid),
+        Arg::Opt("seed", None, Kind::Nid),
+        Arg::Opt("ref", Some('r'), Kind::Any),
+        Arg::Opt("target", Some('t'), Kind::Any),
+        Arg::Opt("interval", Some('i'), Kind::Any),
+        Arg::Opt("timeout", None, Kind::Any),
+        Arg::Flag("help", Some('h')),
+    ],
 };
 
 pub struct Options {
@@ -43,6 +63,7 @@ pub struct Options {
     refstr: git::RefString,
     target: Option<git::Oid>,
     nid: Option<NodeId>,
+    seed: Option<NodeId>,
     interval: time::Duration,
     timeout: time::Duration,
 }
@@ -54,6 +75,7 @@ impl Args for Options {
         let mut parser = lexopt::Parser::from_args(args);
         let mut rid = None;
         let mut nid: Option<NodeId> = None;
+        let mut seed: Option<NodeId> = None;
         let mut target: Option<git::Oid> = None;
         let mut refstr: Option<git::RefString> = None;
         let mut interval: Option<time::Duration> = None;
@@ -61,7 +83,7 @@ impl Args for Options {
 
         while let Some(arg) = parser.next()? {
             match arg {
-                Long("repo") => {
+                Long("repo") | Long("rid") => {
                     let value = parser.value()?;
                     let value = term::args::rid(&value)?;
 
@@ -73,6 +95,12 @@ impl Args for Options {
 
                     nid = Some(value);
                 }
+                Long("seed") => {
+                    let value = parser.value()?;
+                    let value = term::args::nid(&value)?;
+
+                    seed = Some(value);
+                }
                 Long("ref") | Short('r') => {
                     let value = parser.value()?;
                     let value = term::args::refstring("ref", value)?;
@@ -109,6 +137,7 @@ impl Args for Options {
                 rid,
                 refstr: refstr.ok_or_else(|| anyhow!("a reference must be provided"))?,
                 nid,
+                seed,
                 target,
                 interval: interval.unwrap_or(time::Duration::from_secs(1)),
                 timeout,
@@ -135,33 +164,105 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
         }
     };
     let repo = storage.repository(rid)?;
+    let db = match options.seed {
+        Some(_) if nid != profile.public_key => {
+            anyhow::bail!("`--seed` can only be used to watch references under your own namespace")
+        }
+        Some(_) => Some(profile.database()?),
+        None => None,
+    };
+    let current = || -> anyhow::Result<Option<git::Oid>> {
+        match (options.seed, &db) {
+            (Some(seed), Some(db)) => synced(db, &repo, &rid, &seed, &nid, &qualified),
+            _ => Ok(reference(&repo, &nid, &qualified)?),
+        }
+    };
+    let initial = current()?;
+    let done = |oid: Option<git::Oid>| match options.target {
+        Some(target) => oid == Some(target),
+        None => oid != initial,
+    };
     let now = time::SystemTime::now();
 
-    if let Some(target) = options.target {
-        while reference(&repo, &nid, &qualified)? != Some(target) {
-            thread::sleep(options.interval);
-            if now.elapsed()? >= options.timeout {
-                anyhow::bail!("timed out after {}ms", options.timeout.as_millis());
-            }
+    // Node events tell us when to check the reference again. If they aren't available,
+    // we fall back to checking it every interval.
+    let node = Node::new(profile.socket());
+    let mut events = match node.subscribe(options.interval) {
+        Ok(events) => Some(events),
+        Err(e) => {
+            log::debug!(target: "cli", "Not subscribing to node events: {e}");
+            None
         }
-    } else {
-        let initial = reference(&repo, &nid, &qualified)?;
+    };
+    let mut oid = initial;
 
-        loop {
-            thread::sleep(options.interval);
-            let oid = reference(&repo, &nid, &qualified)?;
-            if oid != initial {
-                term::info!("{}", oid.unwrap_or(git::raw::Oid::zero().into()));
-                break;
-            }
-            if now.elapsed()? >= options.timeout {
-                anyhow::bail!("timed out after {}ms", options.timeout.as_millis());
+    while !done(oid) {
+        if now.elapsed()? >= options.timeout {
+            anyhow::bail!("timed out after {}ms", options.timeout.as_millis());
+        }
+        if let Some(ref mut subscription) = events {
+            match subscription.next() {
+                Some(Ok(event)) if !is_relevant(&event, &rid) => continue,
+                Some(Ok(_)) | Some(Err(node::Error::TimedOut)) => {}
+                Some(Err(e)) => {
+                    log::debug!(target: "cli", "Node event subscription failed: {e}");
+                    events = None;
+                }
+                None => events = None,
             }
+        } else {
+            thread::sleep(options.interval);
         }
+        oid = current()?;
+    }
+    if options.target.is_none() {
+        term::info!("{}", oid.unwrap_or(git::raw::Oid::zero().into()));
     }
     Ok(())
 }
 
+/// Whether an event may be a sign that a reference of the given repository changed.
+fn is_relevant(event: &Event, rid: &RepoId) -> bool {
+    match event {
+        Event::RefsFetched { rid: r, .. }
+        | Event::RefsSynced { rid: r, .. }
+        | Event::LocalRefsAnnounced { rid: r, .. } => r == rid,
+        _ => false,
+    }
+}
+
+/// Get the target of a reference under our namespace, as last synced by the given seed.
+fn synced<R: ReadRepository>(
+    db: &node::Database,
+    repo: &R,
+    rid: &RepoId,
+    seed: &NodeId,
+    nid: &NodeId,
+    qual: &git::Qualified,
+) -> anyhow::Result<Option<git::Oid>> {
+    use seed::Store as _;
+
+    let Some(synced) = db
+        .seeds_for(rid)?
+        .filter_map(|s| s.ok())
+        .find(|s| &s.nid == seed)
+    else {
+        return Ok(None);
+    };
+    let refs = RefsAt {
+        remote: *nid,
+        at: synced.synced_at.oid,
+    };
+    // The seed may have synced refs that we no longer have, eg. after a force-push.
+    match refs.load(repo) {
+        Ok(refs) => Ok(refs.sigrefs.get(qual)),
+        Err(e) => {
+            log::debug!(target: "cli", "Failed to load refs synced by {seed} at {}: {e}", refs.at);
+            Ok(None)
+        }
+    }
+}
+
 fn reference<R: ReadRepository>(
     repo: &R,
     nid: &NodeId,
@@ -318,12 +318,10 @@ impl ToPretty for Moved {
         header: &Self::Context,
         repo: &R,
     ) -> Self::Output {
-        let header = header.pretty(hi, &self.diff.stats().copied(), repo);
+        let old = Some((self.old_path.as_path(), self.old.oid));
+        let new = Some((self.new_path.as_path(), self.new.oid));
 
-        term::VStack::default()
-            .border(Some(term::colors::FAINT))
-            .padding(1)
-            .child(term::Line::default().extend(header))
+        pretty_modification(header, &self.diff, old, new, repo, hi)
     }
 }
 
@@ -390,7 +388,7 @@ impl ToPretty for Copied {
     ) -> Self::Output {
         let header = FileHeader::Copied {
             old_path: self.old_path.clone(),
-            new_path: self.old_path.clone(),
+            new_path: self.new_path.clone(),
         }
         .pretty(hi, &self.diff.stats().copied(), repo);
 
@@ -438,6 +436,7 @@ impl ToPretty for Hunk<Modification> {
             overflow: false,
             spacing: 0,
             border: None,
+            plain_if_piped: false,
         });
         let theme = Theme::default();
 
@@ -555,7 +554,7 @@ impl ToPretty for Modification {
     }
 }
 
-/// Render a file added, deleted or modified.
+/// Render a file added, deleted, modified or moved.
 fn pretty_modification<R: Repo>(
     header: &FileHeader,
     diff: &DiffContent,
@@ -126,6 +126,13 @@ fn run(command: Command) -> Result<(), Option<anyhow::Error>> {
 
 fn run_other(exe: &str, args: &[OsString]) -> Result<(), Option<anyhow::Error>> {
     match exe {
+        "alias" => {
+            term::run_command_args::<rad_alias::Options, _>(
+                rad_alias::HELP,
+                rad_alias::run,
+                args.to_vec(),
+            );
+        }
         "auth" => {
             term::run_command_args::<rad_auth::Options, _>(
                 rad_auth::HELP,
@@ -161,6 +168,13 @@ fn run_other(exe: &str, args: &[OsString]) -> Result<(), Option<anyhow::Error>>
                 args.to_vec(),
             );
         }
+        "completions" => {
+            term::run_command_args::<rad_completions::Options, _>(
+                rad_completions::HELP,
+                rad_completions::run,
+                args.to_vec(),
+            );
+        }
         "config" => {
             term::run_command_args::<rad_config::Options, _>(
                 rad_config::HELP,
@@ -239,6 +253,13 @@ fn run_other(exe: &str, args: &[OsString]) -> Result<(), Option<anyhow::Error>>
                 args.to_vec(),
             );
         }
+        "label" => {
+            term::run_command_args::<rad_label::Options, _>(
+                rad_label::HELP,
+                rad_label::run,
+                args.to_vec(),
+            );
+        }
         "ls" => {
             term::run_command_args::<rad_ls::Options, _>(rad_ls::HELP, rad_ls::run, args.to_vec());
         }
@@ -319,6 +340,13 @@ fn run_other(exe: &str, args: &[OsString]) -> Result<(), Option<anyhow::Error>>
                 args.to_vec(),
             );
         }
+        "use" => {
+            term::run_command_args::<rad_use::Options, _>(
+                rad_use::HELP,
+                rad_use::run,
+                args.to_vec(),
+            );
+        }
         "remote" => term::run_command_args::<rad_remote::Options, _>(
             rad_remote::HELP,
             rad_remote::run,
@@ -4,7 +4,7 @@ use std::io;
 use std::io::Write;
 use std::ops::ControlFlow;
 
-use radicle::node::{self, AnnounceResult};
+use radicle::node::{self, AnnounceResult, RefClass};
 use radicle::node::{Handle as _, NodeId};
 use radicle::storage::{ReadRepository, RepositoryError};
 use radicle::{Node, Profile};
@@ -24,6 +24,8 @@ pub struct SyncSettings {
     pub seeds: BTreeSet<NodeId>,
     /// How long to wait for syncing to complete.
     pub timeout: time::Duration,
+    /// The class of references to fetch.
+    pub refs: RefClass,
 }
 
 impl SyncSettings {
@@ -33,6 +35,12 @@ impl SyncSettings {
         self
     }
 
+    /// Set the class of references to fetch. Defaults to [`RefClass::All`].
+    pub fn refs(mut self, refs: RefClass) -> Self {
+        self.refs = refs;
+        self
+    }
+
     /// Set replicas.
     pub fn replicas(mut self, replicas: usize) -> Self {
         self.replicas = replicas;
@@ -69,6 +77,7 @@ impl Default for SyncSettings {
             replicas: 3,
             seeds: BTreeSet::new(),
             timeout: DEFAULT_SYNC_TIMEOUT,
+            refs: RefClass::default(),
         }
     }
 }
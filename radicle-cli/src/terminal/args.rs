@@ -10,6 +10,7 @@ use radicle::crypto;
 use radicle::git::{Oid, RefString};
 use radicle::node::{Address, Alias};
 use radicle::prelude::{Did, NodeId, RepoId};
+use radicle::profile::ProfileName;
 
 use crate::git::Rev;
 use crate::terminal as term;
@@ -38,6 +39,51 @@ pub struct Help {
     pub description: &'static str,
     pub version: &'static str,
     pub usage: &'static str,
+    /// Sub-commands, options and arguments accepted by the command, used to generate shell
+    /// completions. Keep in sync with `usage`.
+    pub options: &'static [Arg],
+}
+
+/// A command-line argument accepted by a command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arg {
+    /// A sub-command, eg. `list` in `rad patch list`.
+    Command(&'static str),
+    /// A flag that takes no value, eg. `--verbose`, with its optional short form.
+    Flag(&'static str, Option<char>),
+    /// An option that takes a value, eg. `--repo <rid>`, with its optional short form.
+    Opt(&'static str, Option<char>, Kind),
+    /// A positional argument.
+    Positional(Kind),
+}
+
+impl Arg {
+    /// Long name of the flag or option, if this is one.
+    pub fn long(&self) -> Option<&'static str> {
+        match self {
+            Self::Flag(long, _) | Self::Opt(long, _, _) => Some(long),
+            Self::Command(_) | Self::Positional(_) => None,
+        }
+    }
+}
+
+/// Kind of value an argument takes, used to complete it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// A value that can't be completed.
+    Any,
+    /// A file system path.
+    Path,
+    /// A repository in storage.
+    Rid,
+    /// A node in the address book.
+    Nid,
+    /// A patch of the current repository.
+    Patch,
+    /// An issue of the current repository.
+    Issue,
+    /// One of the given values.
+    OneOf(&'static [&'static str]),
 }
 
 impl Help {
@@ -159,6 +205,31 @@ pub fn seconds(val: &OsString) -> anyhow::Result<time::Duration> {
     Ok(time::Duration::from_secs(secs))
 }
 
+/// Parse a duration such as `30s`, `15m`, `12h` or `7d`. A plain number is a number of seconds.
+pub fn duration(val: &OsString) -> anyhow::Result<time::Duration> {
+    let val = val.to_string_lossy();
+    let (n, unit) = match val.find(|c: char| !c.is_ascii_digit()) {
+        Some(i) => val.split_at(i),
+        None => (val.as_ref(), "s"),
+    };
+    let n = u64::from_str(n).map_err(|_| anyhow!("invalid duration '{}'", val))?;
+    let secs = match unit {
+        "s" => Some(n),
+        "m" => n.checked_mul(60),
+        "h" => n.checked_mul(60 * 60),
+        "d" => n.checked_mul(60 * 60 * 24),
+        _ => None,
+    }
+    .ok_or_else(|| {
+        anyhow!(
+            "invalid duration '{}', expected eg. `30m`, `12h` or `7d`",
+            val
+        )
+    })?;
+
+    Ok(time::Duration::from_secs(secs))
+}
+
 pub fn milliseconds(val: &OsString) -> anyhow::Result<time::Duration> {
     let val = val.to_string_lossy();
     let secs =
@@ -167,6 +238,17 @@ pub fn milliseconds(val: &OsString) -> anyhow::Result<time::Duration> {
     Ok(time::Duration::from_millis(secs))
 }
 
+/// Parse a `YYYY-MM-DD` date, as midnight UTC.
+pub fn date(val: &OsString) -> anyhow::Result<cob::Timestamp> {
+    let val = val.to_string_lossy();
+    let date = chrono::NaiveDate::parse_from_str(&val, "%Y-%m-%d")
+        .map_err(|_| anyhow!("invalid date '{}', expected YYYY-MM-DD", val))?;
+    let secs = date.and_time(chrono::NaiveTime::MIN).and_utc().timestamp();
+    let secs = u64::try_from(secs).map_err(|_| anyhow!("invalid date '{}'", val))?;
+
+    Ok(cob::Timestamp::from_secs(secs))
+}
+
 pub fn string(val: &OsString) -> String {
     val.to_string_lossy().to_string()
 }
@@ -192,6 +274,15 @@ pub fn alias(val: &OsString) -> anyhow::Result<Alias> {
     Alias::from_str(val).map_err(|e| e.into())
 }
 
+pub fn profile_name(val: &OsString) -> anyhow::Result<ProfileName> {
+    let val = val.as_os_str();
+    let val = val
+        .to_str()
+        .ok_or_else(|| anyhow!("profile name must be valid UTF-8"))?;
+
+    ProfileName::from_str(val).map_err(|e| e.into())
+}
+
 pub fn issue(val: &OsString) -> anyhow::Result<issue::IssueId> {
     let val = val.to_string_lossy();
     issue::IssueId::from_str(&val).map_err(|_| anyhow!("invalid Issue ID '{}'", val))
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::fmt;
 
 use localtime::LocalTime;
@@ -6,9 +7,10 @@ pub use radicle_term::format::*;
 pub use radicle_term::{style, Paint};
 
 use radicle::cob::ObjectId;
+use radicle::identity::alias;
 use radicle::identity::Visibility;
 use radicle::node::policy::Policy;
-use radicle::node::{Alias, AliasStore, NodeId};
+use radicle::node::{AliasStore, NodeId};
 use radicle::prelude::Did;
 use radicle::profile::{env, Profile};
 use radicle::storage::RefUpdate;
@@ -87,6 +89,16 @@ pub fn timestamp(time: impl Into<LocalTime>) -> Paint<String> {
     Paint::new(fmt.convert(duration.into()))
 }
 
+/// Format a timestamp as a UTC calendar date.
+pub fn date(time: impl Into<LocalTime>) -> Paint<String> {
+    let secs = time.into().as_secs();
+    let date = chrono::DateTime::from_timestamp(secs as i64, 0)
+        .map(|t| t.format("%Y-%m-%d").to_string())
+        .unwrap_or_default();
+
+    Paint::new(date)
+}
+
 pub fn bytes(size: usize) -> Paint<String> {
     const KB: usize = 1024;
     const MB: usize = 1024usize.pow(2);
@@ -171,13 +183,13 @@ impl<'a> fmt::Display for Identity<'a> {
 /// This enum renders (nid, alias) in terminal depending on user variant.
 pub struct Author<'a> {
     nid: &'a NodeId,
-    alias: Option<Alias>,
+    alias: Option<String>,
     you: bool,
 }
 
 impl<'a> Author<'a> {
     pub fn new(nid: &'a NodeId, profile: &Profile) -> Author<'a> {
-        let alias = profile.alias(nid);
+        let alias = profile.alias(nid).map(String::from);
 
         Self {
             nid,
@@ -186,8 +198,17 @@ impl<'a> Author<'a> {
         }
     }
 
+    /// Fall back to the alias published by the author in the repository, if the
+    /// author has no alias of their own.
+    pub fn or_repo_alias(mut self, aliases: &BTreeMap<NodeId, alias::Alias>) -> Self {
+        if self.alias.is_none() {
+            self.alias = aliases.get(self.nid).map(|a| a.name.clone());
+        }
+        self
+    }
+
     pub fn alias(&self) -> Option<term::Label> {
-        self.alias.as_ref().map(|a| a.to_string().into())
+        self.alias.as_ref().map(|a| a.clone().into())
     }
 
     pub fn you(&self) -> Option<term::Label> {
@@ -2,12 +2,18 @@ use std::io;
 
 use radicle_term::table::TableOptions;
 use radicle_term::{Table, VStack};
+use serde_json::json;
 
 use radicle::cob;
 use radicle::cob::issue;
-use radicle::cob::issue::CloseReason;
+use radicle::cob::issue::{CloseReason, IssueId};
+use radicle::crypto::Signer;
+use radicle::git;
+use radicle::storage::git::Repository;
+use radicle::storage::WriteRepository as _;
 use radicle::Profile;
 
+use crate::git::Rev;
 use crate::terminal as term;
 use crate::terminal::format::Author;
 use crate::terminal::Element;
@@ -29,6 +35,7 @@ pub enum Format {
     #[default]
     Full,
     Header,
+    Json,
 }
 
 pub fn get_title_description(
@@ -44,6 +51,10 @@ pub fn show(
     format: Format,
     profile: &Profile,
 ) -> anyhow::Result<()> {
+    if format == Format::Json {
+        println!("{}", serde_json::to_string_pretty(&json(issue, id))?);
+        return Ok(());
+    }
     let labels: Vec<String> = issue.labels().cloned().map(|t| t.into()).collect();
     let assignees: Vec<String> = issue
         .assignees()
@@ -87,6 +98,30 @@ pub fn show(
         ]);
     }
 
+    if let Some(milestone) = issue.milestone() {
+        attrs.push([
+            term::format::tertiary("Milestone".to_owned()).into(),
+            term::format::secondary(milestone.to_owned()).into(),
+        ]);
+    }
+
+    if let Some(due) = issue.due() {
+        let now = cob::Timestamp::from(radicle::profile::env::local_time());
+        let date = term::format::date(*due);
+
+        attrs.push([
+            term::format::tertiary("Due".to_owned()).into(),
+            if issue.is_overdue(now) {
+                term::Line::spaced([
+                    term::format::negative(date).into(),
+                    term::format::negative("(overdue)").italic().dim().into(),
+                ])
+            } else {
+                term::format::default(date).into()
+            },
+        ]);
+    }
+
     attrs.push([
         term::format::tertiary("Status".to_owned()).into(),
         match issue.state() {
@@ -103,6 +138,13 @@ pub fn show(
         },
     ]);
 
+    if let Some(original) = issue.duplicate_of() {
+        attrs.push([
+            term::format::tertiary("Duplicate of".to_owned()).into(),
+            term::format::cob(original).into(),
+        ]);
+    }
+
     let description = issue.description();
     let mut widget = VStack::default()
         .border(Some(term::colors::FAINT))
@@ -129,3 +171,102 @@ pub fn show(
 
     Ok(())
 }
+
+/// Machine-readable representation of an issue, as output by `rad issue show --format json`.
+///
+/// Timestamps are in seconds since Epoch.
+pub fn json(issue: &issue::Issue, id: &cob::ObjectId) -> serde_json::Value {
+    let discussion = issue
+        .comments()
+        .map(|(id, comment)| {
+            json!({
+                "id": id,
+                "author": cob::Author::new(comment.author()),
+                "body": comment.body(),
+                "edits": comment.edits().map(|edit| json!({
+                    "author": cob::Author::new(edit.author),
+                    "body": edit.body,
+                    "timestamp": edit.timestamp.as_secs(),
+                    "embeds": edit.embeds,
+                })).collect::<Vec<_>>(),
+                "embeds": comment.embeds(),
+                "reactions": comment.reactions(),
+                "timestamp": comment.timestamp().as_secs(),
+                "replyTo": comment.reply_to(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "id": id,
+        "author": issue.author(),
+        "title": issue.title(),
+        "description": issue.description(),
+        "state": issue.state(),
+        "duplicateOf": issue.duplicate_of(),
+        "assignees": issue.assignees().collect::<Vec<_>>(),
+        "labels": issue.labels().collect::<Vec<_>>(),
+        "milestone": issue.milestone(),
+        "due": issue.due().map(|due| due.as_secs()),
+        "discussion": discussion,
+    })
+}
+
+/// Issues closed by the commits of a merged patch revision.
+#[derive(Debug, Default)]
+pub struct Closed {
+    /// Issues that were closed.
+    pub issues: Vec<IssueId>,
+    /// Issue references that couldn't be closed.
+    pub warnings: Vec<String>,
+}
+
+/// Close the issues referenced by the `Closes: <issue-id>` and `Fixes: <issue-id>` trailers
+/// of the commits in `base..head`, with reason "solved".
+///
+/// Invalid or unknown issue references are reported as warnings rather than errors, as
+/// they mustn't prevent a patch from being merged.
+pub fn close_referenced<G: Signer>(
+    base: git::Oid,
+    head: git::Oid,
+    repo: &Repository,
+    issues: &mut issue::Cache<issue::Issues<'_, Repository>, cob::cache::StoreWriter>,
+    signer: &G,
+) -> Result<Closed, git::raw::Error> {
+    let mut revwalk = repo.raw().revwalk()?;
+    revwalk.push(*head)?;
+    revwalk.hide(*base)?;
+
+    let mut closed = Closed::default();
+    for oid in revwalk {
+        let commit = repo.raw().find_commit(oid?)?;
+        let Some(message) = commit.message() else {
+            continue;
+        };
+        for reference in issue::closing_references(message) {
+            let commit = term::format::oid(commit.id());
+            let Ok(id) = Rev::from(reference.clone()).resolve::<IssueId>(repo.raw()) else {
+                closed.warnings.push(format!(
+                    "invalid issue `{reference}` referenced by commit {commit}"
+                ));
+                continue;
+            };
+            let Ok(mut issue) = issues.get_mut(&id) else {
+                closed.warnings.push(format!(
+                    "unknown issue `{reference}` referenced by commit {commit}"
+                ));
+                continue;
+            };
+            if *issue.state() != issue::State::Open || closed.issues.contains(&id) {
+                continue;
+            }
+            match issue.close(CloseReason::Solved, None, signer) {
+                Ok(_) => closed.issues.push(id),
+                Err(e) => closed
+                    .warnings
+                    .push(format!("failed to close issue {id}: {e}")),
+            }
+        }
+    }
+    Ok(closed)
+}
@@ -1,11 +1,12 @@
 mod common;
-mod timeline;
+pub mod timeline;
 
 use std::fmt;
 use std::fmt::Write;
 use std::io;
 use std::io::IsTerminal as _;
 
+use serde_json::json;
 use thiserror::Error;
 
 use radicle::cob;
@@ -408,6 +409,21 @@ pub fn show(
         term::format::tertiary("Commits".to_owned()).into(),
         ahead_behind,
     ]);
+    for (i, (did, status)) in revision.reviewers().enumerate() {
+        let label = if i == 0 { "Reviewers" } else { "" };
+        let status = match status {
+            patch::ReviewStatus::Pending => term::format::dim(status.to_string()),
+            patch::ReviewStatus::Accepted => term::format::positive(status.to_string()),
+            patch::ReviewStatus::Rejected => term::format::negative(status.to_string()),
+        };
+        attrs.push([
+            term::format::tertiary(label.to_owned()).into(),
+            term::format::Author::new(did.as_key(), profile)
+                .line()
+                .space()
+                .item(status),
+        ]);
+    }
     attrs.push([
         term::format::tertiary("Status".to_owned()).into(),
         match state {
@@ -454,6 +470,70 @@ pub fn show(
     Ok(())
 }
 
+/// Machine-readable representation of a patch, as output by `rad patch show --format json`.
+///
+/// Timestamps are in seconds since Epoch.
+pub fn json(patch: &Patch, id: &PatchId) -> serde_json::Value {
+    let revisions = patch
+        .revisions()
+        .map(|(id, revision)| {
+            json!({
+                "id": id,
+                "author": revision.author(),
+                "description": revision.description(),
+                "base": revision.base(),
+                "oid": revision.head(),
+                "timestamp": revision.timestamp().as_secs(),
+                "reviewers": revision.reviewers().map(|(did, status)| {
+                    json!({
+                        "id": did,
+                        "status": status,
+                    })
+                }).collect::<Vec<_>>(),
+            })
+        })
+        .collect::<Vec<_>>();
+    let reviews = patch
+        .revisions()
+        .flat_map(|(id, revision)| {
+            revision.reviews().map(move |(_, review)| {
+                json!({
+                    "id": review.id(),
+                    "revision": id,
+                    "author": review.author(),
+                    "verdict": review.verdict(),
+                    "summary": review.summary(),
+                    "timestamp": review.timestamp().as_secs(),
+                })
+            })
+        })
+        .collect::<Vec<_>>();
+    let merges = patch
+        .merges()
+        .map(|(nid, merge)| {
+            json!({
+                "author": cob::Author::new(*nid),
+                "revision": merge.revision,
+                "commit": merge.commit,
+                "timestamp": merge.timestamp.as_secs(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "id": id,
+        "author": patch.author(),
+        "title": patch.title(),
+        "description": patch.description(),
+        "state": patch.state(),
+        "target": patch.target(),
+        "labels": patch.labels().collect::<Vec<_>>(),
+        "revisions": revisions,
+        "reviews": reviews,
+        "merges": merges,
+    })
+}
+
 fn patch_commit_lines(
     patch: &patch::Patch,
     stored: &Repository,
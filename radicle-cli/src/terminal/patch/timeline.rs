@@ -1,7 +1,7 @@
 use std::iter;
 
 use radicle::cob;
-use radicle::cob::patch::{Patch, Verdict};
+use radicle::cob::patch::{Activity, Event, Lifecycle, Patch, PatchId, Verdict};
 use radicle::git;
 use radicle::patch::{Merge, Review, Revision, RevisionId};
 use radicle::profile::Profile;
@@ -16,6 +16,115 @@ pub fn timeline<'a>(
     Timeline::build(profile, patch).into_lines(profile)
 }
 
+/// Render the events of a patch timeline, as obtained from [`radicle::patch::Patches::timeline`],
+/// one line per event.
+pub fn events<'a>(
+    profile: &'a Profile,
+    id: &'a PatchId,
+    events: &'a [Event],
+) -> impl Iterator<Item = term::Line> + 'a {
+    events
+        .iter()
+        .map(move |event| event_line(profile, id, event))
+}
+
+fn event_line(profile: &Profile, id: &PatchId, event: &Event) -> term::Line {
+    let mut line = match &event.activity {
+        Activity::Revision { revision, oid, .. } if git::Oid::from(*revision) == **id => {
+            term::Line::spaced([
+                term::format::positive("●").into(),
+                term::format::default("opened").into(),
+                term::format::parens(term::format::secondary(term::format::oid(*oid))).into(),
+            ])
+        }
+        Activity::Revision { revision, oid, .. } => term::Line::spaced([
+            term::format::tertiary("↑").into(),
+            term::format::default("updated to").into(),
+            term::format::dim(term::format::oid(*revision)).into(),
+            term::format::parens(term::format::secondary(term::format::oid(*oid))).into(),
+        ]),
+        Activity::Review {
+            revision, verdict, ..
+        } => {
+            let (symbol, verb) = match verdict {
+                Some(Verdict::Accept) => (term::format::positive("✓"), "accepted"),
+                Some(Verdict::Reject) => (term::format::negative("✗"), "rejected"),
+                None => (term::format::dim("⋄"), "reviewed"),
+            };
+            term::Line::spaced([
+                symbol.into(),
+                term::format::default(verb).into(),
+                term::format::dim(term::format::oid(*revision)).into(),
+            ])
+        }
+        Activity::Comment {
+            revision,
+            body,
+            location,
+            ..
+        } => term::Line::spaced([
+            term::format::dim("≡").into(),
+            term::format::default(if location.is_some() {
+                "commented inline on"
+            } else {
+                "commented on"
+            })
+            .into(),
+            term::format::dim(term::format::oid(*revision)).into(),
+            term::format::italic(summary(body)).into(),
+        ]),
+        Activity::ReviewComment {
+            review,
+            body,
+            location,
+            ..
+        } => term::Line::spaced([
+            term::format::dim("≡").into(),
+            term::format::default(if location.is_some() {
+                "commented inline on review"
+            } else {
+                "commented on review"
+            })
+            .into(),
+            term::format::dim(term::format::oid(**review)).into(),
+            term::format::italic(summary(body)).into(),
+        ]),
+        Activity::Lifecycle { state } => {
+            let (symbol, verb) = match state {
+                Lifecycle::Open => (term::format::positive("○"), "marked as ready"),
+                Lifecycle::Draft => (term::format::dim("○"), "converted to draft"),
+                Lifecycle::Archived => (term::format::yellow("○"), "archived"),
+            };
+            term::Line::spaced([symbol.into(), term::format::default(verb).into()])
+        }
+        Activity::Merge { revision, commit } => term::Line::spaced([
+            term::format::primary("✓").bold().into(),
+            term::format::default("merged").into(),
+            term::format::dim(term::format::oid(*revision)).into(),
+            term::format::parens(term::format::secondary(term::format::oid(*commit))).into(),
+        ]),
+    };
+    line.push(term::Label::space());
+    line.push(term::format::default("by"));
+    line.push(term::Label::space());
+    line = line.extend(Author::new(&event.author, profile).line());
+    line.push(term::Label::space());
+    line.push(term::format::dim(term::format::timestamp(event.timestamp)));
+    line
+}
+
+/// The first line of a comment body, shortened for display.
+fn summary(body: &str) -> String {
+    const MAX: usize = 48;
+
+    let first = body.lines().next().unwrap_or_default().trim();
+    if first.chars().count() > MAX {
+        format!("{}…", first.chars().take(MAX - 1).collect::<String>())
+    } else {
+        first.to_owned()
+    }
+}
+
 /// The timeline of a [`Patch`].
 ///
 /// A `Patch` will always have opened with a root revision and may
@@ -124,6 +124,7 @@ fn formula(root: &Path, test: impl AsRef<Path>) -> Result<TestFormula, Box<dyn s
         .env(env::RAD_KEYGEN_SEED, RAD_SEED)
         .env(env::RAD_RNG_SEED, "0")
         .env(env::RAD_LOCAL_TIME, "1671125284")
+        .env(radicle_term::table::FORCE_TTY, "1")
         .envs(git::env::GIT_DEFAULT_CONFIG)
         .build(&[
             ("radicle-remote-helper", "git-remote-rad"),
@@ -139,6 +140,11 @@ fn rad_auth() {
     test("examples/rad-auth.md", Path::new("."), None, []).unwrap();
 }
 
+#[test]
+fn rad_auth_profiles() {
+    test("examples/rad-auth-profiles.md", Path::new("."), None, []).unwrap();
+}
+
 #[test]
 fn rad_auth_errors() {
     test("examples/rad-auth-errors.md", Path::new("."), None, []).unwrap();
@@ -158,6 +164,48 @@ fn rad_issue() {
     test("examples/rad-issue.md", &working, Some(home), []).unwrap();
 }
 
+#[test]
+fn rad_completions() {
+    let mut environment = Environment::new();
+    let profile = environment.profile(config::profile("alice"));
+    let home = &profile.home;
+    let working = environment.tmp().join("working");
+
+    // Setup a test repository.
+    fixtures::repository(&working);
+
+    test("examples/rad-init.md", &working, Some(home), []).unwrap();
+    test("examples/rad-completions.md", &working, Some(home), []).unwrap();
+}
+
+#[test]
+fn rad_issue_due() {
+    let mut environment = Environment::new();
+    let profile = environment.profile(config::profile("alice"));
+    let home = &profile.home;
+    let working = environment.tmp().join("working");
+
+    // Setup a test repository.
+    fixtures::repository(&working);
+
+    test("examples/rad-init.md", &working, Some(home), []).unwrap();
+    test("examples/rad-issue-due.md", &working, Some(home), []).unwrap();
+}
+
+#[test]
+fn rad_label() {
+    let mut environment = Environment::new();
+    let profile = environment.profile(config::profile("alice"));
+    let home = &profile.home;
+    let working = environment.tmp().join("working");
+
+    // Setup a test repository.
+    fixtures::repository(&working);
+
+    test("examples/rad-init.md", &working, Some(home), []).unwrap();
+    test("examples/rad-label.md", &working, Some(home), []).unwrap();
+}
+
 #[test]
 fn rad_cob_log() {
     let mut environment = Environment::new();
@@ -890,6 +938,28 @@ fn rad_node() {
     .unwrap();
 }
 
+#[test]
+fn rad_node_config() {
+    let mut environment = Environment::new();
+    let alice = environment.node(Config {
+        external_addresses: vec![
+            Address::from(net::SocketAddr::from(([41, 12, 98, 112], 8776))),
+            Address::from_str("seed.cloudhead.io:8776").unwrap(),
+        ],
+        ..Config::test(Alias::new("alice"))
+    });
+    let working = tempfile::tempdir().unwrap();
+    let alice = alice.spawn();
+
+    test(
+        "examples/rad-node-config.md",
+        working.path(),
+        Some(&alice.home),
+        [],
+    )
+    .unwrap();
+}
+
 #[test]
 fn rad_patch() {
     let mut environment = Environment::new();
@@ -919,6 +989,26 @@ fn rad_patch_diff() {
     test("examples/rad-patch-diff.md", working.path(), Some(home), []).unwrap();
 }
 
+#[test]
+fn rad_patch_comment_inline() {
+    let mut environment = Environment::new();
+    let profile = environment.profile(config::profile("alice"));
+    let working = tempfile::tempdir().unwrap();
+    let home = &profile.home;
+
+    // Setup a test repository.
+    fixtures::repository(working.path());
+
+    test("examples/rad-init.md", working.path(), Some(home), []).unwrap();
+    test(
+        "examples/rad-patch-comment-inline.md",
+        working.path(),
+        Some(home),
+        [],
+    )
+    .unwrap();
+}
+
 #[test]
 fn rad_patch_edit() {
     let mut environment = Environment::new();
@@ -1155,6 +1245,26 @@ fn rad_patch_detached_head() {
     .unwrap();
 }
 
+#[test]
+fn rad_issue_close() {
+    let mut environment = Environment::new();
+    let profile = environment.profile(config::profile("alice"));
+    let working = tempfile::tempdir().unwrap();
+    let home = &profile.home;
+
+    // Setup a test repository.
+    fixtures::repository(working.path());
+
+    test("examples/rad-init.md", working.path(), Some(home), []).unwrap();
+    test(
+        "examples/rad-issue-close.md",
+        working.path(),
+        Some(home),
+        [],
+    )
+    .unwrap();
+}
+
 #[test]
 fn rad_patch_merge_draft() {
     let mut environment = Environment::new();
@@ -1331,6 +1441,86 @@ fn rad_clean() {
         .unwrap();
 }
 
+#[test]
+fn rad_clean_prune() {
+    let mut environment = Environment::new();
+    let alice = environment.node(Config::test(Alias::new("alice")));
+    let bob = environment.node(Config::test(Alias::new("bob")));
+    let eve = environment.node(Config::test(Alias::new("eve")));
+    let working = environment.tmp().join("working");
+
+    // Setup a test project.
+    let acme = RepoId::from_str("z42hL2jL4XNk6K8oHQaSWfMgCL7ji").unwrap();
+    fixtures::repository(working.join("acme"));
+    test(
+        "examples/rad-init.md",
+        working.join("acme"),
+        Some(&alice.home),
+        [],
+    )
+    .unwrap();
+
+    let mut alice = alice.spawn();
+    let mut bob = bob.spawn();
+    let mut eve = eve.spawn();
+    alice.handle.seed(acme, Scope::All).unwrap();
+    alice.handle.follow(bob.id, None).unwrap();
+
+    bob.connect(&alice).converge([&alice]);
+    eve.connect(&alice).converge([&alice]);
+
+    bob.fork(acme, bob.home.path()).unwrap();
+    bob.announce(acme, 1, bob.home.path()).unwrap();
+    eve.fork(acme, eve.home.path()).unwrap();
+    eve.announce(acme, 1, eve.home.path()).unwrap();
+    alice.has_remote_of(&acme, &bob.id);
+    alice.has_remote_of(&acme, &eve.id);
+
+    test(
+        "examples/rad-clean-prune.md",
+        working.join("acme"),
+        Some(&alice.home),
+        [],
+    )
+    .unwrap();
+}
+
+#[test]
+fn rad_unfollow() {
+    let mut environment = Environment::new();
+    let alice = environment.node(Config::test(Alias::new("alice")));
+    let bob = environment.node(Config::test(Alias::new("bob")));
+    let working = environment.tmp().join("working");
+
+    // Setup a test project.
+    let acme = RepoId::from_str("z42hL2jL4XNk6K8oHQaSWfMgCL7ji").unwrap();
+    fixtures::repository(working.join("acme"));
+    test(
+        "examples/rad-init.md",
+        working.join("acme"),
+        Some(&alice.home),
+        [],
+    )
+    .unwrap();
+
+    let mut alice = alice.spawn();
+    let mut bob = bob.spawn();
+    alice.handle.seed(acme, Scope::All).unwrap();
+
+    bob.connect(&alice).converge([&alice]);
+    bob.fork(acme, bob.home.path()).unwrap();
+    bob.announce(acme, 1, bob.home.path()).unwrap();
+    alice.has_remote_of(&acme, &bob.id);
+
+    test(
+        "examples/rad-unfollow.md",
+        working.join("acme"),
+        Some(&alice.home),
+        [],
+    )
+    .unwrap();
+}
+
 #[test]
 fn rad_seed_and_follow() {
     let mut environment = Environment::new();
@@ -1777,6 +1967,52 @@ fn rad_init_sync_and_clone() {
     .unwrap();
 }
 
+#[test]
+fn rad_alias() {
+    let mut environment = Environment::new();
+    let working = environment.tmp().join("working");
+    let alice = environment.node(Config::test(Alias::new("alice")));
+    let bob = environment.node(Config::test(Alias::new("bob")));
+
+    let mut alice = alice.spawn();
+    let bob = bob.spawn();
+
+    alice.connect(&bob);
+    fixtures::repository(working.join("alice"));
+
+    test(
+        "examples/rad-init-sync.md",
+        working.join("alice"),
+        Some(&alice.home),
+        [],
+    )
+    .unwrap();
+    test(
+        "examples/rad-alias.md",
+        working.join("alice"),
+        Some(&alice.home),
+        [],
+    )
+    .unwrap();
+
+    bob.converge([&alice]);
+
+    test(
+        "examples/rad-fetch.md",
+        working.join("bob"),
+        Some(&bob.home),
+        [],
+    )
+    .unwrap();
+    test(
+        "examples/rad-alias-fetch.md",
+        working.join("bob"),
+        Some(&bob.home),
+        [],
+    )
+    .unwrap();
+}
+
 #[test]
 fn rad_fetch() {
     let mut environment = Environment::new();
@@ -1861,6 +2097,20 @@ fn rad_diff() {
     test("examples/rad-diff.md", working, None, []).unwrap();
 }
 
+#[test]
+fn rad_diff_repo() {
+    let mut environment = Environment::new();
+    let profile = environment.profile(config::profile("alice"));
+    let home = &profile.home;
+    let working = environment.tmp().join("working");
+
+    // Setup a test repository.
+    fixtures::repository(&working);
+
+    test("examples/rad-init.md", &working, Some(home), []).unwrap();
+    test("examples/rad-diff-repo.md", &working, Some(home), []).unwrap();
+}
+
 #[test]
 // User tries to clone; no seeds are available, but user has the repo locally.
 fn test_clone_without_seeds() {
@@ -2244,6 +2494,61 @@ fn rad_merge_after_update() {
     .unwrap();
 }
 
+#[test]
+fn rad_patch_merge() {
+    let mut environment = Environment::new();
+    let alice = environment.node(Config::test(Alias::new("alice")));
+    let working = environment.tmp().join("working");
+
+    fixtures::repository(working.join("alice"));
+
+    test(
+        "examples/rad-init.md",
+        working.join("alice"),
+        Some(&alice.home),
+        [],
+    )
+    .unwrap();
+
+    test(
+        "examples/rad-patch-merge.md",
+        working.join("alice"),
+        Some(&alice.home),
+        [],
+    )
+    .unwrap();
+}
+
+#[test]
+fn rad_patch_merge_ff() {
+    let mut environment = Environment::new();
+    let alice = environment.node(Config::test(Alias::new("alice")));
+    let bob = environment.node(Config::test(Alias::new("bob")));
+    let working = environment.tmp().join("working");
+
+    fixtures::repository(working.join("alice"));
+
+    let alice = alice.spawn();
+    let mut bob = bob.spawn();
+
+    bob.connect(&alice).converge([&alice]);
+
+    formula(&environment.tmp(), "examples/rad-patch-merge-ff.md")
+        .unwrap()
+        .home(
+            "alice",
+            working.join("alice"),
+            [("RAD_HOME", alice.home.path().display())],
+        )
+        .home(
+            "bob",
+            bob.home.path(),
+            [("RAD_HOME", bob.home.path().display())],
+        )
+        .run()
+        .unwrap();
+}
+
 #[test]
 fn rad_merge_no_ff() {
     let mut environment = Environment::new();
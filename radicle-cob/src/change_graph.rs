@@ -21,20 +21,65 @@ pub enum EvaluateError {
     MissingRoot(EntryId),
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum ChangeGraphError {
+    #[error("unable to load entry: {0}")]
+    Load(Box<dyn std::error::Error + Sync + Send + 'static>),
+    #[error("invalid signature for entry '{0}'")]
+    InvalidSignature(EntryId),
+}
+
+/// A set of entries whose signatures are known to be valid, eg. because they were
+/// verified when an object was last loaded.
+pub trait Verified {
+    /// Of the given entries, get the ones whose signatures were verified before.
+    fn verified(&self, ids: &[EntryId]) -> BTreeSet<EntryId>;
+    /// Record entries whose signatures were verified.
+    fn insert(&mut self, ids: &[EntryId]);
+}
+
+/// How entry signatures are verified when loading a change graph.
+pub enum Verification<'a> {
+    /// Verify the signature of each entry as it is loaded.
+    Full,
+    /// Skip verifying the entries found in the given set, and add the entries that
+    /// were verified to it. Entries are verified once they are all loaded, so that the
+    /// set is only queried once.
+    ///
+    /// Nb. The set is trusted: an entry found in it is never verified again.
+    CachedOk(&'a mut dyn Verified),
+}
+
+impl Default for Verification<'_> {
+    fn default() -> Self {
+        Self::Full
+    }
+}
+
 /// The graph of changes for a particular collaborative object
 pub(super) struct ChangeGraph {
     object_id: ObjectId,
     graph: Dag<Oid, Entry>,
+    /// Entries that were left out of the graph because their signatures are invalid.
+    forged: BTreeSet<Oid>,
 }
 
 impl ChangeGraph {
     /// Load the change graph from the underlying git store by walking
-    /// backwards from references to the object
+    /// backwards from references to the object.
+    ///
+    /// Entries that fail to load, or whose signature does not verify
+    /// against their author's key, are left out of the graph, along
+    /// with any history only reachable through them. Forged entries are
+    /// recorded in the graph, see [`ChangeGraph::forged`]. If the root
+    /// entry is forged, evaluating the graph fails. How signatures are
+    /// verified is determined by `verification`.
     pub(crate) fn load<'a, S>(
         storage: &S,
         tip_refs: impl Iterator<Item = &'a object::Reference> + 'a,
         typename: &TypeName,
         oid: &ObjectId,
+        verification: &mut Verification<'_>,
     ) -> Option<ChangeGraph>
     where
         S: change::Storage<ObjectId = Oid, Parent = Oid, Signatures = ExtendedSignature>,
@@ -43,16 +88,27 @@ impl ChangeGraph {
 
         let mut builder = GraphBuilder::default();
         let mut edges_to_process: Vec<(Oid, Oid)> = Vec::new();
+        let verify = matches!(verification, Verification::Full);
 
         // Populate the initial set of edges_to_process from the refs we have
         for reference in tip_refs {
             log::trace!(target: "cob", "Loading object from reference '{}'", reference.name);
 
-            match storage.load(reference.target.id) {
+            let id = reference.target.id;
+            match Self::load_change(storage, id, verify) {
                 Ok(change) => {
                     let new_edges = builder.add_change(reference.target.id, change);
                     edges_to_process.extend(new_edges);
                 }
+                Err(e @ ChangeGraphError::InvalidSignature(_)) => {
+                    log::warn!(
+                        target: "cob",
+                        "Rejecting change from reference {}->{}: {e}",
+                        reference.name,
+                        reference.target.id,
+                    );
+                    builder.forge(id);
+                }
                 Err(e) => {
                     log::warn!(
                         target: "cob",
@@ -72,12 +128,22 @@ impl ChangeGraph {
                 parent_commit_id,
                 child_commit_id
             );
-            match storage.load(parent_commit_id) {
+            match Self::load_change(storage, parent_commit_id, verify) {
                 Ok(change) => {
                     let new_edges = builder.add_change(parent_commit_id, change);
                     edges_to_process.extend(new_edges);
                     builder.add_edge(child_commit_id, parent_commit_id);
                 }
+                Err(e @ ChangeGraphError::InvalidSignature(_)) => {
+                    log::warn!(
+                        target: "cob",
+                        "Rejecting change {} with forged parent {}: {e}",
+                        child_commit_id,
+                        parent_commit_id,
+                    );
+                    builder.forge(parent_commit_id);
+                    builder.reject(child_commit_id);
+                }
                 Err(e) => {
                     log::warn!(
                         target: "cob",
@@ -87,24 +153,42 @@ impl ChangeGraph {
                 }
             }
         }
+        if let Verification::CachedOk(cache) = verification {
+            builder.verify(&mut **cache);
+        }
         builder.build(*oid)
     }
 
+    /// Load a single entry from the store, optionally verifying its signature.
+    fn load_change<S>(storage: &S, id: Oid, verify: bool) -> Result<Entry, ChangeGraphError>
+    where
+        S: change::Storage<ObjectId = Oid, Parent = Oid, Signatures = ExtendedSignature>,
+    {
+        let entry = storage
+            .load(id)
+            .map_err(|e| ChangeGraphError::Load(Box::new(e)))?;
+
+        if verify && !entry.valid_signatures() {
+            return Err(ChangeGraphError::InvalidSignature(entry.id));
+        }
+        Ok(entry)
+    }
+
     /// Given a graph evaluate it to produce a collaborative object. This will
-    /// filter out branches of the graph which do not have valid signatures.
+    /// filter out branches of the graph which fail to apply.
     pub(crate) fn evaluate<S, T: Evaluate<S>>(
         mut self,
         store: &S,
     ) -> Result<CollaborativeObject<T>, EvaluateError> {
         let root = *self.object_id;
+        if self.forged.contains(&root) {
+            return Err(EvaluateError::Signature(root));
+        }
         let root = self
             .graph
             .get(&root)
             .ok_or(EvaluateError::MissingRoot(root))?;
 
-        if !root.valid_signatures() {
-            return Err(EvaluateError::Signature(root.id));
-        }
         // Evaluate the root separately, since we can't have a COB without a valid root.
         // Then, traverse the graph starting from the root's dependents.
         let mut object =
@@ -116,10 +200,6 @@ impl ChangeGraph {
         self.graph.prune_by(
             &children,
             |_, entry, siblings| {
-                // Check the entry signatures are valid.
-                if !entry.valid_signatures() {
-                    return ControlFlow::Break(());
-                }
                 // Apply the entry to the state, and if there's an error, prune that branch.
                 if object
                     .apply(entry, siblings.map(|(k, n)| (k, &n.value)), store)
@@ -149,6 +229,12 @@ impl ChangeGraph {
         self.graph.len()
     }
 
+    /// Get the entries that were left out of the graph because their signatures
+    /// are invalid.
+    pub(crate) fn forged(&self) -> &BTreeSet<Oid> {
+        &self.forged
+    }
+
     fn chronological(x: (&Oid, &Entry), y: (&Oid, &Entry)) -> Ordering {
         x.1.timestamp.cmp(&y.1.timestamp).then(x.0.cmp(y.0))
     }
@@ -156,11 +242,19 @@ impl ChangeGraph {
 
 struct GraphBuilder {
     graph: Dag<Oid, Entry>,
+    /// Changes building on top of entries with invalid signatures.
+    rejected: BTreeSet<Oid>,
+    /// Entries with invalid signatures.
+    forged: BTreeSet<Oid>,
 }
 
 impl Default for GraphBuilder {
     fn default() -> Self {
-        GraphBuilder { graph: Dag::new() }
+        GraphBuilder {
+            graph: Dag::new(),
+            rejected: BTreeSet::new(),
+            forged: BTreeSet::new(),
+        }
     }
 }
 
@@ -193,11 +287,55 @@ impl GraphBuilder {
         self.graph.dependency(child, parent);
     }
 
-    fn build(self, object_id: ObjectId) -> Option<ChangeGraph> {
-        if self.graph.roots().next().is_some() {
+    /// Record an entry with an invalid signature. It is never added to the graph.
+    fn forge(&mut self, commit_id: Oid) {
+        self.forged.insert(commit_id);
+    }
+
+    /// Mark a change as rejected. It will be removed from the graph, along with
+    /// all its dependents, when the graph is built.
+    fn reject(&mut self, commit_id: Oid) {
+        self.rejected.insert(commit_id);
+    }
+
+    /// Verify the signatures of the changes in the graph that aren't in the given set of
+    /// verified entries. Changes that don't verify are removed from the graph, along with
+    /// their dependents, and the ones that do are added to the set.
+    fn verify(&mut self, cache: &mut dyn Verified) {
+        let ids = Vec::from_iter(self.graph.sorted());
+        let known = cache.verified(&ids);
+        let mut verified = Vec::new();
+
+        for id in ids.into_iter().filter(|id| !known.contains(id)) {
+            let Some(node) = self.graph.get(&id) else {
+                // Already removed, as the dependent of a forged change.
+                continue;
+            };
+            if node.value.valid_signatures() {
+                verified.push(id);
+            } else {
+                log::warn!(target: "cob", "Rejecting change {id} with invalid signature");
+                self.forge(id);
+                self.reject(id);
+                self.graph.remove(&id);
+            }
+        }
+        // Only entries that were verified and kept are recorded.
+        verified.retain(|id| self.graph.contains(id));
+        cache.insert(&verified);
+    }
+
+    fn build(mut self, object_id: ObjectId) -> Option<ChangeGraph> {
+        for id in &self.rejected {
+            self.graph.remove(id);
+        }
+        // A forged root is reported when the graph is evaluated, rather than treating the
+        // object as missing.
+        if self.graph.roots().next().is_some() || self.forged.contains(&*object_id) {
             Some(ChangeGraph {
                 object_id,
                 graph: self.graph,
+                forged: self.forged,
             })
         } else {
             None
@@ -66,6 +66,7 @@ mod backend;
 pub use backend::git;
 
 mod change_graph;
+pub use change_graph::{ChangeGraphError, EvaluateError, Verification, Verified};
 mod trailers;
 
 pub mod change;
@@ -83,8 +84,8 @@ pub use type_name::TypeName;
 
 pub mod object;
 pub use object::{
-    create, get, info, list, remove, update, CollaborativeObject, Create, Evaluate, ObjectId,
-    Update, Updated,
+    create, get, get_with, info, list, list_with, remove, update, CollaborativeObject, Create,
+    Evaluate, ObjectId, Update, Updated,
 };
 
 #[cfg(test)]
@@ -120,4 +121,13 @@ where
             Signatures = ExtendedSignature,
         >,
 {
+    /// Count the number of objects of the given type, without loading
+    /// or evaluating them.
+    ///
+    /// The default implementation counts the entries returned by
+    /// [`object::Storage::types`]. Backends that can count references
+    /// more cheaply should override it.
+    fn object_count(&self, typename: &TypeName) -> Result<usize, Self::TypesError> {
+        self.types(typename).map(|objects| objects.len())
+    }
 }
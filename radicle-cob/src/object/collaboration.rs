@@ -14,12 +14,12 @@ mod create;
 pub use create::{create, Create};
 
 mod get;
-pub use get::get;
+pub use get::{get, get_with};
 
 pub mod info;
 
 mod list;
-pub use list::list;
+pub use list::{list, list_with};
 
 mod remove;
 pub use remove::remove;
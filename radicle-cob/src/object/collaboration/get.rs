@@ -1,6 +1,7 @@
 // Copyright © 2022 The Radicle Link Contributors
 
-use crate::{change_graph::ChangeGraph, CollaborativeObject, Evaluate, ObjectId, Store, TypeName};
+use crate::change_graph::{ChangeGraph, Verification};
+use crate::{CollaborativeObject, Evaluate, ObjectId, Store, TypeName};
 
 use super::error;
 
@@ -18,6 +19,21 @@ pub fn get<T, S>(
     typename: &TypeName,
     oid: &ObjectId,
 ) -> Result<Option<CollaborativeObject<T>>, error::Retrieve>
+where
+    T: Evaluate<S>,
+    S: Store,
+{
+    get_with(storage, typename, oid, Verification::Full)
+}
+
+/// Get a [`CollaborativeObject`], if it exists, verifying the signatures of its
+/// entries according to the given [`Verification`] mode.
+pub fn get_with<T, S>(
+    storage: &S,
+    typename: &TypeName,
+    oid: &ObjectId,
+    mut verification: Verification<'_>,
+) -> Result<Option<CollaborativeObject<T>>, error::Retrieve>
 where
     T: Evaluate<S>,
     S: Store,
@@ -26,7 +42,7 @@ where
         .objects(typename, oid)
         .map_err(|err| error::Retrieve::Refs { err: Box::new(err) })?;
 
-    ChangeGraph::load(storage, tip_refs.iter(), typename, oid)
+    ChangeGraph::load(storage, tip_refs.iter(), typename, oid, &mut verification)
         .map(|graph| graph.evaluate(storage).map_err(error::Retrieve::evaluate))
         .transpose()
 }
@@ -8,7 +8,8 @@ use std::collections::BTreeSet;
 
 use git_ext::Oid;
 
-use crate::{change_graph::ChangeGraph, ObjectId, Store, TypeName};
+use crate::change_graph::{ChangeGraph, EvaluateError, Verification};
+use crate::{ObjectId, Store, TypeName};
 
 use super::error;
 
@@ -21,6 +22,9 @@ pub struct ChangeGraphInfo {
     /// The "tips" of the change graph, i.e the object IDs pointed to by
     /// references to the object
     pub tips: BTreeSet<Oid>,
+    /// The entries that were left out of the change graph because their
+    /// signatures are invalid
+    pub forged: BTreeSet<Oid>,
 }
 
 /// Retrieve additional information about the change graph of an object. This
@@ -43,11 +47,22 @@ where
     let tip_refs = storage
         .objects(typename, oid)
         .map_err(|err| error::Retrieve::Refs { err: Box::new(err) })?;
-    Ok(
-        ChangeGraph::load(storage, tip_refs.iter(), typename, oid).map(|graph| ChangeGraphInfo {
-            object_id: *oid,
-            number_of_nodes: graph.number_of_nodes(),
-            tips: graph.tips(),
-        }),
-    )
+    let Some(graph) = ChangeGraph::load(
+        storage,
+        tip_refs.iter(),
+        typename,
+        oid,
+        &mut Verification::Full,
+    ) else {
+        return Ok(None);
+    };
+    if graph.forged().contains(&**oid) {
+        return Err(error::Retrieve::evaluate(EvaluateError::Signature(**oid)));
+    }
+    Ok(Some(ChangeGraphInfo {
+        object_id: *oid,
+        number_of_nodes: graph.number_of_nodes(),
+        tips: graph.tips(),
+        forged: graph.forged().clone(),
+    }))
 }
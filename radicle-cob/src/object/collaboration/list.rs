@@ -1,6 +1,7 @@
 // Copyright © 2022 The Radicle Link Contributors
 
-use crate::{change_graph::ChangeGraph, CollaborativeObject, Evaluate, Store, TypeName};
+use crate::change_graph::{ChangeGraph, Verification};
+use crate::{CollaborativeObject, Evaluate, Store, TypeName};
 
 use super::error;
 
@@ -15,6 +16,20 @@ pub fn list<T, S>(
     storage: &S,
     typename: &TypeName,
 ) -> Result<Vec<CollaborativeObject<T>>, error::Retrieve>
+where
+    T: Evaluate<S>,
+    S: Store,
+{
+    list_with(storage, typename, Verification::Full)
+}
+
+/// List a set of [`CollaborativeObject`], verifying the signatures of their
+/// entries according to the given [`Verification`] mode.
+pub fn list_with<T, S>(
+    storage: &S,
+    typename: &TypeName,
+    mut verification: Verification<'_>,
+) -> Result<Vec<CollaborativeObject<T>>, error::Retrieve>
 where
     T: Evaluate<S>,
     S: Store,
@@ -26,7 +41,7 @@ where
     let mut result = Vec::new();
     for (oid, tip_refs) in references {
         log::trace!(target: "cob", "Loading object '{oid}'");
-        let loaded = ChangeGraph::load(storage, tip_refs.iter(), typename, &oid)
+        let loaded = ChangeGraph::load(storage, tip_refs.iter(), typename, &oid, &mut verification)
             .map(|graph| graph.evaluate(storage).map_err(error::Retrieve::evaluate));
 
         match loaded {
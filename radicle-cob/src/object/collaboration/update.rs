@@ -6,8 +6,10 @@ use nonempty::NonEmpty;
 use radicle_crypto::PublicKey;
 
 use crate::{
-    change, change_graph::ChangeGraph, history::EntryId, CollaborativeObject, Embed, Evaluate,
-    ObjectId, Store, TypeName,
+    change,
+    change_graph::{ChangeGraph, Verification},
+    history::EntryId,
+    CollaborativeObject, Embed, Evaluate, ObjectId, Store, TypeName,
 };
 
 use super::error;
@@ -81,8 +83,14 @@ where
         .objects(typename, &object_id)
         .map_err(|err| error::Update::Refs { err: Box::new(err) })?;
 
-    let graph = ChangeGraph::load(storage, existing_refs.iter(), typename, &object_id)
-        .ok_or(error::Update::NoSuchObject)?;
+    let graph = ChangeGraph::load(
+        storage,
+        existing_refs.iter(),
+        typename,
+        &object_id,
+        &mut Verification::Full,
+    )
+    .ok_or(error::Update::NoSuchObject)?;
     let mut object: CollaborativeObject<T> =
         graph.evaluate(storage).map_err(error::Update::evaluate)?;
 
@@ -7,8 +7,9 @@ use nonempty::{nonempty, NonEmpty};
 use qcheck::Arbitrary;
 
 use crate::{
-    create, get, list, object, test::arbitrary::Invalid, update, Create, Entry, ObjectId, TypeName,
-    Update, Updated, Version,
+    create, get, get_with, list, object, object::collaboration::error, test::arbitrary::Invalid,
+    update, Create, Entry, EntryId, EvaluateError, ObjectId, Store, TypeName, Update, Updated,
+    Verification, Verified, Version,
 };
 
 use super::test;
@@ -97,6 +98,13 @@ fn list_cobs() {
     actual.sort_by(|x, y| x.id().cmp(y.id()));
 
     assert_eq!(actual, expected);
+    assert_eq!(storage.object_count(&typename).unwrap(), 2);
+    assert_eq!(
+        storage
+            .object_count(&"xyz.rad.patch".parse::<TypeName>().unwrap())
+            .unwrap(),
+        0
+    );
 }
 
 #[test]
@@ -154,6 +162,204 @@ fn update_cob() {
     assert_eq!(object, expected, "{object:#?} {expected:#?}");
 }
 
+#[test]
+fn forged_entries_are_rejected() {
+    let storage = test::Storage::new();
+    let signer = gen::<MockSigner>(1);
+    let forger = ForgingSigner(gen::<MockSigner>(1));
+    let terry = test::Person::new(&storage, "terry", *signer.public_key()).unwrap();
+    let proj = test::Project::new(&storage, "discworld", *signer.public_key()).unwrap();
+    let proj = test::RemoteProject {
+        project: proj,
+        person: terry,
+    };
+    let typename = "xyz.rad.issue".parse::<TypeName>().unwrap();
+    let cob = create::<NonEmpty<Entry>, _, _>(
+        &storage,
+        &signer,
+        Some(proj.project.content_id),
+        vec![],
+        signer.public_key(),
+        Create {
+            contents: nonempty!(Vec::new()),
+            type_name: typename.clone(),
+            message: "creating xyz.rad.issue".to_string(),
+            embeds: vec![],
+            version: Version::default(),
+        },
+    )
+    .unwrap();
+
+    let Updated { head: forged, .. } = update::<NonEmpty<Entry>, _, _>(
+        &storage,
+        &forger,
+        Some(proj.project.content_id),
+        vec![],
+        forger.public_key(),
+        Update {
+            changes: nonempty!(b"forged".to_vec()),
+            object_id: *cob.id(),
+            type_name: typename.clone(),
+            embeds: vec![],
+            message: "forging xyz.rad.issue".to_string(),
+        },
+    )
+    .unwrap();
+
+    let actual = get::<NonEmpty<Entry>, _>(&storage, &typename, cob.id())
+        .unwrap()
+        .expect("BUG: cob was missing");
+    assert_eq!(actual.history().len(), 1);
+    assert!(!actual.history().tips().contains(&forged));
+
+    let info = crate::object::collaboration::info::changegraph(&storage, &typename, cob.id())
+        .unwrap()
+        .expect("BUG: cob was missing");
+    assert_eq!(info.number_of_nodes, 1);
+    assert!(!info.tips.contains(&forged));
+    assert!(info.forged.contains(&forged));
+}
+
+#[test]
+fn forged_root_is_an_error() {
+    let storage = test::Storage::new();
+    let forger = ForgingSigner(gen::<MockSigner>(1));
+    let terry = test::Person::new(&storage, "terry", *forger.public_key()).unwrap();
+    let proj = test::Project::new(&storage, "discworld", *forger.public_key()).unwrap();
+    let proj = test::RemoteProject {
+        project: proj,
+        person: terry,
+    };
+    let typename = "xyz.rad.issue".parse::<TypeName>().unwrap();
+    let cob = create::<NonEmpty<Entry>, _, _>(
+        &storage,
+        &forger,
+        Some(proj.project.content_id),
+        vec![],
+        forger.public_key(),
+        Create {
+            contents: nonempty!(Vec::new()),
+            type_name: typename.clone(),
+            message: "creating xyz.rad.issue".to_string(),
+            embeds: vec![],
+            version: Version::default(),
+        },
+    )
+    .unwrap();
+
+    let err = get::<NonEmpty<Entry>, _>(&storage, &typename, cob.id()).unwrap_err();
+    let error::Retrieve::Evaluate(err) = err else {
+        panic!("unexpected error: {err}");
+    };
+    assert!(matches!(
+        err.downcast_ref::<EvaluateError>(),
+        Some(EvaluateError::Signature(id)) if id == &**cob.id()
+    ));
+    assert!(list::<NonEmpty<Entry>, _>(&storage, &typename)
+        .unwrap()
+        .is_empty());
+    assert!(
+        crate::object::collaboration::info::changegraph(&storage, &typename, cob.id()).is_err()
+    );
+}
+
+#[test]
+fn verification_modes() {
+    let storage = test::Storage::new();
+    let signer = gen::<MockSigner>(1);
+    let forger = ForgingSigner(gen::<MockSigner>(1));
+    let terry = test::Person::new(&storage, "terry", *signer.public_key()).unwrap();
+    let proj = test::Project::new(&storage, "discworld", *signer.public_key()).unwrap();
+    let proj = test::RemoteProject {
+        project: proj,
+        person: terry,
+    };
+    let typename = "xyz.rad.issue".parse::<TypeName>().unwrap();
+    let cob = create::<NonEmpty<Entry>, _, _>(
+        &storage,
+        &signer,
+        Some(proj.project.content_id),
+        vec![],
+        signer.public_key(),
+        Create {
+            contents: nonempty!(Vec::new()),
+            type_name: typename.clone(),
+            message: "creating xyz.rad.issue".to_string(),
+            embeds: vec![],
+            version: Version::default(),
+        },
+    )
+    .unwrap();
+
+    let entries = 128;
+    for i in 0..entries {
+        update::<NonEmpty<Entry>, _, _>(
+            &storage,
+            &signer,
+            Some(proj.project.content_id),
+            vec![],
+            signer.public_key(),
+            Update {
+                changes: nonempty!(i.to_string().into_bytes()),
+                object_id: *cob.id(),
+                type_name: typename.clone(),
+                embeds: vec![],
+                message: "commenting xyz.rad.issue".to_string(),
+            },
+        )
+        .unwrap();
+    }
+
+    // The first load verifies all entries, the second load only uses the cache.
+    let mut cache = Cache::default();
+    let first = get_with::<NonEmpty<Entry>, _>(
+        &storage,
+        &typename,
+        cob.id(),
+        Verification::CachedOk(&mut cache),
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(first.history().len(), entries + 1);
+    assert_eq!(cache.verified.len(), entries + 1);
+    assert_eq!(cache.inserted, entries + 1);
+
+    let second = get_with::<NonEmpty<Entry>, _>(
+        &storage,
+        &typename,
+        cob.id(),
+        Verification::CachedOk(&mut cache),
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(second, first);
+    assert_eq!(cache.inserted, entries + 1);
+
+    // A forged entry is caught when verifying, even if the cache says otherwise.
+    let Updated { head: forged, .. } = update::<NonEmpty<Entry>, _, _>(
+        &storage,
+        &forger,
+        Some(proj.project.content_id),
+        vec![],
+        forger.public_key(),
+        Update {
+            changes: nonempty!(b"forged".to_vec()),
+            object_id: *cob.id(),
+            type_name: typename.clone(),
+            embeds: vec![],
+            message: "forging xyz.rad.issue".to_string(),
+        },
+    )
+    .unwrap();
+    cache.verified.insert(forged);
+
+    let actual = get_with::<NonEmpty<Entry>, _>(&storage, &typename, cob.id(), Verification::Full)
+        .unwrap()
+        .unwrap();
+    assert_eq!(actual.history().len(), entries + 1);
+    assert!(!actual.history().tips().contains(&forged));
+}
+
 #[test]
 fn traverse_cobs() {
     let storage = test::Storage::new();
@@ -298,6 +504,23 @@ fn invalid_parse_refstr(oid: Invalid<ObjectId>, typename: TypeName) {
     );
 }
 
+/// A signer that produces signatures over the wrong message.
+struct ForgingSigner(MockSigner);
+
+impl Signer for ForgingSigner {
+    fn public_key(&self) -> &PublicKey {
+        self.0.public_key()
+    }
+
+    fn sign(&self, msg: &[u8]) -> crypto::Signature {
+        self.0.sign(&[msg, b"forged"].concat())
+    }
+
+    fn try_sign(&self, msg: &[u8]) -> Result<crypto::Signature, crypto::SignerError> {
+        Ok(self.sign(msg))
+    }
+}
+
 fn gen<T: Arbitrary>(size: usize) -> T {
     let mut gen = qcheck::Gen::new(size);
 
@@ -326,3 +549,24 @@ fn copy_to(
     repo.reference(&name, original, false, "copying object reference")?;
     Ok(())
 }
+
+/// A set of verified entries that counts insertions.
+#[derive(Default)]
+struct Cache {
+    verified: std::collections::BTreeSet<EntryId>,
+    inserted: usize,
+}
+
+impl Verified for Cache {
+    fn verified(&self, ids: &[EntryId]) -> std::collections::BTreeSet<EntryId> {
+        ids.iter()
+            .filter(|id| self.verified.contains(id))
+            .copied()
+            .collect()
+    }
+
+    fn insert(&mut self, ids: &[EntryId]) {
+        self.inserted += ids.len();
+        self.verified.extend(ids);
+    }
+}
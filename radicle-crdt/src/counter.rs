@@ -0,0 +1,221 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Max, Semilattice};
+
+/// Grow-only counter.
+///
+/// Each actor keeps track of its own count, and counters are merged by taking
+/// the maximum count of each actor. The value of the counter is the sum of all
+/// actor counts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+#[serde(bound(deserialize = "K: Ord + Deserialize<'de>"))]
+pub struct GCounter<K> {
+    inner: BTreeMap<K, Max<u64>>,
+}
+
+impl<K: Ord> GCounter<K> {
+    /// Increment the count of the given actor.
+    pub fn increment(&mut self, actor: K) {
+        self.inner.entry(actor).or_default().incr();
+    }
+
+    /// Get the count of the given actor.
+    pub fn count(&self, actor: &K) -> u64 {
+        self.inner.get(actor).map(|c| *c.get()).unwrap_or_default()
+    }
+}
+
+impl<K> GCounter<K> {
+    /// Get the value of the counter, ie. the sum of all actor counts.
+    pub fn get(&self) -> u64 {
+        self.inner
+            .values()
+            .fold(0, |acc, c| acc.saturating_add(*c.get()))
+    }
+
+    /// Iterate over the actors and their counts.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, u64)> {
+        self.inner.iter().map(|(k, c)| (k, *c.get()))
+    }
+}
+
+impl<K> Default for GCounter<K> {
+    fn default() -> Self {
+        Self {
+            inner: BTreeMap::default(),
+        }
+    }
+}
+
+impl<K: Ord> Semilattice for GCounter<K> {
+    fn merge(&mut self, other: Self) {
+        for (k, c) in other.inner {
+            self.inner.entry(k).or_default().merge(c);
+        }
+    }
+}
+
+/// Positive-negative counter.
+///
+/// Combines two [`GCounter`]s, one for increments and one for decrements,
+/// so that the counter can go down as well as up.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(deserialize = "K: Ord + Deserialize<'de>"))]
+pub struct PNCounter<K> {
+    #[serde(rename = "p")]
+    inc: GCounter<K>,
+    #[serde(rename = "n")]
+    dec: GCounter<K>,
+}
+
+impl<K: Ord> PNCounter<K> {
+    /// Increment the counter on behalf of the given actor.
+    pub fn increment(&mut self, actor: K) {
+        self.inc.increment(actor);
+    }
+
+    /// Decrement the counter on behalf of the given actor.
+    pub fn decrement(&mut self, actor: K) {
+        self.dec.increment(actor);
+    }
+
+    /// Get the net count of the given actor.
+    pub fn count(&self, actor: &K) -> i64 {
+        net(self.inc.count(actor), self.dec.count(actor))
+    }
+}
+
+impl<K> PNCounter<K> {
+    /// Get the value of the counter, ie. the increments minus the decrements.
+    pub fn get(&self) -> i64 {
+        net(self.inc.get(), self.dec.get())
+    }
+}
+
+impl<K> Default for PNCounter<K> {
+    fn default() -> Self {
+        Self {
+            inc: GCounter::default(),
+            dec: GCounter::default(),
+        }
+    }
+}
+
+impl<K: Ord> Semilattice for PNCounter<K> {
+    fn merge(&mut self, other: Self) {
+        self.inc.merge(other.inc);
+        self.dec.merge(other.dec);
+    }
+}
+
+/// Subtract two counts, saturating at the bounds of an `i64`.
+fn net(inc: u64, dec: u64) -> i64 {
+    let net = inc as i128 - dec as i128;
+    net.clamp(i64::MIN as i128, i64::MAX as i128) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use qcheck_macros::quickcheck;
+
+    use super::*;
+
+    fn gcounter(incs: Vec<u8>) -> GCounter<u8> {
+        let mut counter = GCounter::default();
+        for actor in incs {
+            counter.increment(actor);
+        }
+        counter
+    }
+
+    fn pncounter(ops: Vec<(u8, bool)>) -> PNCounter<u8> {
+        let mut counter = PNCounter::default();
+        for (actor, inc) in ops {
+            if inc {
+                counter.increment(actor);
+            } else {
+                counter.decrement(actor);
+            }
+        }
+        counter
+    }
+
+    #[quickcheck]
+    fn prop_gcounter_semilattice(a: Vec<u8>, b: Vec<u8>, c: Vec<u8>) {
+        crate::test::assert_laws(&gcounter(a), &gcounter(b), &gcounter(c));
+    }
+
+    #[quickcheck]
+    fn prop_pncounter_semilattice(a: Vec<(u8, bool)>, b: Vec<(u8, bool)>, c: Vec<(u8, bool)>) {
+        crate::test::assert_laws(&pncounter(a), &pncounter(b), &pncounter(c));
+    }
+
+    #[quickcheck]
+    fn prop_gcounter_serde(a: Vec<u8>) {
+        let a = gcounter(a);
+        let json = serde_json::to_string(&a).unwrap();
+
+        assert_eq!(serde_json::from_str::<GCounter<u8>>(&json).unwrap(), a);
+    }
+
+    #[quickcheck]
+    fn prop_pncounter_serde(a: Vec<(u8, bool)>) {
+        let a = pncounter(a);
+        let json = serde_json::to_string(&a).unwrap();
+
+        assert_eq!(serde_json::from_str::<PNCounter<u8>>(&json).unwrap(), a);
+    }
+
+    #[test]
+    fn test_gcounter() {
+        let mut a = GCounter::default();
+        let mut b = GCounter::default();
+
+        a.increment('a');
+        a.increment('a');
+        b.increment('a');
+        b.increment('b');
+
+        assert_eq!(a.get(), 2);
+        assert_eq!(b.get(), 2);
+        // Concurrent increments by the same actor are not double counted.
+        assert_eq!(a.clone().join(b.clone()).get(), 3);
+        assert_eq!(a.join(b).count(&'a'), 2);
+    }
+
+    #[test]
+    fn test_pncounter() {
+        let mut a = PNCounter::default();
+        let mut b = PNCounter::default();
+
+        a.increment('a');
+        a.decrement('a');
+        a.increment('a');
+        b.increment('b');
+        b.decrement('b');
+        b.decrement('b');
+
+        assert_eq!(a.get(), 1);
+        assert_eq!(b.get(), -1);
+        assert_eq!(a.count(&'a'), 1);
+        assert_eq!(a.join(b).get(), 0);
+    }
+
+    #[test]
+    fn test_serde_canonical() {
+        let a = pncounter(vec![(2, true), (1, true), (2, false), (1, true)]);
+        let b = pncounter(vec![(1, true), (2, false), (1, true), (2, true)]);
+
+        assert_eq!(
+            serde_json::to_string(&a).unwrap(),
+            r#"{"p":{"1":2,"2":1},"n":{"2":1}}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&a).unwrap(),
+            serde_json::to_string(&b).unwrap()
+        );
+    }
+}
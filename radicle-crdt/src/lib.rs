@@ -3,6 +3,7 @@
 #![allow(clippy::collapsible_else_if)]
 #![allow(clippy::type_complexity)]
 pub mod clock;
+pub mod counter;
 pub mod gmap;
 pub mod gset;
 pub mod immutable;
@@ -18,6 +19,7 @@ pub mod test;
 ////////////////////////////////////////////////////////////////////////////////
 
 pub use clock::Lamport;
+pub use counter::{GCounter, PNCounter};
 pub use gmap::GMap;
 pub use gset::GSet;
 pub use immutable::Immutable;
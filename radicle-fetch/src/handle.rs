@@ -8,7 +8,8 @@ use radicle::identity::{Doc, DocError};
 use radicle::storage::git::Repository;
 use radicle::storage::ReadRepository;
 
-use crate::policy::{Allowed, BlockList};
+use crate::policy::{Allowed, BlockList, RefsFilter};
+use crate::progress::{Progress, Reporter};
 use crate::transport::{ConnectionStream, Transport};
 
 /// The handle used for pulling or cloning changes from a remote peer.
@@ -25,6 +26,11 @@ pub struct Handle<S> {
     /// key in [`crate::pull`], however, we choose to allow the local
     /// peer's key in [`crate::clone`].
     pub(crate) blocked: BlockList,
+    /// The filter for references that should not be fetched from
+    /// any remote.
+    pub(crate) filter: RefsFilter,
+    /// Reports the progress of receiving packfiles.
+    pub(crate) reporter: Reporter,
     // Signals to the pack writer to interrupt the process
     pub(crate) interrupt: Arc<AtomicBool>,
 }
@@ -35,6 +41,7 @@ impl<S> Handle<S> {
         repo: Repository,
         follow: Allowed,
         blocked: BlockList,
+        filter: RefsFilter,
         connection: S,
     ) -> Result<Self, error::Init>
     where
@@ -49,6 +56,8 @@ impl<S> Handle<S> {
             allowed: follow,
             transport,
             blocked,
+            filter,
+            reporter: Reporter::default(),
             interrupt: Arc::new(AtomicBool::new(false)),
         })
     }
@@ -80,6 +89,16 @@ impl<S> Handle<S> {
     pub fn allowed(&self) -> Allowed {
         self.allowed.clone()
     }
+
+    pub fn filter(&self) -> &RefsFilter {
+        &self.filter
+    }
+
+    /// Call `callback` whenever progress is made receiving packfiles from the
+    /// remote.
+    pub fn on_progress(&mut self, callback: impl Fn(Progress) + Send + Sync + 'static) {
+        self.reporter = Reporter::new(callback);
+    }
 }
 
 pub mod error {
@@ -1,6 +1,7 @@
 pub mod git;
 pub mod handle;
 pub mod policy;
+pub mod progress;
 pub mod transport;
 
 pub(crate) mod sigrefs;
@@ -16,8 +17,9 @@ use gix_protocol::handshake;
 
 pub use gix_protocol::{transport::bstr::ByteSlice, RemoteProgress};
 pub use handle::Handle;
-pub use policy::{Allowed, BlockList, Scope};
-pub use state::{FetchLimit, FetchResult};
+pub use policy::{Allowed, BlockList, RefClass, RefsFilter, Scope};
+pub use progress::Progress;
+pub use state::{FetchLimit, FetchOptions, FetchResult, RolledBack};
 pub use transport::Transport;
 
 use radicle::crypto::PublicKey;
@@ -46,14 +48,48 @@ pub enum Error {
     ReplicateSelf,
 }
 
+impl Error {
+    /// Check whether the handshake failed because the stream was closed or reset by the
+    /// remote, eg. when reusing a stream that the remote has since closed. Since the
+    /// handshake is the first exchange on a stream, such failures are detected quickly,
+    /// and the fetch can be retried on a new stream.
+    pub fn is_stream_closed(&self) -> bool {
+        let Self::Handshake { err } = self else {
+            return false;
+        };
+        let mut current: Option<&(dyn std::error::Error + 'static)> = Some(err);
+
+        while let Some(e) = current {
+            if let Some(e) = e.downcast_ref::<io::Error>() {
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::ConnectionReset | io::ErrorKind::BrokenPipe
+                ) {
+                    return true;
+                }
+                // N.b. `io::Error::source` skips over the wrapped error.
+                if let Some(inner) = e.get_ref() {
+                    current = Some(inner);
+                    continue;
+                }
+            }
+            current = e.source();
+        }
+        false
+    }
+}
+
 /// Pull changes from the `remote`.
 ///
 /// It is expected that the local peer has a copy of the repository
 /// and is pulling new changes. If the repository does not exist, then
 /// [`clone`] should be used.
+///
+/// References that are not part of the [`RefClass`] of the `options`
+/// are neither fetched nor pruned.
 pub fn pull<S>(
     handle: &mut Handle<S>,
-    limit: FetchLimit,
+    options: FetchOptions,
     remote: PublicKey,
     refs_at: Option<Vec<RefsAt>>,
 ) -> Result<FetchResult, Error>
@@ -65,11 +101,13 @@ where
     if local == remote {
         return Err(Error::ReplicateSelf);
     }
+    let FetchOptions { limit, refs } = options;
     let handshake = perform_handshake(handle)?;
     let state = FetchState::default();
 
     // N.b. ensure that we ignore the local peer's key.
     handle.blocked.extend([local]);
+    handle.filter = handle.filter.clone().with_class(refs);
     let result = state
         .run(handle, &handshake, limit, remote, refs_at)
         .map_err(Error::Protocol);
@@ -89,7 +127,7 @@ where
 /// they want to populate with the `remote`'s view of the project.
 pub fn clone<S>(
     handle: &mut Handle<S>,
-    limit: FetchLimit,
+    options: FetchOptions,
     remote: PublicKey,
 ) -> Result<FetchResult, Error>
 where
@@ -99,8 +137,11 @@ where
     if *handle.local() == remote {
         return Err(Error::ReplicateSelf);
     }
+    let FetchOptions { limit, refs } = options;
     let handshake = perform_handshake(handle)?;
     let state = FetchState::default();
+
+    handle.filter = handle.filter.clone().with_class(refs);
     let result = state
         .run(handle, &handshake, limit, remote, None)
         .map_err(Error::Protocol);
@@ -1,11 +1,13 @@
 use std::collections::HashSet;
 
 use radicle::crypto::PublicKey;
+use radicle::git::{PatternStr, PatternString, RefStr};
 use radicle::node::policy::config::Config;
 use radicle::node::policy::store::Read;
 use radicle::prelude::RepoId;
 
 pub use radicle::node::policy::{Policy, Scope, SeedingPolicy};
+pub use radicle::node::RefClass;
 
 #[derive(Clone, Debug)]
 pub enum Allowed {
@@ -69,6 +71,92 @@ impl BlockList {
     }
 }
 
+/// A filter on reference names, relative to a remote's namespace, used
+/// to refuse fetching and storing the matching references.
+///
+/// A reference is skipped if it is excluded by the [`RefClass`] being
+/// fetched, or if it matches any of the blocked patterns and none of the
+/// allowed patterns. The `refs/rad` references are never skipped, since
+/// they are required for the fetch itself.
+#[derive(Clone, Debug, Default)]
+pub struct RefsFilter {
+    allowed: Vec<PatternString>,
+    blocked: Vec<PatternString>,
+    class: RefClass,
+}
+
+impl RefsFilter {
+    /// Construct a filter blocking the given patterns.
+    pub fn new(blocked: impl IntoIterator<Item = PatternString>) -> Self {
+        Self {
+            allowed: Vec::new(),
+            blocked: blocked.into_iter().collect(),
+            class: RefClass::All,
+        }
+    }
+
+    /// Only fetch the given class of references.
+    pub fn with_class(mut self, class: RefClass) -> Self {
+        self.class = class;
+        self
+    }
+
+    /// Construct the filter for `rid`, using the repository's reference
+    /// policies on top of the `default` blocked patterns.
+    pub fn from_config(
+        rid: RepoId,
+        config: &Config<Read>,
+        default: impl IntoIterator<Item = PatternString>,
+    ) -> Result<Self, error::Policy> {
+        let mut filter = Self::new(default);
+        let policies = config
+            .refs_policies(&rid)
+            .map_err(|err| error::Policy::FailedRefs { rid, err })?;
+
+        for entry in policies {
+            match entry.policy {
+                Policy::Allow => filter.allowed.push(entry.pattern),
+                Policy::Block => filter.blocked.push(entry.pattern),
+            }
+        }
+        Ok(filter)
+    }
+
+    /// Check whether `refname` should be skipped when fetching.
+    pub fn is_skipped(&self, refname: &RefStr) -> bool {
+        if refname.starts_with("refs/rad/") {
+            return false;
+        }
+        if self.is_excluded(refname) {
+            return true;
+        }
+        self.blocked.iter().any(|p| matches(p, refname))
+            && !self.allowed.iter().any(|p| matches(p, refname))
+    }
+
+    /// Check whether `refname` is skipped since it is not part of the
+    /// [`RefClass`] being fetched. Unlike blocked references, excluded
+    /// references are left as they are in storage.
+    pub fn is_excluded(&self, refname: &RefStr) -> bool {
+        self.class.is_skipped(refname)
+    }
+}
+
+/// Match a reference name against a pattern, where the `*` of the
+/// pattern matches any sequence of characters, including `/`.
+fn matches(pattern: &PatternStr, refname: &RefStr) -> bool {
+    let (pattern, refname) = (pattern.as_str(), refname.as_str());
+
+    match pattern.split_once('*') {
+        Some((prefix, suffix)) => {
+            refname.len() >= prefix.len() + suffix.len()
+                && refname.starts_with(prefix)
+                && refname.ends_with(suffix)
+        }
+        None => pattern == refname,
+    }
+}
+
 pub mod error {
     use radicle::node::policy;
     use radicle::prelude::RepoId;
@@ -95,6 +183,12 @@ pub mod error {
             #[source]
             err: policy::store::Error,
         },
+        #[error("failed to get reference policies for {rid}")]
+        FailedRefs {
+            rid: RepoId,
+            #[source]
+            err: policy::store::Error,
+        },
 
         #[error(transparent)]
         Storage(#[from] storage::Error),
@@ -0,0 +1,153 @@
+//! Progress reporting for packfiles received during a fetch.
+use std::fmt;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use gix_features::progress::prodash::messages::MessageLevel;
+use gix_features::progress::prodash::progress::{Id, Step, StepShared, UNKNOWN};
+use gix_features::progress::{Count, NestedProgress, Unit};
+
+/// The progress of receiving packfiles from the remote.
+///
+/// Counts are cumulative over all packfiles received by a single fetch.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Progress {
+    /// Number of objects received.
+    pub objects: usize,
+    /// Number of packfile bytes received.
+    pub bytes: usize,
+}
+
+type Callback = Arc<dyn Fn(Progress) + Send + Sync>;
+
+/// A [`NestedProgress`] that tracks the objects and bytes received while
+/// writing packfiles, and forwards them to a callback.
+///
+/// All other progress information reported by `gix` is discarded.
+#[derive(Clone)]
+pub struct Reporter {
+    id: Id,
+    step: StepShared,
+    objects: StepShared,
+    bytes: StepShared,
+    callback: Option<Callback>,
+}
+
+impl Default for Reporter {
+    fn default() -> Self {
+        Self {
+            id: UNKNOWN,
+            step: StepShared::default(),
+            objects: StepShared::default(),
+            bytes: StepShared::default(),
+            callback: None,
+        }
+    }
+}
+
+impl fmt::Debug for Reporter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Reporter")
+            .field("progress", &self.progress())
+            .finish()
+    }
+}
+
+impl Reporter {
+    /// Create a reporter that calls `callback` whenever progress is made.
+    pub fn new(callback: impl Fn(Progress) + Send + Sync + 'static) -> Self {
+        Self {
+            callback: Some(Arc::new(callback)),
+            ..Self::default()
+        }
+    }
+
+    /// The progress made so far.
+    pub fn progress(&self) -> Progress {
+        Progress {
+            objects: self.objects.load(Ordering::Relaxed),
+            bytes: self.bytes.load(Ordering::Relaxed),
+        }
+    }
+
+    /// The total counter this progress contributes to, if any.
+    fn total(&self) -> Option<&StepShared> {
+        let objects: Id = gix_pack::index::write::ProgressId::IndexObjects.into();
+        let bytes: Id = gix_pack::bundle::write::ProgressId::ReadPackBytes.into();
+
+        if self.id == objects {
+            Some(&self.objects)
+        } else if self.id == bytes {
+            Some(&self.bytes)
+        } else {
+            None
+        }
+    }
+
+    fn report(&self, delta: Step) {
+        let Some(total) = self.total() else {
+            return;
+        };
+        total.fetch_add(delta, Ordering::Relaxed);
+
+        if let Some(callback) = &self.callback {
+            callback(self.progress());
+        }
+    }
+}
+
+impl Count for Reporter {
+    fn set(&self, step: Step) {
+        let previous = self.step.swap(step, Ordering::Relaxed);
+        self.report(step.saturating_sub(previous));
+    }
+
+    fn step(&self) -> Step {
+        self.step.load(Ordering::Relaxed)
+    }
+
+    fn inc_by(&self, step: Step) {
+        self.step.fetch_add(step, Ordering::Relaxed);
+        self.report(step);
+    }
+
+    fn counter(&self) -> StepShared {
+        self.step.clone()
+    }
+}
+
+impl gix_features::progress::Progress for Reporter {
+    fn init(&mut self, _max: Option<Step>, _unit: Option<Unit>) {}
+
+    fn set_name(&mut self, _name: String) {}
+
+    fn name(&self) -> Option<String> {
+        None
+    }
+
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn message(&self, _level: MessageLevel, message: String) {
+        log::trace!(target: "fetch", "{message}");
+    }
+}
+
+impl NestedProgress for Reporter {
+    type SubProgress = Self;
+
+    fn add_child(&mut self, name: impl Into<String>) -> Self::SubProgress {
+        self.add_child_with_id(name, UNKNOWN)
+    }
+
+    fn add_child_with_id(&mut self, _name: impl Into<String>, id: Id) -> Self::SubProgress {
+        Self {
+            id,
+            step: StepShared::default(),
+            objects: self.objects.clone(),
+            bytes: self.bytes.clone(),
+            callback: self.callback.clone(),
+        }
+    }
+}
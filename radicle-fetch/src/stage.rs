@@ -40,7 +40,7 @@ use radicle::storage::refs::{RefsAt, Special};
 use radicle::storage::ReadRepository;
 
 use crate::git::refs::{Policy, Update, Updates};
-use crate::policy::BlockList;
+use crate::policy::{BlockList, RefsFilter};
 use crate::refs::{ReceivedRef, ReceivedRefname};
 use crate::sigrefs;
 use crate::state::FetchState;
@@ -402,6 +402,10 @@ impl ProtocolStage for SigrefsAt {
 ///
 /// Those refs and objects are then prepared for updating, removing
 /// any that were found to exist before the latest fetch.
+///
+/// Any refs that are skipped by the `filter` are neither fetched nor
+/// updated, and are pruned if they exist locally, unless they are only
+/// excluded by the class of references being fetched.
 #[derive(Debug)]
 pub struct DataRefs {
     /// The node that is being fetched from.
@@ -410,6 +414,8 @@ pub struct DataRefs {
     /// The set of signed references from each remote that was
     /// fetched.
     pub remotes: sigrefs::RemoteRefs,
+    /// The filter for references that should not be fetched.
+    pub filter: RefsFilter,
     /// The data limit for this stage of fetching.
     #[allow(dead_code)]
     pub limit: u64,
@@ -445,6 +451,9 @@ impl ProtocolStage for DataRefs {
             wants_haves.add(
                 refdb,
                 loaded.refs.iter().filter_map(|(refname, tip)| {
+                    if self.filter.is_skipped(refname) {
+                        return None;
+                    }
                     let refname = Qualified::from_refstr(refname)
                         .map(|refname| refname.with_namespace(Component::from(remote)))?;
                     Some((refname, *tip))
@@ -466,6 +475,9 @@ impl ProtocolStage for DataRefs {
         for (remote, refs) in &self.remotes {
             let mut signed = HashSet::with_capacity(refs.refs.len());
             for (name, tip) in refs.iter() {
+                if self.filter.is_skipped(name) {
+                    continue;
+                }
                 let tracking: Namespaced<'_> = Qualified::from_refstr(name)
                     .and_then(|q| refs::ReceivedRefname::remote(*remote, q).to_namespaced())
                     .expect("we checked sigrefs well-formedness in wants_refs already");
@@ -487,6 +499,10 @@ impl ProtocolStage for DataRefs {
                 if name.starts_with(prefix_rad.as_str()) {
                     continue;
                 }
+                // Refs outside of the class being fetched are left as they are
+                if self.filter.is_excluded(&name) {
+                    continue;
+                }
 
                 let name = Qualified::from_refstr(name)
                     .expect("BUG: reference is guaranteed to be Qualified")
@@ -3,14 +3,16 @@ use std::time::Instant;
 
 use gix_protocol::handshake;
 use radicle::crypto::PublicKey;
-use radicle::git::{Oid, Qualified};
+use radicle::git::{Oid, Qualified, RefString};
 use radicle::identity::{Did, Doc, DocError};
+use radicle::node::RefClass;
 
 use radicle::prelude::Verified;
 use radicle::storage;
 use radicle::storage::refs::RefsAt;
 use radicle::storage::{
-    git::Validation, Remote, RemoteId, RemoteRepository, Remotes, ValidateRepository, Validations,
+    git::Validation, ReadRepository, Remote, RemoteId, RemoteRepository, Remotes,
+    ValidateRepository, Validations,
 };
 
 use crate::git;
@@ -111,6 +113,36 @@ impl Default for FetchLimit {
     }
 }
 
+/// Options for [`crate::pull`] and [`crate::clone`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FetchOptions {
+    /// Data limits of the fetch.
+    pub limit: FetchLimit,
+    /// The classes of references to fetch from each remote.
+    pub refs: RefClass,
+}
+
+/// The received `rad/sigrefs` of a remote that were not applied,
+/// since they would roll back our view of the remote's references.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RolledBack {
+    /// The received `rad/sigrefs` are an ancestor of the ones we
+    /// already have.
+    Behind { current: Oid, received: Oid },
+    /// The received `rad/sigrefs` do not match the tip that was
+    /// announced for the remote.
+    Mismatched { announced: Oid, received: Oid },
+}
+
+impl RolledBack {
+    /// The `rad/sigrefs` tip that was received.
+    pub fn received(&self) -> Oid {
+        match self {
+            Self::Behind { received, .. } | Self::Mismatched { received, .. } => *received,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum FetchResult {
     Success {
@@ -120,6 +152,17 @@ pub enum FetchResult {
         remotes: BTreeSet<PublicKey>,
         /// Any validation errors that were found while fetching.
         validations: sigrefs::Validations,
+        /// The signed references of each fetched namespace that were
+        /// skipped, since they are blocked by the fetch's [`crate::RefsFilter`].
+        skipped: BTreeMap<PublicKey, Vec<RefString>>,
+        /// The signed references of each fetched namespace that are not part
+        /// of the fetch's [`RefClass`], and whose stored tips differ from the
+        /// signed ones. The stored references of these namespaces don't match
+        /// their `rad/sigrefs` until the excluded class is fetched.
+        excluded: BTreeMap<PublicKey, Vec<RefString>>,
+        /// The namespaces that were not updated, since their received
+        /// `rad/sigrefs` would roll back the ones we have.
+        rolled_back: BTreeMap<PublicKey, RolledBack>,
     },
     Failed {
         /// The threshold that needed to be met.
@@ -128,6 +171,9 @@ pub enum FetchResult {
         delegates: BTreeSet<PublicKey>,
         /// Validation errors that were found while fetching.
         validations: sigrefs::Validations,
+        /// The namespaces that were not updated, since their received
+        /// `rad/sigrefs` would roll back the ones we have.
+        rolled_back: BTreeMap<PublicKey, RolledBack>,
     },
 }
 
@@ -145,6 +191,12 @@ impl FetchResult {
             Self::Failed { .. } => false,
         }
     }
+
+    pub fn rolled_back(&self) -> &BTreeMap<PublicKey, RolledBack> {
+        match self {
+            Self::Success { rolled_back, .. } | Self::Failed { rolled_back, .. } => rolled_back,
+        }
+    }
 }
 
 #[derive(Default)]
@@ -238,10 +290,12 @@ impl FetchState {
 
         let wants_haves = step.wants_haves(&handle.repo, &refs)?;
         if !wants_haves.wants.is_empty() {
-            let keepfile =
-                handle
-                    .transport
-                    .fetch(wants_haves, handle.interrupt.clone(), handshake)?;
+            let keepfile = handle.transport.fetch(
+                wants_haves,
+                handle.interrupt.clone(),
+                handshake,
+                handle.reporter.clone(),
+            )?;
             self.keepfiles.extend(keepfile);
         } else {
             log::trace!(target: "fetch", "Nothing to fetch")
@@ -351,8 +405,9 @@ impl FetchState {
     ///   5. Fetch the data references, i.e. references found in
     ///      `rad/sigrefs`.
     ///   6. Validate the fetched references for delegates and
-    ///      non-delegates, pruning any invalid remotes from the set
-    ///      of updating tips.
+    ///      non-delegates, pruning any invalid remotes, and remotes
+    ///      whose `rad/sigrefs` would be rolled back, from the set of
+    ///      updating tips.
     ///   7. Apply the valid tips, iff no delegates failed validation.
     ///   8. Signal to the other side that the process has completed.
     pub(super) fn run<S>(
@@ -415,7 +470,7 @@ impl FetchState {
             threshold,
             &limit,
             remote,
-            refs_at,
+            refs_at.clone(),
         )?;
         log::debug!(
             target: "fetch",
@@ -427,6 +482,7 @@ impl FetchState {
         let data_refs = stage::DataRefs {
             remote,
             remotes: signed_refs,
+            filter: handle.filter.clone(),
             limit: limit.refs,
         };
         self.run_stage(handle, handshake, &data_refs)?;
@@ -467,6 +523,14 @@ impl FetchState {
             .filter(|id| delegates.contains(id))
             .collect::<BTreeSet<_>>();
         let mut failed_delegates = BTreeSet::new();
+        let mut rolled_back = BTreeMap::new();
+
+        // The `rad/sigrefs` tips that were announced to us, if any.
+        let announced = refs_at
+            .unwrap_or_default()
+            .into_iter()
+            .map(|RefsAt { remote, at }| (remote, at))
+            .collect::<BTreeMap<_, _>>();
 
         // TODO(finto): this might read better if it got its own
         // private function.
@@ -500,14 +564,21 @@ impl FetchState {
                     remote,
                     data: Some(sigrefs),
                 } => {
+                    if let Some(rollback) =
+                        self::rolled_back(&handle.repo, remote, sigrefs.at, announced.get(&remote))?
+                    {
+                        log::debug!(target: "fetch", "Pruning non-delegate {remote} tips, 'rad/sigrefs' rolled back: {rollback:?}");
+                        self.prune(&remote);
+                        rolled_back.insert(remote, rollback);
+                        continue;
+                    }
                     if let Some(SignedRefsAt { at, .. }) = SignedRefsAt::load(remote, &handle.repo)?
                     {
-                        // Prune non-delegates if they're behind or
-                        // diverged. A diverged case is non-fatal for
-                        // delegates.
+                        // Prune non-delegates if they've diverged. A
+                        // diverged case is fatal for delegates.
                         if matches!(
                             repository::ancestry(&handle.repo, at, sigrefs.at)?,
-                            repository::Ancestry::Behind | repository::Ancestry::Diverged
+                            repository::Ancestry::Diverged
                         ) {
                             self.prune(&remote);
                             continue;
@@ -530,14 +601,18 @@ impl FetchState {
                     remote,
                     data: Some(sigrefs),
                 } => {
+                    if let Some(rollback) =
+                        self::rolled_back(&handle.repo, remote, sigrefs.at, announced.get(&remote))?
+                    {
+                        log::warn!(target: "fetch", "Pruning delegate {remote} tips, 'rad/sigrefs' rolled back: {rollback:?}");
+                        self.prune(&remote);
+                        rolled_back.insert(remote, rollback);
+                        continue;
+                    }
                     if let Some(SignedRefsAt { at, .. }) = SignedRefsAt::load(remote, &handle.repo)?
                     {
                         let ancestry = repository::ancestry(&handle.repo, at, sigrefs.at)?;
-                        if matches!(ancestry, repository::Ancestry::Behind) {
-                            log::trace!(target: "fetch", "Advertised `rad/sigrefs` {} is behind {at} for {remote}", sigrefs.at);
-                            self.prune(&remote);
-                            continue;
-                        } else if matches!(ancestry, repository::Ancestry::Diverged) {
+                        if matches!(ancestry, repository::Ancestry::Diverged) {
                             return Err(error::Protocol::Diverged {
                                 remote,
                                 current: at,
@@ -569,6 +644,32 @@ impl FetchState {
             start.elapsed().as_millis()
         );
 
+        let mut skipped = BTreeMap::new();
+        let mut excluded = BTreeMap::new();
+
+        for (remote, sigrefs) in signed_refs.iter().filter(|(r, _)| remotes.contains(r)) {
+            for (name, oid) in sigrefs.refs.iter() {
+                if handle.filter.is_excluded(name) {
+                    // Excluded references keep their stored tips, which only
+                    // matter if they differ from the signed ones.
+                    let stored = Qualified::from_refstr(name)
+                        .and_then(|q| handle.repository().reference_oid(remote, &q).ok());
+
+                    if stored != Some(*oid) {
+                        excluded
+                            .entry(*remote)
+                            .or_insert_with(Vec::new)
+                            .push(name.clone());
+                    }
+                } else if handle.filter.is_skipped(name) {
+                    skipped
+                        .entry(*remote)
+                        .or_insert_with(Vec::new)
+                        .push(name.clone());
+                }
+            }
+        }
+
         // N.b. only apply to Git repository if there are enough valid
         // delegates that pass the threshold.
         if valid_delegates.len() >= threshold {
@@ -584,6 +685,9 @@ impl FetchState {
                 applied,
                 remotes,
                 validations: failures,
+                skipped,
+                excluded,
+                rolled_back,
             })
         } else {
             log::debug!(
@@ -596,11 +700,41 @@ impl FetchState {
                 threshold,
                 delegates: failed_delegates,
                 validations: failures,
+                rolled_back,
             })
         }
     }
 }
 
+/// Check whether applying the `received` `rad/sigrefs` of `remote`
+/// would roll back our view of the remote's references.
+///
+/// This is the case if the received tip does not match the
+/// `announced` tip, when one was announced, or if the received tip is
+/// an ancestor of the one we already have.
+fn rolled_back(
+    repo: &storage::git::Repository,
+    remote: PublicKey,
+    received: Oid,
+    announced: Option<&Oid>,
+) -> Result<Option<RolledBack>, error::Protocol> {
+    if let Some(announced) = announced {
+        if *announced != received {
+            return Ok(Some(RolledBack::Mismatched {
+                announced: *announced,
+                received,
+            }));
+        }
+    }
+    let Some(SignedRefsAt { at: current, .. }) = SignedRefsAt::load(remote, repo)? else {
+        return Ok(None);
+    };
+    match repository::ancestry(repo, current, received)? {
+        repository::Ancestry::Behind => Ok(Some(RolledBack::Behind { current, received })),
+        _ => Ok(None),
+    }
+}
+
 /// A cached version of [`Handle`] by using the underlying
 /// [`FetchState`]'s data for performing lookups.
 pub(crate) struct Cached<'a, S> {
@@ -681,8 +815,10 @@ impl<'a, S> ValidateRepository for Cached<'a, S> {
     // not have a reference to the COB if they have not interacted
     // with it.
     fn validate_remote(&self, remote: &Remote) -> Result<Validations, storage::Error> {
-        // Contains a copy of the signed refs of this remote.
+        // Contains a copy of the signed refs of this remote, minus the
+        // ones that are skipped, since they are never fetched.
         let mut signed = BTreeMap::from((*remote.refs).clone());
+        signed.retain(|name, _| !self.handle.filter.is_skipped(name));
         let mut validations = Validations::default();
         let mut has_sigrefs = false;
 
@@ -722,3 +858,111 @@ impl<'a, S> ValidateRepository for Cached<'a, S> {
         Ok(validations)
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use radicle::crypto::test::signer::MockSigner;
+    use radicle::crypto::Signer as _;
+    use radicle::storage::git::{Repository, Storage};
+    use radicle::storage::refs::SIGREFS_BRANCH;
+    use radicle::storage::{ReadRepository as _, ReadStorage as _, SignRepository as _};
+    use radicle::storage::{WriteRepository as _, WriteStorage as _};
+    use radicle::test::fixtures;
+
+    use super::*;
+
+    /// Copy the objects and namespaced references of `from` into `to`, as
+    /// if they were fetched.
+    fn copy(from: &Repository, to: &Repository) {
+        let (src, dst) = (from.raw().odb().unwrap(), to.raw().odb().unwrap());
+        src.foreach(|oid| {
+            let obj = src.read(*oid).unwrap();
+            dst.write(obj.kind(), obj.data()).unwrap();
+            true
+        })
+        .unwrap();
+
+        for r in from.raw().references_glob("refs/namespaces/*").unwrap() {
+            let r = r.unwrap();
+            // Skip symbolic references.
+            if let Some(target) = r.target() {
+                to.raw()
+                    .reference(r.name().unwrap(), target, true, "copy")
+                    .unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_sigrefs_rolled_back() {
+        let tmp = tempfile::tempdir().unwrap();
+        let signer = MockSigner::default();
+        let remote = *signer.public_key();
+
+        // The seed has the initial `rad/sigrefs` of the remote.
+        let seed = Storage::open(tmp.path().join("seed"), fixtures::user()).unwrap();
+        let (rid, _, _, _) = fixtures::project(tmp.path().join("working"), &seed, &signer).unwrap();
+        let seed = seed.repository(rid).unwrap();
+        let old = seed.reference_oid(&remote, &SIGREFS_BRANCH).unwrap();
+
+        // We have since received a newer `rad/sigrefs` of the remote.
+        let local = Storage::open(tmp.path().join("local"), fixtures::user()).unwrap();
+        let local = local.create(rid).unwrap();
+        copy(&seed, &local);
+
+        let branch = format!("refs/namespaces/{remote}/refs/heads/master");
+        let head = local
+            .raw()
+            .find_reference(&branch)
+            .unwrap()
+            .peel_to_commit()
+            .unwrap();
+        let sig = local.raw().signature().unwrap();
+        local
+            .raw()
+            .commit(
+                Some(&branch),
+                &sig,
+                &sig,
+                "Second commit",
+                &head.tree().unwrap(),
+                &[&head],
+            )
+            .unwrap();
+        local.sign_refs(&signer).unwrap();
+        let new = local.reference_oid(&remote, &SIGREFS_BRANCH).unwrap();
+
+        // Fetching the seed's `rad/sigrefs` would roll back ours.
+        assert_eq!(
+            rolled_back(&local, remote, old, None).unwrap(),
+            Some(RolledBack::Behind {
+                current: new,
+                received: old
+            })
+        );
+        assert_eq!(rolled_back(&local, remote, new, None).unwrap(), None);
+
+        // A tip that doesn't match what was announced is reported, even
+        // if it is ahead.
+        assert_eq!(
+            rolled_back(&local, remote, new, Some(&old)).unwrap(),
+            Some(RolledBack::Mismatched {
+                announced: old,
+                received: new
+            })
+        );
+
+        // The seed receiving our `rad/sigrefs` is a fast-forward.
+        copy(&local, &seed);
+        seed.raw()
+            .reference(
+                &format!("refs/namespaces/{remote}/{}", &*SIGREFS_BRANCH),
+                *old,
+                true,
+                "rewind",
+            )
+            .unwrap();
+        assert_eq!(rolled_back(&seed, remote, new, Some(&new)).unwrap(), None);
+    }
+}
@@ -23,6 +23,7 @@ use thiserror::Error;
 use crate::git::oid;
 use crate::git::packfile::Keepfile;
 use crate::git::repository;
+use crate::progress::Reporter;
 
 /// Open a reader and writer stream to pass to the ls-refs and fetch
 /// processes for communicating during their respective protocols.
@@ -121,6 +122,7 @@ where
         wants_haves: WantsHaves,
         interrupt: Arc<AtomicBool>,
         handshake: &handshake::Outcome,
+        mut progress: Reporter,
     ) -> io::Result<Option<Keepfile>> {
         log::trace!(
             target: "fetch",
@@ -138,7 +140,7 @@ where
                 },
                 handshake,
                 Connection::new(read, write, FetchConnection::AllowReuse, self.repo.clone()),
-                &mut progress::Discard,
+                &mut progress,
             )
             .map_err(io_other)?
         };
@@ -12,7 +12,7 @@ use serde_json as json;
 
 use crate::identity::RepoId;
 use crate::node::NodeId;
-use crate::node::{Command, CommandResult};
+use crate::node::{Command, CommandResult, Hello, RefClass};
 use crate::runtime;
 use crate::runtime::thread;
 
@@ -85,11 +85,33 @@ where
     let mut writer = LineWriter::new(stream);
     let mut line = String::new();
 
-    reader.read_line(&mut line)?;
-    let input = line.trim_end();
+    // Commands that weren't understood, and the hello exchange, don't end the
+    // connection: the client may follow up with another command.
+    let cmd = loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        let input = line.trim_end();
 
-    log::debug!(target: "control", "Received `{input}` on control socket");
-    let cmd: Command = json::from_str(input)?;
+        log::debug!(target: "control", "Received `{input}` on control socket");
+
+        match json::from_str::<Command>(input) {
+            Ok(Command::Hello(hello)) => {
+                log::debug!(
+                    target: "control",
+                    "Client speaks control protocol version {}", hello.version
+                );
+                CommandResult::Okay(Hello::local()).to_writer(&mut writer)?;
+            }
+            Ok(cmd) => break cmd,
+            Err(e) if e.is_data() => {
+                log::debug!(target: "control", "Received unknown command: {e}");
+                CommandResult::unknown_command(e).to_writer(&mut writer)?;
+            }
+            Err(e) => return Err(e.into()),
+        }
+    };
 
     match cmd {
         Command::Connect { addr, opts } => {
@@ -108,8 +130,14 @@ where
                 CommandResult::ok().to_writer(writer).ok();
             }
         },
-        Command::Fetch { rid, nid, timeout } => {
-            fetch(rid, nid, timeout, writer, &mut handle)?;
+        Command::Fetch {
+            rid,
+            nid,
+            timeout,
+            progress,
+            refs,
+        } => {
+            fetch(rid, nid, timeout, progress, refs, writer, &mut handle)?;
         }
         Command::Config => {
             let config = handle.config()?;
@@ -168,6 +196,27 @@ where
                 return Err(CommandError::Runtime(e));
             }
         },
+        Command::Ban { ban } => match handle.ban(ban) {
+            Ok(result) => {
+                CommandResult::updated(result).to_writer(writer)?;
+            }
+            Err(e) => {
+                return Err(CommandError::Runtime(e));
+            }
+        },
+        Command::Unban { target } => match handle.unban(target) {
+            Ok(result) => {
+                CommandResult::updated(result).to_writer(writer)?;
+            }
+            Err(e) => {
+                return Err(CommandError::Runtime(e));
+            }
+        },
+        Command::Bans => {
+            let bans = handle.bans()?;
+
+            CommandResult::Okay(bans).to_writer(writer)?;
+        }
         Command::AnnounceRefs { rid } => {
             let refs = handle.announce_refs(rid)?;
 
@@ -209,12 +258,40 @@ where
 
             CommandResult::Okay(debug).to_writer(writer)?;
         }
-        Command::Shutdown => {
+        Command::NextSync => {
+            let next = handle.next_sync()?;
+
+            CommandResult::Okay(next).to_writer(writer)?;
+        }
+        Command::Fetching => {
+            let fetching = handle.fetching()?;
+
+            CommandResult::Okay(fetching).to_writer(writer)?;
+        }
+        Command::Hello(_) => {
+            // Handled above.
+        }
+        Command::Shutdown { progress } => {
             log::debug!(target: "control", "Shutdown requested..");
+
+            if !progress {
+                // Clients that don't follow the shutdown only wait for the node to exit,
+                // so there's no need to make them wait for ongoing fetches.
+                CommandResult::ok().to_writer(&mut writer).ok();
+            }
             // Channel might already be disconnected if shutdown
             // came from somewhere else. Ignore errors.
-            handle.shutdown().ok();
-            CommandResult::ok().to_writer(writer).ok();
+            handle
+                .shutdown_with_progress(&mut |phase| {
+                    if progress {
+                        CommandResult::Okay(phase).to_writer(&mut writer).ok();
+                    }
+                })
+                .ok();
+
+            if progress {
+                CommandResult::ok().to_writer(writer).ok();
+            }
         }
     }
     Ok(())
@@ -224,10 +301,23 @@ fn fetch<W: Write, H: Handle<Error = runtime::HandleError>>(
     id: RepoId,
     node: NodeId,
     timeout: time::Duration,
+    progress: bool,
+    refs: RefClass,
     mut writer: W,
     handle: &mut H,
 ) -> Result<(), CommandError> {
-    match handle.fetch(id, node, timeout) {
+    let result = if progress || refs != RefClass::All {
+        handle.fetch_refs(id, node, timeout, refs, &mut |p| {
+            // Progress is best-effort: if the client went away, we'll find out
+            // when writing the result.
+            if progress {
+                CommandResult::Okay(p).to_writer(&mut writer).ok();
+            }
+        })
+    } else {
+        handle.fetch(id, node, timeout)
+    };
+    match result {
         Ok(result) => {
             json::to_writer(&mut writer, &result)?;
         }
@@ -327,4 +417,41 @@ mod tests {
         assert!(handle.unfollow(peer).unwrap());
         assert!(!handle.unfollow(peer).unwrap());
     }
+
+    #[test]
+    fn test_hello() {
+        let tmp = tempfile::tempdir().unwrap();
+        let socket = tmp.path().join("node.sock");
+        let listener = UnixListener::bind(&socket).unwrap();
+        let node = Node::new(&socket);
+
+        thread::spawn(move || listen(listener, test::handle::Handle::default()));
+
+        // Wait for node to be online.
+        while !node.is_running() {}
+
+        assert_eq!(node.hello().unwrap(), Hello::local());
+
+        let stream = UnixStream::connect(&socket).unwrap();
+        let mut lines = BufReader::new(&stream).lines();
+
+        // Unknown commands are answered with an error, and the connection stays open.
+        writeln!(&stream, r#"{{"command":"teleport","to":"mars"}}"#).unwrap();
+        let response: json::Value = json::from_str(&lines.next().unwrap().unwrap()).unwrap();
+        assert_eq!(response["kind"], "unknownCommand");
+
+        // So are commands with fields the node doesn't know.
+        let mut unseed = json::to_value(Command::Unseed {
+            rid: test::arbitrary::gen::<RepoId>(1),
+        })
+        .unwrap();
+        unseed["purge"] = json::json!(true);
+
+        writeln!(&stream, "{unseed}").unwrap();
+        let response: json::Value = json::from_str(&lines.next().unwrap().unwrap()).unwrap();
+        assert_eq!(response["kind"], "unknownCommand");
+
+        writeln!(&stream, "{}", json::to_string(&Command::Status).unwrap()).unwrap();
+        assert_eq!(lines.next().unwrap().unwrap(), "{}");
+    }
 }
@@ -1,4 +1,7 @@
 pub mod handle;
+pub mod hooks;
+pub mod maintenance;
+pub mod metrics;
 pub mod thread;
 
 use std::os::unix::net::UnixListener;
@@ -36,6 +39,8 @@ use crate::{service, LocalTime};
 
 pub use handle::Error as HandleError;
 pub use handle::Handle;
+pub use hooks::Hooks;
+pub use maintenance::Maintenance;
 pub use node::events::Emitter;
 
 /// Maximum pending worker tasks allowed.
@@ -104,10 +109,17 @@ pub struct Runtime {
     pub control: ControlSocket,
     pub handle: Handle,
     pub storage: Storage,
+    pub maintenance: Maintenance<Storage, Handle>,
+    /// Repository event hooks.
+    pub hooks: Hooks,
     pub reactor: Reactor<wire::Control, popol::Poller>,
     pub pool: worker::Pool,
     pub local_addrs: Vec<net::SocketAddr>,
     pub signals: chan::Receiver<Signal>,
+    /// Requests to dial peers through a proxy.
+    pub dials: chan::Receiver<wire::proxy::Dial>,
+    /// Metrics listener and the registry it serves, if metrics are enabled.
+    pub metrics: Option<(net::TcpListener, service::metrics::Registry)>,
 }
 
 impl Runtime {
@@ -141,6 +153,7 @@ impl Runtime {
         log::info!(target: "node", "Opening policy database..");
         let policies = home.policies_mut()?;
         let policies = policy::Config::new(policy, policies);
+        let hooks = Hooks::new(config.hooks.clone(), storage.clone());
         let notifications = home.notifications_mut()?;
         let mut cobs_cache = cob::cache::Store::open(home.cobs().join(cob::cache::COBS_DB_FILE))?;
 
@@ -240,8 +253,20 @@ impl Runtime {
         );
         service.initialize(clock)?;
 
+        let metrics = if let Some(cfg) = &config.metrics {
+            log::info!(target: "node", "Binding metrics listener {}..", cfg.listen);
+
+            Some((
+                net::TcpListener::bind(cfg.listen)?,
+                service.registry().clone(),
+            ))
+        } else {
+            None
+        };
+
         let (worker_send, worker_recv) = chan::bounded::<worker::Task>(MAX_PENDING_TASKS);
-        let mut wire = Wire::new(service, worker_send, signer.clone());
+        let (dial_send, dial_recv) = chan::unbounded::<wire::proxy::Dial>();
+        let mut wire = Wire::new(service, worker_send, dial_send, signer.clone());
         let mut local_addrs = Vec::new();
 
         for addr in listen {
@@ -253,12 +278,20 @@ impl Runtime {
         }
         let reactor = Reactor::named(wire, popol::Poller::new(), thread::name(&id, "service"))?;
         let handle = Handle::new(home.clone(), reactor.controller(), emitter);
+        let maintenance = Maintenance::new(
+            storage.clone(),
+            policy::Store::reader(home.node().join(node::POLICIES_DB_FILE))?,
+            handle.clone(),
+            id,
+            config.limits.namespace_max_age,
+        );
 
         let nid = *signer.public_key();
         let fetch = worker::FetchConfig {
             limit: FetchLimit::default(),
             local: nid,
             expiry: worker::garbage::Expiry::default(),
+            blocked_refs: config.blocked_refs.clone(),
         };
         let pool = worker::Pool::with(
             worker_recv,
@@ -282,11 +315,15 @@ impl Runtime {
             home,
             control,
             storage,
+            maintenance,
+            hooks,
             reactor,
             handle,
             pool,
             signals,
+            dials: dial_recv,
             local_addrs,
+            metrics,
         })
     }
 
@@ -303,6 +340,24 @@ impl Runtime {
             let handle = self.handle.clone();
             || control::listen(listener, handle)
         });
+        thread::spawn(&self.id, "maintenance", {
+            let maintenance = self.maintenance;
+            || maintenance.run()
+        });
+        if !self.hooks.is_empty() {
+            let hooks = self.hooks;
+            let events = self.handle.events();
+
+            thread::spawn(&self.id, "hooks", || hooks.run(events));
+        }
+        thread::spawn(&self.id, "proxy", {
+            let handle = self.handle.clone();
+            let dials = self.dials;
+            || wire::proxy::dial(dials, handle)
+        });
+        if let Some((listener, registry)) = self.metrics {
+            thread::spawn(&self.id, "metrics", || metrics::listen(listener, registry));
+        }
         let _signals = thread::spawn(&self.id, "signals", move || loop {
             match self.signals.recv() {
                 Ok(Signal::Terminate | Signal::Interrupt) => {
@@ -5,6 +5,7 @@ use std::sync::Arc;
 use std::{fmt, io, time};
 
 use crossbeam_channel as chan;
+use radicle::node::ban::{self, Ban};
 use radicle::node::{ConnectOptions, ConnectResult, Seeds};
 use radicle::storage::refs::RefsAt;
 use reactor::poller::popol::PopolWaker;
@@ -12,7 +13,7 @@ use serde_json::json;
 use thiserror::Error;
 
 use crate::identity::RepoId;
-use crate::node::{Alias, Command, FetchResult};
+use crate::node::{Alias, Command, FetchProgress, FetchResult, RefClass, ShutdownPhase, Timestamp};
 use crate::profile::Home;
 use crate::runtime::Emitter;
 use crate::service;
@@ -21,9 +22,13 @@ use crate::service::NodeId;
 use crate::service::{CommandError, Config, QueryState};
 use crate::service::{Event, Events};
 use crate::wire;
+use crate::wire::proxy;
 use crate::wire::StreamId;
 use crate::worker::TaskResult;
 
+/// Maximum unconsumed progress reports per fetch request. Reports beyond this are dropped.
+pub const MAX_PENDING_PROGRESS: usize = 64;
+
 /// An error resulting from a handle method.
 #[derive(Error, Debug)]
 pub enum Error {
@@ -118,6 +123,10 @@ impl Handle {
         self.controller.cmd(wire::Control::Flush { remote, stream })
     }
 
+    pub fn dialed(&mut self, dialed: proxy::Dialed) -> Result<(), io::Error> {
+        self.controller.cmd(wire::Control::Dialed(dialed))
+    }
+
     pub(crate) fn command(&self, cmd: service::Command) -> Result<(), io::Error> {
         self.controller.cmd(wire::Control::User(cmd))
     }
@@ -221,10 +230,63 @@ impl radicle::node::Handle for Handle {
         timeout: time::Duration,
     ) -> Result<FetchResult, Error> {
         let (sender, receiver) = chan::bounded(1);
-        self.command(service::Command::Fetch(id, from, timeout, sender))?;
+        self.command(service::Command::Fetch(
+            id,
+            from,
+            RefClass::All,
+            timeout,
+            sender,
+            None,
+        ))?;
         receiver.recv().map_err(Error::from)
     }
 
+    fn fetch_with_progress(
+        &mut self,
+        id: RepoId,
+        from: NodeId,
+        timeout: time::Duration,
+        progress: &mut dyn FnMut(FetchProgress),
+    ) -> Result<FetchResult, Error> {
+        self.fetch_refs(id, from, timeout, RefClass::All, progress)
+    }
+
+    fn fetch_refs(
+        &mut self,
+        id: RepoId,
+        from: NodeId,
+        timeout: time::Duration,
+        refs: RefClass,
+        progress: &mut dyn FnMut(FetchProgress),
+    ) -> Result<FetchResult, Error> {
+        let (sender, receiver) = chan::bounded(1);
+        let (progress_sender, progress_receiver) = chan::bounded(MAX_PENDING_PROGRESS);
+        self.command(service::Command::Fetch(
+            id,
+            from,
+            refs,
+            timeout,
+            sender,
+            Some(progress_sender),
+        ))?;
+
+        loop {
+            chan::select! {
+                recv(receiver) -> result => {
+                    // Report any progress that was sent before the result came in.
+                    progress_receiver.try_iter().for_each(&mut *progress);
+
+                    return result.map_err(Error::from);
+                }
+                recv(progress_receiver) -> p => match p {
+                    Ok(p) => progress(p),
+                    // If the fetch is done with the channel, simply wait for the result.
+                    Err(_) => return receiver.recv().map_err(Error::from),
+                },
+            }
+        }
+    }
+
     fn follow(&mut self, id: NodeId, alias: Option<Alias>) -> Result<bool, Error> {
         let (sender, receiver) = chan::bounded(1);
         self.command(service::Command::Follow(id, alias, sender))?;
@@ -237,6 +299,24 @@ impl radicle::node::Handle for Handle {
         receiver.recv().map_err(Error::from)
     }
 
+    fn ban(&mut self, ban: Ban) -> Result<bool, Error> {
+        let (sender, receiver) = chan::bounded(1);
+        self.command(service::Command::Ban(ban, sender))?;
+        receiver.recv().map_err(Error::from)
+    }
+
+    fn unban(&mut self, target: ban::Target) -> Result<bool, Error> {
+        let (sender, receiver) = chan::bounded(1);
+        self.command(service::Command::Unban(target, sender))?;
+        receiver.recv().map_err(Error::from)
+    }
+
+    fn bans(&self) -> Result<Vec<Ban>, Error> {
+        let (sender, receiver) = chan::bounded(1);
+        self.command(service::Command::Bans(sender))?;
+        receiver.recv().map_err(Error::from)
+    }
+
     fn seed(&mut self, id: RepoId, scope: policy::Scope) -> Result<bool, Error> {
         let (sender, receiver) = chan::bounded(1);
         self.command(service::Command::Seed(id, scope, sender))?;
@@ -309,6 +389,10 @@ impl radicle::node::Handle for Handle {
     }
 
     fn shutdown(self) -> Result<(), Error> {
+        self.shutdown_with_progress(&mut |_| {})
+    }
+
+    fn shutdown_with_progress(self, progress: &mut dyn FnMut(ShutdownPhase)) -> Result<(), Error> {
         // If the current value is `false`, set it to `true`, otherwise error.
         if self
             .shutdown
@@ -321,9 +405,16 @@ impl radicle::node::Handle for Handle {
         // control thread gracefully. Since the control thread may have called this function,
         // the control socket may already be disconnected. Ignore errors.
         UnixStream::connect(self.home.socket())
-            .and_then(|sock| Command::Shutdown.to_writer(sock))
+            .and_then(|sock| Command::Shutdown { progress: false }.to_writer(sock))
             .ok();
 
+        // Let the service finish ongoing fetches and disconnect from peers before stopping
+        // the reactor. The service drops the sender once it's ready to exit.
+        let (sender, receiver) = chan::unbounded();
+        if self.command(service::Command::Shutdown(sender)).is_ok() {
+            receiver.iter().for_each(progress);
+        }
+
         self.controller
             .shutdown()
             .map_err(|_| Error::ChannelDisconnected)
@@ -378,4 +469,38 @@ impl radicle::node::Handle for Handle {
 
         Ok(debug)
     }
+
+    fn next_sync(&self) -> Result<Option<Timestamp>, Self::Error> {
+        let (sender, receiver) = chan::bounded(1);
+        let query: Arc<QueryState> = Arc::new(move |state| {
+            sender
+                .send(state.schedule().next().map(Timestamp::from))
+                .ok();
+
+            Ok(())
+        });
+        let (err_sender, err_receiver) = chan::bounded(1);
+        self.command(service::Command::QueryState(query, err_sender))?;
+        err_receiver.recv()??;
+
+        let next = receiver.recv()?;
+
+        Ok(next)
+    }
+
+    fn fetching(&self) -> Result<Vec<RepoId>, Self::Error> {
+        let (sender, receiver) = chan::bounded(1);
+        let query: Arc<QueryState> = Arc::new(move |state| {
+            sender.send(state.fetching().keys().copied().collect()).ok();
+
+            Ok(())
+        });
+        let (err_sender, err_receiver) = chan::bounded(1);
+        self.command(service::Command::QueryState(query, err_sender))?;
+        err_receiver.recv()??;
+
+        let fetching = receiver.recv()?;
+
+        Ok(fetching)
+    }
 }
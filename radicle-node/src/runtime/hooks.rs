@@ -0,0 +1,409 @@
+//! Repository event hooks.
+//!
+//! Hooks are user commands that are run when a fetch changes a repository, eg. to trigger CI
+//! or send notifications. They are spawned from a dedicated thread and never waited on, so
+//! that a slow or failing hook can't hold up the node.
+use std::collections::VecDeque;
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::{io, process};
+
+use localtime::{LocalDuration, LocalTime};
+use serde::Serialize;
+
+use radicle::cob;
+use radicle::git;
+use radicle::node::config::{Hook, HookEvent, Hooks as Config};
+use radicle::node::{Event, NodeId};
+use radicle::prelude::RepoId;
+use radicle::storage::{ReadRepository as _, ReadStorage as _, RefUpdate};
+use radicle::Storage;
+
+/// Window over which hook invocations are rate limited.
+pub const RATE_LIMIT_WINDOW: LocalDuration = LocalDuration::from_mins(1);
+
+/// The payload passed to a hook on its standard input.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Payload {
+    /// Event that triggered the hook.
+    pub event: HookEvent,
+    /// Repository that changed.
+    pub rid: RepoId,
+    /// Node the changes were fetched from.
+    pub remote: NodeId,
+    /// Namespaced reference that changed.
+    #[serde(rename = "ref")]
+    pub refname: git::RefString,
+    /// Previous target of the reference, if any.
+    pub old: Option<git::Oid>,
+    /// New target of the reference.
+    pub new: git::Oid,
+}
+
+/// Runs the configured hooks for repository events.
+pub struct Hooks {
+    /// Hooks configuration.
+    config: Config,
+    /// Node storage, used to tell new COBs apart from updated ones.
+    storage: Storage,
+    /// Hook processes that haven't been reaped yet.
+    running: Vec<(PathBuf, process::Child)>,
+    /// Recent invocation times of each hook, for rate limiting.
+    invocations: Vec<VecDeque<LocalTime>>,
+}
+
+impl Hooks {
+    /// Create a new hook runner.
+    pub fn new(config: Config, storage: Storage) -> Self {
+        let invocations = vec![VecDeque::new(); config.commands.len()];
+
+        Self {
+            config,
+            storage,
+            running: Vec::new(),
+            invocations,
+        }
+    }
+
+    /// Whether no hooks are configured.
+    pub fn is_empty(&self) -> bool {
+        self.config.is_empty()
+    }
+
+    /// Run hooks for the given events, until there are no more events.
+    pub fn run(mut self, events: impl IntoIterator<Item = Event>) {
+        for event in events {
+            for payload in self.payloads(&event) {
+                self.trigger(&payload, LocalTime::now());
+            }
+        }
+    }
+
+    /// Derive the hook payloads from a node event.
+    ///
+    /// Only fetches trigger hooks. Branches and tags that were created or updated trigger
+    /// [`HookEvent::RefUpdated`], and patches and issues that weren't in storage before
+    /// trigger [`HookEvent::PatchOpened`] and [`HookEvent::IssueCreated`].
+    pub fn payloads(&self, event: &Event) -> Vec<Payload> {
+        let Event::RefsFetched {
+            remote,
+            rid,
+            updated,
+        } = event
+        else {
+            return Vec::new();
+        };
+        let mut payloads = Vec::new();
+
+        for update in updated {
+            let (refname, old, new) = match update {
+                RefUpdate::Updated { name, old, new } => (name, Some(*old), *new),
+                RefUpdate::Created { name, oid } => (name, None, *oid),
+                RefUpdate::Deleted { .. } | RefUpdate::Skipped { .. } => continue,
+            };
+            let Some(namespaced) = refname.to_namespaced() else {
+                continue;
+            };
+            let qualified = namespaced.strip_namespace();
+            let event = match cob::TypedId::from_qualified(&qualified) {
+                Ok(Some(cob)) if old.is_none() && (cob.is_patch() || cob.is_issue()) => {
+                    if !self.is_new(*rid, &qualified, updated) {
+                        continue;
+                    }
+                    if cob.is_patch() {
+                        HookEvent::PatchOpened
+                    } else {
+                        HookEvent::IssueCreated
+                    }
+                }
+                Ok(Some(_)) | Err(_) => continue,
+                Ok(None) => {
+                    let name = qualified.as_str();
+                    if !name.starts_with("refs/heads/") && !name.starts_with("refs/tags/") {
+                        continue;
+                    }
+                    HookEvent::RefUpdated
+                }
+            };
+            payloads.push(Payload {
+                event,
+                rid: *rid,
+                remote: *remote,
+                refname: refname.clone(),
+                old,
+                new,
+            });
+        }
+        payloads
+    }
+
+    /// Run the hooks that match the given payload.
+    pub fn trigger(&mut self, payload: &Payload, now: LocalTime) {
+        self.reap();
+
+        for (ix, hook) in self.config.commands.iter().enumerate() {
+            if !hook.events.contains(&payload.event) {
+                continue;
+            }
+            if !hook.rids.is_empty() && !hook.rids.contains(&payload.rid) {
+                continue;
+            }
+            let invocations = &mut self.invocations[ix];
+            while invocations
+                .front()
+                .is_some_and(|t| now - *t >= RATE_LIMIT_WINDOW)
+            {
+                invocations.pop_front();
+            }
+            if invocations.len() >= self.config.rate {
+                log::warn!(
+                    target: "node",
+                    "Hook {} was run too often, dropping {} event for {}",
+                    hook.command.display(), payload.event, payload.rid
+                );
+                continue;
+            }
+            if self.running.len() >= self.config.concurrency {
+                log::warn!(
+                    target: "node",
+                    "Too many hooks running, dropping {} event for {}",
+                    payload.event, payload.rid
+                );
+                continue;
+            }
+            match spawn(hook, payload) {
+                Ok(child) => {
+                    log::debug!(
+                        target: "node",
+                        "Running hook {} for {} event on {}",
+                        hook.command.display(), payload.event, payload.refname
+                    );
+                    invocations.push_back(now);
+                    self.running.push((hook.command.clone(), child));
+                }
+                Err(e) => {
+                    log::error!(target: "node", "Failed to run hook {}: {e}", hook.command.display());
+                }
+            }
+        }
+    }
+
+    /// Reap the hooks that have exited, logging failures.
+    fn reap(&mut self) {
+        self.running
+            .retain_mut(|(command, child)| match child.try_wait() {
+                Ok(None) => true,
+                Ok(Some(status)) => {
+                    if !status.success() {
+                        log::warn!(target: "node", "Hook {} failed: {status}", command.display());
+                    }
+                    false
+                }
+                Err(e) => {
+                    log::error!(target: "node", "Failed to wait for hook {}: {e}", command.display());
+                    false
+                }
+            });
+    }
+
+    /// Whether a COB was first seen with the given updates, ie. the only namespaces that have
+    /// the COB reference are the ones it was created in by these updates.
+    fn is_new(&self, rid: RepoId, refname: &git::Qualified, updated: &[RefUpdate]) -> bool {
+        let created = updated
+            .iter()
+            .filter(|u| match u {
+                RefUpdate::Created { name, .. } => name
+                    .to_namespaced()
+                    .is_some_and(|n| n.strip_namespace() == *refname),
+                _ => false,
+            })
+            .count();
+        let Ok(repo) = self.storage.repository(rid) else {
+            return false;
+        };
+        let Ok(remotes) = repo.remote_ids() else {
+            return false;
+        };
+        let existing = remotes
+            .filter_map(Result::ok)
+            .filter(|remote| repo.reference_oid(remote, refname).is_ok())
+            .count();
+
+        existing <= created
+    }
+}
+
+/// Spawn a hook process, passing it the payload as a line of JSON on its standard input, as
+/// well as through environment variables for simple shell hooks.
+fn spawn(hook: &Hook, payload: &Payload) -> io::Result<process::Child> {
+    let old = payload
+        .old
+        .unwrap_or_else(|| git::raw::Oid::zero().into())
+        .to_string();
+    let mut child = process::Command::new(&hook.command)
+        .env("RAD_EVENT", payload.event.to_string())
+        .env("RAD_RID", payload.rid.to_string())
+        .env("RAD_REF", payload.refname.as_str())
+        .env("RAD_OLD", old)
+        .env("RAD_NEW", payload.new.to_string())
+        .stdin(process::Stdio::piped())
+        .stdout(process::Stdio::null())
+        .stderr(process::Stdio::null())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        let mut json = serde_json::to_vec(payload).map_err(io::Error::from)?;
+        json.push(b'\n');
+        // Nb. The hook may exit without reading its input, which is fine.
+        if let Err(e) = stdin.write_all(&json) {
+            log::debug!(target: "node", "Failed to write payload to hook {}: {e}", hook.command.display());
+        }
+    }
+    Ok(child)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use std::os::unix::fs::PermissionsExt as _;
+    use std::{fs, thread, time};
+
+    use radicle::git::raw::Oid;
+    use radicle::test::{arbitrary, fixtures};
+
+    use super::*;
+
+    /// Create a hook script that writes its payload and environment to `out`.
+    fn script(dir: &std::path::Path, out: &std::path::Path) -> PathBuf {
+        let path = dir.join("hook.sh");
+        fs::write(
+            &path,
+            format!(
+                "#!/bin/sh\n\
+                cat > {out}.tmp\n\
+                echo \"$RAD_EVENT $RAD_RID $RAD_REF $RAD_OLD $RAD_NEW\" >> {out}.tmp\n\
+                mv {out}.tmp {out}\n",
+                out = out.display()
+            ),
+        )
+        .unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+
+        path
+    }
+
+    fn hooks(tmp: &std::path::Path, command: PathBuf, rids: Vec<RepoId>) -> Hooks {
+        let storage = Storage::open(tmp.join("storage"), fixtures::user()).unwrap();
+        let config = Config {
+            commands: vec![Hook {
+                events: vec![HookEvent::RefUpdated],
+                rids,
+                command,
+            }],
+            ..Config::default()
+        };
+        Hooks::new(config, storage)
+    }
+
+    fn fetched(rid: RepoId, remote: NodeId, old: git::Oid, new: git::Oid) -> Event {
+        Event::RefsFetched {
+            remote,
+            rid,
+            updated: vec![
+                RefUpdate::Updated {
+                    name: git::RefString::try_from(format!(
+                        "refs/namespaces/{remote}/refs/heads/master"
+                    ))
+                    .unwrap(),
+                    old,
+                    new,
+                },
+                RefUpdate::Updated {
+                    name: git::RefString::try_from(format!(
+                        "refs/namespaces/{remote}/refs/rad/sigrefs"
+                    ))
+                    .unwrap(),
+                    old,
+                    new,
+                },
+            ],
+        }
+    }
+
+    fn wait(path: &std::path::Path) -> String {
+        for _ in 0..100 {
+            if let Ok(output) = fs::read_to_string(path) {
+                return output;
+            }
+            thread::sleep(time::Duration::from_millis(50));
+        }
+        panic!("hook was never run");
+    }
+
+    #[test]
+    fn test_hook_on_ref_update() {
+        let tmp = tempfile::tempdir().unwrap();
+        let out = tmp.path().join("payload");
+        let rid = arbitrary::gen::<RepoId>(1);
+        let remote = arbitrary::gen::<NodeId>(1);
+        let old =
+            git::Oid::from(Oid::from_str("a2b1c3d4e5f60718293a4b5c6d7e8f9012345678").unwrap());
+        let new =
+            git::Oid::from(Oid::from_str("b2b1c3d4e5f60718293a4b5c6d7e8f9012345678").unwrap());
+        let mut hooks = hooks(tmp.path(), script(tmp.path(), &out), vec![]);
+        let event = fetched(rid, remote, old, new);
+
+        // Only the branch update triggers a hook, `rad/sigrefs` doesn't.
+        let payloads = hooks.payloads(&event);
+        assert_eq!(payloads.len(), 1);
+
+        hooks.trigger(&payloads[0], LocalTime::now());
+
+        let output = wait(&out);
+        let mut lines = output.lines();
+        let payload: serde_json::Value = serde_json::from_str(lines.next().unwrap()).unwrap();
+        let refname = format!("refs/namespaces/{remote}/refs/heads/master");
+
+        assert_eq!(payload["event"], "ref-updated");
+        assert_eq!(payload["rid"], rid.to_string());
+        assert_eq!(payload["remote"], remote.to_string());
+        assert_eq!(payload["ref"], refname);
+        assert_eq!(payload["old"], old.to_string());
+        assert_eq!(payload["new"], new.to_string());
+        assert_eq!(
+            lines.next().unwrap(),
+            format!("ref-updated {rid} {refname} {old} {new}")
+        );
+    }
+
+    #[test]
+    fn test_hook_filters() {
+        let tmp = tempfile::tempdir().unwrap();
+        let out = tmp.path().join("payload");
+        let rid = arbitrary::gen::<RepoId>(1);
+        let other = arbitrary::gen::<RepoId>(1);
+        let remote = arbitrary::gen::<NodeId>(1);
+        let oid =
+            git::Oid::from(Oid::from_str("a2b1c3d4e5f60718293a4b5c6d7e8f9012345678").unwrap());
+        let mut hooks = hooks(tmp.path(), script(tmp.path(), &out), vec![rid]);
+        let now = LocalTime::now();
+
+        // Hooks only run for the repositories they are restricted to.
+        for payload in hooks.payloads(&fetched(other, remote, oid, oid)) {
+            hooks.trigger(&payload, now);
+        }
+        assert!(hooks.running.is_empty());
+
+        // Hooks are rate limited.
+        hooks.config.rate = 1;
+        let payloads = hooks.payloads(&fetched(rid, remote, oid, oid));
+        hooks.trigger(&payloads[0], now);
+        hooks.trigger(&payloads[0], now);
+        assert_eq!(hooks.invocations[0].len(), 1);
+
+        hooks.trigger(&payloads[0], now + RATE_LIMIT_WINDOW);
+        assert_eq!(hooks.invocations[0].len(), 1);
+        assert_eq!(hooks.invocations[0][0], now + RATE_LIMIT_WINDOW);
+    }
+}
@@ -0,0 +1,244 @@
+//! Periodic maintenance of the node's storage.
+//!
+//! Unlike the service, which only ever reads from storage, maintenance tasks write to it,
+//! and may take a while to complete. They therefore run on a dedicated thread.
+//!
+//! The policies database on the other hand is only ever written to by the service, so
+//! maintenance tasks read from it, and hand their updates to the service.
+use std::{io, thread, time};
+
+use localtime::{LocalDuration, LocalTime};
+use thiserror::Error;
+
+use radicle::node::policy::store::StoreReader;
+use radicle::node::policy::{self, Scope};
+use radicle::prelude::{NodeId, RepoId, Timestamp};
+use radicle::storage;
+use radicle::storage::{RepositoryError, WriteRepository as _, WriteStorage};
+
+use crate::runtime::Handle;
+use crate::service;
+
+/// How often maintenance tasks run.
+pub const MAINTENANCE_INTERVAL: time::Duration = time::Duration::from_secs(60 * 60);
+
+/// A maintenance error.
+#[derive(Error, Debug)]
+pub enum Error {
+    /// A policies database error.
+    #[error("policies database error: {0}")]
+    Policy(#[from] policy::store::Error),
+    /// A storage error.
+    #[error("storage error: {0}")]
+    Storage(#[from] storage::Error),
+    /// An error handing an update to the service.
+    #[error("service error: {0}")]
+    Service(#[from] io::Error),
+}
+
+/// Policy updates made by maintenance tasks, which are applied by the service.
+pub trait Policies {
+    /// Forget that a node was unfollowed.
+    fn forget_unfollowed(&mut self, nid: NodeId) -> Result<(), Error>;
+}
+
+impl Policies for Handle {
+    fn forget_unfollowed(&mut self, nid: NodeId) -> Result<(), Error> {
+        self.command(service::Command::ForgetUnfollowed(nid))?;
+
+        Ok(())
+    }
+}
+
+/// Storage maintenance.
+pub struct Maintenance<S, P> {
+    /// Node storage.
+    storage: S,
+    /// Node policies, read-only.
+    policies: StoreReader,
+    /// Where policy updates are sent.
+    updates: P,
+    /// Local node.
+    local: NodeId,
+    /// How long to keep the namespaces of unfollowed peers.
+    namespace_max_age: LocalDuration,
+}
+
+impl<S: WriteStorage, P: Policies> Maintenance<S, P> {
+    /// Create a new maintenance task runner.
+    pub fn new(
+        storage: S,
+        policies: StoreReader,
+        updates: P,
+        local: NodeId,
+        namespace_max_age: LocalDuration,
+    ) -> Self {
+        Self {
+            storage,
+            policies,
+            updates,
+            local,
+            namespace_max_age,
+        }
+    }
+
+    /// Run maintenance tasks forever, every [`MAINTENANCE_INTERVAL`].
+    pub fn run(mut self) {
+        loop {
+            match self.prune_namespaces(LocalTime::now()) {
+                Ok(pruned) if !pruned.is_empty() => {
+                    log::info!(target: "node", "Pruned {} namespace(s) of unfollowed peers", pruned.len());
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    log::error!(target: "node", "Error pruning namespaces of unfollowed peers: {e}");
+                }
+            }
+            thread::sleep(MAINTENANCE_INTERVAL);
+        }
+    }
+
+    /// Prune the namespaces of peers that were unfollowed longer ago than the configured
+    /// maximum age. Returns the namespaces that were pruned.
+    ///
+    /// Repositories seeded with the [`Scope::All`] scope are skipped, since the namespaces
+    /// would be fetched again, as are the namespaces of delegates.
+    pub fn prune_namespaces(&mut self, now: LocalTime) -> Result<Vec<(RepoId, NodeId)>, Error> {
+        let expiry = Timestamp::from(now) - self.namespace_max_age.as_millis() as u64;
+        let expired = self
+            .policies
+            .unfollowed()?
+            .into_iter()
+            .filter(|(_, timestamp)| *timestamp <= expiry)
+            .map(|(nid, _)| nid)
+            .collect::<Vec<_>>();
+        if expired.is_empty() {
+            return Ok(vec![]);
+        }
+        let repos = self.storage.repositories()?;
+        let mut pruned = Vec::new();
+
+        for nid in expired {
+            if nid == self.local || self.policies.is_following(&nid)? {
+                self.updates.forget_unfollowed(nid)?;
+                continue;
+            }
+            let mut complete = true;
+
+            for info in &repos {
+                let rid = info.rid;
+                let scope = self
+                    .policies
+                    .seed_policy(&rid)?
+                    .and_then(|policy| policy.scope());
+
+                if scope == Some(Scope::All) {
+                    continue;
+                }
+                let result = self
+                    .storage
+                    .repository_mut(rid)
+                    .and_then(|repo| repo.prune_namespace(&nid));
+
+                match result {
+                    Ok(0) => {}
+                    Ok(n) => {
+                        log::debug!(target: "node", "Pruned {n} reference(s) of {nid} in {rid}");
+                        pruned.push((rid, nid));
+                    }
+                    Err(RepositoryError::DelegateNamespace(_)) => {
+                        log::debug!(target: "node", "Skipping pruning of {nid} in {rid}: delegate namespace");
+                    }
+                    Err(e) => {
+                        log::error!(target: "node", "Error pruning namespace of {nid} in {rid}: {e}");
+                        complete = false;
+                    }
+                }
+            }
+            // Namespaces that couldn't be pruned are retried on the next run.
+            if complete {
+                self.updates.forget_unfollowed(nid)?;
+            }
+        }
+        Ok(pruned)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use radicle::crypto::test::signer::MockSigner;
+    use radicle::crypto::Signer as _;
+    use radicle::node::policy::store::StoreWriter;
+    use radicle::storage::{ReadRepository as _, ReadStorage as _};
+    use radicle::test::fixtures;
+    use radicle::Storage;
+
+    use super::*;
+
+    impl Policies for StoreWriter {
+        fn forget_unfollowed(&mut self, nid: NodeId) -> Result<(), Error> {
+            StoreWriter::forget_unfollowed(self, &nid)?;
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_prune_namespaces() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut rng = fastrand::Rng::new();
+        let signer = MockSigner::new(&mut rng);
+        let storage = Storage::open(tmp.path().join("storage"), fixtures::user()).unwrap();
+        let alice = *signer.public_key();
+        let bob = *MockSigner::new(&mut rng).public_key();
+        let eve = *MockSigner::new(&mut rng).public_key();
+        let local = *MockSigner::new(&mut rng).public_key();
+        let max_age = LocalDuration::from_mins(60);
+
+        radicle::storage::git::transport::local::register(storage.clone());
+
+        let (rid, _, _, head) =
+            fixtures::project(tmp.path().join("project"), &storage, &signer).unwrap();
+        let repo = storage.repository(rid).unwrap();
+
+        for remote in [bob, eve] {
+            repo.raw()
+                .reference(
+                    &format!("refs/namespaces/{remote}/refs/heads/master"),
+                    head,
+                    false,
+                    "test",
+                )
+                .unwrap();
+        }
+        let path = tmp.path().join("policies.db");
+        let mut policies = StoreWriter::open(&path).unwrap();
+        for nid in [alice, bob, eve] {
+            policies.follow(&nid, None).unwrap();
+        }
+        // Alice is a delegate, and is never pruned.
+        policies.unfollow(&alice).unwrap();
+        policies.unfollow(&bob).unwrap();
+
+        let mut maintenance = Maintenance::new(
+            storage.clone(),
+            StoreReader::reader(&path).unwrap(),
+            policies,
+            local,
+            max_age,
+        );
+        let now = LocalTime::now();
+
+        assert!(maintenance.prune_namespaces(now).unwrap().is_empty());
+        assert_eq!(maintenance.policies.unfollowed().unwrap().len(), 2);
+
+        let pruned = maintenance.prune_namespaces(now + max_age).unwrap();
+        assert_eq!(pruned, vec![(rid, bob)]);
+        assert!(repo.references_of(&bob).unwrap().is_empty());
+        assert!(!repo.references_of(&eve).unwrap().is_empty());
+        assert!(!repo.references_of(&alice).unwrap().is_empty());
+        assert!(repo.canonical_identity_head().is_ok());
+        assert!(maintenance.policies.unfollowed().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,56 @@
+//! Metrics listener, serving the service metrics over HTTP in the Prometheus text format.
+use std::io::prelude::*;
+use std::io::BufReader;
+use std::net::{TcpListener, TcpStream};
+use std::{io, time};
+
+use crate::service::metrics::Registry;
+
+/// Content type of the Prometheus text exposition format.
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+/// How long to wait for a scraper to send its request.
+pub const READ_TIMEOUT: time::Duration = time::Duration::from_secs(6);
+
+/// Serve metrics to incoming connections, one at a time.
+pub fn listen(listener: TcpListener, registry: Registry) {
+    log::debug!(target: "node", "Metrics thread listening on {:?}..", listener.local_addr());
+
+    for incoming in listener.incoming() {
+        match incoming {
+            Ok(stream) => {
+                if let Err(e) = serve(stream, &registry) {
+                    log::debug!(target: "node", "Error serving metrics: {e}");
+                }
+            }
+            Err(e) => log::error!(target: "node", "Failed to accept metrics connection: {e}"),
+        }
+    }
+}
+
+/// Serve a single metrics request.
+fn serve(mut stream: TcpStream, registry: &Registry) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+
+    // Skip the request headers, we don't need them.
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 0 && header.trim_end() != "" {
+        header.clear();
+    }
+
+    let mut parts = request.split_whitespace();
+    let (status, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/metrics" | "/")) => ("200 OK", registry.encode()),
+        (Some("GET"), Some(_)) => ("404 Not Found", String::new()),
+        _ => ("405 Method Not Allowed", String::new()),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {CONTENT_TYPE}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
@@ -7,8 +7,12 @@ pub mod gossip;
 pub mod io;
 pub mod limiter;
 pub mod message;
+pub mod metrics;
+pub mod rendezvous;
+pub mod schedule;
 pub mod session;
 
+use std::cmp::Reverse;
 use std::collections::hash_map::Entry;
 use std::collections::{BTreeSet, HashMap, HashSet};
 use std::net::IpAddr;
@@ -27,12 +31,16 @@ use radicle::node;
 use radicle::node::address;
 use radicle::node::address::Store as _;
 use radicle::node::address::{AddressBook, AddressType, KnownAddress};
+use radicle::node::ban;
+use radicle::node::ban::Store as _;
 use radicle::node::config::PeerConfig;
 use radicle::node::refs::Store as _;
+use radicle::node::reputation;
+use radicle::node::reputation::Store as _;
 use radicle::node::routing::Store as _;
 use radicle::node::seed;
 use radicle::node::seed::Store as _;
-use radicle::node::{ConnectOptions, Penalty, Severity};
+use radicle::node::{ConnectOptions, Penalty, RefClass, Severity};
 use radicle::storage::refs::SIGREFS_BRANCH;
 use radicle::storage::RepositoryError;
 use radicle_fetch::policy::SeedingPolicy;
@@ -42,7 +50,8 @@ use crate::identity::RepoId;
 use crate::node::routing;
 use crate::node::routing::InsertResult;
 use crate::node::{
-    Address, Alias, Features, FetchResult, HostName, Seed, Seeds, SyncStatus, SyncedAt,
+    Address, Alias, Features, FetchProgress, FetchResult, HostName, Seed, Seeds, ShutdownPhase,
+    SyncStatus, SyncedAt,
 };
 use crate::prelude::*;
 use crate::runtime::Emitter;
@@ -69,6 +78,8 @@ use self::io::Outbox;
 use self::limiter::RateLimiter;
 use self::message::InventoryAnnouncement;
 use self::policy::NamespacesError;
+use self::rendezvous::Rendezvous;
+use self::schedule::Schedule;
 
 /// How often to run the "idle" task.
 pub const IDLE_INTERVAL: LocalDuration = LocalDuration::from_secs(30);
@@ -106,6 +117,8 @@ pub const CONNECTION_RETRY_DELTA: LocalDuration = LocalDuration::from_mins(10);
 pub const FETCH_TIMEOUT: time::Duration = time::Duration::from_secs(3);
 /// Target number of peers to maintain connections to.
 pub const TARGET_OUTBOUND_PEERS: usize = 8;
+/// How often to check on ongoing fetches while shutting down.
+pub const SHUTDOWN_INTERVAL: LocalDuration = LocalDuration::from_secs(1);
 
 /// Maximum external address limit imposed by message size limits.
 pub use message::ADDRESS_LIMIT;
@@ -146,6 +159,7 @@ pub struct PeerMetrics {
     pub sent_git_bytes: usize,
     pub sent_gossip_messages: usize,
     pub streams_opened: usize,
+    pub streams_reused: usize,
     pub inbound_connection_attempts: usize,
     pub outbound_connection_attempts: usize,
     pub disconnects: usize,
@@ -198,6 +212,10 @@ pub enum Error {
     #[error(transparent)]
     Seeds(#[from] seed::Error),
     #[error(transparent)]
+    Reputation(#[from] reputation::Error),
+    #[error(transparent)]
+    Ban(#[from] ban::Error),
+    #[error(transparent)]
     Policy(#[from] policy::Error),
     #[error(transparent)]
     Repository(#[from] radicle::storage::RepositoryError),
@@ -207,7 +225,13 @@ pub enum Error {
 
 /// A store for all node data.
 pub trait Store:
-    address::Store + gossip::Store + routing::Store + seed::Store + node::refs::Store
+    address::Store
+    + gossip::Store
+    + routing::Store
+    + seed::Store
+    + node::refs::Store
+    + reputation::Store
+    + ban::Store
 {
 }
 
@@ -234,8 +258,16 @@ pub enum Command {
     ListenAddrs(chan::Sender<Vec<std::net::SocketAddr>>),
     /// Lookup seeds for the given repository in the routing table.
     Seeds(RepoId, chan::Sender<Seeds>),
-    /// Fetch the given repository from the network.
-    Fetch(RepoId, NodeId, time::Duration, chan::Sender<FetchResult>),
+    /// Fetch the given classes of references of a repository from the network.
+    /// Progress is reported on the optional progress channel, before the result.
+    Fetch(
+        RepoId,
+        NodeId,
+        RefClass,
+        time::Duration,
+        chan::Sender<FetchResult>,
+        Option<chan::Sender<FetchProgress>>,
+    ),
     /// Seed the given repository.
     Seed(RepoId, Scope, chan::Sender<bool>),
     /// Unseed the given repository.
@@ -244,8 +276,19 @@ pub enum Command {
     Follow(NodeId, Option<Alias>, chan::Sender<bool>),
     /// Unfollow the given node.
     Unfollow(NodeId, chan::Sender<bool>),
+    /// Forget that the given node was unfollowed, once its namespaces were pruned.
+    ForgetUnfollowed(NodeId),
+    /// Ban a node or a range of IP addresses.
+    Ban(ban::Ban, chan::Sender<bool>),
+    /// Lift the ban of a node or a range of IP addresses.
+    Unban(ban::Target, chan::Sender<bool>),
+    /// Get the active bans.
+    Bans(chan::Sender<Vec<ban::Ban>>),
     /// Query the internal service state.
     QueryState(Arc<QueryState>, chan::Sender<Result<(), CommandError>>),
+    /// Shutdown gracefully, reporting progress on the given channel. The channel is
+    /// disconnected once the service is ready to exit.
+    Shutdown(chan::Sender<ShutdownPhase>),
 }
 
 impl fmt::Debug for Command {
@@ -259,12 +302,17 @@ impl fmt::Debug for Command {
             Self::Config(_) => write!(f, "Config"),
             Self::ListenAddrs(_) => write!(f, "ListenAddrs"),
             Self::Seeds(id, _) => write!(f, "Seeds({id})"),
-            Self::Fetch(id, node, _, _) => write!(f, "Fetch({id}, {node})"),
+            Self::Fetch(id, node, refs, _, _, _) => write!(f, "Fetch({id}, {node}, {refs})"),
             Self::Seed(id, scope, _) => write!(f, "Seed({id}, {scope})"),
             Self::Unseed(id, _) => write!(f, "Unseed({id})"),
             Self::Follow(id, _, _) => write!(f, "Follow({id})"),
             Self::Unfollow(id, _) => write!(f, "Unfollow({id})"),
+            Self::ForgetUnfollowed(id) => write!(f, "ForgetUnfollowed({id})"),
+            Self::Ban(ban, _) => write!(f, "Ban({})", ban.target),
+            Self::Unban(target, _) => write!(f, "Unban({target})"),
+            Self::Bans(_) => write!(f, "Bans"),
             Self::QueryState { .. } => write!(f, "QueryState(..)"),
+            Self::Shutdown(_) => write!(f, "Shutdown"),
         }
     }
 }
@@ -300,8 +348,23 @@ pub struct FetchState {
     pub from: NodeId,
     /// What refs we're fetching.
     pub refs_at: Vec<RefsAt>,
+    /// What classes of references we're fetching.
+    pub refs: RefClass,
     /// Channels waiting for fetch results.
     pub subscribers: Vec<chan::Sender<FetchResult>>,
+    /// Channels waiting for fetch progress.
+    pub progress: fetch::ProgressSubscribers,
+    /// When the fetch was initiated.
+    pub started_at: LocalTime,
+}
+
+/// State of a graceful shutdown.
+#[derive(Debug)]
+struct Shutdown {
+    /// Time after which ongoing fetches are no longer waited for.
+    deadline: LocalTime,
+    /// Channel on which progress is reported. Dropped once the service is ready to exit.
+    progress: Option<chan::Sender<ShutdownPhase>>,
 }
 
 impl FetchState {
@@ -370,6 +433,26 @@ where
     pub fn refs_mut(&mut self) -> &mut impl node::refs::Store {
         &mut self.0
     }
+
+    /// Get the database as a reputation store.
+    pub fn reputation(&self) -> &impl reputation::Store {
+        &self.0
+    }
+
+    /// Get the database as a reputation store, mutably.
+    pub fn reputation_mut(&mut self) -> &mut impl reputation::Store {
+        &mut self.0
+    }
+
+    /// Get the database as a ban store.
+    pub fn bans(&self) -> &impl ban::Store {
+        &self.0
+    }
+
+    /// Get the database as a ban store, mutably.
+    pub fn bans_mut(&mut self) -> &mut impl ban::Store {
+        &mut self.0
+    }
 }
 
 impl<D> AsMut<D> for Stores<D> {
@@ -414,6 +497,8 @@ pub struct Service<D, S, G> {
     rng: Rng,
     /// Ongoing fetches.
     fetching: HashMap<RepoId, FetchState>,
+    /// Seeds tried in the current sync round, for seeded repositories we're missing.
+    missing: HashMap<RepoId, HashSet<NodeId>>,
     /// Request/connection rate limiter.
     limiter: RateLimiter,
     /// Current seeded repositories bloom filter.
@@ -442,6 +527,16 @@ pub struct Service<D, S, G> {
     listening: Vec<net::SocketAddr>,
     /// Latest metrics for all nodes connected to since the last start.
     metrics: Metrics,
+    /// Service metric registry, shared with the metrics listener.
+    registry: metrics::Registry,
+    /// Pending rendezvous attempts.
+    rendezvous: Rendezvous,
+    /// Scheduled sync of seeded repositories.
+    schedule: Schedule,
+    /// Bans set by the node operator. Loaded from the database on initialization.
+    bans: ban::Bans,
+    /// Graceful shutdown state, if the service is shutting down.
+    shutdown: Option<Shutdown>,
 }
 
 impl<D, S, G> Service<D, S, G>
@@ -484,6 +579,7 @@ where
         let last_timestamp = node.timestamp;
         let clock = LocalTime::default(); // Updated on initialize.
         let inventory = gossip::inventory(clock.into(), []); // Updated on initialize.
+        let schedule = Schedule::new(config.sync.clone(), rng.clone());
 
         Self {
             config,
@@ -499,6 +595,7 @@ where
             limiter,
             sessions,
             fetching: HashMap::new(),
+            missing: HashMap::new(),
             filter: Filter::empty(),
             relayed_by: HashMap::default(),
             last_idle: LocalTime::default(),
@@ -513,6 +610,11 @@ where
             emitter,
             listening: vec![],
             metrics: Metrics::default(),
+            registry: metrics::Registry::default(),
+            rendezvous: Rendezvous::default(),
+            schedule,
+            bans: ban::Bans::default(),
+            shutdown: None,
         }
     }
 
@@ -521,10 +623,20 @@ where
         self.started_at
     }
 
+    /// Get the service metric registry.
+    pub fn registry(&self) -> &metrics::Registry {
+        &self.registry
+    }
+
     /// Return the next i/o action to execute.
     #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> Option<io::Io> {
-        self.outbox.next()
+        let io = self.outbox.next();
+
+        if let Some(io::Io::Write(_, msgs)) = &io {
+            self.registry.gossip(metrics::Direction::Out, msgs);
+        }
+        io
     }
 
     /// Seed a repository.
@@ -662,6 +774,10 @@ where
             Err(e) => error!(target: "service", "Error checking refs database: {e}"),
         }
 
+        // Load operator bans, dropping the ones that expired while the node was stopped.
+        self.db.bans_mut().purge_bans(time.into())?;
+        self.bans = self.db.bans().bans()?.into_iter().collect();
+
         let announced = self
             .db
             .seeds()
@@ -669,8 +785,11 @@ where
             .collect::<Result<HashMap<_, _>, _>>()?;
         let mut inventory = BTreeSet::new();
         let mut private = BTreeSet::new();
+        let repos = self.storage.repositories()?;
 
-        for repo in self.storage.repositories()? {
+        self.registry.repositories(repos.len());
+
+        for repo in repos {
             let rid = repo.rid;
 
             // If we're not seeding this repo, just skip it.
@@ -725,6 +844,7 @@ where
         self.db
             .routing_mut()
             .remove_inventories(private.iter(), &nid)?;
+        self.sample_routing();
 
         // Setup subscription filter for seeded repos.
         self.filter = Filter::new(
@@ -742,6 +862,9 @@ where
         // Start periodic tasks.
         self.outbox.wakeup(IDLE_INTERVAL);
         self.outbox.wakeup(GOSSIP_INTERVAL);
+        // Schedule the first sync round. Rounds are checked for on every wake-up, so there's
+        // no need to schedule a wake-up of their own.
+        self.schedule.start(time);
 
         Ok(())
     }
@@ -775,14 +898,21 @@ where
             now - self.started_at.expect("Service::wake: service must be initialized")
         );
 
+        // While shutting down, no new work is started.
+        if self.shutdown.is_some() {
+            self.drain();
+            return;
+        }
         if now - self.last_idle >= IDLE_INTERVAL {
             trace!(target: "service", "Running 'idle' task...");
 
             self.keep_alive(&now);
             self.disconnect_unresponsive_peers(&now);
+            self.expire_rendezvous(&now);
             self.idle_connections();
             self.maintain_connections();
             self.dequeue_fetches();
+            self.sample_routing();
             self.outbox.wakeup(IDLE_INTERVAL);
             self.last_idle = now;
         }
@@ -828,6 +958,13 @@ where
             self.outbox.wakeup(PRUNE_INTERVAL);
             self.last_prune = now;
         }
+        if let Some(delay) = self.schedule.round(now) {
+            trace!(target: "service", "Running 'scheduled sync' task...");
+
+            if let Err(e) = self.sync_seeded_repositories(now + delay) {
+                error!(target: "service", "Error syncing seeded repositories: {e}");
+            }
+        }
 
         // Always check whether there are persistent peers that need reconnecting.
         self.maintain_persistent();
@@ -868,8 +1005,8 @@ where
                     error!(target: "service", "Error getting seeds for {rid}: {e}");
                 }
             },
-            Command::Fetch(rid, seed, timeout, resp) => {
-                self.fetch(rid, seed, timeout, Some(resp));
+            Command::Fetch(rid, seed, refs, timeout, resp, progress) => {
+                self.fetch(rid, seed, refs, timeout, Some(resp), progress);
             }
             Command::Seed(rid, scope, resp) => {
                 // Update our seeding policy.
@@ -904,6 +1041,32 @@ where
                     .expect("Service::command: error unfollowing node");
                 resp.send(updated).ok();
             }
+            Command::ForgetUnfollowed(id) => {
+                if let Err(e) = self.policies.forget_unfollowed(&id) {
+                    error!(target: "service", "Error forgetting unfollowed node {id}: {e}");
+                }
+            }
+            Command::Ban(ban, resp) => match self.ban(ban) {
+                Ok(updated) => {
+                    resp.send(updated).ok();
+                }
+                Err(e) => {
+                    error!(target: "service", "Error adding ban: {e}");
+                }
+            },
+            Command::Unban(target, resp) => match self.db.bans_mut().unban(&target) {
+                Ok(updated) => {
+                    self.bans.remove(&target);
+                    resp.send(updated).ok();
+                }
+                Err(e) => {
+                    error!(target: "service", "Error removing ban of {target}: {e}");
+                }
+            },
+            Command::Bans(resp) => {
+                self.purge_bans();
+                resp.send(self.bans.iter().cloned().collect()).ok();
+            }
             Command::AnnounceRefs(id, resp) => {
                 let doc = match self.storage.get(id) {
                     Ok(Some(doc)) => doc,
@@ -944,7 +1107,82 @@ where
             Command::QueryState(query, sender) => {
                 sender.send(query(self)).ok();
             }
+            Command::Shutdown(progress) => {
+                self.shutdown(progress);
+            }
+        }
+    }
+
+    /// Start a graceful shutdown. New fetches and connections are refused, queued fetches
+    /// are dropped, and ongoing fetches are given until the configured shutdown timeout to
+    /// complete, after which all peers are disconnected.
+    fn shutdown(&mut self, progress: chan::Sender<ShutdownPhase>) {
+        if self.shutdown.is_some() {
+            warn!(target: "service", "Ignoring shutdown request, already shutting down");
+            return;
+        }
+        info!(
+            target: "service",
+            "Shutting down, waiting for {} ongoing fetch(es)..", self.fetching.len()
+        );
+
+        for session in self.sessions.values_mut() {
+            while let Some(fetch) = session.dequeue_fetch() {
+                if let Some(c) = fetch.channel {
+                    c.send(FetchResult::Failed {
+                        reason: String::from("node is shutting down"),
+                    })
+                    .ok();
+                }
+            }
+        }
+        self.shutdown = Some(Shutdown {
+            deadline: self.clock + self.config.limits.shutdown_timeout,
+            progress: Some(progress),
+        });
+        self.drain();
+    }
+
+    /// Make progress on a graceful shutdown, if one was started.
+    fn drain(&mut self) {
+        let Some(shutdown) = &mut self.shutdown else {
+            return;
+        };
+        let Some(progress) = &shutdown.progress else {
+            // Already disconnected from peers.
+            return;
+        };
+        if !self.fetching.is_empty() {
+            if self.clock < shutdown.deadline {
+                progress
+                    .send(ShutdownPhase::Draining {
+                        fetches: self.fetching.len(),
+                    })
+                    .ok();
+                self.outbox.wakeup(SHUTDOWN_INTERVAL);
+
+                return;
+            }
+            warn!(
+                target: "service",
+                "Shutdown timeout reached, abandoning {} ongoing fetch(es)..", self.fetching.len()
+            );
         }
+        let peers = self
+            .sessions
+            .connected()
+            .map(|(nid, _)| *nid)
+            .collect::<Vec<_>>();
+
+        progress
+            .send(ShutdownPhase::Disconnecting { peers: peers.len() })
+            .ok();
+
+        for nid in peers {
+            self.outbox.disconnect(nid, DisconnectReason::Shutdown);
+        }
+        progress.send(ShutdownPhase::Exiting).ok();
+        shutdown.progress = None;
     }
 
     /// Initiate an outgoing fetch for some repository, based on another node's announcement.
@@ -963,7 +1201,15 @@ where
                 if status.want.is_empty() {
                     debug!(target: "service", "Skipping fetch for {rid}, all refs are already in storage");
                 } else {
-                    return self._fetch(rid, from, status.want, timeout, channel);
+                    return self._fetch(
+                        rid,
+                        from,
+                        status.want,
+                        RefClass::All,
+                        timeout,
+                        channel,
+                        None,
+                    );
                 }
             }
             Err(e) => {
@@ -979,10 +1225,12 @@ where
         &mut self,
         rid: RepoId,
         from: NodeId,
+        refs: RefClass,
         timeout: time::Duration,
         channel: Option<chan::Sender<FetchResult>>,
+        progress: Option<chan::Sender<FetchProgress>>,
     ) -> bool {
-        self._fetch(rid, from, vec![], timeout, channel)
+        self._fetch(rid, from, vec![], refs, timeout, channel, progress)
     }
 
     fn _fetch(
@@ -990,33 +1238,54 @@ where
         rid: RepoId,
         from: NodeId,
         refs_at: Vec<RefsAt>,
+        refs: RefClass,
         timeout: time::Duration,
         channel: Option<chan::Sender<FetchResult>>,
+        progress: Option<chan::Sender<FetchProgress>>,
     ) -> bool {
-        match self.try_fetch(rid, &from, refs_at.clone(), timeout) {
+        if self.shutdown.is_some() {
+            debug!(target: "service", "Ignoring fetch of {rid} from {from}, shutting down..");
+
+            if let Some(c) = channel {
+                c.send(FetchResult::Failed {
+                    reason: String::from("node is shutting down"),
+                })
+                .ok();
+            }
+            return false;
+        }
+        match self.try_fetch(rid, &from, refs_at.clone(), refs, timeout) {
             Ok(fetching) => {
                 if let Some(c) = channel {
                     fetching.subscribe(c);
                 }
+                if let Some(c) = progress {
+                    fetching.progress.subscribe(c);
+                }
                 return true;
             }
             Err(TryFetchError::AlreadyFetching(fetching)) => {
                 // If we're already fetching the same refs from the requested peer, there's nothing
                 // to do, we simply add the supplied channel to the list of subscribers so that it
                 // is notified on completion. Otherwise, we queue a fetch with the requested peer.
-                if fetching.from == from && fetching.refs_at == refs_at {
+                if fetching.from == from && fetching.refs_at == refs_at && fetching.refs == refs {
                     debug!(target: "service", "Ignoring redundant fetch of {rid} from {from}");
 
                     if let Some(c) = channel {
                         fetching.subscribe(c);
                     }
+                    if let Some(c) = progress {
+                        fetching.progress.subscribe(c);
+                    }
                 } else {
                     let fetch = QueuedFetch {
                         rid,
                         refs_at,
+                        refs,
                         from,
                         timeout,
                         channel,
+                        progress,
                     };
                     debug!(target: "service", "Queueing fetch for {rid} with {from} (already fetching)..");
 
@@ -1028,9 +1297,11 @@ where
                 self.queue_fetch(QueuedFetch {
                     rid,
                     refs_at,
+                    refs,
                     from,
                     timeout,
                     channel,
+                    progress,
                 });
             }
             Err(e) => {
@@ -1062,12 +1333,15 @@ where
         rid: RepoId,
         from: &NodeId,
         refs_at: Vec<RefsAt>,
+        refs: RefClass,
         timeout: time::Duration,
     ) -> Result<&mut FetchState, TryFetchError> {
         let from = *from;
         let Some(session) = self.sessions.get_mut(&from) else {
             return Err(TryFetchError::SessionNotConnected);
         };
+        // The number of ongoing fetches, once this one is started.
+        let count = self.fetching.len() + 1;
         let fetching = self.fetching.entry(rid);
 
         trace!(target: "service", "Trying to fetch {refs_at:?} for {rid}..");
@@ -1096,9 +1370,20 @@ where
         let fetching = fetching.insert(FetchState {
             from,
             refs_at: refs_at.clone(),
+            refs,
             subscribers: vec![],
+            progress: fetch::ProgressSubscribers::default(),
+            started_at: self.clock,
         });
-        self.outbox.fetch(session, rid, refs_at, timeout);
+        self.outbox.fetch(
+            session,
+            rid,
+            refs_at,
+            refs,
+            timeout,
+            fetching.progress.clone(),
+        );
+        self.registry.fetching(count);
 
         Ok(fetching)
     }
@@ -1115,11 +1400,29 @@ where
         };
         debug_assert_eq!(fetching.from, remote);
 
+        self.registry.fetching(self.fetching.len());
+        self.registry.fetched(result.is_ok());
+
         if let Some(s) = self.sessions.get_mut(&remote) {
             // Mark this RID as fetched for this session.
             s.fetched(rid);
         }
 
+        // Keep track of how fetches from this peer went, to choose where to fetch from next time.
+        let recorded = match &result {
+            Ok(_) => self.db.reputation_mut().fetch_succeeded(
+                &remote,
+                self.clock.diff(fetching.started_at),
+                self.clock,
+            ),
+            Err(e) if e.is_remote() => self.db.reputation_mut().fetch_failed(&remote, self.clock),
+            // Local errors say nothing about the remote.
+            Err(_) => Ok(()),
+        };
+        if let Err(e) = recorded {
+            error!(target: "service", "Error recording fetch statistics for {remote}: {e}");
+        }
+
         // Notify all fetch subscribers of the fetch result. This is used when the user requests
         // a fetch via the CLI, for example.
         for sub in &fetching.subscribers {
@@ -1145,11 +1448,15 @@ where
         match result {
             Ok(fetch::FetchResult {
                 updated,
-                namespaces,
+                mut namespaces,
+                excluded,
                 clone,
                 doc,
+                ..
             }) => {
                 info!(target: "service", "Fetched {rid} from {remote} successfully");
+                self.missing.remove(&rid);
+                self.schedule.fetched(rid, self.clock);
                 // Update our routing table in case this fetch was user-initiated and doesn't
                 // come from an announcement.
                 self.seed_discovered(rid, remote, self.clock.into());
@@ -1175,11 +1482,22 @@ where
                     if let Err(e) = self.add_inventory(rid) {
                         error!(target: "service", "Error announcing inventory for {rid}: {e}");
                     }
+                    self.storage_changed();
+                }
+
+                // Namespaces that were only partially fetched have a `rad/sigrefs` that
+                // doesn't match their stored references, so we don't announce them.
+                for nid in &excluded {
+                    if namespaces.remove(nid) {
+                        debug!(target: "service", "Not announcing partially fetched refs of {nid} for {rid}..");
+                    }
                 }
 
                 // It's possible for a fetch to succeed but nothing was updated.
                 if updated.is_empty() || updated.iter().all(|u| u.is_skipped()) {
                     debug!(target: "service", "Nothing to announce, no refs were updated..");
+                } else if namespaces.is_empty() {
+                    debug!(target: "service", "Nothing to announce, all updated refs were partially fetched..");
                 } else {
                     // Finally, announce the refs. This is useful for nodes to know what we've synced,
                     // beyond just knowing that we have added an item to our inventory.
@@ -1193,13 +1511,22 @@ where
 
                 // For now, we only disconnect the remote in case of timeout. In the future,
                 // there may be other reasons to disconnect.
+                let remote_error = err.is_remote();
                 if err.is_timeout() {
                     self.outbox.disconnect(remote, DisconnectReason::Fetch(err));
                 }
+                // If we're missing this repository, try the next seed.
+                if remote_error && self.missing.contains_key(&rid) && !self.fetch_missing(rid) {
+                    debug!(target: "service", "No more connected seeds to fetch {rid} from..");
+                }
             }
         }
-        // We can now try to dequeue more fetches.
-        self.dequeue_fetches();
+        if self.shutdown.is_some() {
+            self.drain();
+        } else {
+            // We can now try to dequeue more fetches.
+            self.dequeue_fetches();
+        }
     }
 
     /// Attempt to dequeue fetches from all peers.
@@ -1229,8 +1556,10 @@ where
                 rid,
                 from,
                 refs_at,
+                refs: class,
                 timeout,
                 channel,
+                progress,
             }) = sess.dequeue_fetch()
             {
                 debug!(target: "service", "Dequeued fetch for {rid} from session {from}..");
@@ -1246,7 +1575,7 @@ where
                     self.fetch_refs_at(rid, from, refs, scope, timeout, channel);
                 } else {
                     // If no refs are specified, always do a full fetch.
-                    self.fetch(rid, from, timeout, channel);
+                    self.fetch(rid, from, class, timeout, channel, progress);
                 }
             }
         }
@@ -1254,6 +1583,10 @@ where
 
     /// Inbound connection attempt.
     pub fn accepted(&mut self, ip: IpAddr) -> bool {
+        if self.shutdown.is_some() {
+            debug!(target: "service", "Rejecting inbound connection from {ip}, shutting down..");
+            return false;
+        }
         // Always accept localhost connections, even if we already reached
         // our inbound connection limit.
         if ip.is_loopback() || ip.is_unspecified() {
@@ -1272,6 +1605,10 @@ where
             }
             Err(e) => error!(target: "service", "Error querying ban status for {ip}: {e}"),
         }
+        if let Some(ban) = self.banned_ip(&ip) {
+            debug!(target: "service", "Rejecting inbound connection from {ip}, banned by operator ({})", ban.target);
+            return false;
+        }
         let host: HostName = ip.into();
 
         if self.limiter.limit(
@@ -1295,6 +1632,7 @@ where
             #[cfg(debug_assertions)]
             panic!("Service::attempted: unknown session {nid}@{addr}");
         }
+        self.sessions_changed();
     }
 
     pub fn listening(&mut self, local_addr: net::SocketAddr) {
@@ -1305,8 +1643,22 @@ where
 
     pub fn connected(&mut self, remote: NodeId, addr: Address, link: Link) {
         info!(target: "service", "Connected to {remote} ({addr}) ({link:?})");
+
+        // Inbound connections are accepted before the remote's identity is known.
+        if link.is_inbound() {
+            if let Some(ban) = self.banned_node(&remote) {
+                debug!(target: "service", "Disconnecting {remote}, banned by operator ({})", ban.target);
+                self.outbox.disconnect(remote, DisconnectReason::Banned);
+
+                return;
+            }
+        }
         self.emitter.emit(Event::PeerConnected { nid: remote });
 
+        if self.rendezvous.complete(&remote).is_some() {
+            info!(target: "service", "Rendezvous with {remote} succeeded");
+        }
+
         let msgs = self.initial(link);
 
         if link.is_outbound() {
@@ -1359,6 +1711,7 @@ where
                 }
             }
         }
+        self.sessions_changed();
     }
 
     pub fn disconnected(&mut self, remote: NodeId, link: Link, reason: &DisconnectReason) {
@@ -1397,6 +1750,7 @@ where
             }
             false
         });
+        self.registry.fetching(self.fetching.len());
 
         // Attempt to re-connect to persistent peers.
         if self.config.peer(&remote).is_some() {
@@ -1427,9 +1781,12 @@ where
                     }
                 }
                 DisconnectReason::Session(e) => e.severity(),
-                DisconnectReason::Command
+                DisconnectReason::Proxy(_)
+                | DisconnectReason::Command
                 | DisconnectReason::Conflict
-                | DisconnectReason::SelfConnection => Severity::Low,
+                | DisconnectReason::SelfConnection
+                | DisconnectReason::Banned
+                | DisconnectReason::Shutdown => Severity::Low,
             };
 
             if let Err(e) = self
@@ -1445,10 +1802,18 @@ where
                 self.maintain_connections();
             }
         }
+        // If we weren't able to reach the peer, it may be behind a NAT. Ask our other
+        // peers to introduce us.
+        if link.is_outbound() && reason.is_dial_err() {
+            self.request_rendezvous(remote);
+        }
+        self.sessions_changed();
         self.dequeue_fetches();
     }
 
     pub fn received_message(&mut self, remote: NodeId, message: Message) {
+        self.registry.gossip(metrics::Direction::In, [&message]);
+
         if let Err(err) = self.handle_message(&remote, message) {
             // If there's an error, stop processing messages from this peer.
             // However, we still relay messages returned up to this point.
@@ -1613,7 +1978,7 @@ where
 
                 for rid in missing {
                     debug!(target: "service", "Missing seeded inventory {rid}; initiating fetch..");
-                    self.fetch(rid, *announcer, FETCH_TIMEOUT, None);
+                    self.fetch(rid, *announcer, RefClass::All, FETCH_TIMEOUT, None, None);
                 }
                 return Ok(relay);
             }
@@ -1764,11 +2129,78 @@ where
                     at: *at,
                 });
             }
+            Info::RendezvousRequest { target } => {
+                self.handle_rendezvous_request(remote, *target);
+            }
+            Info::Rendezvous { node, addr } => {
+                self.handle_rendezvous(remote, *node, addr.clone());
+            }
         }
 
         Ok(())
     }
 
+    /// Handle a request from a peer to be introduced to another node we're connected to.
+    fn handle_rendezvous_request(&mut self, remote: NodeId, target: NodeId) {
+        if target == remote || target == self.node_id() {
+            return;
+        }
+        let Some(from) = self.sessions.get(&remote).filter(|s| s.is_connected()) else {
+            return;
+        };
+        let Some(to) = self.sessions.get(&target).filter(|s| s.is_connected()) else {
+            debug!(target: "service", "Ignoring rendezvous request from {remote}: not connected to {target}");
+            return;
+        };
+        // Only send rendezvous messages to peers that understand them.
+        if !to.features.has(Features::RENDEZVOUS) {
+            debug!(target: "service", "Ignoring rendezvous request from {remote}: {target} doesn't support rendezvous");
+            return;
+        }
+        debug!(target: "service", "Introducing {remote} ({}) to {target} ({})", from.addr, to.addr);
+
+        // Tell each side the address we observed for the other, so that they can
+        // connect to each other simultaneously.
+        self.outbox.write(
+            to,
+            Message::Info(Info::Rendezvous {
+                node: remote,
+                addr: from.addr.clone(),
+            }),
+        );
+        self.outbox.write(
+            from,
+            Message::Info(Info::Rendezvous {
+                node: target,
+                addr: to.addr.clone(),
+            }),
+        );
+    }
+
+    /// Handle a rendezvous message from a peer, telling us to connect to the given node.
+    fn handle_rendezvous(&mut self, remote: NodeId, node: NodeId, addr: Address) {
+        if node == self.node_id() || self.sessions.is_connected(&node) {
+            return;
+        }
+        if !self.rendezvous.dial(node, addr.clone(), self.clock) {
+            debug!(target: "service", "Ignoring rendezvous from {remote} for {node} ({addr})");
+            return;
+        }
+        info!(target: "service", "Connecting to {node} ({addr}) via rendezvous with {remote}..");
+
+        // The resulting connection is treated like any other outbound connection.
+        let connecting = match self.sessions.get(&node) {
+            Some(s) if s.is_disconnected() => self.reconnect(node, addr),
+            Some(_) => false,
+            None => self.connect(node, addr),
+        };
+        if !connecting {
+            self.rendezvous.complete(&node);
+        } else {
+            self.outbox.wakeup(rendezvous::RENDEZVOUS_TIMEOUT);
+        }
+    }
+
     pub fn handle_message(
         &mut self,
         remote: &NodeId,
@@ -1826,8 +2258,18 @@ where
             Message::Announcement(ann) => {
                 let relayer = remote;
                 let relayer_addr = peer.addr.clone();
-
-                if let Some(id) = self.handle_announcement(relayer, &relayer_addr, &ann)? {
+                let relay = self.handle_announcement(relayer, &relayer_addr, &ann)?;
+
+                // Keep track of the features supported by our peers. Peers send us their
+                // node announcement upon connecting, though it may already be known to us.
+                if let AnnouncementMessage::Node(NodeAnnouncement { features, .. }) = &ann.message {
+                    if ann.node == *remote {
+                        if let Some(peer) = self.sessions.get_mut(remote) {
+                            peer.features = *features;
+                        }
+                    }
+                }
+                if let Some(id) = relay {
                     if self.config.is_relay() {
                         if let AnnouncementMessage::Inventory(_) = ann.message {
                             if let Err(e) = self
@@ -2094,6 +2536,7 @@ where
                 }
             }
         }
+
         Ok(synced)
     }
 
@@ -2200,10 +2643,47 @@ where
         Ok((refs, timestamp))
     }
 
+    /// Ask our peers to introduce us to the given node, which we weren't able to connect to.
+    /// Only peers supporting rendezvous are asked.
+    fn request_rendezvous(&mut self, target: NodeId) -> bool {
+        if self.rendezvous.is_pending(&target) {
+            return false;
+        }
+        let peers = self
+            .sessions
+            .connected()
+            .filter(|(nid, s)| **nid != target && s.features.has(Features::RENDEZVOUS))
+            .map(|(nid, _)| *nid)
+            .collect::<Vec<_>>();
+
+        if peers.is_empty() || !self.rendezvous.request(target, self.clock) {
+            return false;
+        }
+        debug!(target: "service", "Requesting rendezvous with {target} from {} peer(s)..", peers.len());
+
+        for nid in peers {
+            if let Some(peer) = self.sessions.get(&nid) {
+                self.outbox
+                    .write(peer, Message::Info(Info::RendezvousRequest { target }));
+            }
+        }
+        self.outbox.wakeup(rendezvous::RENDEZVOUS_TIMEOUT);
+
+        true
+    }
+
+    /// Give up on rendezvous attempts that took too long.
+    fn expire_rendezvous(&mut self, now: &LocalTime) {
+        for (nid, attempt) in self.rendezvous.expire(*now) {
+            debug!(target: "service", "Rendezvous with {nid} timed out ({:?})", attempt.state);
+        }
+    }
+
     fn reconnect(&mut self, nid: NodeId, addr: Address) -> bool {
         if let Some(sess) = self.sessions.get_mut(&nid) {
             sess.to_initial();
             self.outbox.connect(nid, addr);
+            self.sessions_changed();
 
             return true;
         }
@@ -2213,6 +2693,10 @@ where
     fn connect(&mut self, nid: NodeId, addr: Address) -> bool {
         debug!(target: "service", "Connecting to {nid} ({addr})..");
 
+        if self.shutdown.is_some() {
+            debug!(target: "service", "Not connecting to {nid}, shutting down..");
+            return false;
+        }
         if self.sessions.contains_key(&nid) {
             warn!(target: "service", "Attempted connection to peer {nid} which already has a session");
             return false;
@@ -2225,6 +2709,13 @@ where
             error!(target: "service", "Outbound connection limit reached when attempting {nid} ({addr})");
             return false;
         }
+        if let Some(ban) = self.banned_node(&nid).or_else(|| match addr.host {
+            HostName::Ip(ip) => self.banned_ip(&ip),
+            _ => None,
+        }) {
+            debug!(target: "service", "Not connecting to {nid} ({addr}), banned by operator ({})", ban.target);
+            return false;
+        }
         let persistent = self.config.is_persistent(&nid);
         let timestamp: Timestamp = self.clock.into();
 
@@ -2242,10 +2733,66 @@ where
             ),
         );
         self.outbox.connect(nid, addr);
+        self.sessions_changed();
 
         true
     }
 
+    /// Add an operator ban, and disconnect the peers it applies to.
+    fn ban(&mut self, ban: ban::Ban) -> Result<bool, Error> {
+        let updated = self.db.bans_mut().ban(&ban)?;
+        let banned = self
+            .sessions
+            .iter()
+            .filter(|(nid, session)| match ban.target {
+                ban::Target::Node(target) => **nid == target,
+                ban::Target::Subnet(subnet) => match session.addr.host {
+                    HostName::Ip(ip) => subnet.contains(&ip),
+                    _ => false,
+                },
+            })
+            .map(|(nid, _)| *nid)
+            .collect::<Vec<_>>();
+
+        info!(target: "service", "Banning {} ({} peer(s) affected)", ban.target, banned.len());
+
+        self.bans.insert(ban);
+
+        for nid in banned {
+            self.outbox.disconnect(nid, DisconnectReason::Banned);
+        }
+        Ok(updated)
+    }
+
+    /// Remove expired operator bans.
+    fn purge_bans(&mut self) {
+        let now = self.clock.into();
+
+        for ban in self.bans.purge(now) {
+            debug!(target: "service", "Ban of {} expired", ban.target);
+        }
+        if let Err(e) = self.db.bans_mut().purge_bans(now) {
+            error!(target: "service", "Error purging expired bans: {e}");
+        }
+    }
+
+    /// Get the operator ban of the given node, if any.
+    fn banned_node(&mut self, nid: &NodeId) -> Option<ban::Ban> {
+        // Expired bans are only purged when they are found.
+        if self.bans.node(nid)?.is_expired(self.clock.into()) {
+            self.purge_bans();
+        }
+        self.bans.node(nid).cloned()
+    }
+
+    /// Get an operator ban of a range containing the given IP address, if any.
+    fn banned_ip(&mut self, ip: &IpAddr) -> Option<ban::Ban> {
+        if self.bans.ip(ip)?.is_expired(self.clock.into()) {
+            self.purge_bans();
+        }
+        self.bans.ip(ip).cloned()
+    }
+
     fn seeds(&self, rid: &RepoId) -> Result<Seeds, Error> {
         let mut seeds = Seeds::new(self.rng.clone());
 
@@ -2291,6 +2838,27 @@ where
         Ok(seeds)
     }
 
+    /// Rank the given seeds as fetch targets, best first. Seeds that rank the same are
+    /// returned in the order they were given.
+    fn fetch_targets<'a>(&self, seeds: impl Iterator<Item = &'a Seed>) -> Vec<NodeId> {
+        let mut ranked = seeds
+            .map(|seed| {
+                let preferred = self.config.preferred_seeds.contains(&seed.nid);
+                let stats = self
+                    .db
+                    .reputation()
+                    .stats(&seed.nid)
+                    .unwrap_or_else(|e| {
+                        error!(target: "service", "Error getting fetch statistics for {}: {e}", seed.nid);
+                        None
+                    });
+                (reputation::Rank::new(preferred, stats.as_ref()), seed.nid)
+            })
+            .collect::<Vec<_>>();
+        ranked.sort_by_key(|(rank, _)| *rank);
+        ranked.into_iter().map(|(_, nid)| nid).collect()
+    }
+
     /// Return a new filter object, based on our seeding policy.
     fn filter(&self) -> Filter {
         if self.config.seeding_policy.is_allow() {
@@ -2405,6 +2973,8 @@ where
             Some(delta),
             &nid,
         )?;
+        self.sample_routing();
+
         Ok(())
     }
 
@@ -2464,7 +3034,18 @@ where
                     })
                     .into_values()
                     .collect::<Vec<_>>();
-                peers.sort_by_key(|p| p.penalty);
+
+                // Try the addresses we most recently connected to first, and prefer peers with
+                // a working address among peers with the same penalty.
+                for peer in &mut peers {
+                    peer.addresses.sort_by_key(|ka| Reverse(ka.last_success));
+                }
+                peers.sort_by_key(|p| {
+                    (
+                        p.penalty,
+                        Reverse(p.addresses.first().and_then(|ka| ka.last_success)),
+                    )
+                });
                 peers
             }
             Err(e) => {
@@ -2476,6 +3057,9 @@ where
 
     /// Fetch all repositories that are seeded but missing from storage.
     fn fetch_missing_repositories(&mut self) -> Result<(), Error> {
+        // Every sync round starts over with the best seeds.
+        self.missing.clear();
+
         for policy in self.policies.seed_policies()? {
             let rid = policy.rid;
 
@@ -2485,30 +3069,86 @@ where
             if self.storage.contains(&rid)? {
                 continue;
             }
-            match self.seeds(&rid) {
-                Ok(seeds) => {
-                    if let Some(connected) = NonEmpty::from_vec(seeds.connected().collect()) {
-                        for seed in connected {
-                            self.fetch(rid, seed.nid, FETCH_TIMEOUT, None);
-                        }
-                    } else {
-                        // TODO: We should make sure that this fetch is retried later, either
-                        // when we connect to a seed, or when we discover a new seed.
-                        // Since new connections and routing table updates are both conditions for
-                        // fetching, we should trigger fetches when those conditions appear.
-                        // Another way to handle this would be to update our database, saying
-                        // that we're trying to fetch a certain repo. We would then just
-                        // iterate over those entries in the above circumstances. This is
-                        // merely an optimization though, we can also iterate over all seeded
-                        // repos and check which ones are not in our inventory.
-                        debug!(target: "service", "No connected seeds found for {rid}..");
-                    }
-                }
+            if !self.fetch_missing(rid) {
+                // TODO: We should make sure that this fetch is retried later, either
+                // when we connect to a seed, or when we discover a new seed.
+                // Since new connections and routing table updates are both conditions for
+                // fetching, we should trigger fetches when those conditions appear.
+                // Another way to handle this would be to update our database, saying
+                // that we're trying to fetch a certain repo. We would then just
+                // iterate over those entries in the above circumstances. This is
+                // merely an optimization though, we can also iterate over all seeded
+                // repos and check which ones are not in our inventory.
+                debug!(target: "service", "No connected seeds found for {rid}..");
+            }
+        }
+        Ok(())
+    }
+
+    /// Fetch a repository we're missing from the best connected seed that wasn't tried yet
+    /// in this sync round. Returns `false` if there is no such seed.
+    fn fetch_missing(&mut self, rid: RepoId) -> bool {
+        let seeds = match self.seeds(&rid) {
+            Ok(seeds) => seeds,
+            Err(e) => {
+                error!(target: "service", "Couldn't fetch missing repo {rid}: failed to lookup seeds: {e}");
+                return false;
+            }
+        };
+        let targets = self.fetch_targets(seeds.connected());
+        let tried = self.missing.entry(rid).or_default();
+        let Some(seed) = targets.into_iter().find(|nid| tried.insert(*nid)) else {
+            return false;
+        };
+        self.fetch(rid, seed, RefClass::All, FETCH_TIMEOUT, None, None);
+
+        true
+    }
+
+    /// Fetch seeded repositories from a few connected seeds each, skipping those that were
+    /// fetched recently. Fetches are queued if a seed is at capacity.
+    fn sync_seeded_repositories(&mut self, next: LocalTime) -> Result<(), Error> {
+        let now = self.clock;
+        let mut rids = Vec::new();
+
+        for policy in self.policies.seed_policies()? {
+            let rid = policy.rid;
+
+            if !policy.is_allow() {
+                continue;
+            }
+            // Missing repositories are fetched by the 'sync' task.
+            if !self.storage.contains(&rid)? {
+                continue;
+            }
+            if self.fetching.contains_key(&rid) || self.schedule.is_recent(&rid, now) {
+                debug!(target: "service", "Skipping scheduled sync of {rid}: recently fetched");
+                continue;
+            }
+            let seeds = match self.seeds(&rid) {
+                Ok(seeds) => seeds,
                 Err(e) => {
-                    error!(target: "service", "Couldn't fetch missing repo {rid}: failed to lookup seeds: {e}");
+                    error!(target: "service", "Couldn't sync repo {rid}: failed to lookup seeds: {e}");
+                    continue;
                 }
+            };
+            let targets = self.fetch_targets(seeds.connected());
+            if targets.is_empty() {
+                debug!(target: "service", "No connected seeds found for {rid}..");
+                continue;
+            }
+            for seed in targets.into_iter().take(self.schedule.seeds()) {
+                self.fetch(rid, seed, RefClass::All, FETCH_TIMEOUT, None, None);
             }
+            rids.push(rid);
         }
+        info!(target: "service", "Started scheduled sync of {} repositories..", rids.len());
+
+        self.emitter.emit(Event::SyncRound {
+            rids,
+            next: next.into(),
+        });
+
         Ok(())
     }
 
@@ -2592,6 +3232,30 @@ where
         }
     }
 
+    /// Update the session metrics, after a session changed state.
+    fn sessions_changed(&self) {
+        self.registry.sessions(self.sessions.values());
+    }
+
+    /// Update the routing table metrics.
+    ///
+    /// Counting the routing table entries is too costly to do on every inventory update,
+    /// so this is only done periodically, from the "idle" task.
+    fn sample_routing(&self) {
+        match self.db.routing().len() {
+            Ok(n) => self.registry.routing_entries(n),
+            Err(e) => error!(target: "service", "Error counting routing table entries: {e}"),
+        }
+    }
+
+    /// Update the storage metrics, after a repository was added to storage.
+    fn storage_changed(&self) {
+        match self.storage.repositories() {
+            Ok(repos) => self.registry.repositories(repos.len()),
+            Err(e) => error!(target: "service", "Error listing repositories in storage: {e}"),
+        }
+    }
+
     /// Maintain persistent peer connections.
     fn maintain_persistent(&mut self) {
         trace!(target: "service", "Maintaining persistent peers..");
@@ -2644,6 +3308,8 @@ pub trait ServiceState {
     fn config(&self) -> &Config;
     /// Get service metrics.
     fn metrics(&self) -> &Metrics;
+    /// Get the scheduled sync state.
+    fn schedule(&self) -> &Schedule;
 }
 
 impl<D, S, G> ServiceState for Service<D, S, G>
@@ -2695,6 +3361,10 @@ where
     fn metrics(&self) -> &Metrics {
         &self.metrics
     }
+
+    fn schedule(&self) -> &Schedule {
+        &self.schedule
+    }
 }
 
 /// Disconnect reason.
@@ -2703,6 +3373,8 @@ pub enum DisconnectReason {
     /// Error while dialing the remote. This error occures before a connection is
     /// even established. Errors of this kind are usually not transient.
     Dial(Arc<dyn std::error::Error + Sync + Send>),
+    /// Error with the proxy used to reach the remote. Says nothing about the remote itself.
+    Proxy(Arc<dyn std::error::Error + Sync + Send>),
     /// Error with an underlying established connection. Sometimes, reconnecting
     /// after such an error is possible.
     Connection(Arc<dyn std::error::Error + Sync + Send>),
@@ -2716,6 +3388,10 @@ pub enum DisconnectReason {
     SelfConnection,
     /// User requested disconnect
     Command,
+    /// Peer was banned by the operator.
+    Banned,
+    /// The local node is shutting down.
+    Shutdown,
 }
 
 impl DisconnectReason {
@@ -2738,8 +3414,11 @@ impl fmt::Display for DisconnectReason {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Dial(err) => write!(f, "{err}"),
+            Self::Proxy(err) => write!(f, "{err}"),
             Self::Connection(err) => write!(f, "{err}"),
             Self::Command => write!(f, "command"),
+            Self::Banned => write!(f, "banned"),
+            Self::Shutdown => write!(f, "shutdown"),
             Self::SelfConnection => write!(f, "self-connection"),
             Self::Conflict => write!(f, "conflict"),
             Self::Session(err) => write!(f, "{err}"),
@@ -2,11 +2,13 @@ use std::collections::VecDeque;
 use std::time;
 
 use log::*;
+use radicle::node::RefClass;
 use radicle::storage::refs::RefsAt;
 
 use crate::prelude::*;
 use crate::service::session::Session;
 use crate::service::Link;
+use crate::worker::fetch::ProgressSubscribers;
 
 use super::gossip;
 use super::message::{Announcement, AnnouncementMessage};
@@ -28,8 +30,12 @@ pub enum Io {
         remote: NodeId,
         /// If the node is fetching specific `rad/sigrefs`.
         refs_at: Option<Vec<RefsAt>>,
+        /// The classes of references being fetched.
+        refs: RefClass,
         /// Fetch timeout.
         timeout: time::Duration,
+        /// Channels waiting for the progress of the fetch.
+        progress: ProgressSubscribers,
     },
     /// Ask for a wakeup in a specified amount of time.
     Wakeup(LocalDuration),
@@ -123,7 +129,9 @@ impl Outbox {
         peer: &mut Session,
         rid: RepoId,
         refs_at: Vec<RefsAt>,
+        refs: RefClass,
         timeout: time::Duration,
+        progress: ProgressSubscribers,
     ) {
         peer.fetching(rid);
 
@@ -141,8 +149,10 @@ impl Outbox {
         self.io.push_back(Io::Fetch {
             rid,
             refs_at,
+            refs,
             remote: peer.id,
             timeout,
+            progress,
         });
     }
 
@@ -245,6 +245,13 @@ pub enum Info {
     /// Tell a node that sent a refs announcement that it was already synced at the given `Oid`,
     /// for this particular `rid`.
     RefsAlreadySynced { rid: RepoId, at: git::Oid },
+    /// Ask a peer to introduce us to the given node, which we're unable to connect to.
+    /// Only sent to peers supporting [`node::Features::RENDEZVOUS`].
+    RendezvousRequest { target: NodeId },
+    /// Tell a peer to connect to the given node, at the address we observed for it.
+    /// The node is told the same about the peer, so that both can connect simultaneously.
+    /// Only sent to peers supporting [`node::Features::RENDEZVOUS`].
+    Rendezvous { node: NodeId, addr: Address },
 }
 
 /// Announcement messages are messages that are relayed between peers.
@@ -489,6 +496,16 @@ impl Message {
                     "{verb} `refs-already-synced` info {prep} {remote} for {rid}"
                 )
             },
+            Self::Info(Info::RendezvousRequest { target }) => {
+                format!(
+                    "{verb} `rendezvous-request` info {prep} {remote} for {target}"
+                )
+            },
+            Self::Info(Info::Rendezvous { node, addr }) => {
+                format!(
+                    "{verb} `rendezvous` info {prep} {remote} for {node} ({addr})"
+                )
+            },
             Self::Ping { .. } => format!("{verb} ping {prep} {remote}"),
             Self::Pong { .. } => format!("{verb} pong {prep} {remote}"),
             Self::Subscribe(Subscribe { .. }) => {
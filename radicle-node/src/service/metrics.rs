@@ -0,0 +1,251 @@
+//! Service metrics, exported in the Prometheus text format.
+//!
+//! The registry is updated by the service as events happen, eg. when a session changes state
+//! or a fetch completes, and is shared with the metrics listener, which only ever reads it.
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex};
+
+use crate::service::message::{Announcement, AnnouncementMessage, Message};
+use crate::service::session::{Session, State};
+use crate::Link;
+
+/// Direction of a gossip message.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Direction {
+    /// Received from a peer.
+    In,
+    /// Sent to a peer.
+    Out,
+}
+
+impl Direction {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::In => "in",
+            Self::Out => "out",
+        }
+    }
+}
+
+/// Metric values.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Values {
+    /// Connected peers, by link direction.
+    pub peers: BTreeMap<&'static str, u64>,
+    /// Sessions, by state.
+    pub sessions: BTreeMap<&'static str, u64>,
+    /// Fetches in progress.
+    pub fetches_in_progress: u64,
+    /// Fetches that succeeded.
+    pub fetches_succeeded: u64,
+    /// Fetches that failed.
+    pub fetches_failed: u64,
+    /// Gossip messages, by direction and message type.
+    pub gossip_messages: BTreeMap<(Direction, &'static str), u64>,
+    /// Routing table entries.
+    pub routing_entries: u64,
+    /// Repositories in storage.
+    pub repositories: u64,
+}
+
+/// Metric registry. Cheap to clone; clones share the same values.
+#[derive(Debug, Default, Clone)]
+pub struct Registry {
+    values: Arc<Mutex<Values>>,
+}
+
+impl Registry {
+    /// Get a snapshot of the current values.
+    pub fn values(&self) -> Values {
+        self.lock().clone()
+    }
+
+    /// Update the session gauges from the current sessions.
+    pub fn sessions<'a>(&self, sessions: impl IntoIterator<Item = &'a Session>) {
+        let mut peers = BTreeMap::from([("inbound", 0), ("outbound", 0)]);
+        let mut states = BTreeMap::from([
+            ("initial", 0),
+            ("attempted", 0),
+            ("connected", 0),
+            ("disconnected", 0),
+        ]);
+        for session in sessions {
+            *states.entry(state(&session.state)).or_default() += 1;
+
+            if session.is_connected() {
+                *peers.entry(link(&session.link)).or_default() += 1;
+            }
+        }
+        let mut values = self.lock();
+
+        values.peers = peers;
+        values.sessions = states;
+    }
+
+    /// Set the number of fetches in progress.
+    pub fn fetching(&self, count: usize) {
+        self.lock().fetches_in_progress = count as u64;
+    }
+
+    /// Record the outcome of a fetch.
+    pub fn fetched(&self, success: bool) {
+        let mut values = self.lock();
+
+        if success {
+            values.fetches_succeeded += 1;
+        } else {
+            values.fetches_failed += 1;
+        }
+    }
+
+    /// Record gossip messages received or sent.
+    pub fn gossip<'a>(&self, direction: Direction, msgs: impl IntoIterator<Item = &'a Message>) {
+        let mut values = self.lock();
+
+        for msg in msgs {
+            *values
+                .gossip_messages
+                .entry((direction, message_type(msg)))
+                .or_default() += 1;
+        }
+    }
+
+    /// Set the number of routing table entries.
+    pub fn routing_entries(&self, count: usize) {
+        self.lock().routing_entries = count as u64;
+    }
+
+    /// Set the number of repositories in storage.
+    pub fn repositories(&self, count: usize) {
+        self.lock().repositories = count as u64;
+    }
+
+    /// Encode the current values in the Prometheus text exposition format.
+    pub fn encode(&self) -> String {
+        let values = self.values();
+        let mut out = String::new();
+
+        metric(
+            &mut out,
+            "radicle_node_peers_connected",
+            "gauge",
+            "Connected peers, by link direction.",
+            values
+                .peers
+                .iter()
+                .map(|(link, n)| (format!("link=\"{link}\""), *n)),
+        );
+        metric(
+            &mut out,
+            "radicle_node_sessions",
+            "gauge",
+            "Peer sessions, by state.",
+            values
+                .sessions
+                .iter()
+                .map(|(state, n)| (format!("state=\"{state}\""), *n)),
+        );
+        metric(
+            &mut out,
+            "radicle_node_fetches_in_progress",
+            "gauge",
+            "Fetches in progress.",
+            [(String::new(), values.fetches_in_progress)],
+        );
+        metric(
+            &mut out,
+            "radicle_node_fetches_total",
+            "counter",
+            "Completed fetches, by result.",
+            [
+                (String::from("result=\"success\""), values.fetches_succeeded),
+                (String::from("result=\"failure\""), values.fetches_failed),
+            ],
+        );
+        metric(
+            &mut out,
+            "radicle_node_gossip_messages_total",
+            "counter",
+            "Gossip messages, by direction and type.",
+            values.gossip_messages.iter().map(|((direction, kind), n)| {
+                (
+                    format!("direction=\"{}\",type=\"{kind}\"", direction.as_str()),
+                    *n,
+                )
+            }),
+        );
+        metric(
+            &mut out,
+            "radicle_node_routing_entries",
+            "gauge",
+            "Entries in the routing table.",
+            [(String::new(), values.routing_entries)],
+        );
+        metric(
+            &mut out,
+            "radicle_node_storage_repositories",
+            "gauge",
+            "Repositories in storage.",
+            [(String::new(), values.repositories)],
+        );
+        out
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Values> {
+        // Nb. Metrics are only ever updated with simple assignments, so a poisoned lock
+        // still holds consistent values.
+        self.values.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Write a metric family, with one sample per label set.
+fn metric(
+    out: &mut String,
+    name: &str,
+    kind: &str,
+    help: &str,
+    samples: impl IntoIterator<Item = (String, u64)>,
+) {
+    writeln!(out, "# HELP {name} {help}").ok();
+    writeln!(out, "# TYPE {name} {kind}").ok();
+
+    for (labels, value) in samples {
+        if labels.is_empty() {
+            writeln!(out, "{name} {value}").ok();
+        } else {
+            writeln!(out, "{name}{{{labels}}} {value}").ok();
+        }
+    }
+}
+
+fn state(state: &State) -> &'static str {
+    match state {
+        State::Initial => "initial",
+        State::Attempted => "attempted",
+        State::Connected { .. } => "connected",
+        State::Disconnected { .. } => "disconnected",
+    }
+}
+
+fn link(link: &Link) -> &'static str {
+    if link.is_inbound() {
+        "inbound"
+    } else {
+        "outbound"
+    }
+}
+
+fn message_type(msg: &Message) -> &'static str {
+    match msg {
+        Message::Subscribe(_) => "subscribe",
+        Message::Announcement(Announcement { message, .. }) => match message {
+            AnnouncementMessage::Node(_) => "node-announcement",
+            AnnouncementMessage::Inventory(_) => "inventory-announcement",
+            AnnouncementMessage::Refs(_) => "refs-announcement",
+        },
+        Message::Info(_) => "info",
+        Message::Ping(_) => "ping",
+        Message::Pong { .. } => "pong",
+    }
+}
@@ -0,0 +1,189 @@
+//! Rendezvous-assisted connection establishment.
+//!
+//! Two nodes behind NAT cannot accept each other's inbound connections, but may both be
+//! connected to a common peer. When a connection attempt to such a node fails, we ask our
+//! peers to act as a rendezvous point: a peer connected to both nodes tells each side what
+//! address it observed for the other, and both sides then dial each other at the same time,
+//! in the hope that the outbound connections open a path through their respective NATs.
+//!
+//! This module tracks the pending rendezvous attempts of the local node.
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use localtime::{LocalDuration, LocalTime};
+use radicle::node::{Address, NodeId};
+
+/// How long to wait for a rendezvous attempt to succeed before giving up on it.
+pub const RENDEZVOUS_TIMEOUT: LocalDuration = LocalDuration::from_secs(30);
+/// Maximum number of concurrent rendezvous attempts.
+pub const MAX_PENDING_RENDEZVOUS: usize = 16;
+
+/// State of a rendezvous attempt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum State {
+    /// We asked our peers to introduce us to the node, and are waiting for an address.
+    Requested,
+    /// We were given an address for the node, and are trying to connect to it.
+    Dialing { addr: Address },
+}
+
+/// A pending rendezvous attempt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attempt {
+    /// Attempt state.
+    pub state: State,
+    /// When the attempt was started, or last transitioned.
+    pub since: LocalTime,
+}
+
+/// Pending rendezvous attempts, keyed by the node we're trying to connect to.
+#[derive(Debug, Default)]
+pub struct Rendezvous {
+    pending: HashMap<NodeId, Attempt>,
+}
+
+impl Rendezvous {
+    /// Record that we asked our peers to introduce us to `nid`.
+    /// Returns `false` if an attempt is already pending, or we are at capacity.
+    pub fn request(&mut self, nid: NodeId, now: LocalTime) -> bool {
+        if self.pending.contains_key(&nid) || self.pending.len() >= MAX_PENDING_RENDEZVOUS {
+            return false;
+        }
+        self.pending.insert(
+            nid,
+            Attempt {
+                state: State::Requested,
+                since: now,
+            },
+        );
+        true
+    }
+
+    /// Record that we were given `addr` to connect to `nid`, and are dialing it.
+    /// This can happen whether or not we requested the rendezvous, since the other side
+    /// is also told to dial us.
+    ///
+    /// Returns `false` if we're already dialing that node, or we are at capacity.
+    pub fn dial(&mut self, nid: NodeId, addr: Address, now: LocalTime) -> bool {
+        let len = self.pending.len();
+
+        match self.pending.entry(nid) {
+            Entry::Occupied(e) if matches!(e.get().state, State::Dialing { .. }) => false,
+            Entry::Occupied(mut e) => {
+                e.insert(Attempt {
+                    state: State::Dialing { addr },
+                    since: now,
+                });
+                true
+            }
+            Entry::Vacant(_) if len >= MAX_PENDING_RENDEZVOUS => false,
+            Entry::Vacant(e) => {
+                e.insert(Attempt {
+                    state: State::Dialing { addr },
+                    since: now,
+                });
+                true
+            }
+        }
+    }
+
+    /// Mark the rendezvous with `nid` as complete, eg. because we connected to it.
+    /// Returns the attempt, if there was one.
+    pub fn complete(&mut self, nid: &NodeId) -> Option<Attempt> {
+        self.pending.remove(nid)
+    }
+
+    /// Remove and return the attempts that timed out.
+    pub fn expire(&mut self, now: LocalTime) -> Vec<(NodeId, Attempt)> {
+        let expired = self
+            .pending
+            .iter()
+            .filter(|(_, a)| now - a.since >= RENDEZVOUS_TIMEOUT)
+            .map(|(nid, _)| *nid)
+            .collect::<Vec<_>>();
+
+        expired
+            .into_iter()
+            .filter_map(|nid| self.pending.remove(&nid).map(|a| (nid, a)))
+            .collect()
+    }
+
+    /// Get the pending attempt for the given node, if any.
+    pub fn get(&self, nid: &NodeId) -> Option<&Attempt> {
+        self.pending.get(nid)
+    }
+
+    /// Check whether there is a pending attempt for the given node.
+    pub fn is_pending(&self, nid: &NodeId) -> bool {
+        self.pending.contains_key(nid)
+    }
+
+    /// Number of pending attempts.
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Whether there are no pending attempts.
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+    use crate::test::arbitrary;
+
+    #[test]
+    fn test_rendezvous_lifecycle() {
+        let mut r = Rendezvous::default();
+        let nid = arbitrary::gen::<NodeId>(1);
+        let addr: Address = arbitrary::gen(1);
+        let t = LocalTime::from_secs(1);
+
+        assert!(r.request(nid, t));
+        assert!(!r.request(nid, t), "Requests are not duplicated");
+        assert_eq!(r.get(&nid).unwrap().state, State::Requested);
+
+        assert!(r.dial(nid, addr.clone(), t + LocalDuration::from_secs(1)));
+        assert!(!r.dial(nid, addr.clone(), t), "Dials are not duplicated");
+        assert_eq!(r.get(&nid).unwrap().state, State::Dialing { addr });
+
+        assert!(r.complete(&nid).is_some());
+        assert!(r.is_empty());
+    }
+
+    #[test]
+    fn test_rendezvous_expire() {
+        let mut r = Rendezvous::default();
+        let alice = arbitrary::gen::<NodeId>(1);
+        let bob = arbitrary::gen::<NodeId>(1);
+        let t = LocalTime::from_secs(1);
+
+        r.request(alice, t);
+        r.request(bob, t + LocalDuration::from_secs(10));
+
+        assert!(r
+            .expire(t + RENDEZVOUS_TIMEOUT - LocalDuration::from_secs(1))
+            .is_empty());
+
+        let expired = r.expire(t + RENDEZVOUS_TIMEOUT);
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].0, alice);
+        assert!(r.is_pending(&bob));
+    }
+
+    #[test]
+    fn test_rendezvous_capacity() {
+        let mut r = Rendezvous::default();
+        let t = LocalTime::from_secs(1);
+
+        for _ in 0..MAX_PENDING_RENDEZVOUS {
+            assert!(r.request(arbitrary::gen::<NodeId>(1), t));
+        }
+        assert!(!r.request(arbitrary::gen::<NodeId>(1), t));
+        assert!(!r.dial(arbitrary::gen(1), arbitrary::gen(1), t));
+        assert_eq!(r.len(), MAX_PENDING_RENDEZVOUS);
+    }
+}
@@ -0,0 +1,185 @@
+//! Scheduled sync of seeded repositories.
+//!
+//! Nodes normally only fetch when they receive announcements, which means that a node
+//! that was offline for a while doesn't catch up on what it missed. To remedy this, the
+//! node periodically runs a sync round, fetching the repositories it seeds from a few
+//! connected seeds.
+//!
+//! Rounds are spread out with random jitter, so that nodes that start at the same time
+//! don't all fetch at once, and repositories that were fetched recently are skipped.
+//!
+//! This module tracks when the next round is due, and when each repository was last
+//! fetched.
+use std::collections::HashMap;
+
+use fastrand::Rng;
+use localtime::{LocalDuration, LocalTime};
+use radicle::identity::RepoId;
+use radicle::node::config::ScheduledSync;
+
+/// How long to wait after startup before the first sync round, to give the node time
+/// to connect to peers.
+pub const SYNC_STARTUP_DELAY: LocalDuration = LocalDuration::from_mins(1);
+
+/// Sync round schedule.
+#[derive(Debug)]
+pub struct Schedule {
+    /// Schedule configuration.
+    config: ScheduledSync,
+    /// When the next round is due, or `None` if scheduled syncs are disabled or the
+    /// schedule wasn't started.
+    next: Option<LocalTime>,
+    /// When each repository was last fetched.
+    fetched: HashMap<RepoId, LocalTime>,
+    /// Source of jitter.
+    rng: Rng,
+}
+
+impl Schedule {
+    /// Create a new schedule. Call [`Schedule::start`] to schedule the first round.
+    pub fn new(config: ScheduledSync, rng: Rng) -> Self {
+        Self {
+            config,
+            next: None,
+            fetched: HashMap::new(),
+            rng,
+        }
+    }
+
+    /// Maximum number of seeds to fetch each repository from, per round.
+    pub fn seeds(&self) -> usize {
+        self.config.seeds
+    }
+
+    /// Schedule the first round, after the startup delay.
+    /// Returns how long until the round is due, or `None` if scheduled syncs are disabled.
+    pub fn start(&mut self, now: LocalTime) -> Option<LocalDuration> {
+        self.schedule(now, SYNC_STARTUP_DELAY)
+    }
+
+    /// Start a round if one is due, and schedule the next one.
+    /// Returns how long until the next round is due, or `None` if no round is due.
+    pub fn round(&mut self, now: LocalTime) -> Option<LocalDuration> {
+        if !self.is_due(now) {
+            return None;
+        }
+        self.schedule(now, self.config.interval)
+    }
+
+    /// Check whether a round is due.
+    pub fn is_due(&self, now: LocalTime) -> bool {
+        self.next.map_or(false, |next| now >= next)
+    }
+
+    /// When the next round is due, if scheduled.
+    pub fn next(&self) -> Option<LocalTime> {
+        self.next
+    }
+
+    /// Record that a repository was fetched.
+    pub fn fetched(&mut self, rid: RepoId, now: LocalTime) {
+        self.fetched.insert(rid, now);
+    }
+
+    /// Check whether a repository was fetched within the last interval, in which case it
+    /// doesn't need to be fetched in this round.
+    pub fn is_recent(&self, rid: &RepoId, now: LocalTime) -> bool {
+        self.fetched
+            .get(rid)
+            .map_or(false, |t| now - *t < self.config.interval)
+    }
+
+    /// Schedule the next round after the given delay, plus up to a tenth of the interval
+    /// of jitter.
+    fn schedule(&mut self, now: LocalTime, delay: LocalDuration) -> Option<LocalDuration> {
+        if !self.config.enabled {
+            return None;
+        }
+        let max = (self.config.interval / 10).as_millis();
+        let jitter = LocalDuration::from_millis(self.rng.u128(0..=max));
+        let delay = delay + jitter;
+
+        self.next = Some(now + delay);
+
+        Some(delay)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+    use radicle::test::arbitrary;
+
+    fn config(interval: LocalDuration) -> ScheduledSync {
+        ScheduledSync {
+            enabled: true,
+            interval,
+            seeds: 2,
+        }
+    }
+
+    #[test]
+    fn test_start_jitter() {
+        let now = LocalTime::from_secs(1);
+        let interval = LocalDuration::from_mins(60);
+        let starts = (0..8)
+            .map(|seed| {
+                let mut schedule = Schedule::new(config(interval), Rng::with_seed(seed));
+                schedule.start(now).unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        for delay in &starts {
+            assert!(*delay >= SYNC_STARTUP_DELAY);
+            assert!(*delay <= SYNC_STARTUP_DELAY + interval / 10);
+        }
+        assert!(starts.iter().any(|d| *d != starts[0]));
+    }
+
+    #[test]
+    fn test_round() {
+        let mut now = LocalTime::from_secs(1);
+        let interval = LocalDuration::from_mins(60);
+        let mut schedule = Schedule::new(config(interval), Rng::with_seed(1));
+
+        assert!(!schedule.is_due(now));
+        let delay = schedule.start(now).unwrap();
+        assert_eq!(schedule.round(now), None);
+
+        now = now + delay;
+        assert!(schedule.is_due(now));
+        let delay = schedule.round(now).unwrap();
+        assert!(delay >= interval);
+        assert!(!schedule.is_due(now));
+        assert_eq!(schedule.next(), Some(now + delay));
+    }
+
+    #[test]
+    fn test_disabled() {
+        let now = LocalTime::from_secs(1);
+        let mut schedule = Schedule::new(
+            ScheduledSync {
+                enabled: false,
+                ..ScheduledSync::default()
+            },
+            Rng::with_seed(1),
+        );
+        assert_eq!(schedule.start(now), None);
+        assert_eq!(schedule.next(), None);
+        assert!(!schedule.is_due(now + LocalDuration::from_mins(24 * 60)));
+    }
+
+    #[test]
+    fn test_recent() {
+        let now = LocalTime::from_secs(1);
+        let interval = LocalDuration::from_mins(60);
+        let rid = arbitrary::gen::<RepoId>(1);
+        let mut schedule = Schedule::new(config(interval), Rng::with_seed(1));
+
+        assert!(!schedule.is_recent(&rid, now));
+        schedule.fetched(rid, now);
+        assert!(schedule.is_recent(&rid, now + LocalDuration::from_mins(59)));
+        assert!(!schedule.is_recent(&rid, now + interval));
+    }
+}
@@ -4,7 +4,7 @@ use std::{fmt, time};
 use crossbeam_channel as chan;
 
 use crate::node::config::Limits;
-use crate::node::{FetchResult, Severity};
+use crate::node::{Features, FetchProgress, FetchResult, RefClass, Severity};
 use crate::service::message;
 use crate::service::message::Message;
 use crate::service::{Address, LocalDuration, LocalTime, NodeId, Outbox, RepoId, Rng};
@@ -78,10 +78,14 @@ pub struct QueuedFetch {
     pub from: NodeId,
     /// Refs being fetched.
     pub refs_at: Vec<RefsAt>,
+    /// Classes of references being fetched.
+    pub refs: RefClass,
     /// The timeout given for the fetch request.
     pub timeout: time::Duration,
     /// Result channel.
     pub channel: Option<chan::Sender<FetchResult>>,
+    /// Progress channel.
+    pub progress: Option<chan::Sender<FetchProgress>>,
 }
 
 impl PartialEq for QueuedFetch {
@@ -89,8 +93,11 @@ impl PartialEq for QueuedFetch {
         self.rid == other.rid
             && self.from == other.from
             && self.refs_at == other.refs_at
+            && self.refs == other.refs
             && self.channel.is_none()
             && other.channel.is_none()
+            && self.progress.is_none()
+            && other.progress.is_none()
     }
 }
 
@@ -110,6 +117,8 @@ pub struct Session {
     pub state: State,
     /// Peer subscription.
     pub subscribe: Option<message::Subscribe>,
+    /// Features supported by the peer, as announced when connecting.
+    pub features: Features,
     /// Last time a message was received from the peer.
     pub last_active: LocalTime,
     /// Fetch queue.
@@ -167,6 +176,7 @@ impl Session {
             state: State::Initial,
             link: Link::Outbound,
             subscribe: None,
+            features: Features::NONE,
             persistent,
             last_active: LocalTime::default(),
             queue: VecDeque::with_capacity(MAX_FETCH_QUEUE_SIZE),
@@ -196,6 +206,7 @@ impl Session {
             },
             link: Link::Inbound,
             subscribe: None,
+            features: Features::NONE,
             persistent,
             last_active: time,
             queue: VecDeque::new(),
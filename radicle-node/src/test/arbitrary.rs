@@ -6,7 +6,7 @@ use radicle::node::UserAgent;
 
 use crate::crypto;
 use crate::identity::DocAt;
-use crate::node::Alias;
+use crate::node::{Address, Alias};
 use crate::prelude::{BoundedVec, NodeId, RepoId, Timestamp};
 use crate::service::filter::{Filter, FILTER_SIZE_L, FILTER_SIZE_M, FILTER_SIZE_S};
 use crate::service::message::{
@@ -37,6 +37,8 @@ impl Arbitrary for FetchResult {
         FetchResult {
             updated: vec![],
             namespaces: HashSet::arbitrary(g),
+            excluded: HashSet::new(),
+            cobs: Default::default(),
             clone: bool::arbitrary(g),
             doc: DocAt::arbitrary(g),
         }
@@ -101,9 +103,18 @@ impl Arbitrary for Message {
                 .into()
             }
             MessageType::Info => {
-                let message = Info::RefsAlreadySynced {
-                    rid: RepoId::arbitrary(g),
-                    at: oid(),
+                let message = match g.choose(&[1, 2, 3]).unwrap() {
+                    1 => Info::RefsAlreadySynced {
+                        rid: RepoId::arbitrary(g),
+                        at: oid(),
+                    },
+                    2 => Info::RendezvousRequest {
+                        target: NodeId::arbitrary(g),
+                    },
+                    _ => Info::Rendezvous {
+                        node: NodeId::arbitrary(g),
+                        addr: Address::arbitrary(g),
+                    },
                 };
                 Self::Info(message)
             }
@@ -4,10 +4,14 @@ use std::sync::{Arc, Mutex};
 use std::time;
 
 use radicle::git;
+use radicle::node::ban::{self, Ban};
 use radicle::storage::refs::RefsAt;
 
 use crate::identity::RepoId;
-use crate::node::{Alias, Config, ConnectOptions, ConnectResult, Event, FetchResult, Seeds};
+use crate::node::{
+    Alias, Config, ConnectOptions, ConnectResult, Event, FetchProgress, FetchResult, RefClass,
+    Seeds, Timestamp,
+};
 use crate::runtime::HandleError;
 use crate::service::policy;
 use crate::service::NodeId;
@@ -71,6 +75,17 @@ impl radicle::node::Handle for Handle {
         })
     }
 
+    fn fetch_refs(
+        &mut self,
+        id: RepoId,
+        from: NodeId,
+        timeout: time::Duration,
+        _refs: RefClass,
+        _progress: &mut dyn FnMut(FetchProgress),
+    ) -> Result<FetchResult, Self::Error> {
+        self.fetch(id, from, timeout)
+    }
+
     fn seed(&mut self, id: RepoId, _scope: policy::Scope) -> Result<bool, Self::Error> {
         Ok(self.seeding.lock().unwrap().insert(id))
     }
@@ -83,6 +98,18 @@ impl radicle::node::Handle for Handle {
         Ok(self.following.lock().unwrap().insert(id))
     }
 
+    fn ban(&mut self, _ban: Ban) -> Result<bool, Self::Error> {
+        unimplemented!()
+    }
+
+    fn unban(&mut self, _target: ban::Target) -> Result<bool, Self::Error> {
+        unimplemented!()
+    }
+
+    fn bans(&self) -> Result<Vec<Ban>, Self::Error> {
+        Ok(vec![])
+    }
+
     fn subscribe(&self, _timeout: time::Duration) -> Result<Self::Events, Self::Error> {
         Ok(vec![])
     }
@@ -123,4 +150,12 @@ impl radicle::node::Handle for Handle {
     fn debug(&self) -> Result<serde_json::Value, Self::Error> {
         Ok(serde_json::Value::Null)
     }
+
+    fn next_sync(&self) -> Result<Option<Timestamp>, Self::Error> {
+        Ok(None)
+    }
+
+    fn fetching(&self) -> Result<Vec<RepoId>, Self::Error> {
+        Ok(Vec::new())
+    }
 }
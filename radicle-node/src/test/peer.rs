@@ -307,7 +307,7 @@ where
         Message::node(
             NodeAnnouncement {
                 version: PROTOCOL_VERSION,
-                features: node::Features::SEED,
+                features: self.config().features(),
                 timestamp: self.timestamp(),
                 alias: Alias::from_str(self.name).unwrap(),
                 addresses: Some(net::SocketAddr::from((self.ip, node::DEFAULT_PORT)).into()).into(),
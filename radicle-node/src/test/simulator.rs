@@ -659,6 +659,8 @@ impl<S: WriteStorage + 'static, G: Signer> Simulation<S, G> {
                                 Rc::new(Ok(fetch::FetchResult {
                                     updated: vec![],
                                     namespaces: HashSet::new(),
+                                    excluded: HashSet::new(),
+                                    cobs: Default::default(),
                                     clone: true,
                                     doc: arbitrary::gen(1),
                                 })),
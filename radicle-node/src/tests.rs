@@ -4,6 +4,7 @@ use std::collections::BTreeSet;
 use std::default::*;
 use std::env;
 use std::io;
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time;
 
@@ -12,9 +13,11 @@ use netservices::Direction as Link;
 use once_cell::sync::Lazy;
 use radicle::identity::Visibility;
 use radicle::node::address::Store as _;
+use radicle::node::ban::Store as _;
 use radicle::node::refs::Store as _;
+use radicle::node::reputation::Store as _;
 use radicle::node::routing::Store as _;
-use radicle::node::{ConnectOptions, DEFAULT_TIMEOUT};
+use radicle::node::{ConnectOptions, RefClass, DEFAULT_TIMEOUT};
 use radicle::storage::refs::RefsAt;
 use radicle::storage::RefUpdate;
 use radicle::test::arbitrary::gen;
@@ -227,6 +230,188 @@ fn test_inbound_connection() {
     assert!(peers.contains(&bob.id()));
 }
 
+fn ban(alice: &mut Peer<MockStorage, MockSigner>, ban: node::ban::Ban) -> bool {
+    let (sender, receiver) = chan::bounded(1);
+    alice.command(Command::Ban(ban, sender));
+    receiver.recv().unwrap()
+}
+
+fn bans(alice: &mut Peer<MockStorage, MockSigner>) -> Vec<node::ban::Ban> {
+    let (sender, receiver) = chan::bounded(1);
+    alice.command(Command::Bans(sender));
+    receiver.recv().unwrap()
+}
+
+/// Check whether `alice` attempts to connect to `peer`.
+fn dials(alice: &mut Peer<MockStorage, MockSigner>, peer: &Peer<MockStorage, MockSigner>) -> bool {
+    alice.outbox().for_each(drop);
+    alice.command(Command::Connect(
+        peer.id(),
+        peer.address(),
+        ConnectOptions::default(),
+    ));
+    alice
+        .outbox()
+        .any(|o| matches!(o, Io::Connect(id, _) if id == peer.id()))
+}
+
+#[test]
+fn test_ban_node() {
+    let mut alice = Peer::new("alice", [8, 8, 8, 8]);
+    let bob = Peer::new("bob", [9, 9, 9, 9]);
+    let eve = Peer::new("eve", [7, 7, 7, 7]);
+
+    alice.connect_to(&bob);
+    assert!(ban(
+        &mut alice,
+        node::ban::Ban::new(node::ban::Target::Node(bob.id()))
+    ));
+
+    // Bob is disconnected, and we don't connect to him again.
+    assert!(alice
+        .outbox()
+        .any(|o| matches!(o, Io::Disconnect(id, DisconnectReason::Banned) if id == bob.id())));
+    alice.disconnected(bob.id(), Link::Outbound, &DisconnectReason::Banned);
+    assert!(!dials(&mut alice, &bob));
+
+    // Nor do we accept connections from him.
+    alice.connected(bob.id(), bob.address(), Link::Inbound);
+    assert!(alice
+        .outbox()
+        .any(|o| matches!(o, Io::Disconnect(id, DisconnectReason::Banned) if id == bob.id())));
+    assert!(alice.sessions().get(&bob.id()).is_none());
+
+    // Other peers are not affected.
+    alice.connect_from(&eve);
+    assert!(alice.sessions().is_connected(&eve.id()));
+
+    // Once the ban is lifted, we connect to Bob again.
+    let (sender, receiver) = chan::bounded(1);
+    alice.command(Command::Unban(node::ban::Target::Node(bob.id()), sender));
+    assert!(receiver.recv().unwrap());
+    assert!(dials(&mut alice, &bob));
+    assert!(bans(&mut alice).is_empty());
+}
+
+#[test]
+fn test_ban_address() {
+    let mut alice = Peer::new("alice", [8, 8, 8, 8]);
+    let bob = Peer::new("bob", [9, 9, 9, 9]);
+    let eve = Peer::new("eve", [7, 7, 7, 7]);
+    let subnet = node::ban::Target::from_str("9.9.0.0/16").unwrap();
+
+    alice.connect_to(&bob);
+    alice.connect_to(&eve);
+    assert!(ban(&mut alice, node::ban::Ban::new(subnet)));
+
+    // Bob's address is in the banned range, Eve's isn't.
+    assert!(alice
+        .outbox()
+        .any(|o| matches!(o, Io::Disconnect(id, DisconnectReason::Banned) if id == bob.id())));
+    assert!(alice.sessions().is_connected(&eve.id()));
+
+    alice.disconnected(bob.id(), Link::Outbound, &DisconnectReason::Banned);
+    assert!(!dials(&mut alice, &bob));
+    assert!(!alice.accepted([9, 9, 1, 1].into()));
+    assert!(alice.accepted([9, 10, 1, 1].into()));
+
+    // The ban is persisted.
+    assert_eq!(
+        alice.database().bans().bans().unwrap(),
+        vec![node::ban::Ban::new(subnet)]
+    );
+}
+
+#[test]
+fn test_ban_expiry() {
+    let mut alice = Peer::new("alice", [8, 8, 8, 8]);
+    let bob = Peer::new("bob", [9, 9, 9, 9]);
+    let expires = alice.timestamp() + LocalDuration::from_mins(1).as_millis() as u64;
+
+    assert!(ban(
+        &mut alice,
+        node::ban::Ban {
+            target: node::ban::Target::Node(bob.id()),
+            reason: Some(String::from("spam")),
+            expires: Some(expires),
+        }
+    ));
+    assert!(ban(
+        &mut alice,
+        node::ban::Ban {
+            target: node::ban::Target::from_str("9.9.9.9").unwrap(),
+            reason: None,
+            expires: Some(expires),
+        }
+    ));
+    assert_eq!(bans(&mut alice).len(), 2);
+    assert!(!alice.accepted(bob.ip));
+    assert!(!dials(&mut alice, &bob));
+
+    // Once the bans expire, they are no longer enforced, and are removed.
+    alice.elapse(LocalDuration::from_mins(2));
+
+    assert!(alice.accepted(bob.ip));
+    assert!(dials(&mut alice, &bob));
+    assert!(bans(&mut alice).is_empty());
+    assert!(alice.database().bans().bans().unwrap().is_empty());
+}
+
+#[test]
+fn test_metrics() {
+    use std::io::{Read as _, Write as _};
+
+    let mut alice = Peer::new("alice", [8, 8, 8, 8]);
+    let bob = Peer::new("bob", [9, 9, 9, 9]);
+    let eve = Peer::new("eve", [7, 7, 7, 7]);
+
+    alice.connect_to(&bob);
+    alice.connect_from(&eve);
+    alice.receive(
+        bob.id(),
+        Message::Ping(Ping {
+            ponglen: 1,
+            zeroes: ZeroBytes::new(42),
+        }),
+    );
+    while alice.service.next().is_some() {}
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let registry = alice.registry().clone();
+    std::thread::spawn(move || runtime::metrics::listen(listener, registry));
+
+    let mut stream = std::net::TcpStream::connect(addr).unwrap();
+    stream
+        .write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n")
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{response}");
+    assert!(response.contains(&format!("Content-Type: {}", runtime::metrics::CONTENT_TYPE)));
+
+    for line in [
+        "radicle_node_peers_connected{link=\"inbound\"} 1",
+        "radicle_node_peers_connected{link=\"outbound\"} 1",
+        "radicle_node_sessions{state=\"connected\"} 2",
+        "radicle_node_sessions{state=\"initial\"} 0",
+        "radicle_node_fetches_in_progress 0",
+        "radicle_node_fetches_total{result=\"success\"} 0",
+        "radicle_node_gossip_messages_total{direction=\"in\",type=\"node-announcement\"} 2",
+        "radicle_node_gossip_messages_total{direction=\"in\",type=\"ping\"} 1",
+        "radicle_node_gossip_messages_total{direction=\"out\",type=\"pong\"} 1",
+        "# TYPE radicle_node_gossip_messages_total counter",
+        "# TYPE radicle_node_routing_entries gauge",
+        "# TYPE radicle_node_storage_repositories gauge",
+    ] {
+        assert!(
+            response.lines().any(|l| l == line),
+            "{line:?} not in {response}"
+        );
+    }
+}
+
 #[test]
 fn test_persistent_peer_connect() {
     use std::collections::HashSet;
@@ -537,6 +722,7 @@ fn test_announcement_rebroadcast_duplicates() {
     }
 
     // Alice relays just the expected ones back to Eve.
+    // A peer that doesn't announce support for rendezvous.
     alice.connect_from(&eve);
     alice.receive(
         eve.id(),
@@ -577,6 +763,7 @@ fn test_announcement_rebroadcast_timestamp_filtered() {
     }
 
     // Eve subscribes to messages within the period of the second batch only.
+    // A peer that doesn't announce support for rendezvous.
     alice.connect_from(&eve);
     alice.receive(
         eve.id(),
@@ -1394,6 +1581,179 @@ fn test_maintain_connections_failed_attempt() {
     assert!(!alice.outbox().any(|o| matches!(o, Io::Connect(_, _))));
 }
 
+#[test]
+fn test_rendezvous() {
+    let mut alice = Peer::new("alice", [7, 7, 7, 7]);
+    let mut bob = Peer::new("bob", [8, 8, 8, 8]);
+    let mut seed = Peer::new("seed", [9, 9, 9, 9]);
+    let reason = DisconnectReason::Dial(Arc::new(io::Error::from(io::ErrorKind::TimedOut)));
+
+    // Alice and Bob are both connected to the seed, but can't reach each other.
+    alice.connect_to(&seed);
+    bob.connect_to(&seed);
+    seed.connect_from(&alice);
+    seed.connect_from(&bob);
+
+    alice.command(Command::Connect(
+        bob.id(),
+        bob.address(),
+        ConnectOptions::default(),
+    ));
+    alice.attempted(bob.id(), bob.address());
+    alice.disconnected(bob.id(), Link::Outbound, &reason);
+
+    // Alice asks the seed to introduce her to Bob.
+    let request = alice
+        .messages(seed.id())
+        .find(|m| matches!(m, Message::Info(Info::RendezvousRequest { target }) if *target == bob.id()))
+        .expect("Alice requests a rendezvous with Bob");
+
+    // The seed tells each side about the other.
+    seed.receive(alice.id(), request);
+    let to_bob = seed
+        .messages(bob.id())
+        .find(|m| matches!(m, Message::Info(Info::Rendezvous { node, addr }) if *node == alice.id() && *addr == alice.address()))
+        .expect("The seed sends Alice's address to Bob");
+    let to_alice = seed
+        .messages(alice.id())
+        .find(|m| matches!(m, Message::Info(Info::Rendezvous { node, addr }) if *node == bob.id() && *addr == bob.address()))
+        .expect("The seed sends Bob's address to Alice");
+
+    // Both sides connect to each other.
+    alice.outbox().for_each(drop);
+    alice.receive(seed.id(), to_alice);
+    alice
+        .outbox()
+        .find(|o| matches!(o, Io::Connect(id, addr) if *id == bob.id() && *addr == bob.address()))
+        .expect("Alice connects to Bob");
+
+    bob.receive(seed.id(), to_bob);
+    bob.outbox()
+        .find(
+            |o| matches!(o, Io::Connect(id, addr) if *id == alice.id() && *addr == alice.address()),
+        )
+        .expect("Bob connects to Alice");
+
+    // The resulting connection is a regular outbound connection.
+    alice.attempted(bob.id(), bob.address());
+    alice.connected(bob.id(), bob.address(), Link::Outbound);
+    assert!(alice.sessions().is_connected(&bob.id()));
+
+    // Failing to connect to Bob again triggers a new rendezvous request.
+    alice.disconnected(bob.id(), Link::Outbound, &reason);
+    alice
+        .messages(seed.id())
+        .find(|m| matches!(m, Message::Info(Info::RendezvousRequest { .. })))
+        .expect("Alice requests a rendezvous with Bob again");
+}
+
+#[test]
+fn test_rendezvous_unsupported() {
+    let mut alice = Peer::new("alice", [7, 7, 7, 7]);
+    let bob = Peer::new("bob", [8, 8, 8, 8]);
+    let eve = Peer::new("eve", [9, 9, 9, 9]);
+    let mut seed = Peer::new("seed", [10, 10, 10, 10]);
+
+    seed.connect_from(&alice);
+    seed.connect_from(&bob);
+    // A peer that doesn't announce support for rendezvous.
+    alice.connect_from(&eve);
+    alice.receive(
+        eve.id(),
+        Message::node(
+            NodeAnnouncement {
+                features: node::Features::SEED,
+                ..gossip::node(eve.config(), eve.timestamp())
+            }
+            .solve(0)
+            .unwrap(),
+            eve.signer(),
+        ),
+    );
+
+    // Alice doesn't ask peers that don't support rendezvous.
+    alice.command(Command::Connect(
+        bob.id(),
+        bob.address(),
+        ConnectOptions::default(),
+    ));
+    alice.attempted(bob.id(), bob.address());
+    alice.disconnected(
+        bob.id(),
+        Link::Outbound,
+        &DisconnectReason::Dial(Arc::new(io::Error::from(io::ErrorKind::TimedOut))),
+    );
+    assert!(!alice
+        .messages(eve.id())
+        .any(|m| matches!(m, Message::Info(Info::RendezvousRequest { .. }))));
+
+    // The seed ignores requests for nodes it isn't connected to.
+    seed.receive(
+        alice.id(),
+        Message::Info(Info::RendezvousRequest { target: eve.id() }),
+    );
+    assert!(!seed
+        .messages(alice.id())
+        .any(|m| matches!(m, Message::Info(Info::Rendezvous { .. }))));
+}
+
+#[test]
+fn test_rendezvous_timeout() {
+    let mut alice = Peer::new("alice", [7, 7, 7, 7]);
+    let bob = Peer::new("bob", [8, 8, 8, 8]);
+    let seed = Peer::new("seed", [9, 9, 9, 9]);
+    let reason = DisconnectReason::Dial(Arc::new(io::Error::from(io::ErrorKind::TimedOut)));
+
+    alice.connect_to(&seed);
+    alice.command(Command::Connect(
+        bob.id(),
+        bob.address(),
+        ConnectOptions::default(),
+    ));
+    alice.attempted(bob.id(), bob.address());
+    alice.disconnected(bob.id(), Link::Outbound, &reason);
+    assert_eq!(
+        alice
+            .messages(seed.id())
+            .filter(|m| matches!(m, Message::Info(Info::RendezvousRequest { .. })))
+            .count(),
+        1
+    );
+
+    // While the rendezvous is pending, we don't ask again.
+    alice.command(Command::Connect(
+        bob.id(),
+        bob.address(),
+        ConnectOptions::default(),
+    ));
+    alice.attempted(bob.id(), bob.address());
+    alice.disconnected(bob.id(), Link::Outbound, &reason);
+    assert_eq!(
+        alice
+            .messages(seed.id())
+            .filter(|m| matches!(m, Message::Info(Info::RendezvousRequest { .. })))
+            .count(),
+        0
+    );
+
+    // Once it times out, we can ask again.
+    alice.elapse(rendezvous::RENDEZVOUS_TIMEOUT);
+    alice.command(Command::Connect(
+        bob.id(),
+        bob.address(),
+        ConnectOptions::default(),
+    ));
+    alice.attempted(bob.id(), bob.address());
+    alice.disconnected(bob.id(), Link::Outbound, &reason);
+    assert_eq!(
+        alice
+            .messages(seed.id())
+            .filter(|m| matches!(m, Message::Info(Info::RendezvousRequest { .. })))
+            .count(),
+        1
+    );
+}
+
 #[test]
 fn test_seed_repo_subscribe() {
     let mut alice = Peer::new("alice", [7, 7, 7, 7]);
@@ -1474,6 +1834,288 @@ fn test_fetch_missing_inventory_on_schedule() {
         .unwrap();
 }
 
+/// Have `alice` connect to `seed` and learn that it has `rid`.
+fn discover_seed(
+    alice: &mut Peer<MockStorage, MockSigner>,
+    rid: RepoId,
+    seed: &Peer<MockStorage, MockSigner>,
+) {
+    alice.connect_to(seed);
+    alice.receive(
+        seed.id(),
+        Message::inventory(
+            InventoryAnnouncement {
+                inventory: vec![rid].try_into().unwrap(),
+                timestamp: LocalTime::now().into(),
+            },
+            seed.signer(),
+        ),
+    );
+}
+
+/// Respond to all of `alice`'s fetches over the given number of sync intervals, failing the
+/// ones from `flaky`. Returns the nodes she fetched from, in order.
+fn sync(alice: &mut Peer<MockStorage, MockSigner>, intervals: usize, flaky: NodeId) -> Vec<NodeId> {
+    let mut targets = Vec::new();
+
+    for i in 0..intervals {
+        if i > 0 {
+            alice.elapse(service::SYNC_INTERVAL);
+        }
+        loop {
+            let fetches = alice.fetches().collect::<Vec<_>>();
+            if fetches.is_empty() {
+                break;
+            }
+            for (rid, nid) in fetches {
+                let result = if nid == flaky {
+                    Err(worker::FetchError::Io(
+                        io::ErrorKind::ConnectionReset.into(),
+                    ))
+                } else {
+                    Ok(arbitrary::gen::<fetch::FetchResult>(1))
+                };
+                targets.push(nid);
+                alice.fetched(rid, nid, result);
+            }
+        }
+    }
+    targets
+}
+
+#[test]
+fn test_fetch_target_reputation() {
+    let rid = arbitrary::gen::<RepoId>(1);
+    let mut alice = Peer::new("alice", [7, 7, 7, 7]);
+    let bob = Peer::new("bob", [8, 8, 8, 8]);
+    let eve = Peer::new("eve", [9, 9, 9, 9]);
+
+    alice.seed(&rid, node::policy::Scope::All).unwrap();
+
+    // Bob is the only seed we know of, so we keep fetching from him, even though it fails.
+    discover_seed(&mut alice, rid, &bob);
+    let targets = sync(&mut alice, 4, bob.id);
+    assert_eq!(targets, vec![bob.id; 4]);
+
+    let stats = alice
+        .database()
+        .reputation()
+        .stats(&bob.id)
+        .unwrap()
+        .unwrap();
+    assert_eq!(stats.fetches_ok, 0);
+    assert_eq!(stats.fetches_failed, 4);
+
+    // Once Eve shows up, fetches migrate to her.
+    discover_seed(&mut alice, rid, &eve);
+    let targets = sync(&mut alice, 8, bob.id);
+    assert_eq!(targets, vec![eve.id; 8]);
+}
+
+#[test]
+fn test_fetch_target_preferred() {
+    let rid = arbitrary::gen::<RepoId>(1);
+    let bob = Peer::new("bob", [8, 8, 8, 8]);
+    let eve = Peer::new("eve", [9, 9, 9, 9]);
+    let mut alice = Peer::config(
+        "alice",
+        [7, 7, 7, 7],
+        MockStorage::empty(),
+        peer::Config {
+            config: Config {
+                preferred_seeds: vec![bob.id],
+                ..Config::new(node::Alias::new("alice"))
+            },
+            ..peer::Config::default()
+        },
+    )
+    .initialized();
+
+    alice.seed(&rid, node::policy::Scope::All).unwrap();
+    discover_seed(&mut alice, rid, &bob);
+    sync(&mut alice, 4, bob.id);
+
+    // Preferred seeds are fetched from first, even if they are flaky. When they fail, the
+    // next seed is tried.
+    discover_seed(&mut alice, rid, &eve);
+    let targets = sync(&mut alice, 8, bob.id);
+    assert_eq!(targets.len(), 15);
+    assert!(targets.iter().skip(1).step_by(2).all(|nid| *nid == bob.id));
+    assert!(targets.iter().skip(2).step_by(2).all(|nid| *nid == eve.id));
+}
+
+#[test]
+fn test_fetch_local_error_reputation() {
+    let rid = arbitrary::gen::<RepoId>(1);
+    let mut alice = Peer::new("alice", [7, 7, 7, 7]);
+    let bob = Peer::new("bob", [8, 8, 8, 8]);
+
+    alice.seed(&rid, node::policy::Scope::All).unwrap();
+    discover_seed(&mut alice, rid, &bob);
+    alice.elapse(service::SYNC_INTERVAL);
+
+    let fetches = alice.fetches().collect::<Vec<_>>();
+    assert!(!fetches.is_empty());
+
+    // Local errors aren't held against the remote.
+    for (rid, nid) in fetches {
+        let err = fetch::error::Fetch::StorageCopy(io::ErrorKind::Other.into());
+        alice.fetched(rid, nid, Err(worker::FetchError::Fetch(err)));
+    }
+    let stats = alice.database().reputation().stats(&bob.id).unwrap();
+    assert!(stats.map_or(true, |s| s.fetches_failed == 0));
+}
+
+/// Advance `alice`'s clock to `until`, keeping her connection to `bob` alive.
+/// Returns the fetches she initiated in the meantime.
+fn elapse_connected(
+    alice: &mut Peer<MockStorage, MockSigner>,
+    bob: &Peer<MockStorage, MockSigner>,
+    until: LocalTime,
+) -> Vec<(RepoId, NodeId)> {
+    let mut fetches = Vec::new();
+
+    while *alice.clock() < until {
+        let step = (until - *alice.clock()).min(LocalDuration::from_secs(30));
+
+        alice.elapse(step);
+        alice.receive(
+            bob.id(),
+            Message::Pong {
+                zeroes: ZeroBytes::new(0),
+            },
+        );
+        fetches.extend(alice.fetches());
+    }
+    fetches
+}
+
+/// Fail all of `alice`'s ongoing and queued fetches, eg. the ones triggered by an inventory
+/// announcement, so that they aren't considered recently fetched.
+fn fail_fetches(alice: &mut Peer<MockStorage, MockSigner>) {
+    loop {
+        let Some((rid, nid)) = alice.fetches().next() else {
+            break;
+        };
+        alice.fetched(
+            rid,
+            nid,
+            Err(worker::FetchError::Io(
+                io::ErrorKind::ConnectionReset.into(),
+            )),
+        );
+    }
+}
+
+#[test]
+fn test_scheduled_sync_staggered() {
+    let storage = arbitrary::nonempty_storage(1);
+    let rid = *storage.repos.keys().next().unwrap();
+    let bob = Peer::new("bob", [8, 8, 8, 8]);
+    let interval = ScheduledSync::default().interval;
+
+    let delays = (0..4)
+        .map(|seed| {
+            let mut alice = Peer::config(
+                "alice",
+                [7, 7, 7, 7],
+                storage.clone(),
+                peer::Config {
+                    rng: fastrand::Rng::with_seed(seed),
+                    ..peer::Config::default()
+                },
+            )
+            .initialized();
+            let started = *alice.clock();
+
+            discover_seed(&mut alice, rid, &bob);
+            fail_fetches(&mut alice);
+
+            // Nothing is fetched on startup.
+            let fetches =
+                elapse_connected(&mut alice, &bob, started + schedule::SYNC_STARTUP_DELAY);
+            assert!(!fetches.iter().any(|(r, _)| *r == rid));
+
+            // The first round starts after some jitter.
+            let next = alice.schedule().next().unwrap();
+            assert!(next - started <= schedule::SYNC_STARTUP_DELAY + interval / 10);
+
+            let fetches = elapse_connected(&mut alice, &bob, next);
+            assert!(fetches.contains(&(rid, bob.id)));
+
+            next - started
+        })
+        .collect::<Vec<_>>();
+
+    assert!(delays.iter().any(|d| *d != delays[0]), "{delays:?}");
+}
+
+#[test]
+fn test_scheduled_sync_skips_recently_fetched() {
+    let storage = arbitrary::nonempty_storage(2);
+    let rids = storage.repos.keys().copied().collect::<Vec<_>>();
+    let (recent, stale) = (rids[0], rids[1]);
+    let doc = storage.repos.get(&recent).unwrap().doc.clone();
+    let mut alice = Peer::with_storage("alice", [7, 7, 7, 7], storage);
+    let bob = Peer::new("bob", [8, 8, 8, 8]);
+    let events = alice.events();
+
+    alice.connect_to(&bob);
+    alice.receive(
+        bob.id(),
+        Message::inventory(
+            InventoryAnnouncement {
+                inventory: rids.clone().try_into().unwrap(),
+                timestamp: LocalTime::now().into(),
+            },
+            bob.signer(),
+        ),
+    );
+
+    fail_fetches(&mut alice);
+
+    // Alice fetches one of the repositories just before the round.
+    let next = alice.schedule().next().unwrap();
+    elapse_connected(&mut alice, &bob, next - LocalDuration::from_secs(1));
+
+    let (send, _recv) = chan::bounded::<node::FetchResult>(1);
+    alice.command(Command::Fetch(
+        recent,
+        bob.id,
+        RefClass::All,
+        DEFAULT_TIMEOUT,
+        send,
+        None,
+    ));
+    assert_matches!(alice.fetches().next(), Some((rid, _)) if rid == recent);
+    alice.fetched(recent, bob.id, Ok(fetch::FetchResult::new(doc.clone())));
+
+    let mut fetches = elapse_connected(&mut alice, &bob, next);
+    assert!(!fetches.contains(&(recent, bob.id)));
+    assert!(fetches.contains(&(stale, bob.id)));
+
+    let synced = events
+        .try_iter()
+        .find_map(|e| match e {
+            Event::SyncRound { rids, .. } => Some(rids),
+            _ => None,
+        })
+        .unwrap();
+    assert!(!synced.contains(&recent));
+    assert!(synced.contains(&stale));
+
+    // Complete the round's fetches, including the ones that were queued.
+    while let Some((rid, nid)) = fetches.pop() {
+        alice.fetched(rid, nid, Ok(fetch::FetchResult::new(doc.clone())));
+        fetches.extend(alice.fetches());
+    }
+
+    // The repository is synced again in the next round.
+    let next = alice.schedule().next().unwrap();
+    let fetches = elapse_connected(&mut alice, &bob, next);
+    assert!(fetches.iter().any(|(rid, _)| *rid == recent));
+}
+
 #[test]
 fn test_queued_fetch_max_capacity() {
     let storage = arbitrary::nonempty_storage(3);
@@ -1491,15 +2133,36 @@ fn test_queued_fetch_max_capacity() {
 
     // Send the first fetch.
     let (send, _recv1) = chan::bounded::<node::FetchResult>(1);
-    alice.command(Command::Fetch(rid1, bob.id, DEFAULT_TIMEOUT, send));
+    alice.command(Command::Fetch(
+        rid1,
+        bob.id,
+        RefClass::All,
+        DEFAULT_TIMEOUT,
+        send,
+        None,
+    ));
 
     // Send the 2nd fetch that will be queued.
     let (send2, _recv2) = chan::bounded::<node::FetchResult>(1);
-    alice.command(Command::Fetch(rid2, bob.id, DEFAULT_TIMEOUT, send2));
+    alice.command(Command::Fetch(
+        rid2,
+        bob.id,
+        RefClass::All,
+        DEFAULT_TIMEOUT,
+        send2,
+        None,
+    ));
 
     // Send the 3rd fetch that will be queued.
     let (send3, _recv3) = chan::bounded::<node::FetchResult>(1);
-    alice.command(Command::Fetch(rid3, bob.id, DEFAULT_TIMEOUT, send3));
+    alice.command(Command::Fetch(
+        rid3,
+        bob.id,
+        RefClass::All,
+        DEFAULT_TIMEOUT,
+        send3,
+        None,
+    ));
 
     // The first fetch is initiated.
     assert_matches!(alice.fetches().next(), Some((rid, _)) if rid == rid1);
@@ -1589,6 +2252,8 @@ fn test_queued_fetch_from_ann_same_rid() {
                 oid,
             }],
             namespaces: [carol.id()].into_iter().collect(),
+            excluded: Default::default(),
+            cobs: Default::default(),
             clone: false,
             doc: arbitrary::gen(1),
         }),
@@ -1597,6 +2262,81 @@ fn test_queued_fetch_from_ann_same_rid() {
     assert_matches!(alice.fetches().next(), None);
 }
 
+#[test]
+fn test_fetched_excluded_namespaces_not_announced() {
+    let mut repo = gen::<MockRepository>(1);
+    let mut alice = Peer::new("alice", [7, 7, 7, 7]);
+    let bob = Peer::new("bob", [8, 8, 8, 8]);
+    let eve = Peer::new("eve", [9, 9, 9, 9]);
+    let carol = Peer::new("carol", [10, 10, 10, 10]);
+
+    repo.doc.doc = repo
+        .doc
+        .doc
+        .with_edits(|doc| {
+            doc.visibility = Visibility::Public;
+        })
+        .unwrap();
+    // The signed refs of remotes must point to this repository's identity.
+    repo.id = RepoId::from(repo.doc.blob);
+    let rid = repo.id;
+    for peer in [&bob, &carol] {
+        let refs = peer.signed_refs_at(arbitrary::gen::<Refs>(1), arbitrary::oid(), &repo);
+        repo.remotes.insert(peer.id(), refs);
+    }
+    let doc = repo.doc.clone();
+
+    alice.storage_mut().repos.insert(rid, repo);
+    alice.seed(&rid, policy::Scope::All).unwrap();
+    alice.connect_to(&bob);
+    alice.connect_to(&eve);
+    alice.receive(eve.id(), Message::Subscribe(Subscribe::all()));
+    alice.messages(eve.id()).for_each(drop);
+
+    let (send, _recv) = chan::bounded::<node::FetchResult>(1);
+    alice.command(Command::Fetch(
+        rid,
+        bob.id,
+        RefClass::CodeOnly,
+        DEFAULT_TIMEOUT,
+        send,
+        None,
+    ));
+    assert_matches!(alice.fetches().next(), Some((rid_, _)) if rid_ == rid);
+
+    // Carol's code was fetched, but her stored COBs no longer match her `rad/sigrefs`.
+    alice.fetched(
+        rid,
+        bob.id,
+        Ok(fetch::FetchResult {
+            updated: vec![RefUpdate::Created {
+                name: carol
+                    .id()
+                    .to_namespace()
+                    .join(git::refname!("refs/heads/master")),
+                oid: arbitrary::oid(),
+            }],
+            namespaces: [bob.id(), carol.id()].into_iter().collect(),
+            excluded: [carol.id()].into_iter().collect(),
+            cobs: Default::default(),
+            clone: false,
+            doc,
+        }),
+    );
+
+    let Some(Message::Announcement(Announcement {
+        message: AnnouncementMessage::Refs(ann),
+        ..
+    })) = alice.messages(eve.id()).next()
+    else {
+        panic!("Alice should announce the fetched refs to Eve");
+    };
+    assert_eq!(
+        ann.refs.iter().map(|r| r.remote).collect::<Vec<_>>(),
+        vec![bob.id()]
+    );
+}
+
 #[test]
 fn test_queued_fetch_from_command_same_rid() {
     let storage = arbitrary::nonempty_storage(3);
@@ -1615,15 +2355,36 @@ fn test_queued_fetch_from_command_same_rid() {
 
     // Send the first fetch.
     let (send, _recv1) = chan::bounded::<node::FetchResult>(1);
-    alice.command(Command::Fetch(rid1, bob.id, DEFAULT_TIMEOUT, send));
+    alice.command(Command::Fetch(
+        rid1,
+        bob.id,
+        RefClass::All,
+        DEFAULT_TIMEOUT,
+        send,
+        None,
+    ));
 
     // Send the 2nd fetch that will be queued.
     let (send2, _recv2) = chan::bounded::<node::FetchResult>(1);
-    alice.command(Command::Fetch(rid1, eve.id, DEFAULT_TIMEOUT, send2));
+    alice.command(Command::Fetch(
+        rid1,
+        eve.id,
+        RefClass::All,
+        DEFAULT_TIMEOUT,
+        send2,
+        None,
+    ));
 
     // Send the 3rd fetch that will be queued.
     let (send3, _recv3) = chan::bounded::<node::FetchResult>(1);
-    alice.command(Command::Fetch(rid1, carol.id, DEFAULT_TIMEOUT, send3));
+    alice.command(Command::Fetch(
+        rid1,
+        carol.id,
+        RefClass::All,
+        DEFAULT_TIMEOUT,
+        send3,
+        None,
+    ));
 
     // Peers Alice will fetch from.
     let mut peers = [bob.id, eve.id, carol.id]
@@ -1707,6 +2468,84 @@ fn test_refs_synced_event() {
         .unwrap();
 }
 
+#[test]
+fn test_sync_status() {
+    let temp = tempfile::tempdir().unwrap();
+    let storage = Storage::open(temp.path(), fixtures::user()).unwrap();
+    let mut alice = Peer::with_storage("alice", [8, 8, 8, 8], storage.clone());
+    let bob = Peer::new("bob", [9, 9, 9, 9]);
+    let eve = Peer::with_storage("eve", [7, 7, 7, 7], storage);
+    let acme = alice.project("acme", "");
+    let local = RefsAt::new(&alice.storage().repository(acme).unwrap(), alice.id).unwrap();
+    let stale = RefsAt {
+        remote: alice.id,
+        at: arbitrary::oid(),
+    };
+    let seeds = |alice: &mut Peer<Storage, MockSigner>| {
+        let (sender, receiver) = chan::bounded(1);
+        alice.command(Command::Seeds(acme, sender));
+        Vec::<node::Seed>::from(receiver.recv().unwrap())
+    };
+    let timestamp = bob.timestamp();
+
+    alice.seed(&acme, policy::Scope::All).unwrap();
+    alice.connect_to(&bob);
+
+    // Bob acknowledges an older version of our refs.
+    alice.receive(
+        bob.id,
+        bob.announcement(RefsAnnouncement {
+            rid: acme,
+            refs: vec![stale].try_into().unwrap(),
+            timestamp,
+        }),
+    );
+    let seed = seeds(&mut alice)
+        .into_iter()
+        .find(|s| s.nid == bob.id)
+        .unwrap();
+    assert_matches!(
+        seed.sync,
+        Some(node::SyncStatus::OutOfSync { local: l, remote: r })
+        if l.oid == local.at && r.oid == stale.at && r.timestamp == LocalTime::from(timestamp)
+    );
+
+    // Bob catches up with our refs.
+    alice.receive(
+        bob.id,
+        bob.announcement(RefsAnnouncement {
+            rid: acme,
+            refs: vec![local].try_into().unwrap(),
+            timestamp: timestamp + 1,
+        }),
+    );
+    let seed = seeds(&mut alice)
+        .into_iter()
+        .find(|s| s.nid == bob.id)
+        .unwrap();
+    assert_matches!(seed.sync, Some(node::SyncStatus::Synced { at }) if at.oid == local.at);
+    assert!(seed.is_synced());
+
+    // Eve seeds the repository, but never told us which refs of ours she has.
+    alice.receive(bob.id, eve.node_announcement());
+    alice.receive(
+        bob.id,
+        Message::inventory(
+            InventoryAnnouncement {
+                inventory: vec![acme].try_into().unwrap(),
+                timestamp: eve.timestamp(),
+            },
+            eve.signer(),
+        ),
+    );
+
+    let seed = seeds(&mut alice)
+        .into_iter()
+        .find(|s| s.nid == eve.id)
+        .unwrap();
+    assert_eq!(seed.sync, None);
+}
+
 #[test]
 fn test_init_and_seed() {
     let tempdir = tempfile::tempdir().unwrap();
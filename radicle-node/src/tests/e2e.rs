@@ -1,7 +1,13 @@
+use std::os::unix::net::UnixListener;
 use std::{collections::HashSet, thread, time};
 
+use crossbeam_channel as chan;
+
 use radicle::crypto::{test::signer::MockSigner, Signer};
-use radicle::node::{Alias, ConnectResult, FetchResult, Handle as _, DEFAULT_TIMEOUT};
+use radicle::node::{
+    Alias, ConnectResult, FetchProgress, FetchResult, Handle as _, RefClass, ShutdownPhase,
+    DEFAULT_TIMEOUT,
+};
 use radicle::storage::{
     ReadRepository, ReadStorage, RefUpdate, RemoteRepository, SignRepository, ValidateRepository,
     WriteRepository, WriteStorage,
@@ -13,6 +19,7 @@ use radicle::{git, issue};
 use crate::node::config::Limits;
 use crate::node::{Config, ConnectOptions};
 use crate::service;
+use crate::service::policy;
 use crate::service::policy::Scope;
 use crate::storage::git::transport;
 use crate::test::environment::{converge, Environment, Node};
@@ -276,6 +283,255 @@ fn test_replication_ref_in_sigrefs() {
     );
 }
 
+#[test]
+fn test_fetch_with_progress() {
+    logger::init(log::Level::Debug);
+
+    let tmp = tempfile::tempdir().unwrap();
+    let alice = Node::init(tmp.path(), config::relay("alice"));
+    let mut bob = Node::init(tmp.path(), config::relay("bob"));
+    let acme = bob.project("acme", "");
+
+    let mut alice = alice.spawn();
+    let bob = bob.spawn();
+
+    alice.connect(&bob);
+    converge([&alice, &bob]);
+
+    let socket = tmp.path().join("alice.sock");
+    let listener = UnixListener::bind(&socket).unwrap();
+    thread::spawn({
+        let handle = (*alice.handle).clone();
+        move || crate::control::listen(listener, handle)
+    });
+
+    alice.handle.seed(acme, Scope::All).unwrap();
+
+    let mut node = radicle::node::Node::new(&socket);
+    let mut progress = Vec::new();
+    let result = node
+        .fetch_with_progress(acme, bob.id, DEFAULT_TIMEOUT, &mut |p| progress.push(p))
+        .unwrap();
+    assert!(result.is_success());
+
+    assert_eq!(
+        progress.first(),
+        Some(&FetchProgress::negotiating(acme, bob.id))
+    );
+    assert_matches!(
+        progress.last(),
+        Some(FetchProgress::Receiving { rid, remote, objects, bytes })
+        if *rid == acme && *remote == bob.id && *objects > 0 && *bytes > 0
+    );
+}
+
+#[test]
+fn test_replication_blocked_refs() {
+    logger::init(log::Level::Debug);
+
+    let tmp = tempfile::tempdir().unwrap();
+    let mut alice = Node::init(
+        tmp.path(),
+        Config {
+            blocked_refs: vec![git::refspec::pattern!("refs/heads/tmp/*")],
+            ..config::relay("alice")
+        },
+    );
+    let mut bob = Node::init(tmp.path(), config::relay("bob"));
+    let acme = bob.project("acme", "");
+
+    // Alice allows one of the blocked branches for this repository.
+    alice
+        .policies
+        .set_refs_policy(
+            &acme,
+            &git::refspec::pattern!("refs/heads/tmp/keep"),
+            policy::Policy::Allow,
+        )
+        .unwrap();
+
+    let repo = bob.storage.repository(acme).unwrap();
+    let head = repo.head().unwrap().1;
+    for branch in ["tmp/scratch", "tmp/keep"] {
+        let name =
+            git::refs::storage::branch_of(&bob.id, &git::RefString::try_from(branch).unwrap());
+        repo.raw()
+            .reference(name.as_str(), *head, false, "test: create branch")
+            .unwrap();
+    }
+    repo.sign_refs(&bob.signer).unwrap();
+
+    let mut alice = alice.spawn();
+    let bob = bob.spawn();
+
+    alice.connect(&bob);
+    converge([&alice, &bob]);
+
+    alice.handle.seed(acme, Scope::All).unwrap();
+    let result = alice.handle.fetch(acme, bob.id, DEFAULT_TIMEOUT).unwrap();
+    assert_matches!(result, FetchResult::Success { .. });
+
+    let repo = alice.storage.repository(acme).unwrap();
+    assert!(repo
+        .reference(&bob.id, &git::qualified!("refs/heads/master"))
+        .is_ok());
+    assert!(repo
+        .reference(&bob.id, &git::qualified!("refs/heads/tmp/keep"))
+        .is_ok());
+    assert!(
+        repo.reference(&bob.id, &git::qualified!("refs/heads/tmp/scratch"))
+            .is_err(),
+        "refs/namespaces/{}/refs/heads/tmp/scratch should not be fetched",
+        bob.id
+    );
+}
+
+#[test]
+fn test_replication_ref_class() {
+    logger::init(log::Level::Debug);
+
+    let tmp = tempfile::tempdir().unwrap();
+    let alice = Node::init(tmp.path(), config::relay("alice"));
+    let mut bob = Node::init(tmp.path(), config::relay("bob"));
+    let eve = Node::init(tmp.path(), config::relay("eve"));
+    let acme = bob.project("acme", "");
+
+    let mut alice = alice.spawn();
+    let mut eve = eve.spawn();
+    let bob = bob.spawn();
+    let issue = bob.issue(acme, "Ref classes", "Fetch code and COBs separately");
+    let master = git::qualified!("refs/heads/master");
+    let cob = git::refs::storage::cob(&bob.id, &issue::TYPENAME, &issue);
+
+    alice.connect(&bob);
+    eve.connect(&bob);
+    converge([&alice, &bob, &eve]);
+
+    // Seed only once connected, so that the repository isn't cloned in full.
+    alice.handle.seed(acme, Scope::All).unwrap();
+    eve.handle.seed(acme, Scope::All).unwrap();
+
+    // Alice only fetches code, so Bob's issue is not fetched.
+    let result = alice
+        .handle
+        .fetch_refs(
+            acme,
+            bob.id,
+            DEFAULT_TIMEOUT,
+            RefClass::CodeOnly,
+            &mut |_| {},
+        )
+        .unwrap();
+    assert_matches!(result, FetchResult::Success { .. });
+    {
+        let repo = alice.storage.repository(acme).unwrap();
+        assert!(repo.reference(&bob.id, &master).is_ok());
+        assert!(repo
+            .reference(&bob.id, &git::qualified!("refs/rad/sigrefs"))
+            .is_ok());
+        assert!(repo.backend.find_reference(cob.as_str()).is_err());
+    }
+
+    // Eve only fetches COBs, so Bob's branches are not fetched.
+    let result = eve
+        .handle
+        .fetch_refs(
+            acme,
+            bob.id,
+            DEFAULT_TIMEOUT,
+            RefClass::CobsOnly,
+            &mut |_| {},
+        )
+        .unwrap();
+    assert_matches!(result, FetchResult::Success { .. });
+    {
+        let repo = eve.storage.repository(acme).unwrap();
+        assert!(repo.reference(&bob.id, &master).is_err());
+        assert!(repo.backend.find_reference(cob.as_str()).is_ok());
+    }
+
+    // A full fetch brings in the issue.
+    let result = alice.handle.fetch(acme, bob.id, DEFAULT_TIMEOUT).unwrap();
+    assert_matches!(result, FetchResult::Success { .. });
+    {
+        let repo = alice.storage.repository(acme).unwrap();
+        assert!(repo.reference(&bob.id, &master).is_ok());
+        assert!(repo.backend.find_reference(cob.as_str()).is_ok());
+    }
+
+    // Fetching only code again does not prune the issue.
+    let result = alice
+        .handle
+        .fetch_refs(
+            acme,
+            bob.id,
+            DEFAULT_TIMEOUT,
+            RefClass::CodeOnly,
+            &mut |_| {},
+        )
+        .unwrap();
+    assert_matches!(result, FetchResult::Success { .. });
+    {
+        let repo = alice.storage.repository(acme).unwrap();
+        assert!(repo.backend.find_reference(cob.as_str()).is_ok());
+    }
+}
+
+#[test]
+fn test_shutdown_drains_fetches() {
+    logger::init(log::Level::Debug);
+
+    let tmp = tempfile::tempdir().unwrap();
+    let mut alice = Node::init(tmp.path(), config::relay("alice"));
+    let mut bob = Node::init(tmp.path(), config::relay("bob"));
+    let acme = alice.project("acme", "");
+
+    bob.policies.seed(&acme, Scope::All).unwrap();
+
+    let alice = alice.spawn();
+    let mut bob = bob.spawn();
+
+    bob.connect(&alice);
+    converge([&alice, &bob]);
+
+    let (started, underway) = chan::bounded(1);
+    let fetch = thread::spawn({
+        let mut handle = (*bob.handle).clone();
+        let alice = alice.id;
+
+        move || {
+            handle.fetch_with_progress(acme, alice, DEFAULT_TIMEOUT, &mut |_| {
+                started.try_send(()).ok();
+            })
+        }
+    });
+    // Request a shutdown once the fetch is underway.
+    underway.recv().unwrap();
+
+    let mut phases = Vec::new();
+    (*bob.handle)
+        .clone()
+        .shutdown_with_progress(&mut |phase| phases.push(phase))
+        .unwrap();
+
+    // The ongoing fetch was allowed to complete before peers were disconnected.
+    assert_matches!(fetch.join().unwrap(), Ok(FetchResult::Success { .. }));
+    assert_matches!(
+        phases.as_slice(),
+        [
+            ..,
+            ShutdownPhase::Disconnecting { peers: 1 },
+            ShutdownPhase::Exiting
+        ]
+    );
+
+    let repo = bob.storage.repository(acme).unwrap();
+    assert!(repo.validate().unwrap().is_empty());
+    assert!(repo
+        .reference(&alice.id, &git::qualified!("refs/heads/master"))
+        .is_ok());
+}
+
 #[test]
 fn test_replication_invalid() {
     let tmp = tempfile::tempdir().unwrap();
@@ -624,6 +880,47 @@ fn test_fetch_up_to_date() {
     );
 }
 
+#[test]
+fn test_fetch_reuses_stream() {
+    logger::init(log::Level::Debug);
+
+    let tmp = tempfile::tempdir().unwrap();
+    let alice = Node::init(tmp.path(), config::relay("alice"));
+    let mut bob = Node::init(tmp.path(), config::relay("bob"));
+    let acme = bob.project("acme", "");
+
+    let mut alice = alice.spawn();
+    let bob = bob.spawn();
+
+    alice.connect(&bob);
+    converge([&alice, &bob]);
+
+    transport::local::register(alice.storage.clone());
+
+    let _ = alice.handle.seed(acme, Scope::All).unwrap();
+    let result = alice.handle.fetch(acme, bob.id, DEFAULT_TIMEOUT).unwrap();
+    assert!(result.is_success());
+
+    // The second fetch is carried out on the stream opened for the first one.
+    let result = alice.handle.fetch(acme, bob.id, DEFAULT_TIMEOUT).unwrap();
+    assert!(result.is_success());
+
+    let timer = time::Instant::now();
+    loop {
+        let debug = alice.handle.debug().unwrap();
+        let metrics = &debug["metrics"]["peers"][bob.id.to_string()];
+
+        if metrics["streamsReused"].as_u64() == Some(1) {
+            assert_eq!(metrics["streamsOpened"].as_u64(), Some(1));
+            break;
+        }
+        if timer.elapsed() > DEFAULT_TIMEOUT {
+            panic!("timed out waiting for stream to be reused: {metrics}");
+        }
+        thread::sleep(time::Duration::from_millis(100));
+    }
+}
+
 #[test]
 fn test_fetch_unseeded() {
     logger::init(log::Level::Debug);
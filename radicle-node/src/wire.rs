@@ -1,6 +1,7 @@
 mod frame;
 mod message;
 mod protocol;
+pub mod proxy;
 mod varint;
 
 pub use frame::StreamId;
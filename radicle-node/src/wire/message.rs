@@ -190,6 +190,8 @@ impl wire::Decode for InventoryAnnouncement {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InfoType {
     RefsAlreadySynced = 1,
+    RendezvousRequest = 2,
+    Rendezvous = 3,
 }
 
 impl From<InfoType> for u16 {
@@ -204,6 +206,8 @@ impl TryFrom<u16> for InfoType {
     fn try_from(other: u16) -> Result<Self, Self::Error> {
         match other {
             1 => Ok(Self::RefsAlreadySynced),
+            2 => Ok(Self::RendezvousRequest),
+            3 => Ok(Self::Rendezvous),
             n => Err(n),
         }
     }
@@ -219,6 +223,8 @@ impl From<&Info> for InfoType {
     fn from(info: &Info) -> Self {
         match info {
             Info::RefsAlreadySynced { .. } => Self::RefsAlreadySynced,
+            Info::RendezvousRequest { .. } => Self::RendezvousRequest,
+            Info::Rendezvous { .. } => Self::Rendezvous,
         }
     }
 }
@@ -232,6 +238,13 @@ impl wire::Encode for Info {
                 n += rid.encode(writer)?;
                 n += at.encode(writer)?;
             }
+            Info::RendezvousRequest { target } => {
+                n += target.encode(writer)?;
+            }
+            Info::Rendezvous { node, addr } => {
+                n += node.encode(writer)?;
+                n += addr.encode(writer)?;
+            }
         }
 
         Ok(n)
@@ -249,6 +262,17 @@ impl wire::Decode for Info {
 
                 Ok(Self::RefsAlreadySynced { rid, at })
             }
+            Ok(InfoType::RendezvousRequest) => {
+                let target = NodeId::decode(reader)?;
+
+                Ok(Self::RendezvousRequest { target })
+            }
+            Ok(InfoType::Rendezvous) => {
+                let node = NodeId::decode(reader)?;
+                let addr = Address::decode(reader)?;
+
+                Ok(Self::Rendezvous { node, addr })
+            }
             Err(other) => Err(wire::Error::UnknownInfoType(other)),
         }
     }
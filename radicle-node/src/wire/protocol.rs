@@ -14,15 +14,17 @@ use cyphernet::addr::{HostName, InetHost, NetAddr};
 use cyphernet::encrypt::noise::{HandshakePattern, Keyset, NoiseState};
 use cyphernet::proxy::socks5;
 use cyphernet::{Digest, EcSk, Ecdh, Sha256};
-use localtime::LocalTime;
+use localtime::{LocalDuration, LocalTime};
 use netservices::resource::{ListenerEvent, NetAccept, NetTransport, SessionEvent};
 use netservices::session::{NoiseSession, ProtocolArtifact, Socks5Session};
 use netservices::{NetConnection, NetReader, NetWriter};
 use reactor::{ResourceId, ResourceType, Timestamp};
 
 use radicle::collections::RandomMap;
+use radicle::identity::RepoId;
 use radicle::node::config::AddressConfig;
-use radicle::node::NodeId;
+use radicle::node::{NodeId, RefClass};
+use radicle::storage::refs::RefsAt;
 use radicle::storage::WriteStorage;
 
 use crate::crypto::Signer;
@@ -33,8 +35,10 @@ use crate::service::FETCH_TIMEOUT;
 use crate::service::{session, DisconnectReason, Metrics, Service};
 use crate::wire::frame;
 use crate::wire::frame::{Frame, FrameData, StreamId};
+use crate::wire::proxy;
 use crate::wire::Encode;
 use crate::worker;
+use crate::worker::fetch::ProgressSubscribers;
 use crate::worker::{ChannelEvent, FetchRequest, FetchResult, Task, TaskResult};
 use crate::Link;
 
@@ -53,6 +57,14 @@ pub const DEFAULT_DIAL_TIMEOUT: time::Duration = time::Duration::from_secs(6);
 /// Maximum size of a peer inbox, in bytes.
 pub const MAX_INBOX_SIZE: usize = 1024 * 1024 * 2;
 
+/// Time after which an idle git stream is closed instead of being reused for the next fetch.
+/// Streams opened by the remote are kept for twice as long, so that the remote closes them
+/// first.
+pub const STREAM_IDLE_TIMEOUT: LocalDuration = LocalDuration::from_secs(30);
+
+/// Maximum number of fetches carried out on a single git stream.
+pub const MAX_STREAM_FETCHES: usize = 16;
+
 /// Control message used internally between workers, users, and the service.
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug)]
@@ -63,6 +75,8 @@ pub enum Control {
     Worker(TaskResult),
     /// Flush data in the given stream to the remote.
     Flush { remote: NodeId, stream: StreamId },
+    /// Outcome of dialing a peer through a proxy.
+    Dialed(proxy::Dialed),
 }
 
 /// Peer session type.
@@ -83,6 +97,10 @@ struct Stream {
     sent_bytes: usize,
     /// Data received.
     received_bytes: usize,
+    /// Worker end of the stream, while no worker is using it.
+    idle: Option<Idle>,
+    /// Number of fetches carried out on this stream.
+    fetches: usize,
 }
 
 impl Stream {
@@ -91,16 +109,38 @@ impl Stream {
             channels,
             sent_bytes: 0,
             received_bytes: 0,
+            idle: None,
+            fetches: 1,
         }
     }
 }
 
+/// A stream kept open after a fetch, so that it can be reused.
+struct Idle {
+    /// Worker channels.
+    channels: worker::Channels,
+    /// Since when the stream is idle.
+    since: LocalTime,
+}
+
+/// A fetch carried out on a reused stream, retried on a new stream if the reused one
+/// turns out to have been closed by the remote.
+struct Retry {
+    rid: RepoId,
+    refs_at: Option<Vec<RefsAt>>,
+    refs: RefClass,
+    timeout: time::Duration,
+    progress: ProgressSubscribers,
+}
+
 /// Streams associated with a connected peer.
 struct Streams {
     /// Active streams and their associated worker channels.
     /// Note that the gossip and control streams are not included here as they are always
     /// implied to exist.
     streams: RandomMap<StreamId, Stream>,
+    /// Fetches in progress on reused streams.
+    retries: RandomMap<StreamId, Retry>,
     /// Connection direction.
     link: Link,
     /// Sequence number used to compute the next stream id.
@@ -112,6 +152,7 @@ impl Streams {
     fn new(link: Link) -> Self {
         Self {
             streams: RandomMap::default(),
+            retries: RandomMap::default(),
             link,
             seq: 0,
         }
@@ -160,6 +201,89 @@ impl Streams {
         self.streams.remove(stream)
     }
 
+    /// Keep a stream open once its worker is done with it. Returns `false` if the stream
+    /// was already closed.
+    fn park(&mut self, stream: &StreamId, channels: worker::Channels, now: LocalTime) -> bool {
+        let Some(s) = self.streams.get_mut(stream) else {
+            return false;
+        };
+        s.idle = Some(Idle {
+            channels,
+            since: now,
+        });
+        true
+    }
+
+    /// How long a stream may stay idle, and how many fetches it may carry out.
+    fn limits(&self, stream: &StreamId) -> (LocalDuration, usize) {
+        if stream.link() == self.link {
+            (STREAM_IDLE_TIMEOUT, MAX_STREAM_FETCHES)
+        } else {
+            // Streams opened by the remote are for the remote to reuse.
+            (STREAM_IDLE_TIMEOUT * 2, usize::MAX)
+        }
+    }
+
+    /// Unregister idle streams that timed out or were used for too many fetches, and
+    /// return them, so that they can be closed.
+    fn expire(&mut self, now: LocalTime) -> Vec<StreamId> {
+        let expired = self
+            .streams
+            .iter()
+            .filter(|(id, s)| {
+                let Some(idle) = &s.idle else {
+                    return false;
+                };
+                let (max_idle, max_fetches) = self.limits(id);
+
+                idle.since + max_idle <= now || s.fetches >= max_fetches
+            })
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+
+        for id in &expired {
+            self.streams.remove(id);
+        }
+        expired
+    }
+
+    /// Take an idle stream we opened, to reuse it for a fetch.
+    ///
+    /// Idle streams that expired are unregistered, and returned so that they can be closed.
+    fn reuse(
+        &mut self,
+        timeout: time::Duration,
+        now: LocalTime,
+    ) -> (Option<(StreamId, worker::Channels)>, Vec<StreamId>) {
+        let expired = self.expire(now);
+        let reusable = self
+            .streams
+            .iter()
+            .find(|(id, s)| id.link() == self.link && s.idle.is_some())
+            .map(|(id, _)| *id);
+
+        let reused = reusable.and_then(|id| {
+            let s = self.streams.get_mut(&id)?;
+            let mut channels = s.idle.take()?.channels;
+
+            s.fetches += 1;
+            channels.set_timeout(timeout);
+
+            Some((id, channels))
+        });
+        (reused, expired)
+    }
+
+    /// Take an idle stream the remote opened, to serve another fetch on it.
+    fn resume(&mut self, stream: &StreamId) -> Option<worker::Channels> {
+        let s = self.streams.get_mut(stream)?;
+        let idle = s.idle.take()?;
+
+        s.fetches += 1;
+
+        Some(idle.channels)
+    }
+
     /// Close all streams.
     fn shutdown(&mut self) {
         for (sid, stream) in self.streams.drain() {
@@ -311,6 +435,8 @@ pub struct Wire<D, S, G: Signer + Ecdh> {
     service: Service<D, S, G>,
     /// Worker pool interface.
     worker: chan::Sender<Task>,
+    /// Proxy dialer interface.
+    proxy: chan::Sender<proxy::Dial>,
     /// Used for authentication.
     signer: G,
     /// Node metrics.
@@ -333,12 +459,18 @@ where
     S: WriteStorage + 'static,
     G: Signer + Ecdh<Pk = NodeId>,
 {
-    pub fn new(service: Service<D, S, G>, worker: chan::Sender<Task>, signer: G) -> Self {
+    pub fn new(
+        service: Service<D, S, G>,
+        worker: chan::Sender<Task>,
+        proxy: chan::Sender<proxy::Dial>,
+        signer: G,
+    ) -> Self {
         assert!(service.started().is_some(), "Service must be initialized");
 
         Self {
             service,
             worker,
+            proxy,
             signer,
             metrics: Metrics::default(),
             actions: VecDeque::new(),
@@ -409,23 +541,67 @@ where
             return;
         };
 
-        if let Peer::Connected { link, streams, .. } = peer {
-            // Nb. It's possible that the stream would already be unregistered if we received an
-            // early "close" from the remote. Otherwise, we unregister it here and send the "close"
-            // ourselves.
-            if let Some(s) = streams.unregister(&task.stream) {
+        let retry = if let Peer::Connected { link, streams, .. } = peer {
+            let retry = streams.retries.remove(&task.stream);
+
+            let parked = task
+                .channels
+                .is_some_and(|c| streams.park(&task.stream, c, LocalTime::now()));
+
+            // Keep the stream open, so that it can be reused for the next fetch.
+            if parked {
+                let (max_idle, _) = streams.limits(&task.stream);
+
+                // Make sure we wake up to close the stream if it isn't reused in time.
+                self.actions
+                    .push_back(reactor::Action::SetTimer(max_idle.into()));
                 log::debug!(
-                    target: "wire", "Stream {} of {} closing with {} byte(s) sent and {} byte(s) received",
-                    task.stream, task.remote, s.sent_bytes, s.received_bytes
-                );
-                let frame = Frame::<service::Message>::control(
-                    *link,
-                    frame::Control::Close {
-                        stream: task.stream,
-                    },
+                    target: "wire", "Stream {} of {} is idle, keeping it open for reuse",
+                    task.stream, task.remote
                 );
-                self.actions.push_back(Action::Send(fd, frame.to_bytes()));
+                // The remote may have started reusing the stream before we were done.
+                let pending = streams
+                    .get(&task.stream)
+                    .and_then(|s| s.idle.as_ref())
+                    .is_some_and(|idle| !idle.channels.is_empty());
+
+                if task.stream.link() != *link && pending {
+                    if let Some(channels) = streams.resume(&task.stream) {
+                        let task = Task {
+                            fetch: FetchRequest::Responder {
+                                remote: nid,
+                                emitter: self.service.emitter(),
+                            },
+                            stream: task.stream,
+                            channels,
+                        };
+                        if let Err(e) = self.worker.try_send(task) {
+                            log::error!(
+                                target: "wire",
+                                "Worker pool failed to accept incoming fetch request: {e}"
+                            );
+                        }
+                    }
+                }
+            } else {
+                // Nb. It's possible that the stream would already be unregistered if we
+                // received an early "close" from the remote. Otherwise, we unregister it
+                // here and send the "close" ourselves.
+                if let Some(s) = streams.unregister(&task.stream) {
+                    log::debug!(
+                        target: "wire", "Stream {} of {} closing with {} byte(s) sent and {} byte(s) received",
+                        task.stream, task.remote, s.sent_bytes, s.received_bytes
+                    );
+                    let frame = Frame::<service::Message>::control(
+                        *link,
+                        frame::Control::Close {
+                            stream: task.stream,
+                        },
+                    );
+                    self.actions.push_back(Action::Send(fd, frame.to_bytes()));
+                }
             }
+            retry
         } else {
             // If the peer disconnected, we'll get here, but we still want to let the service know
             // about the fetch result, so we don't return here.
@@ -435,6 +611,26 @@ where
 
         // Only call into the service if we initiated this fetch.
         match task.result {
+            FetchResult::Initiator {
+                rid,
+                result: Err(e),
+            } if e.is_stream_closed() && retry.is_some() => {
+                log::debug!(
+                    target: "wire",
+                    "Reused stream {} for {rid} was closed by {nid}, retrying on a new stream..",
+                    task.stream
+                );
+                if let Some(Retry {
+                    rid,
+                    refs_at,
+                    refs,
+                    timeout,
+                    progress,
+                }) = retry
+                {
+                    self.fetch(rid, nid, timeout, refs_at, refs, progress);
+                }
+            }
             FetchResult::Initiator { rid, result } => {
                 self.service.fetched(rid, nid, result);
             }
@@ -450,6 +646,119 @@ where
         }
     }
 
+    /// Close the idle streams of connected peers that expired.
+    fn expire_streams(&mut self) {
+        let now = LocalTime::now();
+
+        for (fd, peer) in self.peers.0.iter_mut() {
+            let Peer::Connected {
+                link, nid, streams, ..
+            } = peer
+            else {
+                continue;
+            };
+            for stream in streams.expire(now) {
+                log::debug!(target: "wire", "Closing idle stream {stream} of {nid}");
+
+                self.actions.push_back(Action::Send(
+                    *fd,
+                    Frame::<service::Message>::control(*link, frame::Control::Close { stream })
+                        .to_bytes(),
+                ));
+            }
+        }
+    }
+
+    fn fetch(
+        &mut self,
+        rid: RepoId,
+        remote: NodeId,
+        timeout: time::Duration,
+        refs_at: Option<Vec<RefsAt>>,
+        refs: RefClass,
+        progress: ProgressSubscribers,
+    ) {
+        log::trace!(target: "wire", "Processing fetch for {rid} from {remote}..");
+
+        let Some((fd, Peer::Connected { link, streams, .. })) = self.peers.lookup_mut(&remote)
+        else {
+            // Nb. It's possible that a peer is disconnected while an `Io::Fetch`
+            // is in the service's i/o buffer. Since the service may not purge the
+            // buffer on disconnect, we should just ignore i/o actions that don't
+            // have a connected peer.
+            log::error!(target: "wire", "Peer {remote} is not connected: dropping fetch");
+            return;
+        };
+        let link = *link;
+        let (reused, expired) = streams.reuse(timeout, LocalTime::now());
+
+        for stream in expired {
+            log::debug!(target: "wire", "Closing idle stream {stream} of {remote}");
+
+            self.actions.push_back(Action::Send(
+                fd,
+                Frame::<service::Message>::control(link, frame::Control::Close { stream })
+                    .to_bytes(),
+            ));
+        }
+        let metrics = self.metrics.peer(remote);
+        let (stream, channels) = if let Some((stream, channels)) = reused {
+            log::debug!(target: "wire", "Reusing stream {stream} for {rid} and remote {remote}");
+
+            streams.retries.insert(
+                stream,
+                Retry {
+                    rid,
+                    refs_at: refs_at.clone(),
+                    refs,
+                    timeout,
+                    progress: progress.clone(),
+                },
+            );
+            metrics.streams_reused += 1;
+
+            (stream, channels)
+        } else {
+            let (stream, channels) = streams.open(timeout);
+
+            log::debug!(target: "wire", "Opened new stream with id {stream} for {rid} and remote {remote}");
+
+            metrics.streams_opened += 1;
+            self.actions.push_back(Action::Send(
+                fd,
+                Frame::<service::Message>::control(link, frame::Control::Open { stream })
+                    .to_bytes(),
+            ));
+            (stream, channels)
+        };
+        metrics.sent_fetch_requests += 1;
+
+        let task = Task {
+            fetch: FetchRequest::Initiator {
+                rid,
+                remote,
+                refs_at,
+                refs,
+                progress,
+            },
+            stream,
+            channels,
+        };
+
+        if !self.worker.is_empty() {
+            log::warn!(
+                target: "wire",
+                "Worker pool is busy: {} tasks pending, fetch requests may be delayed", self.worker.len()
+            );
+        }
+        if let Err(e) = self.worker.try_send(task) {
+            log::error!(
+                target: "wire",
+                "Worker pool failed to accept outgoing fetch request: {e}"
+            );
+        }
+    }
+
     fn flush(&mut self, remote: NodeId, stream: StreamId) {
         let Some((fd, peer)) = self.peers.lookup_mut(&remote) else {
             log::warn!(target: "wire", "Peer {remote} is not known; ignoring flush");
@@ -480,6 +789,70 @@ where
         }
     }
 
+    /// Register a new outbound transport with the reactor.
+    fn register_outbound(
+        &mut self,
+        nid: NodeId,
+        addr: NetAddr<HostName>,
+        transport: NetTransport<WireSession<G>>,
+    ) {
+        self.outbound.insert(
+            transport.as_raw_fd(),
+            Outbound {
+                id: None,
+                nid,
+                addr,
+            },
+        );
+        log::debug!(
+            target: "wire",
+            "Registering outbound transport for {nid} (fd={})..",
+            transport.as_raw_fd()
+        );
+        self.actions
+            .push_back(reactor::Action::RegisterTransport(transport));
+    }
+
+    /// Handle the outcome of dialing a peer through a proxy.
+    fn dialed(&mut self, dialed: proxy::Dialed) {
+        let proxy::Dialed { nid, addr, result } = dialed;
+
+        let connection = match result {
+            Ok(connection) => connection,
+            Err(err) => {
+                log::error!(target: "wire", "Error establishing connection to {addr}: {err}");
+
+                // Don't hold a failing proxy against the peer.
+                let reason = if err.is_proxy_err() {
+                    DisconnectReason::Proxy(Arc::new(err))
+                } else {
+                    DisconnectReason::Dial(Arc::new(err))
+                };
+                self.service.disconnected(nid, Link::Outbound, &reason);
+
+                return;
+            }
+        };
+        if self.peers.connected().any(|(_, id)| id == &nid) {
+            log::debug!(target: "wire", "Peer {nid} connected while dialing through proxy; dropping connection..");
+            return;
+        }
+        match proxied::<G>(connection, nid, self.signer.clone()).and_then(|session| {
+            NetTransport::<WireSession<G>>::with_session(session, Link::Outbound)
+        }) {
+            Ok(transport) => self.register_outbound(nid, addr.to_inner(), transport),
+            Err(err) => {
+                log::error!(target: "wire", "Error establishing connection to {addr}: {err}");
+
+                self.service.disconnected(
+                    nid,
+                    Link::Outbound,
+                    &DisconnectReason::Dial(Arc::new(err)),
+                );
+            }
+        }
+    }
+
     fn cleanup(&mut self, id: ResourceId, fd: RawFd) {
         if self.inbound.remove(&fd).is_some() {
             log::debug!(target: "wire", "Cleaning up inbound peer state with id={id} (fd={fd})");
@@ -526,6 +899,7 @@ where
     }
 
     fn handle_timer(&mut self) {
+        self.expire_streams();
         self.service.wake();
     }
 
@@ -737,6 +1111,7 @@ where
             SessionEvent::Data(data) => {
                 if let Some(Peer::Connected {
                     nid,
+                    link,
                     inbox,
                     streams,
                     ..
@@ -830,6 +1205,32 @@ where
                                     if s.channels.send(ChannelEvent::Data(data)).is_err() {
                                         log::error!(target: "wire", "Worker is disconnected; cannot send data");
                                     }
+                                    if s.idle.is_some() {
+                                        if stream.link() == *link {
+                                            // Nb. The remote isn't expected to send data on
+                                            // idle streams we opened, so don't reuse it.
+                                            s.fetches = usize::MAX;
+                                        } else if let Some(channels) = streams.resume(&stream) {
+                                            // The remote is reusing the stream for another fetch.
+                                            log::debug!(target: "wire", "Resuming stream {stream} for new fetch from {nid}");
+                                            metrics.received_fetch_requests += 1;
+
+                                            let task = Task {
+                                                fetch: FetchRequest::Responder {
+                                                    remote: *nid,
+                                                    emitter: self.service.emitter(),
+                                                },
+                                                stream,
+                                                channels,
+                                            };
+                                            if let Err(e) = self.worker.try_send(task) {
+                                                log::error!(
+                                                    target: "wire",
+                                                    "Worker pool failed to accept incoming fetch request: {e}"
+                                                );
+                                            }
+                                        }
+                                    }
                                 } else {
                                     log::debug!(target: "wire", "Ignoring frame on closed or unknown stream {stream}");
                                 }
@@ -867,6 +1268,7 @@ where
             Control::User(cmd) => self.service.command(cmd),
             Control::Worker(result) => self.worker_result(result),
             Control::Flush { remote, stream } => self.flush(remote, stream),
+            Control::Dialed(dialed) => self.dialed(dialed),
         }
     }
 
@@ -1009,31 +1411,42 @@ where
                     self.service.attempted(node_id, addr.clone());
                     self.metrics.peer(node_id).outbound_connection_attempts += 1;
 
-                    match dial::<G>(
-                        addr.to_inner(),
-                        node_id,
-                        self.signer.clone(),
-                        self.service.config(),
-                    )
-                    .and_then(|session| {
-                        NetTransport::<WireSession<G>>::with_session(session, Link::Outbound)
-                    }) {
+                    let dialed = match route(&addr.to_inner(), self.service.config()) {
+                        Ok(Route::Proxy(proxy)) => {
+                            log::debug!(target: "wire", "Dialing {node_id} at {addr} via proxy {proxy}..");
+
+                            // The proxy handshake is carried out on a separate thread, since
+                            // it can take a while. See [`Control::Dialed`] for the outcome.
+                            if let Err(err) = self.proxy.send(proxy::Dial {
+                                nid: node_id,
+                                addr: addr.clone(),
+                                proxy,
+                            }) {
+                                log::error!(target: "wire", "Proxy dialer is not available: {err}");
+
+                                self.service.disconnected(
+                                    node_id,
+                                    Link::Outbound,
+                                    &DisconnectReason::Proxy(Arc::new(err)),
+                                );
+                            }
+                            continue;
+                        }
+                        Ok(Route::Direct(inet_addr)) => {
+                            dial::<G>(addr.to_inner(), inet_addr, node_id, self.signer.clone())
+                                .and_then(|session| {
+                                    NetTransport::<WireSession<G>>::with_session(
+                                        session,
+                                        Link::Outbound,
+                                    )
+                                })
+                        }
+                        Err(err) => Err(err),
+                    };
+
+                    match dialed {
                         Ok(transport) => {
-                            self.outbound.insert(
-                                transport.as_raw_fd(),
-                                Outbound {
-                                    id: None,
-                                    nid: node_id,
-                                    addr: addr.to_inner(),
-                                },
-                            );
-                            log::debug!(
-                                target: "wire",
-                                "Registering outbound transport for {node_id} (fd={})..",
-                                transport.as_raw_fd()
-                            );
-                            self.actions
-                                .push_back(reactor::Action::RegisterTransport(transport));
+                            self.register_outbound(node_id, addr.to_inner(), transport);
                         }
                         Err(err) => {
                             log::error!(target: "wire", "Error establishing connection to {addr}: {err}");
@@ -1063,55 +1476,10 @@ where
                     remote,
                     timeout,
                     refs_at,
+                    refs,
+                    progress,
                 } => {
-                    log::trace!(target: "wire", "Processing fetch for {rid} from {remote}..");
-
-                    let Some((fd, Peer::Connected { link, streams, .. })) =
-                        self.peers.lookup_mut(&remote)
-                    else {
-                        // Nb. It's possible that a peer is disconnected while an `Io::Fetch`
-                        // is in the service's i/o buffer. Since the service may not purge the
-                        // buffer on disconnect, we should just ignore i/o actions that don't
-                        // have a connected peer.
-                        log::error!(target: "wire", "Peer {remote} is not connected: dropping fetch");
-                        continue;
-                    };
-                    let (stream, channels) = streams.open(timeout);
-
-                    log::debug!(target: "wire", "Opened new stream with id {stream} for {rid} and remote {remote}");
-
-                    let link = *link;
-                    let task = Task {
-                        fetch: FetchRequest::Initiator {
-                            rid,
-                            remote,
-                            refs_at,
-                        },
-                        stream,
-                        channels,
-                    };
-
-                    if !self.worker.is_empty() {
-                        log::warn!(
-                            target: "wire",
-                            "Worker pool is busy: {} tasks pending, fetch requests may be delayed", self.worker.len()
-                        );
-                    }
-                    if let Err(e) = self.worker.try_send(task) {
-                        log::error!(
-                            target: "wire",
-                            "Worker pool failed to accept outgoing fetch request: {e}"
-                        );
-                    }
-                    let metrics = self.metrics.peer(remote);
-                    metrics.streams_opened += 1;
-                    metrics.sent_fetch_requests += 1;
-
-                    self.actions.push_back(Action::Send(
-                        fd,
-                        Frame::<service::Message>::control(link, frame::Control::Open { stream })
-                            .to_bytes(),
-                    ));
+                    self.fetch(rid, remote, timeout, refs_at, refs, progress);
                 }
             }
         }
@@ -1119,61 +1487,88 @@ where
     }
 }
 
-/// Establish a new outgoing connection.
-pub fn dial<G: Signer + Ecdh<Pk = NodeId>>(
-    remote_addr: NetAddr<HostName>,
-    remote_id: <G as EcSk>::Pk,
-    signer: G,
-    config: &service::Config,
-) -> io::Result<WireSession<G>> {
-    // Determine what address to establish a TCP connection with, given the remote peer
-    // address and our node configuration.
-    let inet_addr: NetAddr<InetHost> = match (&remote_addr.host, config.proxy) {
+/// How to reach a remote address.
+enum Route {
+    /// Connect to the given address directly.
+    Direct(NetAddr<InetHost>),
+    /// Connect through the given SOCKS5 proxy.
+    Proxy(net::SocketAddr),
+}
+
+/// Determine how to reach the remote peer address, given our node configuration.
+fn route(remote_addr: &NetAddr<HostName>, config: &service::Config) -> io::Result<Route> {
+    match (&remote_addr.host, config.proxy) {
         // For IP and DNS addresses, use the global proxy if set, otherwise use the address as-is.
-        (HostName::Ip(_), Some(proxy)) => proxy.into(),
-        (HostName::Ip(ip), None) => NetAddr::new(InetHost::Ip(*ip), remote_addr.port),
-        (HostName::Dns(_), Some(proxy)) => proxy.into(),
-        (HostName::Dns(dns), None) => NetAddr::new(InetHost::Dns(dns.clone()), remote_addr.port),
+        // DNS names are resolved by the proxy.
+        (HostName::Ip(_), Some(proxy)) => Ok(Route::Proxy(proxy)),
+        (HostName::Ip(ip), None) => Ok(Route::Direct(NetAddr::new(
+            InetHost::Ip(*ip),
+            remote_addr.port,
+        ))),
+        (HostName::Dns(_), Some(proxy)) => Ok(Route::Proxy(proxy)),
+        (HostName::Dns(dns), None) => Ok(Route::Direct(NetAddr::new(
+            InetHost::Dns(dns.clone()),
+            remote_addr.port,
+        ))),
         // For onion addresses, handle with care.
         (HostName::Tor(onion), proxy) => match config.onion {
             // In onion proxy mode, simply use the configured proxy address.
             // This takes precedence over any global proxy.
-            Some(AddressConfig::Proxy { address }) => address.into(),
+            Some(AddressConfig::Proxy { address }) => Ok(Route::Proxy(address)),
             // In "forward" mode, if a global proxy is set, we use that, otherwise
             // we treat `.onion` addresses as regular DNS names.
             Some(AddressConfig::Forward) => {
                 if let Some(proxy) = proxy {
-                    proxy.into()
+                    Ok(Route::Proxy(proxy))
                 } else {
-                    NetAddr::new(InetHost::Dns(onion.to_string()), remote_addr.port)
+                    Ok(Route::Direct(NetAddr::new(
+                        InetHost::Dns(onion.to_string()),
+                        remote_addr.port,
+                    )))
                 }
             }
             // If onion address support isn't configured, refuse to connect.
-            None => {
-                return Err(io::Error::new(
-                    io::ErrorKind::Unsupported,
-                    "no configuration found for .onion addresses",
-                ));
-            }
-        },
-        _ => {
-            return Err(io::Error::new(
+            None => Err(io::Error::new(
                 io::ErrorKind::Unsupported,
-                "unsupported remote address type",
-            ));
-        }
-    };
+                "no configuration found for .onion addresses",
+            )),
+        },
+        _ => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "unsupported remote address type",
+        )),
+    }
+}
+
+/// Establish a new outgoing connection, directly.
+pub fn dial<G: Signer + Ecdh<Pk = NodeId>>(
+    remote_addr: NetAddr<HostName>,
+    inet_addr: NetAddr<InetHost>,
+    remote_id: <G as EcSk>::Pk,
+    signer: G,
+) -> io::Result<WireSession<G>> {
     // Nb. This timeout is currently not used by the underlying library due to the
     // `socket2` library not supporting non-blocking connect with timeout.
     let connection = net::TcpStream::connect_nonblocking(inet_addr, DEFAULT_DIAL_TIMEOUT)?;
-    // Whether to tunnel regular connections through the proxy.
-    let force_proxy = config.proxy.is_some();
+
+    session::<G>(remote_addr, Some(remote_id), connection, signer)
+}
+
+/// Establish a new outgoing connection, given a connection to the remote through a proxy.
+pub fn proxied<G: Signer + Ecdh<Pk = NodeId>>(
+    connection: net::TcpStream,
+    remote_id: <G as EcSk>::Pk,
+    signer: G,
+) -> io::Result<WireSession<G>> {
+    // Since the proxy handshake is already done, the SOCKS5 layer of the session is
+    // addressed to the proxy directly, making it a pass-through.
+    let proxy = connection.peer_addr()?;
+    connection.set_nonblocking(true)?;
 
     session::<G>(
-        remote_addr,
+        NetAddr::new(HostName::Ip(proxy.ip()), proxy.port()),
         Some(remote_id),
         connection,
-        force_proxy,
         signer,
     )
 }
@@ -1184,7 +1579,7 @@ pub fn accept<G: Signer + Ecdh<Pk = NodeId>>(
     connection: net::TcpStream,
     signer: G,
 ) -> io::Result<WireSession<G>> {
-    session::<G>(remote_addr, None, connection, false, signer)
+    session::<G>(remote_addr, None, connection, signer)
 }
 
 /// Create a new [`WireSession`].
@@ -1192,7 +1587,6 @@ fn session<G: Signer + Ecdh<Pk = NodeId>>(
     remote_addr: NetAddr<HostName>,
     remote_id: Option<NodeId>,
     connection: net::TcpStream,
-    force_proxy: bool,
     signer: G,
 ) -> io::Result<WireSession<G>> {
     // There are issues with setting TCP_NODELAY on WSL. Not a big deal.
@@ -1211,7 +1605,8 @@ fn session<G: Signer + Ecdh<Pk = NodeId>>(
         log::warn!(target: "wire", "Unable to set TCP_KEEPALIVE on fd {}: {e}", sock.as_raw_fd());
     }
 
-    let socks5 = socks5::Socks5::with(remote_addr, force_proxy);
+    // Nb. Connections through a proxy are established when dialing, see [`proxied`].
+    let socks5 = socks5::Socks5::with(remote_addr, false);
     let proxy = Socks5Session::with(sock.into(), socks5);
     let pair = G::generate_keypair();
     let keyset = Keyset {
@@ -1236,6 +1631,25 @@ mod test {
     use crate::wire;
     use crate::wire::varint;
 
+    #[test]
+    fn test_streams_expire() {
+        let mut streams = Streams::new(Link::Outbound);
+        let timeout = time::Duration::from_secs(9);
+        let now = LocalTime::now();
+
+        let (ours, channels) = streams.open(timeout);
+        assert!(streams.park(&ours, channels, now));
+
+        let theirs = StreamId::git(Link::Inbound).nth(1).unwrap();
+        let channels = streams.register(theirs, timeout).unwrap();
+        assert!(streams.park(&theirs, channels, now));
+
+        assert!(streams.expire(now).is_empty());
+        assert_eq!(streams.expire(now + STREAM_IDLE_TIMEOUT), vec![ours]);
+        assert_eq!(streams.expire(now + STREAM_IDLE_TIMEOUT * 2), vec![theirs]);
+        assert!(streams.get(&theirs).is_none());
+    }
+
     #[test]
     fn test_pong_message_with_extension() {
         use crate::deserializer;
@@ -1368,4 +1782,20 @@ mod test {
             assert!(de.is_empty());
         }
     }
+
+    #[test]
+    fn test_route() {
+        let proxy = net::SocketAddr::from(([127, 0, 0, 1], 9050));
+        let ip = NetAddr::new(HostName::Ip([10, 0, 0, 1].into()), 8776);
+        let dns = NetAddr::new(HostName::Dns(String::from("seed.radicle.example")), 8776);
+        let mut config = service::Config::test(radicle::node::Alias::new("alice"));
+
+        assert!(matches!(route(&ip, &config), Ok(Route::Direct(_))));
+        assert!(matches!(route(&dns, &config), Ok(Route::Direct(_))));
+
+        // With a proxy set, host names are resolved by the proxy.
+        config.proxy = Some(proxy);
+        assert!(matches!(route(&ip, &config), Ok(Route::Proxy(p)) if p == proxy));
+        assert!(matches!(route(&dns, &config), Ok(Route::Proxy(p)) if p == proxy));
+    }
 }
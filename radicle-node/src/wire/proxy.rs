@@ -0,0 +1,448 @@
+//! Outbound connections through a SOCKS5 proxy, eg. Tor.
+//!
+//! Since establishing a connection through a proxy can take a while, especially for
+//! `.onion` addresses, the handshake is done on a separate thread, and the established
+//! connection is then handed over to the wire protocol. Host names are passed as-is to
+//! the proxy, for it to resolve.
+//!
+//! Errors distinguish failures of the proxy itself from failures to reach the peer, such
+//! that an unavailable proxy doesn't count against the peers we're trying to reach.
+use std::io::{Read, Write};
+use std::{io, net, thread, time};
+
+use crossbeam_channel as chan;
+use cyphernet::addr::{HostName, NetAddr};
+use thiserror::Error;
+
+use radicle::node::{Address, NodeId};
+
+use crate::runtime::Handle;
+use crate::wire::protocol::DEFAULT_DIAL_TIMEOUT;
+
+/// SOCKS protocol version.
+pub const VERSION: u8 = 0x05;
+/// Time to wait for the proxy to reach the peer. This is fairly long, as building
+/// a Tor circuit can take a while.
+pub const HANDSHAKE_TIMEOUT: time::Duration = time::Duration::from_secs(30);
+
+/// The "no authentication" method.
+const METHOD_NONE: u8 = 0x00;
+/// Returned by the proxy when none of our authentication methods are acceptable.
+const METHOD_UNACCEPTABLE: u8 = 0xff;
+/// The `CONNECT` command.
+const CMD_CONNECT: u8 = 0x01;
+/// Address types.
+const ATYP_IPV4: u8 = 0x01;
+const ATYP_DOMAIN: u8 = 0x03;
+const ATYP_IPV6: u8 = 0x04;
+
+/// Proxy error.
+#[derive(Error, Debug)]
+pub enum Error {
+    /// The proxy couldn't be reached.
+    #[error("proxy {proxy} is unreachable: {err}")]
+    Unreachable {
+        proxy: net::SocketAddr,
+        #[source]
+        err: io::Error,
+    },
+    /// The proxy didn't follow the protocol, or requires authentication.
+    #[error("proxy {proxy} error: {reason}")]
+    Protocol {
+        proxy: net::SocketAddr,
+        reason: &'static str,
+    },
+    /// The proxy failed to carry out the request.
+    #[error("proxy {proxy} failure: {reply}")]
+    Proxy {
+        proxy: net::SocketAddr,
+        reply: Reply,
+    },
+    /// The peer couldn't be reached through the proxy.
+    #[error("peer unreachable via proxy {proxy}: {reply}")]
+    Peer {
+        proxy: net::SocketAddr,
+        reply: Reply,
+    },
+    /// The proxy took too long to reach the peer.
+    #[error("peer unreachable via proxy {proxy}: timed out")]
+    Timeout { proxy: net::SocketAddr },
+    /// The peer address can't be proxied.
+    #[error("unsupported address type for proxying: {0}")]
+    Address(String),
+    /// I/O error while talking to the proxy.
+    #[error("proxy {proxy} I/O error: {err}")]
+    Io {
+        proxy: net::SocketAddr,
+        #[source]
+        err: io::Error,
+    },
+}
+
+impl Error {
+    /// Whether the error is caused by the proxy, as opposed to the peer.
+    pub fn is_proxy_err(&self) -> bool {
+        match self {
+            Self::Unreachable { .. }
+            | Self::Protocol { .. }
+            | Self::Proxy { .. }
+            | Self::Io { .. } => true,
+            Self::Peer { .. } | Self::Timeout { .. } | Self::Address(_) => false,
+        }
+    }
+}
+
+/// Reply code sent by the proxy in response to a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reply {
+    Succeeded,
+    GeneralFailure,
+    NotAllowed,
+    NetworkUnreachable,
+    HostUnreachable,
+    ConnectionRefused,
+    TtlExpired,
+    CommandNotSupported,
+    AddressNotSupported,
+    Unassigned(u8),
+}
+
+impl Reply {
+    /// Whether the reply is about the peer, rather than the proxy.
+    pub fn is_peer_err(&self) -> bool {
+        matches!(
+            self,
+            Self::NetworkUnreachable
+                | Self::HostUnreachable
+                | Self::ConnectionRefused
+                | Self::TtlExpired
+        )
+    }
+}
+
+impl From<u8> for Reply {
+    fn from(code: u8) -> Self {
+        match code {
+            0x00 => Self::Succeeded,
+            0x01 => Self::GeneralFailure,
+            0x02 => Self::NotAllowed,
+            0x03 => Self::NetworkUnreachable,
+            0x04 => Self::HostUnreachable,
+            0x05 => Self::ConnectionRefused,
+            0x06 => Self::TtlExpired,
+            0x07 => Self::CommandNotSupported,
+            0x08 => Self::AddressNotSupported,
+            other => Self::Unassigned(other),
+        }
+    }
+}
+
+impl std::fmt::Display for Reply {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Succeeded => write!(f, "succeeded"),
+            Self::GeneralFailure => write!(f, "general failure"),
+            Self::NotAllowed => write!(f, "connection not allowed by ruleset"),
+            Self::NetworkUnreachable => write!(f, "network unreachable"),
+            Self::HostUnreachable => write!(f, "host unreachable"),
+            Self::ConnectionRefused => write!(f, "connection refused"),
+            Self::TtlExpired => write!(f, "TTL expired"),
+            Self::CommandNotSupported => write!(f, "command not supported"),
+            Self::AddressNotSupported => write!(f, "address type not supported"),
+            Self::Unassigned(code) => write!(f, "unknown reply code {code:#04x}"),
+        }
+    }
+}
+
+/// A request to dial a peer through a proxy.
+#[derive(Debug)]
+pub struct Dial {
+    /// Peer we're dialing.
+    pub nid: NodeId,
+    /// Peer address.
+    pub addr: Address,
+    /// Proxy to dial through.
+    pub proxy: net::SocketAddr,
+}
+
+/// The outcome of a [`Dial`].
+#[derive(Debug)]
+pub struct Dialed {
+    /// Peer we dialed.
+    pub nid: NodeId,
+    /// Peer address.
+    pub addr: Address,
+    /// The connection to the peer, through the proxy.
+    pub result: Result<net::TcpStream, Error>,
+}
+
+/// Dial peers through their proxy as requests come in, handing the outcome over
+/// to the wire protocol.
+pub fn dial(requests: chan::Receiver<Dial>, handle: Handle) {
+    for Dial { nid, addr, proxy } in requests {
+        let mut handle = handle.clone();
+
+        thread::Builder::new()
+            .name(format!("{nid} <proxy>"))
+            .spawn(move || {
+                let result = connect(proxy, &addr, DEFAULT_DIAL_TIMEOUT);
+
+                if let Err(e) = handle.dialed(Dialed { nid, addr, result }) {
+                    log::error!(target: "wire", "Unable to hand over proxied connection to {nid}: {e}");
+                }
+            })
+            .ok();
+    }
+}
+
+/// Connect to the given address through a SOCKS5 proxy.
+///
+/// Returns a blocking TCP stream connected to the peer, once the proxy has reached it.
+pub fn connect(
+    proxy: net::SocketAddr,
+    addr: &NetAddr<HostName>,
+    timeout: time::Duration,
+) -> Result<net::TcpStream, Error> {
+    let request = request(addr)?;
+    let mut stream = net::TcpStream::connect_timeout(&proxy, timeout)
+        .map_err(|err| Error::Unreachable { proxy, err })?;
+    let io_err = |err: io::Error| Error::Io { proxy, err };
+
+    stream.set_read_timeout(Some(timeout)).map_err(io_err)?;
+    stream.set_write_timeout(Some(timeout)).map_err(io_err)?;
+
+    // Method negotiation. We only support unauthenticated proxies.
+    let mut reply = [0u8; 2];
+    stream
+        .write_all(&[VERSION, 1, METHOD_NONE])
+        .map_err(io_err)?;
+    stream.read_exact(&mut reply).map_err(io_err)?;
+
+    match reply {
+        [VERSION, METHOD_NONE] => {}
+        [VERSION, METHOD_UNACCEPTABLE] => {
+            return Err(Error::Protocol {
+                proxy,
+                reason: "authentication is required",
+            })
+        }
+        _ => {
+            return Err(Error::Protocol {
+                proxy,
+                reason: "invalid method selection reply",
+            })
+        }
+    }
+
+    // Connection request. Once the proxy is known to be up, we allow more time for it
+    // to reach the peer.
+    stream
+        .set_read_timeout(Some(HANDSHAKE_TIMEOUT.max(timeout)))
+        .map_err(io_err)?;
+    stream.write_all(&request).map_err(io_err)?;
+
+    let mut header = [0u8; 4];
+    if let Err(err) = stream.read_exact(&mut header) {
+        if matches!(
+            err.kind(),
+            io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+        ) {
+            return Err(Error::Timeout { proxy });
+        }
+        return Err(io_err(err));
+    }
+    let [version, code, _, atyp] = header;
+    if version != VERSION {
+        return Err(Error::Protocol {
+            proxy,
+            reason: "invalid protocol version in reply",
+        });
+    }
+    match Reply::from(code) {
+        Reply::Succeeded => {}
+        reply if reply.is_peer_err() => return Err(Error::Peer { proxy, reply }),
+        reply => return Err(Error::Proxy { proxy, reply }),
+    }
+
+    // Skip the address the proxy bound to, we don't need it.
+    let len = match atyp {
+        ATYP_IPV4 => 4,
+        ATYP_IPV6 => 16,
+        ATYP_DOMAIN => {
+            let mut len = [0u8; 1];
+            stream.read_exact(&mut len).map_err(io_err)?;
+            len[0] as usize
+        }
+        _ => {
+            return Err(Error::Protocol {
+                proxy,
+                reason: "invalid address type in reply",
+            })
+        }
+    };
+    let mut bound = vec![0u8; len + 2];
+    stream.read_exact(&mut bound).map_err(io_err)?;
+
+    stream.set_read_timeout(None).map_err(io_err)?;
+    stream.set_write_timeout(None).map_err(io_err)?;
+
+    Ok(stream)
+}
+
+/// Encode a `CONNECT` request for the given address.
+fn request(addr: &NetAddr<HostName>) -> Result<Vec<u8>, Error> {
+    let mut request = vec![VERSION, CMD_CONNECT, 0x00];
+
+    match &addr.host {
+        HostName::Ip(net::IpAddr::V4(ip)) => {
+            request.push(ATYP_IPV4);
+            request.extend_from_slice(&ip.octets());
+        }
+        HostName::Ip(net::IpAddr::V6(ip)) => {
+            request.push(ATYP_IPV6);
+            request.extend_from_slice(&ip.octets());
+        }
+        HostName::Dns(name) => domain(&mut request, name)?,
+        HostName::Tor(onion) => domain(&mut request, &onion.to_string())?,
+        other => return Err(Error::Address(other.to_string())),
+    }
+    request.extend_from_slice(&addr.port.to_be_bytes());
+
+    Ok(request)
+}
+
+/// Encode a domain name, to be resolved by the proxy.
+fn domain(request: &mut Vec<u8>, name: &str) -> Result<(), Error> {
+    let len = u8::try_from(name.len()).map_err(|_| Error::Address(name.to_owned()))?;
+
+    request.push(ATYP_DOMAIN);
+    request.push(len);
+    request.extend_from_slice(name.as_bytes());
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+
+    /// Minimal SOCKS5 proxy, accepting a single connection. The proxy checks the
+    /// handshake, replies with the given code, and on success, echoes data back.
+    fn stub(code: u8) -> (net::SocketAddr, thread::JoinHandle<(String, u16)>) {
+        let listener = net::TcpListener::bind((net::Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let addr = listener.local_addr().unwrap();
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut greeting = [0u8; 3];
+            stream.read_exact(&mut greeting).unwrap();
+            assert_eq!(greeting, [VERSION, 1, METHOD_NONE]);
+            stream.write_all(&[VERSION, METHOD_NONE]).unwrap();
+
+            let mut header = [0u8; 5];
+            stream.read_exact(&mut header).unwrap();
+            assert_eq!(header[..4], [VERSION, CMD_CONNECT, 0x00, ATYP_DOMAIN]);
+
+            let mut host = vec![0u8; header[4] as usize];
+            let mut port = [0u8; 2];
+            stream.read_exact(&mut host).unwrap();
+            stream.read_exact(&mut port).unwrap();
+            stream
+                .write_all(&[VERSION, code, 0x00, ATYP_IPV4, 127, 0, 0, 1, 0x22, 0x48])
+                .unwrap();
+
+            if code == 0x00 {
+                let mut buf = [0u8; 5];
+                stream.read_exact(&mut buf).unwrap();
+                stream.write_all(&buf).unwrap();
+            }
+            (String::from_utf8(host).unwrap(), u16::from_be_bytes(port))
+        });
+        (addr, handle)
+    }
+
+    fn peer() -> NetAddr<HostName> {
+        NetAddr::new(HostName::Dns(String::from("seed.radicle.example")), 8776)
+    }
+
+    #[test]
+    fn test_connect() {
+        let (proxy, stub) = stub(0x00);
+        let mut stream = connect(proxy, &peer(), DEFAULT_DIAL_TIMEOUT).unwrap();
+        let mut buf = [0u8; 5];
+
+        stream.write_all(b"hello").unwrap();
+        stream.read_exact(&mut buf).unwrap();
+
+        assert_eq!(&buf, b"hello");
+        assert_eq!(
+            stub.join().unwrap(),
+            (String::from("seed.radicle.example"), 8776)
+        );
+    }
+
+    #[test]
+    fn test_connect_refused() {
+        let (proxy, stub) = stub(0x05);
+        let err = connect(proxy, &peer(), DEFAULT_DIAL_TIMEOUT).unwrap_err();
+
+        assert!(matches!(
+            err,
+            Error::Peer {
+                reply: Reply::ConnectionRefused,
+                ..
+            }
+        ));
+        assert!(!err.is_proxy_err());
+        stub.join().unwrap();
+    }
+
+    #[test]
+    fn test_proxy_failure() {
+        let (proxy, stub) = stub(0x01);
+        let err = connect(proxy, &peer(), DEFAULT_DIAL_TIMEOUT).unwrap_err();
+
+        assert!(matches!(
+            err,
+            Error::Proxy {
+                reply: Reply::GeneralFailure,
+                ..
+            }
+        ));
+        assert!(err.is_proxy_err());
+        stub.join().unwrap();
+    }
+
+    #[test]
+    fn test_proxy_unreachable() {
+        let proxy = {
+            let listener = net::TcpListener::bind((net::Ipv4Addr::LOCALHOST, 0)).unwrap();
+            listener.local_addr().unwrap()
+        };
+        let err = connect(proxy, &peer(), DEFAULT_DIAL_TIMEOUT).unwrap_err();
+
+        assert!(matches!(err, Error::Unreachable { .. }));
+        assert!(err.is_proxy_err());
+    }
+
+    #[test]
+    fn test_request() {
+        let addr = NetAddr::new(HostName::Ip(net::Ipv4Addr::new(10, 0, 0, 1).into()), 8776);
+
+        assert_eq!(
+            request(&addr).unwrap(),
+            vec![
+                VERSION,
+                CMD_CONNECT,
+                0x00,
+                ATYP_IPV4,
+                10,
+                0,
+                0,
+                1,
+                0x22,
+                0x48
+            ]
+        );
+    }
+}
@@ -11,12 +11,12 @@ use std::path::PathBuf;
 use crossbeam_channel as chan;
 
 use radicle::identity::RepoId;
-use radicle::node::{notifications, Event};
+use radicle::node::{notifications, Event, RefClass};
 use radicle::prelude::NodeId;
 use radicle::storage::refs::RefsAt;
 use radicle::storage::{ReadRepository, ReadStorage};
-use radicle::{cob, crypto, Storage};
-use radicle_fetch::FetchLimit;
+use radicle::{cob, crypto, git, Storage};
+use radicle_fetch::{FetchLimit, FetchOptions};
 
 use crate::runtime::{thread, Emitter, Handle};
 use crate::service::policy;
@@ -65,6 +65,28 @@ impl FetchError {
     pub fn is_timeout(&self) -> bool {
         matches!(self, FetchError::Io(e) if e.kind() == io::ErrorKind::TimedOut)
     }
+
+    /// Check if the fetch failed because of the remote, eg. because it timed out, failed the
+    /// fetch protocol, or sent data that doesn't validate, rather than because of a local
+    /// error.
+    pub fn is_remote(&self) -> bool {
+        matches!(
+            self,
+            FetchError::CommandFailed { .. }
+                | FetchError::Io(_)
+                | FetchError::Fetch(
+                    fetch::error::Fetch::Run(_) | fetch::error::Fetch::Validation { .. }
+                )
+        )
+    }
+
+    /// Check if the fetch failed because the remote closed the stream before the handshake.
+    pub fn is_stream_closed(&self) -> bool {
+        matches!(
+            self,
+            FetchError::Fetch(fetch::error::Fetch::Run(e)) if e.is_stream_closed()
+        )
+    }
 }
 
 /// Error returned by fetch responder.
@@ -105,6 +127,10 @@ pub enum FetchRequest {
         remote: NodeId,
         /// If this fetch is for a particular set of `rad/sigrefs`.
         refs_at: Option<Vec<RefsAt>>,
+        /// The class of references to fetch.
+        refs: RefClass,
+        /// Channels waiting for the progress of this fetch.
+        progress: fetch::ProgressSubscribers,
     },
     /// Server is responding to a fetch request by uploading the
     /// specified `refspecs` sent by the client.
@@ -141,6 +167,16 @@ pub enum FetchResult {
     },
 }
 
+impl FetchResult {
+    /// Check whether the upload or fetch succeeded.
+    pub fn is_ok(&self) -> bool {
+        match self {
+            Self::Initiator { result, .. } => result.is_ok(),
+            Self::Responder { result, .. } => result.is_ok(),
+        }
+    }
+}
+
 /// Task to be accomplished on a worker thread.
 /// This is either going to be an outgoing or incoming fetch.
 pub struct Task {
@@ -155,6 +191,8 @@ pub struct TaskResult {
     pub remote: NodeId,
     pub result: FetchResult,
     pub stream: StreamId,
+    /// The stream's channels, if the stream can be reused for another fetch.
+    pub channels: Option<Channels>,
 }
 
 #[derive(Debug, Clone)]
@@ -166,6 +204,9 @@ pub struct FetchConfig {
     /// Configuration for `git gc` garbage collection. Defaults to `1
     /// hour ago`.
     pub expiry: garbage::Expiry,
+    /// Reference patterns that are not fetched, unless allowed by a
+    /// repository's reference policy.
+    pub blocked_refs: Vec<git::PatternString>,
 }
 
 /// A worker that replicates git objects.
@@ -198,8 +239,12 @@ impl Worker {
             stream,
         } = task;
         let remote = fetch.remote();
-        let channels = channels::ChannelsFlush::new(self.handle.clone(), channels, remote, stream);
-        let result = self._process(fetch, stream, channels, self.notifications.clone());
+        let flush =
+            channels::ChannelsFlush::new(self.handle.clone(), channels.clone(), remote, stream);
+        let result = self._process(fetch, stream, flush, self.notifications.clone());
+        // The git exchange is over once the upload or fetch succeeded, so the stream
+        // can be handed back for reuse.
+        let channels = result.is_ok().then_some(channels);
 
         log::trace!(target: "worker", "Sending response back to service..");
 
@@ -209,6 +254,7 @@ impl Worker {
                 remote,
                 stream,
                 result,
+                channels,
             })
             .is_err()
         {
@@ -228,9 +274,11 @@ impl Worker {
                 rid,
                 remote,
                 refs_at,
+                refs,
+                progress,
             } => {
                 log::debug!(target: "worker", "Worker processing outgoing fetch for {rid}");
-                let result = self.fetch(rid, remote, refs_at, channels, notifs);
+                let result = self.fetch(rid, remote, refs_at, refs, channels, notifs, progress);
                 FetchResult::Initiator { rid, result }
             }
             FetchRequest::Responder { remote, emitter } => {
@@ -300,38 +348,57 @@ impl Worker {
         rid: RepoId,
         remote: NodeId,
         refs_at: Option<Vec<RefsAt>>,
+        refs: RefClass,
         channels: channels::ChannelsFlush,
         notifs: notifications::StoreWriter,
+        progress: fetch::ProgressSubscribers,
     ) -> Result<fetch::FetchResult, FetchError> {
         let FetchConfig {
             limit,
             local,
             expiry,
+            blocked_refs,
         } = &self.fetch_config;
         // N.b. if the `rid` is blocked this will return an error, so
         // we won't continue with any further set up of the fetch.
         let allowed = radicle_fetch::Allowed::from_config(rid, &self.policies)?;
         let blocked = radicle_fetch::BlockList::from_config(&self.policies)?;
+        let filter =
+            radicle_fetch::RefsFilter::from_config(rid, &self.policies, blocked_refs.clone())?;
 
         let mut cache = self.cache.clone();
-        let handle = fetch::Handle::new(
+        let mut handle = fetch::Handle::new(
             rid,
             *local,
             &self.storage,
             allowed,
             blocked,
+            filter,
             channels,
             notifs,
         )?;
+        let reporter = fetch::Reporter::new(rid, remote, progress);
+        reporter.negotiating();
+        handle.on_progress({
+            let reporter = reporter.clone();
+            move |progress| reporter.receiving(progress)
+        });
+
         let result = handle.fetch(
             rid,
             &self.storage,
             &mut cache,
             &mut self.db,
-            *limit,
+            FetchOptions {
+                limit: *limit,
+                refs,
+            },
             remote,
             refs_at,
-        )?;
+        );
+        reporter.flush();
+
+        let result = result?;
 
         if let Err(e) = garbage::collect(&self.storage, rid, *expiry) {
             // N.b. ensure that `git gc` works in debug mode.
@@ -83,11 +83,21 @@ impl<T> fmt::Debug for ChannelEvent<T> {
 }
 
 /// Worker channels for communicating through the git stream with the remote.
+///
+/// Clones share the same underlying channels, which allows a stream to outlive the
+/// worker using it.
+#[derive(Clone)]
 pub struct Channels<T = Vec<u8>> {
     sender: ChannelWriter<T>,
     receiver: ChannelReader<T>,
 }
 
+impl<T> fmt::Debug for Channels<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Channels").finish_non_exhaustive()
+    }
+}
+
 impl<T: AsRef<[u8]>> Channels<T> {
     pub fn new(
         sender: chan::Sender<ChannelEvent<T>>,
@@ -114,6 +124,17 @@ impl<T: AsRef<[u8]>> Channels<T> {
         self.receiver.try_iter()
     }
 
+    /// Check whether there are no events waiting to be received.
+    pub fn is_empty(&self) -> bool {
+        self.receiver.is_empty()
+    }
+
+    /// Set the timeout for sending and receiving on these channels.
+    pub fn set_timeout(&mut self, timeout: time::Duration) {
+        self.sender.timeout = timeout;
+        self.receiver.timeout = timeout;
+    }
+
     pub fn send(&self, event: ChannelEvent<T>) -> io::Result<()> {
         self.sender.send(event)
     }
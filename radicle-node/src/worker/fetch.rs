@@ -1,13 +1,17 @@
 pub mod error;
 
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
 use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
+use crossbeam_channel as chan;
 use localtime::LocalTime;
 
 use radicle::cob::TypedId;
 use radicle::crypto::PublicKey;
 use radicle::identity::DocAt;
+use radicle::node::FetchProgress;
 use radicle::prelude::RepoId;
 use radicle::storage::refs::RefsAt;
 use radicle::storage::{
@@ -15,16 +19,131 @@ use radicle::storage::{
     WriteRepository as _,
 };
 use radicle::{cob, git, node, Storage};
-use radicle_fetch::{Allowed, BlockList, FetchLimit};
+use radicle_fetch::{Allowed, BlockList, FetchOptions, RefsFilter};
 
 use super::channels::ChannelsFlush;
 
+/// Minimum time between two fetch progress events.
+pub const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Channels waiting for the progress of a fetch.
+///
+/// Shared between the service, which subscribes the channels of the fetch requests, and
+/// the worker performing the fetch, which reports to them.
+#[derive(Debug, Clone, Default)]
+pub struct ProgressSubscribers(Arc<Mutex<Vec<chan::Sender<FetchProgress>>>>);
+
+impl ProgressSubscribers {
+    /// Add a subscriber to the fetch.
+    pub fn subscribe(&self, c: chan::Sender<FetchProgress>) {
+        // SAFETY: We deliberately propagate panics from other threads holding the lock.
+        #[allow(clippy::unwrap_used)]
+        let mut subscribers = self.0.lock().unwrap();
+
+        if !subscribers.iter().any(|s| s.same_channel(&c)) {
+            subscribers.push(c);
+        }
+    }
+
+    /// Send progress to all subscribers, dropping the ones that went away.
+    ///
+    /// Progress is best-effort: it is never waited on, and is dropped for subscribers
+    /// that are not keeping up.
+    fn send(&self, progress: FetchProgress) {
+        // SAFETY: We deliberately propagate panics from other threads holding the lock.
+        #[allow(clippy::unwrap_used)]
+        self.0
+            .lock()
+            .unwrap()
+            .retain(|c| match c.try_send(progress.clone()) {
+                Ok(()) | Err(chan::TrySendError::Full(_)) => true,
+                Err(chan::TrySendError::Disconnected(_)) => false,
+            });
+    }
+}
+
+/// Reports [`FetchProgress`] for a fetch of `rid` from `remote` to its subscribers.
+///
+/// Progress on receiving objects is reported at most once every
+/// [`PROGRESS_INTERVAL`]. Any progress held back is reported on [`Reporter::flush`].
+#[derive(Clone)]
+pub struct Reporter {
+    rid: RepoId,
+    remote: PublicKey,
+    subscribers: ProgressSubscribers,
+    state: Arc<Mutex<ReporterState>>,
+}
+
+#[derive(Default)]
+struct ReporterState {
+    last: Option<Instant>,
+    pending: Option<radicle_fetch::Progress>,
+}
+
+impl Reporter {
+    pub fn new(rid: RepoId, remote: PublicKey, subscribers: ProgressSubscribers) -> Self {
+        Self {
+            rid,
+            remote,
+            subscribers,
+            state: Arc::default(),
+        }
+    }
+
+    /// Report that we're negotiating with the remote.
+    pub fn negotiating(&self) {
+        self.subscribers
+            .send(FetchProgress::negotiating(self.rid, self.remote));
+    }
+
+    /// Report progress on receiving objects.
+    pub fn receiving(&self, progress: radicle_fetch::Progress) {
+        // SAFETY: We deliberately propagate panics from other threads holding the lock.
+        #[allow(clippy::unwrap_used)]
+        let mut state = self.state.lock().unwrap();
+
+        if state.last.is_some_and(|t| t.elapsed() < PROGRESS_INTERVAL) {
+            state.pending = Some(progress);
+            return;
+        }
+        state.last = Some(Instant::now());
+        state.pending = None;
+
+        self.emit(progress);
+    }
+
+    /// Emit any progress that was held back.
+    pub fn flush(&self) {
+        // SAFETY: We deliberately propagate panics from other threads holding the lock.
+        #[allow(clippy::unwrap_used)]
+        let pending = self.state.lock().unwrap().pending.take();
+
+        if let Some(progress) = pending {
+            self.emit(progress);
+        }
+    }
+
+    fn emit(&self, progress: radicle_fetch::Progress) {
+        self.subscribers.send(FetchProgress::receiving(
+            self.rid,
+            self.remote,
+            progress.objects,
+            progress.bytes,
+        ));
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct FetchResult {
     /// The set of updated references.
     pub updated: Vec<RefUpdate>,
     /// The set of remote namespaces that were updated.
     pub namespaces: HashSet<PublicKey>,
+    /// The set of remote namespaces whose stored references don't match their
+    /// `rad/sigrefs`, since some of the signed references were excluded from the fetch.
+    pub excluded: HashSet<PublicKey>,
+    /// The set of COBs whose references were updated.
+    pub cobs: BTreeSet<TypedId>,
     /// The fetch was a full clone.
     pub clone: bool,
     /// Identity doc of fetched repo.
@@ -36,6 +155,8 @@ impl FetchResult {
         Self {
             updated: vec![],
             namespaces: HashSet::new(),
+            excluded: HashSet::new(),
+            cobs: BTreeSet::new(),
             clone: false,
             doc,
         }
@@ -60,31 +181,44 @@ impl Handle {
         storage: &Storage,
         follow: Allowed,
         blocked: BlockList,
+        filter: RefsFilter,
         channels: ChannelsFlush,
         notifications: node::notifications::StoreWriter,
     ) -> Result<Self, error::Handle> {
         let exists = storage.contains(&rid)?;
         if exists {
             let repo = storage.repository(rid)?;
-            let handle = radicle_fetch::Handle::new(local, repo, follow, blocked, channels)?;
+            let handle =
+                radicle_fetch::Handle::new(local, repo, follow, blocked, filter, channels)?;
             Ok(Handle::Pull {
                 handle,
                 notifications,
             })
         } else {
             let (repo, tmp) = storage.lock_repository(rid)?;
-            let handle = radicle_fetch::Handle::new(local, repo, follow, blocked, channels)?;
+            let handle =
+                radicle_fetch::Handle::new(local, repo, follow, blocked, filter, channels)?;
             Ok(Handle::Clone { handle, tmp })
         }
     }
 
+    /// Call `callback` whenever progress is made receiving objects.
+    pub fn on_progress(
+        &mut self,
+        callback: impl Fn(radicle_fetch::Progress) + Send + Sync + 'static,
+    ) {
+        match self {
+            Self::Clone { handle, .. } | Self::Pull { handle, .. } => handle.on_progress(callback),
+        }
+    }
+
     pub fn fetch<D: node::refs::Store>(
         self,
         rid: RepoId,
         storage: &Storage,
         cache: &mut cob::cache::StoreWriter,
         refsdb: &mut D,
-        limit: FetchLimit,
+        options: FetchOptions,
         remote: PublicKey,
         refs_at: Option<Vec<RefsAt>>,
     ) -> Result<FetchResult, error::Fetch> {
@@ -93,7 +227,7 @@ impl Handle {
         let (result, clone, notifs) = match self {
             Self::Clone { mut handle, tmp } => {
                 log::debug!(target: "worker", "{} cloning from {remote}", handle.local());
-                let result = radicle_fetch::clone(&mut handle, limit, remote)?;
+                let result = radicle_fetch::clone(&mut handle, options, remote)?;
                 mv(tmp, storage, &rid)?;
                 (result, true, None)
             }
@@ -102,7 +236,7 @@ impl Handle {
                 notifications,
             } => {
                 log::debug!(target: "worker", "{} pulling from {remote}", handle.local());
-                let result = radicle_fetch::pull(&mut handle, limit, remote, refs_at)?;
+                let result = radicle_fetch::pull(&mut handle, options, remote, refs_at)?;
                 (result, false, Some(notifications))
             }
         };
@@ -110,12 +244,20 @@ impl Handle {
         for rejected in result.rejected() {
             log::warn!(target: "worker", "Rejected update for {}", rejected.refname())
         }
+        for (namespace, rollback) in result.rolled_back() {
+            log::warn!(
+                target: "worker",
+                "Ignored 'rad/sigrefs' {} of {namespace} from {remote}: {rollback:?}",
+                rollback.received(),
+            )
+        }
 
         match result {
             radicle_fetch::FetchResult::Failed {
                 threshold,
                 delegates,
                 validations,
+                ..
             } => {
                 for fail in validations.iter() {
                     log::error!(target: "worker", "Validation error: {}", fail);
@@ -129,10 +271,23 @@ impl Handle {
                 applied,
                 remotes,
                 validations,
+                skipped,
+                excluded,
+                ..
             } => {
                 for warn in validations {
                     log::warn!(target: "worker", "Validation error: {}", warn);
                 }
+                for (remote, refs) in skipped {
+                    for name in refs {
+                        log::debug!(target: "worker", "Skipped reference {name} of {remote}");
+                    }
+                }
+                for (remote, refs) in &excluded {
+                    for name in refs {
+                        log::debug!(target: "worker", "Excluded reference {name} of {remote}");
+                    }
+                }
 
                 // N.b. We do not go through handle for this since the cloning handle
                 // points to a repository that is temporary and gets moved by [`mv`].
@@ -163,12 +318,15 @@ impl Handle {
                     }
                 }
 
-                cache_cobs(&rid, &applied.updated, &repo, cache)?;
+                let cobs = changed_cobs(&applied.updated)?;
+                cache_cobs(&cobs, &repo, cache)?;
                 cache_refs(&rid, &applied.updated, refsdb)?;
 
                 Ok(FetchResult {
                     updated: applied.updated,
                     namespaces: remotes.into_iter().collect(),
+                    excluded: excluded.into_keys().collect(),
+                    cobs,
                     doc: repo.identity_doc()?,
                     clone,
                 })
@@ -290,89 +448,59 @@ where
     Ok(())
 }
 
-/// Write new `RefUpdate`s that are related a `Patch` or an `Issue`
-/// COB to the COB cache.
-fn cache_cobs<S, C>(
-    rid: &RepoId,
-    refs: &[RefUpdate],
-    storage: &S,
-    cache: &mut C,
-) -> Result<(), error::Cache>
-where
-    S: ReadRepository + cob::Store,
-    C: cob::cache::Update<cob::issue::Issue> + cob::cache::Update<cob::patch::Patch>,
-    C: cob::cache::Remove<cob::issue::Issue> + cob::cache::Remove<cob::patch::Patch>,
-{
-    let mut issues = cob::store::Store::<cob::issue::Issue, _>::open(storage)?;
-    let mut patches = cob::store::Store::<cob::patch::Patch, _>::open(storage)?;
+/// The COBs whose references were changed by the given `RefUpdate`s.
+///
+/// A COB shows up once, however many of its references were updated.
+fn changed_cobs(refs: &[RefUpdate]) -> Result<BTreeSet<TypedId>, error::Cache> {
+    let mut cobs = BTreeSet::new();
 
     for update in refs {
         match update {
             RefUpdate::Updated { name, .. }
             | RefUpdate::Created { name, .. }
-            | RefUpdate::Deleted { name, .. } => match name.to_namespaced() {
-                Some(name) => {
-                    let Some(identifier) = cob::TypedId::from_namespaced(&name)? else {
-                        continue;
-                    };
-                    if identifier.is_issue() {
-                        update_or_remove(&mut issues, cache, rid, identifier)?;
-                    } else if identifier.is_patch() {
-                        update_or_remove(&mut patches, cache, rid, identifier)?;
-                    } else {
-                        // Unknown COB, don't cache.
-                        continue;
-                    }
+            | RefUpdate::Deleted { name, .. } => {
+                let Some(name) = name.to_namespaced() else {
+                    continue;
+                };
+                if let Some(identifier) = cob::TypedId::from_namespaced(&name)? {
+                    cobs.insert(identifier);
                 }
-                None => continue,
-            },
+            }
             RefUpdate::Skipped { .. } => { /* Do nothing */ }
         }
     }
-
-    Ok(())
+    Ok(cobs)
 }
 
-/// Update or remove a cache entry.
-fn update_or_remove<R, C, T>(
-    store: &mut cob::store::Store<T, R>,
-    cache: &mut C,
-    rid: &RepoId,
-    tid: TypedId,
+/// Write the changed `Patch` and `Issue` COBs to the COB cache, leaving
+/// the other cached COBs of the repository untouched.
+fn cache_cobs<S>(
+    cobs: &BTreeSet<TypedId>,
+    storage: &S,
+    cache: &cob::cache::StoreWriter,
 ) -> Result<(), error::Cache>
 where
-    R: cob::Store + ReadRepository,
-    T: cob::Evaluate<R> + cob::store::Cob,
-    C: cob::cache::Update<T> + cob::cache::Remove<T>,
+    S: ReadRepository + cob::Store,
 {
-    match store.get(&tid.id) {
-        Ok(Some(obj)) => {
-            // Object loaded correctly, update cache.
-            return cache.update(rid, &tid.id, &obj).map(|_| ()).map_err(|e| {
-                error::Cache::Update {
-                    id: tid.id,
-                    type_name: tid.type_name,
-                    err: e.into(),
-                }
-            });
-        }
-        Ok(None) => {
-            // Object was not found. Fall-through.
-        }
-        Err(e) => {
-            // Object was found, but failed to load. Fall-through.
-            log::error!(target: "fetch", "Error loading COB {tid} from storage: {e}");
-        }
-    }
-    // The object has either been removed entirely from the repository,
-    // or it failed to load. So we also remove it from the cache.
-    cob::cache::Remove::<T>::remove(cache, &tid.id)
-        .map(|_| ())
-        .map_err(|e| error::Cache::Remove {
-            id: tid.id,
-            type_name: tid.type_name,
-            err: Box::new(e),
-        })?;
+    let issues = cobs
+        .iter()
+        .filter(|tid| tid.is_issue())
+        .map(|tid| tid.id)
+        .collect::<Vec<_>>();
+    let patches = cobs
+        .iter()
+        .filter(|tid| tid.is_patch())
+        .map(|tid| tid.id)
+        .collect::<Vec<_>>();
+    // Other COB types are not cached.
 
+    if !issues.is_empty() {
+        let store = cob::issue::Issues::open(storage)?;
+        cob::issue::Cache::new(store, cache.clone()).write_changed(&issues)?;
+    }
+    if !patches.is_empty() {
+        let store = cob::patch::Patches::open(storage)?;
+        cob::patch::Cache::new(store, cache.clone()).write_changed(&patches)?;
+    }
     Ok(())
 }
@@ -34,22 +34,12 @@ pub enum Cache {
     Parse(#[from] cob::ParseIdentifierError),
     #[error(transparent)]
     Repository(#[from] storage::RepositoryError),
-    #[error("failed to remove {type_name} '{id}' from cache: {err}")]
-    Remove {
-        id: cob::ObjectId,
-        type_name: cob::TypeName,
-        #[source]
-        err: Box<dyn std::error::Error + Send + Sync + 'static>,
-    },
     #[error(transparent)]
     Store(#[from] cob::store::Error),
-    #[error("failed to update {type_name} '{id}' in cache: {err}")]
-    Update {
-        id: cob::ObjectId,
-        type_name: cob::TypeName,
-        #[source]
-        err: Box<dyn std::error::Error + Send + Sync + 'static>,
-    },
+    #[error("failed to cache issues: {0}")]
+    Issue(#[from] cob::issue::Error),
+    #[error("failed to cache patches: {0}")]
+    Patch(#[from] cob::patch::Error),
 }
 
 #[derive(Debug, Error)]
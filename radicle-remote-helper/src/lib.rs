@@ -293,3 +293,21 @@ pub(crate) fn patches_mut<'a>(
         Err(err) => Err(err.into()),
     }
 }
+
+/// Get the mutable issue store.
+pub(crate) fn issues_mut<'a>(
+    profile: &Profile,
+    repo: &'a storage::git::Repository,
+) -> Result<
+    cob::issue::Cache<cob::issue::Issues<'a, storage::git::Repository>, cob::cache::StoreWriter>,
+    push::Error,
+> {
+    match profile.issues_mut(repo) {
+        Ok(issues) => Ok(issues),
+        Err(err @ profile::Error::CobsCache(cob::cache::Error::OutOfDate)) => {
+            hint(cli::cob::MIGRATION_HINT);
+            Err(err.into())
+        }
+        Err(err) => Err(err.into()),
+    }
+}
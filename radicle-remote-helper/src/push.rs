@@ -324,7 +324,9 @@ pub fn run(
                                 Err(e) => return Err(e.into()),
                             };
                         }
-                        push(src, &dst, *force, &nid, &working, stored, patches, &signer)
+                        push(
+                            src, &dst, *force, &nid, &working, stored, patches, &signer, profile,
+                        )
                     }
                 }
             }
@@ -568,6 +570,7 @@ fn patch_update<G: Signer>(
     Ok(Some(ExplorerResource::Patch { id: patch_id }))
 }
 
+#[allow(clippy::too_many_arguments)]
 fn push<G: Signer>(
     src: &git::RefStr,
     dst: &git::Qualified,
@@ -580,6 +583,7 @@ fn push<G: Signer>(
         cob::cache::StoreWriter,
     >,
     signer: &G,
+    profile: &Profile,
 ) -> Result<Option<ExplorerResource>, Error> {
     let head = match working.find_reference(src.as_str()) {
         Ok(obj) => obj.peel_to_commit()?,
@@ -616,7 +620,28 @@ fn push<G: Signer>(
                     &mut patches,
                     signer,
                 )?;
-                patch_merge_all(old.into(), head.into(), working, &mut patches, signer)?;
+                let merged =
+                    patch_merge_all(old.into(), head.into(), working, &mut patches, signer)?;
+
+                if !merged.is_empty() {
+                    let mut issues = crate::issues_mut(profile, stored)?;
+
+                    for (base, head) in merged {
+                        let closed =
+                            term::issue::close_referenced(base, head, stored, &mut issues, signer)?;
+
+                        for id in closed.issues {
+                            eprintln!(
+                                "{} Issue {} closed",
+                                term::format::positive("✓"),
+                                term::format::tertiary(term::format::cob(&id))
+                            );
+                        }
+                        for warning in closed.warnings {
+                            warn(warning);
+                        }
+                    }
+                }
             }
         }
     }
@@ -686,6 +711,7 @@ fn patch_revert_all<G: Signer>(
 }
 
 /// Merge all patches that have been included in the base branch.
+/// Returns the base and head of each merged revision.
 fn patch_merge_all<G: Signer>(
     old: git::Oid,
     new: git::Oid,
@@ -695,7 +721,7 @@ fn patch_merge_all<G: Signer>(
         cob::cache::StoreWriter,
     >,
     signer: &G,
-) -> Result<(), Error> {
+) -> Result<Vec<(git::Oid, git::Oid)>, Error> {
     let mut revwalk = working.revwalk()?;
     revwalk.push_range(&format!("{old}..{new}"))?;
 
@@ -704,8 +730,9 @@ fn patch_merge_all<G: Signer>(
         .map(|r| r.map(git::Oid::from))
         .collect::<Result<Vec<git::Oid>, _>>()?;
     if commits.is_empty() {
-        return Ok(());
+        return Ok(vec![]);
     }
+    let mut merged = Vec::new();
 
     let open = patches
         .opened()?
@@ -718,22 +745,25 @@ fn patch_merge_all<G: Signer>(
         let revisions = patch
             .revisions()
             .rev()
-            .map(|(id, r)| (id, r.head()))
+            .map(|(id, r)| (id, *r.base(), r.head()))
             .collect::<Vec<_>>();
 
         // Try to find a revision to merge. Favor revisions that match the more recent commits.
         // It's possible for more than one revision to be merged by this push, so we pick the
         // revision that is closest to the tip of the commit chain we're pushing.
         for commit in &commits {
-            if let Some((revision_id, head)) = revisions.iter().find(|(_, head)| commit == head) {
+            if let Some((revision_id, base, head)) =
+                revisions.iter().find(|(_, _, head)| commit == head)
+            {
                 let patch = patch::PatchMut::new(id, patch, patches);
                 patch_merge(patch, *revision_id, *head, working, signer)?;
+                merged.push((*base, *head));
 
                 break;
             }
         }
     }
-    Ok(())
+    Ok(merged)
 }
 
 fn patch_merge<C: cob::cache::Update<patch::Patch>, G: Signer>(
@@ -123,9 +123,7 @@ impl Cell for str {
     type Padded = String;
 
     fn width(&self) -> usize {
-        self.graphemes(true)
-            .map(|g| unicode::width(g) as usize)
-            .sum()
+        segments(self).map(|(_, width)| width).sum()
     }
 
     fn truncate(&self, width: usize, delim: &str) -> String {
@@ -139,19 +137,23 @@ impl Cell for str {
             // while being smaller than the given max width.
             let mut cols = 0; // Number of visual columns we need.
             let mut boundary = 0; // Boundary in bytes.
-            for g in self.graphemes(true) {
-                let c = Cell::width(g);
+            let mut styled = false; // Whether we kept any escape sequences.
+            for (segment, c) in segments(self) {
                 if cols + c + d > width {
                     break;
                 }
-                boundary += g.len();
+                boundary += segment.len();
                 cols += c;
+                styled |= segment.starts_with(ESCAPE);
             }
+            // Don't let the style of the truncated text bleed past it.
+            let reset = if styled { "\x1b[0m" } else { "" };
+
             // Don't add the delimiter if we just trimmed whitespace.
             if self[boundary..].trim().is_empty() {
-                self[..boundary + 1].to_owned()
+                format!("{}{reset}", &self[..boundary + 1])
             } else {
-                format!("{}{delim}", &self[..boundary])
+                format!("{}{delim}{reset}", &self[..boundary])
             }
         } else {
             self.to_owned()
@@ -207,11 +209,62 @@ impl<T: Cell + fmt::Display> Cell for Filled<T> {
     }
 }
 
+/// Start of an ANSI escape sequence.
+const ESCAPE: char = '\x1b';
+
+/// Split a string into graphemes and ANSI escape sequences, along with their display width.
+/// Escape sequences don't take up any space.
+fn segments(s: &str) -> impl Iterator<Item = (&str, usize)> {
+    let mut rest = s;
+
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let (segment, width) = match escape_len(rest) {
+            Some(len) => (&rest[..len], 0),
+            None => {
+                let g = rest.graphemes(true).next()?;
+                (g, unicode::width(g) as usize)
+            }
+        };
+        rest = &rest[segment.len()..];
+
+        Some((segment, width))
+    })
+}
+
+/// Length in bytes of the CSI escape sequence the string starts with, if any.
+fn escape_len(s: &str) -> Option<usize> {
+    let params = s.strip_prefix(ESCAPE)?.strip_prefix('[')?;
+    let end = params.bytes().position(|b| (0x40..=0x7e).contains(&b))?;
+
+    Some(2 + end + 1)
+}
+
 #[cfg(test)]
 mod test {
+    use super::Cell as _;
+
     #[test]
     fn test_width() {
         assert_eq!(unicode_display_width::width("❤️"), 2);
         assert_eq!(unicode_display_width::width("🪵"), 2);
     }
+
+    #[test]
+    fn test_width_ansi() {
+        assert_eq!("\x1b[31mred\x1b[0m".width(), 3);
+        assert_eq!("\x1b[1;32m🍍\x1b[0m!".width(), 3);
+        assert_eq!("\x1b".width(), 1);
+    }
+
+    #[test]
+    fn test_truncate_ansi() {
+        assert_eq!(
+            "\x1b[31mpineapple\x1b[0m".truncate(5, "…"),
+            "\x1b[31mpine…\x1b[0m"
+        );
+        assert_eq!("\x1b[31mred\x1b[0m".truncate(3, "…"), "\x1b[31mred\x1b[0m");
+    }
 }
@@ -16,6 +16,11 @@
 //! aphid       ladybug
 //! spider mite persimilis
 //! ```
+//!
+//! When a table doesn't fit in the terminal, its columns are truncated according to
+//! their [`Column`] options, lowest priority first.
+use std::cmp::Reverse;
+use std::env;
 use std::fmt;
 
 use crate::cell::Cell;
@@ -24,6 +29,10 @@ use crate::{Color, Constraint, Line, Paint, Size};
 
 pub use crate::Element;
 
+/// Environment variable that, when set, makes tables render as they would in a terminal,
+/// even when the output isn't one. See [`TableOptions::plain_if_piped`].
+pub const FORCE_TTY: &str = "RAD_FORCE_TTY";
+
 #[derive(Debug)]
 pub struct TableOptions {
     /// Whether the table should be allowed to overflow.
@@ -32,6 +41,9 @@ pub struct TableOptions {
     pub spacing: usize,
     /// Table border.
     pub border: Option<Color>,
+    /// Whether to print the table as tab-separated values when the output is not a
+    /// terminal, so that it can be processed by other tools.
+    pub plain_if_piped: bool,
 }
 
 impl Default for TableOptions {
@@ -40,6 +52,7 @@ impl Default for TableOptions {
             overflow: false,
             spacing: 1,
             border: None,
+            plain_if_piped: false,
         }
     }
 }
@@ -54,6 +67,39 @@ impl TableOptions {
     }
 }
 
+/// Sizing options of a table column.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Column {
+    /// Columns with a lower priority are truncated first.
+    pub priority: u8,
+    /// Width below which the column is not truncated.
+    pub min: usize,
+    /// Width above which the column is always truncated.
+    pub max: Option<usize>,
+}
+
+impl Column {
+    /// Create a column with the given truncation priority.
+    pub fn new(priority: u8) -> Self {
+        Self {
+            priority,
+            ..Self::default()
+        }
+    }
+
+    /// Set the minimum width of the column.
+    pub fn min(mut self, min: usize) -> Self {
+        self.min = min;
+        self
+    }
+
+    /// Set the maximum width of the column.
+    pub fn max(mut self, max: usize) -> Self {
+        self.max = Some(max);
+        self
+    }
+}
+
 #[derive(Debug)]
 enum Row<const W: usize, T> {
     Header([T; W]),
@@ -65,6 +111,7 @@ enum Row<const W: usize, T> {
 pub struct Table<const W: usize, T> {
     rows: Vec<Row<W, T>>,
     widths: [usize; W],
+    columns: [Column; W],
     opts: TableOptions,
 }
 
@@ -73,6 +120,7 @@ impl<const W: usize, T> Default for Table<W, T> {
         Self {
             rows: Vec::new(),
             widths: [0; W],
+            columns: [Column::default(); W],
             opts: TableOptions::default(),
         }
     }
@@ -91,6 +139,7 @@ where
         let border = self.opts.border;
         let inner = self.inner(parent);
         let cols = inner.cols;
+        let widths = self.widths(parent);
 
         // Don't print empty tables.
         if self.is_empty() {
@@ -118,13 +167,15 @@ where
                         let pad = if i == cells.len() - 1 {
                             0
                         } else {
-                            self.widths[i] + self.opts.spacing
+                            widths[i] + self.opts.spacing
                         };
-                        line = line.extend(
-                            cell.pad(pad)
-                                .into()
-                                .style(Style::default().bg(cell.background())),
-                        );
+                        let mut text: Line = cell.pad(0).into();
+                        if text.width() > widths[i] {
+                            Line::truncate(&mut text, widths[i], "…");
+                        }
+                        Line::pad(&mut text, pad);
+
+                        line = line.extend(text.style(Style::default().bg(cell.background())));
                     }
                     Line::pad(&mut line, cols);
                     Line::truncate(&mut line, cols, "…");
@@ -158,6 +209,31 @@ where
         }
         lines
     }
+
+    fn print(&self) {
+        let constraint = Constraint::from_env().or_else(|| {
+            env::var_os(FORCE_TTY)
+                .is_some()
+                .then_some(Constraint::UNBOUNDED)
+        });
+        match constraint {
+            Some(constraint) => {
+                for line in self.render(constraint) {
+                    println!("{}", line.to_string().trim_end());
+                }
+            }
+            None if self.opts.plain_if_piped => {
+                for line in self.plain() {
+                    println!("{line}");
+                }
+            }
+            None => {
+                for line in self.render(Constraint::UNBOUNDED) {
+                    println!("{}", line.to_string().trim_end());
+                }
+            }
+        }
+    }
 }
 
 impl<const W: usize, T: Cell> Table<W, T> {
@@ -165,10 +241,17 @@ impl<const W: usize, T: Cell> Table<W, T> {
         Self {
             rows: Vec::new(),
             widths: [0; W],
+            columns: [Column::default(); W],
             opts,
         }
     }
 
+    /// Set the sizing options of the table columns.
+    pub fn with_columns(mut self, columns: [Column; W]) -> Self {
+        self.columns = columns;
+        self
+    }
+
     pub fn size(&self, parent: Constraint) -> Size {
         self.outer(parent)
     }
@@ -201,6 +284,58 @@ impl<const W: usize, T: Cell> Table<W, T> {
         !self.rows.iter().any(|r| matches!(r, Row::Data { .. }))
     }
 
+    /// Render the table as tab-separated values, without borders or truncation.
+    ///
+    /// Columns with a blank header continue the column before them, eg. an author
+    /// followed by a "(you)" marker, and are joined with it.
+    pub fn plain(&self) -> Vec<String> {
+        let mut continued = [false; W];
+        if let Some(Row::Header(cells)) = self.rows.iter().find(|r| matches!(r, Row::Header(_))) {
+            for (i, cell) in cells.iter().enumerate().skip(1) {
+                continued[i] = cell.to_string().is_empty();
+            }
+        }
+        self.rows
+            .iter()
+            .filter_map(|row| match row {
+                Row::Header(cells) | Row::Data(cells) => {
+                    let mut line = String::new();
+
+                    for (i, cell) in cells.iter().enumerate() {
+                        let cell = cell.to_string();
+
+                        if continued[i] {
+                            if !cell.is_empty() {
+                                line.push(' ');
+                                line.push_str(&cell);
+                            }
+                            continue;
+                        }
+                        if i > 0 {
+                            line.push('\t');
+                        }
+                        line.push_str(&cell);
+                    }
+                    Some(line)
+                }
+                Row::Divider => None,
+            })
+            .collect()
+    }
+
+    /// Column widths, given the constraint. Columns are truncated if the table doesn't fit.
+    fn widths(&self, c: Constraint) -> [usize; W] {
+        let mut overhead = (W - 1) * self.opts.spacing;
+        if self.opts.border.is_some() {
+            overhead += 4;
+        }
+        distribute(
+            self.widths,
+            &self.columns,
+            c.max.cols.saturating_sub(overhead),
+        )
+    }
+
     fn inner(&self, c: Constraint) -> Size {
         let mut outer = self.outer(c);
 
@@ -212,7 +347,7 @@ impl<const W: usize, T: Cell> Table<W, T> {
     }
 
     fn outer(&self, c: Constraint) -> Size {
-        let mut cols = self.widths.iter().sum::<usize>() + (W - 1) * self.opts.spacing;
+        let mut cols = self.widths(c).iter().sum::<usize>() + (W - 1) * self.opts.spacing;
         let mut rows = self.rows.len();
         let padding = 2;
 
@@ -225,6 +360,37 @@ impl<const W: usize, T: Cell> Table<W, T> {
     }
 }
 
+/// Distribute the available width between columns of the given natural widths.
+///
+/// Columns are first capped to their maximum width. If they still don't fit, they are
+/// shrunk down to their minimum width, lowest priority first, and right to left among
+/// columns of the same priority. If even that isn't enough, the widths are returned as-is.
+fn distribute<const W: usize>(
+    mut widths: [usize; W],
+    columns: &[Column; W],
+    available: usize,
+) -> [usize; W] {
+    for (width, column) in widths.iter_mut().zip(columns) {
+        if let Some(max) = column.max {
+            *width = (*width).min(max);
+        }
+    }
+    let mut excess = widths.iter().sum::<usize>().saturating_sub(available);
+    let mut order = (0..W).collect::<Vec<_>>();
+    order.sort_by_key(|i| (columns[*i].priority, Reverse(*i)));
+
+    for i in order {
+        if excess == 0 {
+            break;
+        }
+        let shrink = widths[i].saturating_sub(columns[i].min).min(excess);
+
+        widths[i] -= shrink;
+        excess -= shrink;
+    }
+    widths
+}
+
 #[cfg(test)]
 mod test {
     use crate::Element;
@@ -446,4 +612,76 @@ mod test {
             ].join("")
         );
     }
+
+    #[test]
+    fn test_distribute() {
+        let columns = [Column::new(2), Column::new(0).min(4), Column::new(1)];
+
+        // Fits.
+        assert_eq!(distribute([4, 10, 6], &columns, 20), [4, 10, 6]);
+        // Lowest priority shrinks first.
+        assert_eq!(distribute([4, 10, 6], &columns, 16), [4, 6, 6]);
+        // Then the next, once the first reached its minimum.
+        assert_eq!(distribute([4, 10, 6], &columns, 12), [4, 4, 4]);
+        assert_eq!(distribute([4, 10, 6], &columns, 4), [0, 4, 0]);
+        // Minimums are never violated.
+        assert_eq!(distribute([4, 10, 6], &columns, 0), [0, 4, 0]);
+        // Columns of the same priority shrink right to left.
+        assert_eq!(
+            distribute([5, 5, 5], &[Column::default(); 3], 12),
+            [5, 5, 2]
+        );
+        // Maximums always apply.
+        assert_eq!(
+            distribute([4, 10, 6], &[Column::default().max(8); 3], usize::MAX),
+            [4, 8, 6]
+        );
+    }
+
+    #[test]
+    fn test_table_columns_truncate() {
+        let mut t = Table::new(TableOptions::default()).with_columns([
+            Column::new(1),
+            Column::new(0).min(2),
+            Column::new(1),
+        ]);
+        t.push(["8a3fe1c", "🍍 pineapple 🍍", "ok"]);
+        t.push(["f1b2f41", "🍎 apples", "ok"]);
+
+        #[rustfmt::skip]
+        assert_eq!(
+            t.display(Constraint::max(Size::new(20, usize::MAX))),
+            [
+                "8a3fe1c 🍍 pinea… ok\n",
+                "f1b2f41 🍎 apples ok\n"
+            ].join("")
+        );
+
+        // Wide characters that don't fit are dropped whole.
+        #[rustfmt::skip]
+        assert_eq!(
+            t.display(Constraint::max(Size::new(13, usize::MAX))),
+            [
+                "8a3fe1c …  ok\n",
+                "f1b2f41 …  ok\n"
+            ].join("")
+        );
+    }
+
+    #[test]
+    fn test_table_plain() {
+        let mut t = Table::new(TableOptions {
+            plain_if_piped: true,
+            ..TableOptions::bordered()
+        });
+        t.header(["Code", "Name", ""]);
+        t.divider();
+        t.push(["FR", "France", ""]);
+        t.push(["CH", "Switzerland", "(home)"]);
+
+        assert_eq!(
+            t.plain(),
+            ["Code\tName", "FR\tFrance", "CH\tSwitzerland (home)"]
+        );
+    }
 }
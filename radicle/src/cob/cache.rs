@@ -1,6 +1,6 @@
 mod migrations;
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::convert::Infallible;
 use std::fmt;
 use std::marker::PhantomData;
@@ -8,7 +8,7 @@ use std::path::Path;
 use std::sync::Arc;
 use std::time;
 
-use radicle_cob::ObjectId;
+use radicle_cob::{history::EntryId, ObjectId, Verified};
 use sqlite as sql;
 use thiserror::Error;
 
@@ -28,6 +28,7 @@ const DB_WRITE_TIMEOUT: time::Duration = time::Duration::from_secs(6);
 const MIGRATIONS: &[Migration] = &[
     Migration::Sql(include_str!("cache/migrations/1.sql")),
     Migration::Native(migrations::_2::run),
+    Migration::Sql(include_str!("cache/migrations/3.sql")),
 ];
 
 /// Function signature for native migrations.
@@ -269,6 +270,53 @@ impl<T> Store<T> {
     }
 }
 
+impl Verified for Store<Write> {
+    fn verified(&self, ids: &[EntryId]) -> BTreeSet<EntryId> {
+        let result = self
+            .db
+            .prepare("SELECT 1 FROM verified_entries WHERE oid = ?1")
+            .and_then(|mut stmt| {
+                let mut verified = BTreeSet::new();
+
+                for id in ids {
+                    stmt.reset()?;
+                    stmt.bind((1, id.to_string().as_str()))?;
+
+                    if stmt.next()? == sql::State::Row {
+                        verified.insert(*id);
+                    }
+                }
+                Ok(verified)
+            });
+
+        match result {
+            Ok(verified) => verified,
+            Err(e) => {
+                log::warn!(target: "db", "Failed to query verified entries: {e}");
+                BTreeSet::new()
+            }
+        }
+    }
+
+    fn insert(&mut self, ids: &[EntryId]) {
+        let result = transaction(&self.db, |db| {
+            let mut stmt =
+                db.prepare("INSERT OR IGNORE INTO verified_entries (oid) VALUES (?1)")?;
+
+            for id in ids {
+                stmt.reset()?;
+                stmt.bind((1, id.to_string().as_str()))?;
+                stmt.next()?;
+            }
+            Ok::<_, sql::Error>(())
+        });
+
+        if let Err(e) = result {
+            log::warn!(target: "db", "Failed to record verified entries: {e}");
+        }
+    }
+}
+
 /// Get the `user_version` value from the database header.
 pub fn version(db: &sql::Connection) -> Result<usize, Error> {
     let version = db
@@ -390,6 +438,14 @@ impl<T> Remove<T> for NoCache {
     }
 }
 
+impl Verified for NoCache {
+    fn verified(&self, _ids: &[EntryId]) -> BTreeSet<EntryId> {
+        BTreeSet::new()
+    }
+
+    fn insert(&mut self, _ids: &[EntryId]) {}
+}
+
 /// Track the progress of cache writes when transferring the
 /// repository COBs to their respective caches.
 ///
@@ -445,6 +501,7 @@ impl Progress {
 mod tests {
     use super::*;
     use crate::assert_matches;
+    use crate::test::arbitrary;
 
     #[test]
     fn test_check_version() {
@@ -466,10 +523,32 @@ mod tests {
         assert_eq!(db.migrate_to(2, migrate::ignore).unwrap(), 2); // 1 -> 2
         assert_eq!(db.version().unwrap(), 2);
 
-        assert_eq!(db.migrate_to(1, migrate::ignore).unwrap(), 2); // No-op.
-        assert_eq!(db.version().unwrap(), 2);
+        assert_eq!(db.migrate_to(3, migrate::ignore).unwrap(), 3); // 2 -> 3
+        assert_eq!(db.version().unwrap(), 3);
 
-        assert_eq!(db.migrate_to(99, migrate::ignore).unwrap(), 2); // No-op.
-        assert_eq!(db.version().unwrap(), 2);
+        assert_eq!(db.migrate_to(1, migrate::ignore).unwrap(), 3); // No-op.
+        assert_eq!(db.version().unwrap(), 3);
+
+        assert_eq!(db.migrate_to(99, migrate::ignore).unwrap(), 3); // No-op.
+        assert_eq!(db.version().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_verified_entries() {
+        let mut db = StoreWriter::memory()
+            .unwrap()
+            .with_migrations(migrate::ignore)
+            .unwrap();
+        let a = arbitrary::oid();
+        let b = arbitrary::oid();
+
+        assert_eq!(db.verified(&[a, b]), BTreeSet::new());
+
+        db.insert(&[a, a]);
+        assert_eq!(db.verified(&[a, b]), BTreeSet::from([a]));
+
+        db.insert(&[a, b]);
+        assert_eq!(db.verified(&[a, b]), BTreeSet::from([a, b]));
+        assert_eq!(db.verified(&[b]), BTreeSet::from([b]));
     }
 }
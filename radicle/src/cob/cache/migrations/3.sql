@@ -0,0 +1,5 @@
+-- Entries whose signatures were verified
+create table if not exists "verified_entries" (
+  -- Entry ID
+  "oid"           text      primary key not null
+) strict;
@@ -4,6 +4,7 @@ use std::collections::BTreeSet;
 use std::ops::Deref;
 use std::str::FromStr;
 
+use localtime::LocalTime;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -51,6 +52,9 @@ pub enum Error {
     /// Title is invalid.
     #[error("invalid title: {0:?}")]
     InvalidTitle(String),
+    /// Milestone is invalid.
+    #[error("invalid milestone: {0:?}")]
+    InvalidMilestone(String),
     /// The identity doc is missing.
     #[error("identity document missing")]
     MissingIdentity,
@@ -126,6 +130,25 @@ impl State {
     }
 }
 
+/// Commit message trailers that close an issue once the commit is merged,
+/// eg. `Closes: <issue-id>`.
+pub const CLOSING_TRAILERS: [&str; 2] = ["Closes", "Fixes"];
+
+/// Get the issues referenced by the closing trailers of a commit message.
+///
+/// References are returned as written, since they may be short issue ids that have yet to
+/// be resolved.
+pub fn closing_references(message: &str) -> Vec<String> {
+    let Ok(trailers) = git2::message_trailers_strs(message) else {
+        return vec![];
+    };
+    trailers
+        .iter()
+        .filter(|(key, _)| CLOSING_TRAILERS.iter().any(|t| t.eq_ignore_ascii_case(key)))
+        .map(|(_, value)| value.trim().to_owned())
+        .collect()
+}
+
 /// Issue state. Accumulates [`Action`].
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -140,6 +163,15 @@ pub struct Issue {
     pub(super) labels: BTreeSet<Label>,
     /// Discussion around this issue.
     pub(super) thread: Thread,
+    /// When the issue is due, if ever.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) due: Option<Timestamp>,
+    /// Milestone the issue is part of, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) milestone: Option<String>,
+    /// Issue this issue was closed as a duplicate of, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) duplicate_of: Option<IssueId>,
 }
 
 impl store::Cob for Issue {
@@ -243,6 +275,9 @@ impl Issue {
             state: State::default(),
             labels: BTreeSet::default(),
             thread,
+            due: None,
+            milestone: None,
+            duplicate_of: None,
         }
     }
 
@@ -262,6 +297,24 @@ impl Issue {
         self.labels.iter()
     }
 
+    pub fn due(&self) -> Option<&Timestamp> {
+        self.due.as_ref()
+    }
+
+    pub fn milestone(&self) -> Option<&str> {
+        self.milestone.as_deref()
+    }
+
+    /// Issue this issue was closed as a duplicate of, if any.
+    pub fn duplicate_of(&self) -> Option<&IssueId> {
+        self.duplicate_of.as_ref()
+    }
+
+    /// Whether the issue is still open past its due date.
+    pub fn is_overdue(&self, now: Timestamp) -> bool {
+        self.state == State::Open && self.due.is_some_and(|due| due < now)
+    }
+
     pub fn timestamp(&self) -> Timestamp {
         self.thread
             .comments()
@@ -271,6 +324,22 @@ impl Issue {
             .timestamp()
     }
 
+    /// Time the issue was opened.
+    pub fn created_at(&self) -> Timestamp {
+        self.timestamp()
+    }
+
+    /// Time of the latest activity in the issue's timeline, ie. the latest comment or
+    /// comment edit.
+    pub fn updated_at(&self) -> Timestamp {
+        self.thread
+            .timeline()
+            .filter_map(|id| self.thread.comment(id))
+            .flat_map(|c| c.edits().map(|e| e.timestamp))
+            .max()
+            .unwrap_or_else(|| self.timestamp())
+    }
+
     pub fn author(&self) -> Author {
         self.thread
             .comments()
@@ -341,7 +410,7 @@ impl Issue {
             // Issue authors can edit their own issues.
             Action::Edit { .. } => Authorization::from(*actor == author),
             // Issue authors can close or re-open their own issue.
-            Action::Lifecycle { state } => Authorization::from(match state {
+            Action::Lifecycle { state, .. } => Authorization::from(match state {
                 State::Closed { .. } => *actor == author,
                 State::Open => *actor == author,
             }),
@@ -370,6 +439,10 @@ impl Issue {
             }
             // All roles can react to a comment on an issue.
             Action::CommentReact { .. } => Authorization::Allow,
+            // Issue authors can schedule their own issues.
+            Action::Due { .. } | Action::Milestone { .. } => Authorization::from(*actor == author),
+            // Actions we don't know about are ignored.
+            Action::Unknown => Authorization::Allow,
         };
         Ok(outcome)
     }
@@ -416,8 +489,15 @@ impl Issue {
                 }
                 self.title = title;
             }
-            Action::Lifecycle { state } => {
+            Action::Lifecycle {
+                state,
+                duplicate_of,
+            } => {
                 self.state = state;
+                self.duplicate_of = match state {
+                    State::Closed { .. } => duplicate_of,
+                    State::Open => None,
+                };
             }
             Action::Label { labels } => {
                 self.labels = BTreeSet::from_iter(labels);
@@ -455,6 +535,20 @@ impl Issue {
             } => {
                 thread::react(&mut self.thread, entry, author, id, reaction, active)?;
             }
+            Action::Due { due } => {
+                self.due = due.map(Timestamp::from);
+            }
+            Action::Milestone { milestone } => {
+                if let Some(m) = &milestone {
+                    if m.is_empty() || m.contains('\n') || m.contains('\r') {
+                        return Err(Error::InvalidMilestone(m.clone()));
+                    }
+                }
+                self.milestone = milestone;
+            }
+            Action::Unknown => {
+                log::debug!(target: "issue", "Ignoring unknown action in {entry}");
+            }
         }
         Ok(())
     }
@@ -511,7 +605,22 @@ impl<R: ReadRepository> store::Transaction<Issue, R> {
 
     /// Lifecycle an issue.
     pub fn lifecycle(&mut self, state: State) -> Result<(), store::Error> {
-        self.push(Action::Lifecycle { state })
+        self.push(Action::Lifecycle {
+            state,
+            duplicate_of: None,
+        })
+    }
+
+    /// Close an issue, optionally as a duplicate of another issue.
+    pub fn close(
+        &mut self,
+        reason: CloseReason,
+        duplicate_of: Option<IssueId>,
+    ) -> Result<(), store::Error> {
+        self.push(Action::Lifecycle {
+            state: State::Closed { reason },
+            duplicate_of,
+        })
     }
 
     /// Comment on an issue.
@@ -550,6 +659,18 @@ impl<R: ReadRepository> store::Transaction<Issue, R> {
         })
     }
 
+    /// Set or clear the issue due date.
+    pub fn due(&mut self, due: Option<Timestamp>) -> Result<(), store::Error> {
+        self.push(Action::Due {
+            due: due.map(LocalTime::from),
+        })
+    }
+
+    /// Set or clear the issue milestone.
+    pub fn milestone(&mut self, milestone: Option<String>) -> Result<(), store::Error> {
+        self.push(Action::Milestone { milestone })
+    }
+
     ////////////////////////////////////////////////////////////////////////////////////////////////
 
     /// Create the issue thread.
@@ -638,6 +759,34 @@ where
         self.transaction("Lifecycle", signer, |tx| tx.lifecycle(state))
     }
 
+    /// Close an issue, optionally as a duplicate of another issue.
+    pub fn close<G: Signer>(
+        &mut self,
+        reason: CloseReason,
+        duplicate_of: Option<IssueId>,
+        signer: &G,
+    ) -> Result<EntryId, Error> {
+        self.transaction("Close", signer, |tx| tx.close(reason, duplicate_of))
+    }
+
+    /// Set or clear the issue due date.
+    pub fn set_due<G: Signer>(
+        &mut self,
+        due: Option<Timestamp>,
+        signer: &G,
+    ) -> Result<EntryId, Error> {
+        self.transaction("Set due date", signer, |tx| tx.due(due))
+    }
+
+    /// Set or clear the issue milestone.
+    pub fn set_milestone<G: Signer>(
+        &mut self,
+        milestone: Option<String>,
+        signer: &G,
+    ) -> Result<EntryId, Error> {
+        self.transaction("Set milestone", signer, |tx| tx.milestone(milestone))
+    }
+
     /// Comment on an issue.
     pub fn comment<G: Signer, S: ToString>(
         &mut self,
@@ -886,8 +1035,14 @@ pub enum Action {
     Edit { title: String },
 
     /// Transition to a different state.
+    #[serde(rename_all = "camelCase")]
     #[serde(rename = "lifecycle")]
-    Lifecycle { state: State },
+    Lifecycle {
+        state: State,
+        /// Issue this issue is closed as a duplicate of.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        duplicate_of: Option<IssueId>,
+    },
 
     /// Modify issue labels.
     #[serde(rename = "label")]
@@ -931,6 +1086,23 @@ pub enum Action {
         reaction: Reaction,
         active: bool,
     },
+
+    /// Set or clear the issue due date.
+    #[serde(rename = "due")]
+    Due {
+        /// Due date, in seconds since Epoch.
+        #[serde(with = "crate::serde_ext::localtime::option::time")]
+        due: Option<LocalTime>,
+    },
+
+    /// Set or clear the issue milestone.
+    #[serde(rename = "milestone")]
+    Milestone { milestone: Option<String> },
+
+    /// An action that was introduced by a newer version of the issue COB.
+    /// These are ignored, so that issues using them can still be loaded.
+    #[serde(other, skip_serializing)]
+    Unknown,
 }
 
 impl CobAction for Action {}
@@ -938,6 +1110,8 @@ impl CobAction for Action {}
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod test {
+    use std::collections::BTreeMap;
+
     use pretty_assertions::assert_eq;
 
     use super::*;
@@ -1226,6 +1400,125 @@ mod test {
         assert_eq!(r, "Sorry typo");
     }
 
+    #[test]
+    fn test_issue_due_and_milestone() {
+        let test::setup::NodeWithRepo { node, repo, .. } = test::setup::NodeWithRepo::default();
+        let mut issues = Cache::no_cache(&*repo).unwrap();
+        let mut issue = issues
+            .create(
+                "My first issue",
+                "Blah blah blah.",
+                &[],
+                &[],
+                [],
+                &node.signer,
+            )
+            .unwrap();
+        let due = Timestamp::from_secs(1_700_000_000);
+
+        assert_eq!(issue.due(), None);
+        assert_eq!(issue.milestone(), None);
+
+        issue.set_due(Some(due), &node.signer).unwrap();
+        issue
+            .set_milestone(Some("v1.0".to_owned()), &node.signer)
+            .unwrap();
+
+        let id = issue.id;
+        let mut issue = issues.get_mut(&id).unwrap();
+        assert_eq!(issue.due(), Some(&due));
+        assert_eq!(issue.milestone(), Some("v1.0"));
+        assert!(issue.is_overdue(Timestamp::from_secs(1_700_000_001)));
+        assert!(!issue.is_overdue(Timestamp::from_secs(1_600_000_000)));
+
+        issue
+            .set_milestone(Some("v1.1".to_owned()), &node.signer)
+            .unwrap();
+        issue
+            .set_milestone(Some("".to_owned()), &node.signer)
+            .unwrap_err();
+        issue
+            .lifecycle(
+                State::Closed {
+                    reason: CloseReason::Solved,
+                },
+                &node.signer,
+            )
+            .unwrap();
+        assert!(!issue.is_overdue(Timestamp::from_secs(1_700_000_001)));
+
+        issue.set_due(None, &node.signer).unwrap();
+        let issue = issues.get(&id).unwrap().unwrap();
+        assert_eq!(issue.due(), None);
+        assert_eq!(issue.milestone(), Some("v1.1"));
+
+        // Issues without scheduling information don't serialize these fields.
+        let value = serde_json::to_value(Issue::new(issue.thread.clone())).unwrap();
+        assert!(value.get("due").is_none());
+        assert!(value.get("milestone").is_none());
+        assert_eq!(serde_json::from_value::<Issue>(value).unwrap().due(), None);
+    }
+
+    #[test]
+    fn test_issue_unknown_action() {
+        use cob::change::Storage as _;
+        use cob::object::Storage as _;
+        use nonempty::NonEmpty;
+
+        let test::setup::NodeWithRepo { node, repo, .. } = test::setup::NodeWithRepo::default();
+        let identity = repo.identity().unwrap().head();
+        let type_name = Issue::type_name().clone();
+        let mut issues = Cache::no_cache(&*repo).unwrap();
+        let mut issue = issues
+            .create(
+                "My first issue",
+                "Blah blah blah.",
+                &[],
+                &[],
+                [],
+                &node.signer,
+            )
+            .unwrap();
+
+        // An action from a newer client, which we don't know about, alongside one we do.
+        let unknown = serde_json::json!({ "type": "priority", "priority": "high" });
+        let contents = NonEmpty::from_vec(vec![
+            cob::store::encoding::encode(unknown).unwrap(),
+            cob::store::encoding::encode(Action::Edit {
+                title: String::from("Renamed"),
+            })
+            .unwrap(),
+        ])
+        .unwrap();
+        let entry = repo
+            .store(
+                Some(identity),
+                vec![],
+                &node.signer,
+                cob::change::Template {
+                    tips: vec![*issue.id],
+                    embeds: vec![],
+                    contents,
+                    type_name: type_name.clone(),
+                    message: String::from("Set priority"),
+                },
+            )
+            .unwrap();
+        repo.update(node.signer.public_key(), &type_name, &issue.id, &entry.id)
+            .unwrap();
+
+        // The operation is not rejected: the known action is applied, and the unknown one is
+        // ignored.
+        let cob = cob::get::<Issue, _>(&*repo, &type_name, issue.id())
+            .unwrap()
+            .unwrap();
+        assert_eq!(cob.history.len(), 2);
+        assert_eq!(cob.object.title(), "Renamed");
+
+        issue.reload().unwrap();
+        assert_eq!(issue.title(), "Renamed");
+    }
+
     #[test]
     fn test_issue_edit_description() {
         let test::setup::NodeWithRepo { node, repo, .. } = test::setup::NodeWithRepo::default();
@@ -1278,8 +1571,50 @@ mod test {
         let authors = reactions.get(&reaction).unwrap();
 
         assert_eq!(authors.first().unwrap(), &node.signer.public_key());
+        assert_eq!(
+            issue.comment(&comment).unwrap().reaction_counts(),
+            BTreeMap::from_iter([(&reaction, 1)])
+        );
 
-        // TODO: Test multiple reactions from same author and different authors
+        // Reacting twice with the same reaction is only counted once, and
+        // removing a reaction decrements its count.
+        let mut issue = issues.get_mut(&id).unwrap();
+        issue.react(comment, reaction, true, &node.signer).unwrap();
+        assert_eq!(
+            issue.thread().comment(&comment).unwrap().reaction_counts(),
+            BTreeMap::from_iter([(&reaction, 1)])
+        );
+        issue.react(comment, reaction, false, &node.signer).unwrap();
+        assert!(issue
+            .thread()
+            .comment(&comment)
+            .unwrap()
+            .reaction_counts()
+            .is_empty());
+        issue.react(comment, reaction, true, &node.signer).unwrap();
+
+        // Counts survive serialization, and are derived from the reactions
+        // when missing.
+        let comment = issue.thread().comment(&comment).unwrap().clone();
+        let mut json = serde_json::to_value(&comment).unwrap();
+        let counts = json
+            .as_object_mut()
+            .unwrap()
+            .remove("reactionCounts")
+            .unwrap();
+        let migrated = serde_json::from_value::<Comment>(json.clone()).unwrap();
+
+        assert_eq!(
+            serde_json::from_value::<Comment>(serde_json::to_value(&comment).unwrap()).unwrap(),
+            comment
+        );
+        assert_eq!(migrated.reaction_counts(), comment.reaction_counts());
+        assert_eq!(migrated.reactions(), comment.reactions());
+
+        json.as_object_mut()
+            .unwrap()
+            .insert("reactionCounts".to_owned(), counts);
+        assert_eq!(serde_json::from_value::<Comment>(json).unwrap(), comment);
     }
 
     #[test]
@@ -1374,6 +1709,38 @@ mod test {
         assert!(labels.contains(&wontfix_label));
     }
 
+    #[test]
+    fn test_issue_updated_at() {
+        let test::setup::NodeWithRepo { node, repo, .. } = test::setup::NodeWithRepo::default();
+        let mut issues = Cache::no_cache(&*repo).unwrap();
+        let mut issue = issues
+            .create(
+                "My first issue",
+                "Blah blah blah.",
+                &[],
+                &[],
+                [],
+                &node.signer,
+            )
+            .unwrap();
+
+        assert_eq!(issue.created_at(), issue.timestamp());
+        assert_eq!(issue.updated_at(), issue.created_at());
+
+        let (root, _) = issue.root();
+        let root = *root;
+        let comment = issue
+            .comment("Ho ho ho.", root, vec![], &node.signer)
+            .unwrap();
+
+        let id = issue.id;
+        let issue = issues.get(&id).unwrap().unwrap();
+        let comment = issue.thread().comment(&comment).unwrap();
+
+        assert_eq!(issue.updated_at(), comment.timestamp());
+        assert!(issue.updated_at() >= issue.created_at());
+    }
+
     #[test]
     fn test_issue_comment() {
         let test::setup::NodeWithRepo { node, repo, .. } = test::setup::NodeWithRepo::default();
@@ -1447,6 +1814,54 @@ mod test {
         issue.redact_comment(*issue.id, &node.signer).unwrap_err();
     }
 
+    #[test]
+    fn test_closing_references() {
+        let message = "Fix the frobnicator\n\
+            \n\
+            It was broken.\n\
+            \n\
+            Closes: d87dcfe8c2b3200e78b128d9b959cfdf7063fefe\n\
+            Signed-off-by: Alice <alice@radicle.xyz>\n\
+            fixes: 2e8c1bf\n";
+
+        assert_eq!(
+            closing_references(message),
+            vec![
+                "d87dcfe8c2b3200e78b128d9b959cfdf7063fefe".to_owned(),
+                "2e8c1bf".to_owned()
+            ]
+        );
+        assert!(closing_references("Closes: 2e8c1bf").is_empty());
+        assert!(closing_references("Fix it\n\nCloses the gap.").is_empty());
+    }
+
+    #[test]
+    fn test_issue_close_duplicate() {
+        let test::setup::NodeWithRepo { node, repo, .. } = test::setup::NodeWithRepo::default();
+        let mut issues = Cache::no_cache(&*repo).unwrap();
+        let original = issues
+            .create("Original", "Blah", &[], &[], [], &node.signer)
+            .unwrap();
+        let original = original.id;
+        let mut issue = issues
+            .create("Duplicate", "Blah", &[], &[], [], &node.signer)
+            .unwrap();
+
+        issue
+            .close(CloseReason::Other, Some(original), &node.signer)
+            .unwrap();
+        assert_eq!(
+            *issue.state(),
+            State::Closed {
+                reason: CloseReason::Other
+            }
+        );
+        assert_eq!(issue.duplicate_of(), Some(&original));
+
+        issue.lifecycle(State::Open, &node.signer).unwrap();
+        assert_eq!(issue.duplicate_of(), None);
+    }
+
     #[test]
     fn test_issue_state_serde() {
         assert_eq!(
@@ -1477,6 +1892,11 @@ mod test {
             .create("Third", "Blah", &[], &[], [], &node.signer)
             .unwrap();
 
+        assert_eq!(
+            crate::cob::Store::object_count(&*repo, &TYPENAME).unwrap(),
+            3
+        );
+
         let issues = issues
             .list()
             .unwrap()
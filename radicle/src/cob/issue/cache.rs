@@ -135,6 +135,42 @@ impl<'a, R, C> Cache<super::Issues<'a, R>, C> {
         Ok(())
     }
 
+    /// Read the issues with the given `ids` from the [`super::Issues`] store and
+    /// write them to the `cache`, leaving all other cached issues untouched.
+    ///
+    /// Issues that no longer exist in storage, or that fail to load, are removed
+    /// from the `cache`.
+    pub fn write_changed(&mut self, ids: &[IssueId]) -> Result<(), super::Error>
+    where
+        R: ReadRepository + cob::Store,
+        C: Update<Issue> + Remove<Issue>,
+    {
+        for id in ids {
+            match self.store.get(id) {
+                Ok(Some(issue)) => {
+                    self.update(&self.rid(), id, &issue).map_err(|e| {
+                        super::Error::CacheUpdate {
+                            id: *id,
+                            err: e.into(),
+                        }
+                    })?;
+                    continue;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    log::error!(target: "cob", "Error loading issue {id} from storage: {e}");
+                }
+            }
+            self.cache
+                .remove(id)
+                .map_err(|e| super::Error::CacheRemove {
+                    id: *id,
+                    err: e.into(),
+                })?;
+        }
+        Ok(())
+    }
+
     /// Read all the issues from the [`super::Issues`] store and
     /// writing them to `cache`.
     ///
@@ -147,16 +183,19 @@ impl<'a, R, C> Cache<super::Issues<'a, R>, C> {
     ) -> Result<(), super::Error>
     where
         R: ReadRepository + cob::Store,
-        C: Update<Issue> + Remove<Issue>,
+        C: Update<Issue> + Remove<Issue> + cob::Verified,
     {
         // Start by clearing the cache. This will get rid of issues that are cached but
         // no longer exist in storage.
         self.remove_all(&self.rid())
             .map_err(|e| super::Error::CacheRemoveAll { err: e.into() })?;
 
-        let issues = self.store.all()?;
+        // Entries verified in a previous run don't need to be verified again.
+        let issues = self
+            .store
+            .all_with(cob::Verification::CachedOk(&mut self.cache))?;
         let mut progress = cache::Progress::new(issues.len());
-        for issue in self.store.all()? {
+        for issue in issues {
             progress.inc();
             match on_issue(&issue, &progress) {
                 ControlFlow::Continue(()) => match issue {
@@ -537,6 +576,7 @@ mod tests {
     use crate::cob::migrate;
     use crate::cob::thread::Thread;
     use crate::issue::{CloseReason, Issue, IssueCounts, IssueId, State};
+    use crate::test;
     use crate::test::arbitrary;
     use crate::test::storage::MockRepository;
 
@@ -699,4 +739,47 @@ mod tests {
             assert_eq!(None, cache.get(id).unwrap());
         }
     }
+
+    #[test]
+    fn test_write_changed() {
+        let test::setup::NodeWithRepo { node, repo, .. } = test::setup::NodeWithRepo::default();
+        let db = Store::<Write>::memory()
+            .unwrap()
+            .with_migrations(migrate::ignore)
+            .unwrap();
+        let mut cache = Cache::new(crate::issue::Issues::open(&*repo).unwrap(), db);
+        let a = *cache
+            .create("A", "", &[], &[], [], &node.signer)
+            .unwrap()
+            .id();
+        let b = *cache
+            .create("B", "", &[], &[], [], &node.signer)
+            .unwrap()
+            .id();
+        let cached_b = cache.get(&b).unwrap().unwrap();
+
+        // Change both issues in storage, without going through the cache.
+        let mut issues = Cache::no_cache(&*repo).unwrap();
+        issues
+            .get_mut(&a)
+            .unwrap()
+            .edit("A2", &node.signer)
+            .unwrap();
+        issues
+            .get_mut(&b)
+            .unwrap()
+            .edit("B2", &node.signer)
+            .unwrap();
+
+        // Only the issue that we say changed is re-evaluated.
+        cache.write_changed(&[a]).unwrap();
+        assert_eq!(cache.get(&a).unwrap().unwrap().title(), "A2");
+        assert_eq!(cache.get(&b).unwrap().unwrap(), cached_b);
+
+        // Issues missing from storage are removed from the cache.
+        issues.remove(&a, &node.signer).unwrap();
+        cache.write_changed(&[a]).unwrap();
+        assert_eq!(cache.get(&a).unwrap(), None);
+        assert_eq!(cache.get(&b).unwrap().unwrap(), cached_b);
+    }
 }
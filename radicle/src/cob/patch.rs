@@ -1,4 +1,5 @@
 pub mod cache;
+pub mod timeline;
 
 use std::collections::btree_map;
 use std::collections::{BTreeMap, BTreeSet, HashMap};
@@ -29,6 +30,7 @@ use crate::prelude::*;
 use crate::storage;
 
 pub use cache::Cache;
+pub use timeline::{Activity, Event};
 
 /// Type name of a patch.
 pub static TYPENAME: Lazy<TypeName> =
@@ -233,6 +235,17 @@ pub enum Action {
     ReviewCommentResolve { review: ReviewId, comment: EntryId },
     #[serde(rename = "review.comment.unresolve")]
     ReviewCommentUnresolve { review: ReviewId, comment: EntryId },
+    /// Request reviews of a revision, or withdraw review requests.
+    #[serde(rename = "review.request")]
+    ReviewRequest {
+        revision: RevisionId,
+        /// Users to request a review from.
+        #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+        add: BTreeSet<Did>,
+        /// Users to withdraw the review request from.
+        #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+        remove: BTreeSet<Did>,
+    },
 
     //
     // Revision actions
@@ -700,6 +713,15 @@ impl Patch {
                 // Redacted.
                 Authorization::Unknown
             }
+            // The patch and revision authors can request reviews.
+            Action::ReviewRequest { revision, .. } => {
+                if let Some(revision) = lookup::revision(self, revision)? {
+                    Authorization::from(actor == author || actor == revision.author.public_key())
+                } else {
+                    // Redacted.
+                    Authorization::Unknown
+                }
+            }
             // Anyone can propose revisions.
             Action::Revision { .. } => Authorization::Allow,
             // Only the revision author can edit or redact their revision.
@@ -862,6 +884,21 @@ impl Patch {
                     }
                 }
             }
+            Action::ReviewRequest {
+                revision,
+                add,
+                remove,
+            } => {
+                // Nb. Each request only touches the reviewers it names, so concurrent
+                // requests for different reviewers are all kept. For the same reviewer,
+                // the request applied last wins.
+                if let Some(revision) = lookup::revision_mut(self, &revision)? {
+                    for did in remove {
+                        revision.reviewers.remove(&did);
+                    }
+                    revision.reviewers.extend(add);
+                }
+            }
             Action::RevisionRedact { revision } => {
                 // Not allowed to delete the root revision.
                 let (root, _) = self.root();
@@ -1384,6 +1421,9 @@ pub struct Revision {
         deserialize_with = "ser::deserialize_reactions"
     )]
     pub(super) reactions: BTreeMap<Option<CodeLocation>, Reactions>,
+    /// Users whose review of this revision was requested.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub(super) reviewers: BTreeSet<Did>,
 }
 
 impl Revision {
@@ -1409,6 +1449,7 @@ impl Revision {
             timestamp,
             resolves,
             reactions: Default::default(),
+            reviewers: BTreeSet::default(),
         }
     }
 
@@ -1476,6 +1517,40 @@ impl Revision {
     pub fn review_by(&self, author: &ActorId) -> Option<&Review> {
         self.reviews.get(author)
     }
+
+    /// Users whose review of this revision was requested, and the status of their review.
+    pub fn reviewers(&self) -> impl Iterator<Item = (&Did, ReviewStatus)> {
+        self.reviewers.iter().map(|did| {
+            let status = match self.reviews.get(did.as_key()).and_then(|r| r.verdict()) {
+                Some(Verdict::Accept) => ReviewStatus::Accepted,
+                Some(Verdict::Reject) => ReviewStatus::Rejected,
+                None => ReviewStatus::Pending,
+            };
+            (did, status)
+        })
+    }
+}
+
+/// Status of a requested review.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReviewStatus {
+    /// The reviewer hasn't given a verdict yet.
+    Pending,
+    /// The reviewer accepted the revision.
+    Accepted,
+    /// The reviewer rejected the revision.
+    Rejected,
+}
+
+impl fmt::Display for ReviewStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pending => write!(f, "pending"),
+            Self::Accepted => write!(f, "accepted"),
+            Self::Rejected => write!(f, "rejected"),
+        }
+    }
 }
 
 /// Patch state.
@@ -1931,6 +2006,20 @@ impl<R: ReadRepository> store::Transaction<Patch, R> {
         self.push(Action::Assign { assignees })
     }
 
+    /// Request reviews of a revision, or withdraw review requests.
+    pub fn request_review(
+        &mut self,
+        revision: RevisionId,
+        add: BTreeSet<Did>,
+        remove: BTreeSet<Did>,
+    ) -> Result<(), store::Error> {
+        self.push(Action::ReviewRequest {
+            revision,
+            add,
+            remove,
+        })
+    }
+
     /// Label a patch.
     pub fn label(&mut self, labels: impl IntoIterator<Item = Label>) -> Result<(), store::Error> {
         self.push(Action::Label {
@@ -2288,6 +2377,19 @@ where
         self.transaction("Assign", signer, |tx| tx.assign(assignees))
     }
 
+    /// Request reviews of a revision, or withdraw review requests.
+    pub fn request_review<G: Signer>(
+        &mut self,
+        revision: RevisionId,
+        add: BTreeSet<Did>,
+        remove: BTreeSet<Did>,
+        signer: &G,
+    ) -> Result<EntryId, Error> {
+        self.transaction("Request review", signer, |tx| {
+            tx.request_review(revision, add, remove)
+        })
+    }
+
     /// Archive a patch.
     pub fn archive<G: Signer>(&mut self, signer: &G) -> Result<bool, Error> {
         self.lifecycle(Lifecycle::Archived, signer)?;
@@ -2457,6 +2559,22 @@ where
         self.raw.get(id)
     }
 
+    /// Get the timeline of a patch, ie. its events in chronological order.
+    pub fn timeline(&self, id: &PatchId) -> Result<Vec<Event>, Error> {
+        let Some(cob) = cob::get::<NonEmpty<cob::Entry>, R>(self.as_ref(), &TYPENAME, id)
+            .map_err(store::Error::from)?
+        else {
+            return Err(store::Error::NotFound(TYPENAME.clone(), *id).into());
+        };
+        let ops = cob
+            .object
+            .iter()
+            .map(Op::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(timeline::timeline(ops))
+    }
+
     /// Get proposed patches.
     pub fn proposed(&self) -> Result<impl Iterator<Item = (PatchId, Patch)> + '_, Error> {
         let all = self.all()?;
@@ -3102,6 +3220,147 @@ mod test {
         assert_eq!(patch.revisions().count(), 1);
     }
 
+    #[test]
+    fn test_patch_review_request() {
+        let base = arbitrary::oid();
+        let oid = arbitrary::oid();
+        let repo = gen::<MockRepository>(1);
+        let time = env::local_time();
+        let alice = MockSigner::default();
+        let bob = MockSigner::default();
+        let eve = MockSigner::default();
+        let mut h0: cob::test::HistoryBuilder<Patch> = cob::test::history(
+            &[
+                Action::Revision {
+                    description: String::from("Original"),
+                    base,
+                    oid,
+                    resolves: Default::default(),
+                },
+                Action::Edit {
+                    title: String::from("Some patch"),
+                    target: MergeTarget::Delegates,
+                },
+            ],
+            time.into(),
+            &alice,
+        );
+        let revision = RevisionId(*h0.root().id());
+        h0.commit(
+            &Action::ReviewRequest {
+                revision,
+                add: BTreeSet::from([bob.public_key().into(), eve.public_key().into()]),
+                remove: BTreeSet::default(),
+            },
+            &alice,
+        );
+        h0.commit(
+            &Action::Review {
+                revision,
+                summary: None,
+                verdict: Some(Verdict::Accept),
+                labels: vec![],
+            },
+            &bob,
+        );
+        let patch = Patch::from_history(&h0, &repo).unwrap();
+        let (_, r) = patch.latest();
+        let mut expected = vec![
+            (Did::from(bob.public_key()), ReviewStatus::Accepted),
+            (Did::from(eve.public_key()), ReviewStatus::Pending),
+        ];
+        expected.sort();
+
+        assert_eq!(
+            r.reviewers().map(|(d, s)| (*d, s)).collect::<Vec<_>>(),
+            expected
+        );
+
+        // Only the patch or revision author can request reviews, so this is ignored.
+        h0.commit(
+            &Action::ReviewRequest {
+                revision,
+                add: BTreeSet::default(),
+                remove: BTreeSet::from([bob.public_key().into()]),
+            },
+            &eve,
+        );
+        let patch = Patch::from_history(&h0, &repo).unwrap();
+        let (_, r) = patch.latest();
+        assert_eq!(r.reviewers().count(), 2);
+    }
+
+    #[test]
+    fn test_patch_review_request_concurrent() {
+        let base = arbitrary::oid();
+        let oid = arbitrary::oid();
+        let repo = gen::<MockRepository>(1);
+        let time = env::local_time();
+        let alice = MockSigner::default();
+        let bob = Did::from(MockSigner::default().public_key());
+        let eve = Did::from(MockSigner::default().public_key());
+        let mut h0: cob::test::HistoryBuilder<Patch> = cob::test::history(
+            &[
+                Action::Revision {
+                    description: String::from("Original"),
+                    base,
+                    oid,
+                    resolves: Default::default(),
+                },
+                Action::Edit {
+                    title: String::from("Some patch"),
+                    target: MergeTarget::Delegates,
+                },
+            ],
+            time.into(),
+            &alice,
+        );
+        let revision = RevisionId(*h0.root().id());
+        h0.commit(
+            &Action::ReviewRequest {
+                revision,
+                add: BTreeSet::from([bob]),
+                remove: BTreeSet::default(),
+            },
+            &alice,
+        );
+
+        // Concurrently, Eve's review is requested and Bob's is withdrawn.
+        let mut h1 = h0.clone();
+        h1.commit(
+            &Action::ReviewRequest {
+                revision,
+                add: BTreeSet::from([eve]),
+                remove: BTreeSet::default(),
+            },
+            &alice,
+        );
+        let mut h2 = h0.clone();
+        h2.commit(
+            &Action::ReviewRequest {
+                revision,
+                add: BTreeSet::default(),
+                remove: BTreeSet::from([bob]),
+            },
+            &alice,
+        );
+
+        let mut a = h1.clone();
+        a.merge(h2.clone());
+        let mut b = h2;
+        b.merge(h1);
+
+        let a = Patch::from_history(&a, &repo).unwrap();
+        let b = Patch::from_history(&b, &repo).unwrap();
+        assert_eq!(a, b);
+
+        let (_, r) = a.latest();
+        assert_eq!(
+            r.reviewers().map(|(d, s)| (*d, s)).collect::<Vec<_>>(),
+            vec![(eve, ReviewStatus::Pending)]
+        );
+    }
+
     #[test]
     fn test_revision_reaction() {
         let base = git::Oid::from_str("cb18e95ada2bb38aadd8e6cef0963ce37a87add3").unwrap();
@@ -3401,6 +3660,107 @@ mod test {
         assert_eq!(revision.description(), "I've made changes.");
     }
 
+    #[test]
+    fn test_patch_timeline() {
+        let alice = test::setup::NodeWithRepo::default();
+        let checkout = alice.repo.checkout();
+        let branch = checkout.branch_with([("README", b"Hello World!")]);
+        let mut patches = Cache::no_cache(&*alice.repo).unwrap();
+        let mut patch = patches
+            .create(
+                "My first patch",
+                "Blah blah blah.",
+                MergeTarget::Delegates,
+                branch.base,
+                branch.oid,
+                &[],
+                &alice.signer,
+            )
+            .unwrap();
+        let id = patch.id;
+        let update = checkout.branch_with([("README", b"Hello Radicle!")]);
+        let r1 = patch
+            .update("I've made changes.", branch.base, update.oid, &alice.signer)
+            .unwrap();
+        let review = patch
+            .review(
+                r1,
+                Some(Verdict::Accept),
+                Some("LGTM".to_owned()),
+                vec![],
+                &alice.signer,
+            )
+            .unwrap();
+        let comment = patch
+            .comment(r1, "Ship it!", None, None, [], &alice.signer)
+            .unwrap();
+
+        let store = Patches::open(&*alice.repo).unwrap();
+        let events = store.timeline(&id).unwrap();
+
+        assert!(events
+            .windows(2)
+            .all(|w| (w[0].timestamp, w[0].id) <= (w[1].timestamp, w[1].id)));
+        assert!(events
+            .iter()
+            .all(|e| e.author == *alice.signer.public_key()));
+
+        let mut activities = events.into_iter().map(|e| e.activity).collect::<Vec<_>>();
+        activities.sort_by_key(|a| serde_json::to_string(a).unwrap());
+
+        let mut expected = vec![
+            Activity::Revision {
+                revision: RevisionId(*id),
+                base: branch.base,
+                oid: branch.oid,
+                description: String::from("Blah blah blah."),
+            },
+            Activity::Revision {
+                revision: r1,
+                base: branch.base,
+                oid: update.oid,
+                description: String::from("I've made changes."),
+            },
+            Activity::Review {
+                review,
+                revision: r1,
+                verdict: Some(Verdict::Accept),
+                summary: Some(String::from("LGTM")),
+            },
+            Activity::Comment {
+                comment,
+                revision: r1,
+                body: String::from("Ship it!"),
+                location: None,
+                reply_to: None,
+            },
+        ];
+        expected.sort_by_key(|a| serde_json::to_string(a).unwrap());
+        assert_eq!(activities, expected);
+
+        // Operations are ordered by timestamp, regardless of the order they were applied in.
+        let entries = cob::get::<NonEmpty<cob::Entry>, _>(&*alice.repo, &TYPENAME, &id)
+            .unwrap()
+            .unwrap()
+            .object;
+        let ops = entries
+            .iter()
+            .map(Op::try_from)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let n = ops.len() as u64;
+        let ops = ops.into_iter().enumerate().map(|(i, mut op)| {
+            op.timestamp = Timestamp::from_secs(n - i as u64);
+            op
+        });
+        let events = timeline::timeline(ops);
+        let ids = events.iter().map(|e| e.id).collect::<Vec<_>>();
+        let mut expected = entries.iter().map(|e| *e.id()).collect::<Vec<_>>();
+        expected.reverse();
+
+        assert_eq!(ids, expected);
+    }
+
     #[test]
     fn test_patch_redact() {
         let alice = test::setup::Node::default();
@@ -202,6 +202,42 @@ impl<'a, R, C> Cache<super::Patches<'a, R>, C> {
         Ok(())
     }
 
+    /// Read the patches with the given `ids` from the [`super::Patches`] store and
+    /// write them to the `cache`, leaving all other cached patches untouched.
+    ///
+    /// Patches that no longer exist in storage, or that fail to load, are removed
+    /// from the `cache`.
+    pub fn write_changed(&mut self, ids: &[PatchId]) -> Result<(), super::Error>
+    where
+        R: ReadRepository + cob::Store,
+        C: Update<Patch> + Remove<Patch>,
+    {
+        for id in ids {
+            match self.store.get(id) {
+                Ok(Some(patch)) => {
+                    self.update(&self.rid(), id, &patch).map_err(|e| {
+                        super::Error::CacheUpdate {
+                            id: *id,
+                            err: e.into(),
+                        }
+                    })?;
+                    continue;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    log::error!(target: "cob", "Error loading patch {id} from storage: {e}");
+                }
+            }
+            self.cache
+                .remove(id)
+                .map_err(|e| super::Error::CacheRemove {
+                    id: *id,
+                    err: e.into(),
+                })?;
+        }
+        Ok(())
+    }
+
     /// Read all the patches from the [`super::Patches`] store and
     /// writing them to `cache`.
     ///
@@ -214,16 +250,19 @@ impl<'a, R, C> Cache<super::Patches<'a, R>, C> {
     ) -> Result<(), super::Error>
     where
         R: ReadRepository + cob::Store,
-        C: Update<Patch> + Remove<Patch>,
+        C: Update<Patch> + Remove<Patch> + cob::Verified,
     {
         // Start by clearing the cache. This will get rid of patches that are cached but
         // no longer exist in storage.
         self.remove_all(&self.rid())
             .map_err(|e| super::Error::CacheRemoveAll { err: e.into() })?;
 
-        let patches = self.store.all()?;
+        // Entries verified in a previous run don't need to be verified again.
+        let patches = self
+            .store
+            .all_with(cob::Verification::CachedOk(&mut self.cache))?;
         let mut progress = cache::Progress::new(patches.len());
-        for patch in self.store.all()? {
+        for patch in patches {
             progress.inc();
             match callback(&patch, &progress) {
                 ControlFlow::Continue(()) => match patch {
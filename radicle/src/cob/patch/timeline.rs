@@ -0,0 +1,183 @@
+//! Patch timeline, ie. the chronological history of a patch.
+//!
+//! Unlike [`super::Patch`], which is the materialized state of the patch, the timeline
+//! describes what happened, when and by whom: revisions being published, reviews,
+//! comments, state changes and merges.
+use serde::Serialize;
+
+use crate::cob::common::{CodeLocation, Timestamp};
+use crate::cob::thread::CommentId;
+use crate::cob::{ActorId, EntryId};
+use crate::git;
+
+use super::{Action, Lifecycle, Op, ReviewId, RevisionId, Verdict};
+
+/// An event in the timeline of a patch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Event {
+    /// Entry under which the event happened.
+    pub id: EntryId,
+    /// Author of the event.
+    pub author: ActorId,
+    /// When the event happened.
+    pub timestamp: Timestamp,
+    /// What happened.
+    #[serde(flatten)]
+    pub activity: Activity,
+}
+
+/// Something that happened to a patch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum Activity {
+    /// A revision was published. The first revision opens the patch.
+    #[serde(rename_all = "camelCase")]
+    Revision {
+        revision: RevisionId,
+        base: git::Oid,
+        oid: git::Oid,
+        description: String,
+    },
+    /// A revision was reviewed.
+    #[serde(rename_all = "camelCase")]
+    Review {
+        review: ReviewId,
+        revision: RevisionId,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        verdict: Option<Verdict>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        summary: Option<String>,
+    },
+    /// A revision was commented on, either at the top-level or inline, on the code.
+    #[serde(rename_all = "camelCase")]
+    Comment {
+        comment: CommentId,
+        revision: RevisionId,
+        body: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        location: Option<CodeLocation>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        reply_to: Option<CommentId>,
+    },
+    /// A review was commented on.
+    #[serde(rename_all = "camelCase")]
+    ReviewComment {
+        comment: CommentId,
+        review: ReviewId,
+        body: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        location: Option<CodeLocation>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        reply_to: Option<CommentId>,
+    },
+    /// The patch state changed, eg. it was marked as ready or archived.
+    Lifecycle { state: Lifecycle },
+    /// A revision was merged.
+    Merge {
+        revision: RevisionId,
+        commit: git::Oid,
+    },
+}
+
+impl Activity {
+    /// Get the activity described by an action, if any. Actions that don't make for
+    /// a meaningful event, eg. reactions or edits, are skipped.
+    fn from_action(id: EntryId, action: Action) -> Option<Self> {
+        match action {
+            Action::Revision {
+                description,
+                base,
+                oid,
+                ..
+            } => Some(Self::Revision {
+                revision: RevisionId(id),
+                base,
+                oid,
+                description,
+            }),
+            Action::Review {
+                revision,
+                summary,
+                verdict,
+                ..
+            } => Some(Self::Review {
+                review: ReviewId(id),
+                revision,
+                verdict,
+                summary,
+            }),
+            Action::RevisionComment {
+                revision,
+                location,
+                body,
+                reply_to,
+                ..
+            } => Some(Self::Comment {
+                comment: id,
+                revision,
+                body,
+                location,
+                reply_to,
+            }),
+            Action::ReviewComment {
+                review,
+                body,
+                location,
+                reply_to,
+                ..
+            } => Some(Self::ReviewComment {
+                comment: id,
+                review,
+                body,
+                location,
+                reply_to,
+            }),
+            Action::Lifecycle { state } => Some(Self::Lifecycle { state }),
+            Action::Merge { revision, commit } => Some(Self::Merge { revision, commit }),
+            Action::Edit { .. }
+            | Action::Label { .. }
+            | Action::Assign { .. }
+            | Action::ReviewEdit { .. }
+            | Action::ReviewRedact { .. }
+            | Action::ReviewCommentEdit { .. }
+            | Action::ReviewCommentRedact { .. }
+            | Action::ReviewCommentReact { .. }
+            | Action::ReviewCommentResolve { .. }
+            | Action::ReviewCommentUnresolve { .. }
+            | Action::ReviewRequest { .. }
+            | Action::RevisionEdit { .. }
+            | Action::RevisionReact { .. }
+            | Action::RevisionRedact { .. }
+            | Action::RevisionCommentEdit { .. }
+            | Action::RevisionCommentRedact { .. }
+            | Action::RevisionCommentReact { .. } => None,
+        }
+    }
+}
+
+/// Build a timeline from patch operations, in any order.
+///
+/// Events are ordered by timestamp, with ties broken by entry id. Events of the same
+/// entry keep the order of the actions they stem from.
+pub fn timeline(ops: impl IntoIterator<Item = Op>) -> Vec<Event> {
+    let mut events = ops
+        .into_iter()
+        .flat_map(|op| {
+            let (id, author, timestamp) = (op.id, op.author, op.timestamp);
+
+            op.actions.into_iter().filter_map(move |action| {
+                Activity::from_action(id, action).map(|activity| Event {
+                    id,
+                    author,
+                    timestamp,
+                    activity,
+                })
+            })
+        })
+        .collect::<Vec<_>>();
+
+    // Nb. This is a stable sort, which preserves the order of actions within an entry.
+    events.sort_by_key(|e| (e.timestamp, e.id));
+    events
+}
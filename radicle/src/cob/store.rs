@@ -273,7 +273,15 @@ where
     pub fn all(
         &self,
     ) -> Result<impl ExactSizeIterator<Item = Result<(ObjectId, T), Error>> + 'a, Error> {
-        let raw = cob::list::<T, _>(self.repo, T::type_name())?;
+        self.all_with(cob::Verification::Full)
+    }
+
+    /// Return all objects, verifying entry signatures according to `verification`.
+    pub fn all_with(
+        &self,
+        verification: cob::Verification<'_>,
+    ) -> Result<impl ExactSizeIterator<Item = Result<(ObjectId, T), Error>> + 'a, Error> {
+        let raw = cob::list_with::<T, _>(self.repo, T::type_name(), verification)?;
 
         Ok(raw.into_iter().map(|o| Ok((*o.id(), o.object))))
     }
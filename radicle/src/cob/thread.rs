@@ -3,6 +3,7 @@ use std::collections::{BTreeMap, BTreeSet};
 use std::str::FromStr;
 
 use once_cell::sync::Lazy;
+use radicle_crdt::PNCounter;
 use serde::{ser::SerializeStruct, Deserialize, Serialize};
 use thiserror::Error;
 
@@ -52,6 +53,9 @@ pub type CommentId = EntryId;
 /// Reactions to a comment or other action.
 pub type Reactions = BTreeSet<(ActorId, Reaction)>;
 
+/// Reaction counts of a comment, tracked per actor.
+pub type ReactionCounts = BTreeMap<Reaction, PNCounter<ActorId>>;
+
 /// A comment edit is just some text and an edit time.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -91,7 +95,7 @@ pub enum Never {}
 
 /// A comment on a discussion thread.
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(from = "CommentData<T>")]
 pub struct Comment<T = Never> {
     /// Comment author.
     pub(in crate::cob) author: ActorId,
@@ -99,6 +103,8 @@ pub struct Comment<T = Never> {
     pub(in crate::cob) edits: Vec<Edit>,
     /// Reactions to this comment.
     pub(in crate::cob) reactions: Reactions,
+    /// Reaction counts, materialized from the reactions to this comment.
+    pub(in crate::cob) counts: ReactionCounts,
     /// Comment this is a reply to.
     /// Should always be set, except for the root comment.
     pub(in crate::cob) reply_to: Option<CommentId>,
@@ -113,12 +119,15 @@ impl<T: Serialize> Serialize for Comment<T> {
     where
         S: serde::ser::Serializer,
     {
-        let mut state = serializer.serialize_struct("Comment", 8)?;
+        let mut state = serializer.serialize_struct("Comment", 9)?;
         state.serialize_field("author", &self.author())?;
         if let Some(to) = self.reply_to {
             state.serialize_field("replyTo", &to)?;
         }
         state.serialize_field("reactions", &self.reactions)?;
+        if !self.counts.is_empty() {
+            state.serialize_field("reactionCounts", &self.counts)?;
+        }
         state.serialize_field("resolved", &self.resolved)?;
         state.serialize_field("body", self.body())?;
         state.serialize_field("edits", &self.edits)?;
@@ -134,6 +143,45 @@ impl<T: Serialize> Serialize for Comment<T> {
     }
 }
 
+/// Deserialized form of a [`Comment`].
+///
+/// Comments that were serialized before reaction counts were tracked are
+/// missing them, in which case they are derived from the reactions.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CommentData<T> {
+    author: ActorId,
+    edits: Vec<Edit>,
+    reactions: Reactions,
+    reaction_counts: Option<ReactionCounts>,
+    reply_to: Option<CommentId>,
+    location: Option<T>,
+    resolved: bool,
+}
+
+impl<T> From<CommentData<T>> for Comment<T> {
+    fn from(data: CommentData<T>) -> Self {
+        let counts = data.reaction_counts.unwrap_or_else(|| {
+            data.reactions
+                .iter()
+                .fold(ReactionCounts::new(), |mut acc, (author, reaction)| {
+                    acc.entry(*reaction).or_default().increment(*author);
+                    acc
+                })
+        });
+
+        Self {
+            author: data.author,
+            edits: data.edits,
+            reactions: data.reactions,
+            counts,
+            reply_to: data.reply_to,
+            location: data.location,
+            resolved: data.resolved,
+        }
+    }
+}
+
 impl<L> Comment<L> {
     /// Create a new comment.
     pub fn new(
@@ -149,6 +197,7 @@ impl<L> Comment<L> {
         Self {
             author,
             reactions: BTreeSet::default(),
+            counts: ReactionCounts::default(),
             edits: vec![edit],
             reply_to,
             location,
@@ -209,6 +258,19 @@ impl<L> Comment<L> {
             })
     }
 
+    /// Comment reaction counts.
+    pub fn reaction_counts(&self) -> BTreeMap<&Reaction, usize> {
+        self.counts
+            .iter()
+            .filter_map(|(reaction, counter)| {
+                usize::try_from(counter.get())
+                    .ok()
+                    .filter(|n| *n > 0)
+                    .map(|n| (reaction, n))
+            })
+            .collect()
+    }
+
     /// Get comment location, if any.
     pub fn location(&self) -> Option<&L> {
         self.location.as_ref()
@@ -575,10 +637,22 @@ pub fn react<T>(
         debug_assert!(!thread.timeline.contains(&id));
         thread.timeline.push(id);
 
+        // N.b. counts only change when the set of reactions does, so that reacting
+        // twice with the same emoji is only counted once.
         if active {
-            comment.reactions.insert(key);
-        } else {
-            comment.reactions.remove(&key);
+            if comment.reactions.insert(key) {
+                comment
+                    .counts
+                    .entry(reaction)
+                    .or_default()
+                    .increment(author);
+            }
+        } else if comment.reactions.remove(&key) {
+            comment
+                .counts
+                .entry(reaction)
+                .or_default()
+                .decrement(author);
         }
     }
     Ok(())
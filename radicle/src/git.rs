@@ -208,6 +208,14 @@ pub mod refs {
             Qualified::from_components(name::component!("rad"), name::component!("sigrefs"), None)
         });
 
+        /// Where a remote's alias, ie. its preferred display name in the repository, is stored.
+        ///
+        /// `refs/rad/alias`
+        ///
+        pub static ALIAS_BRANCH: Lazy<Qualified> = Lazy::new(|| {
+            Qualified::from_components(name::component!("rad"), name::component!("alias"), None)
+        });
+
         /// The set of special references used in the Heartwood protocol.
         #[derive(Clone, Copy, Debug)]
         pub enum Special {
@@ -275,6 +283,14 @@ pub mod refs {
             SIGREFS_BRANCH.with_namespace(remote.into())
         }
 
+        /// Get the branch where the `remote`'s alias is stored.
+        ///
+        /// `refs/namespaces/<remote>/refs/rad/alias`
+        ///
+        pub fn alias(remote: &RemoteId) -> Namespaced {
+            ALIAS_BRANCH.with_namespace(remote.into())
+        }
+
         /// The collaborative object reference, identified by `typename` and `object_id`, under the given `remote`.
         ///
         /// `refs/namespaces/<remote>/refs/cobs/<typename>/<object_id>`
@@ -69,6 +69,10 @@ pub struct Diverging {
     base: Oid,
     longest: Oid,
     head: Oid,
+    /// Delegates whose tips include `longest`.
+    longest_voters: Vec<Did>,
+    /// Delegates whose tips include `head`.
+    head_voters: Vec<Did>,
 }
 
 impl fmt::Display for Diverging {
@@ -78,11 +82,63 @@ impl fmt::Display for Diverging {
             base,
             longest,
             head,
+            longest_voters,
+            head_voters,
         } = self;
-        write!(f, "found diverging commits {longest} and {head}, with base commit {base} and threshold {threshold}")
+        let voters = |dids: &[Did]| {
+            dids.iter()
+                .map(|did| did.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        write!(
+            f,
+            "found diverging commits {longest} (from {}) and {head} (from {}), \
+            with base commit {base} and threshold {threshold}",
+            voters(longest_voters),
+            voters(head_voters),
+        )
     }
 }
 
+/// The position of a delegate's tip, relative to the canonical tip of a reference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Position {
+    /// The tip is the canonical tip.
+    Even,
+    /// The tip is ahead of the canonical tip by the given number of commits.
+    Ahead(usize),
+    /// The tip is behind the canonical tip by the given number of commits.
+    Behind(usize),
+    /// The tip and the canonical tip have diverged.
+    Diverged { ahead: usize, behind: usize },
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Even => write!(f, "even"),
+            Self::Ahead(n) => write!(f, "{n} ahead"),
+            Self::Behind(n) => write!(f, "{n} behind"),
+            Self::Diverged { ahead, behind } => {
+                write!(f, "diverged ({ahead} ahead, {behind} behind)")
+            }
+        }
+    }
+}
+
+/// The canonical tip of a branch, along with the position of each delegate's tip.
+#[derive(Debug, Clone)]
+pub struct CanonicalBranch {
+    /// Fully qualified branch name, eg. `refs/heads/master`.
+    pub name: Qualified<'static>,
+    /// Canonical tip of the branch.
+    pub head: Oid,
+    /// Position of each delegate's tip, relative to the canonical tip. Delegates
+    /// that don't have the branch are not included.
+    pub positions: BTreeMap<Did, Position>,
+}
+
 impl Canonical {
     /// Construct the set of canonical tips of the `Project::default_branch` for
     /// the given `delegates`.
@@ -134,6 +190,38 @@ impl Canonical {
     pub fn tips(&self) -> impl Iterator<Item = (&Did, &Oid)> {
         self.tips.iter()
     }
+
+    /// Return the position of each delegate's tip, relative to the given `head`.
+    pub fn positions(
+        &self,
+        head: Oid,
+        repo: &raw::Repository,
+    ) -> Result<BTreeMap<Did, Position>, raw::Error> {
+        let mut positions = BTreeMap::new();
+
+        for (did, tip) in &self.tips {
+            let position = match repo.graph_ahead_behind(**tip, *head)? {
+                (0, 0) => Position::Even,
+                (ahead, 0) => Position::Ahead(ahead),
+                (0, behind) => Position::Behind(behind),
+                (ahead, behind) => Position::Diverged { ahead, behind },
+            };
+            positions.insert(*did, position);
+        }
+        Ok(positions)
+    }
+
+    /// Return the delegates whose tips are equal to, or descend from the given commit.
+    fn voters(&self, oid: Oid, repo: &raw::Repository) -> Result<Vec<Did>, raw::Error> {
+        let mut voters = Vec::new();
+
+        for (did, tip) in &self.tips {
+            if *tip == oid || repo.graph_descendant_of(**tip, *oid)? {
+                voters.push(*did);
+            }
+        }
+        Ok(voters)
+    }
 }
 
 /// Check that a given `target` converges with any of the provided `tips`.
@@ -239,6 +327,8 @@ impl Canonical {
                     base: base.into(),
                     longest,
                     head: *head,
+                    longest_voters: self.voters(longest, repo)?,
+                    head_voters: self.voters(*head, repo)?,
                 }));
             }
         }
@@ -497,6 +587,82 @@ mod tests {
         assert_eq!(quorum(&[*m1, *b2, *c1, *c0], 4, &repo).unwrap(), c0);
     }
 
+    #[test]
+    fn test_positions() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (repo, c0) = fixtures::repository(tmp.path());
+        let c0: git::Oid = c0.into();
+        let c1 = fixtures::commit("C1", &[*c0], &repo);
+        let c2 = fixtures::commit("C2", &[*c1], &repo);
+        let b2 = fixtures::commit("B2", &[*c1], &repo);
+        let alice = Did::from(MockSigner::from_seed([1; 32]).public_key());
+        let bob = Did::from(MockSigner::from_seed([2; 32]).public_key());
+        let eve = Did::from(MockSigner::from_seed([3; 32]).public_key());
+
+        // All delegates agree.
+        let canonical = Canonical {
+            tips: [(alice, c1), (bob, c1)].into_iter().collect(),
+        };
+        let head = canonical.quorum(2, &repo).unwrap();
+        let positions = canonical.positions(head, &repo).unwrap();
+
+        assert_eq!(head, c1);
+        assert_eq!(positions[&alice], Position::Even);
+        assert_eq!(positions[&bob], Position::Even);
+
+        // One delegate is behind.
+        let canonical = Canonical {
+            tips: [(alice, c2), (bob, c2), (eve, c0)].into_iter().collect(),
+        };
+        let head = canonical.quorum(2, &repo).unwrap();
+        let positions = canonical.positions(head, &repo).unwrap();
+
+        assert_eq!(head, c2);
+        assert_eq!(positions[&alice], Position::Even);
+        assert_eq!(positions[&bob], Position::Even);
+        assert_eq!(positions[&eve], Position::Behind(2));
+
+        // One delegate is ahead, and another has forked off.
+        let canonical = Canonical {
+            tips: [(alice, c2), (bob, c1), (eve, b2)].into_iter().collect(),
+        };
+        let head = canonical.quorum(3, &repo).unwrap();
+        let positions = canonical.positions(head, &repo).unwrap();
+
+        assert_eq!(head, c1);
+        assert_eq!(positions[&alice], Position::Ahead(1));
+        assert_eq!(positions[&bob], Position::Even);
+        assert_eq!(positions[&eve], Position::Ahead(1));
+
+        let positions = canonical.positions(c2, &repo).unwrap();
+        assert_eq!(
+            positions[&eve],
+            Position::Diverged {
+                ahead: 1,
+                behind: 1
+            }
+        );
+
+        // The delegates have hard-forked, and the error says who is on which side.
+        let canonical = Canonical {
+            tips: [(alice, c2), (bob, b2)].into_iter().collect(),
+        };
+        let err = canonical.quorum(1, &repo).unwrap_err();
+        let QuorumError::Diverging(diverging) = &err else {
+            panic!("expected the tips to diverge, got {err}");
+        };
+        let (c2_voters, b2_voters) = if diverging.longest == c2 {
+            (&diverging.longest_voters, &diverging.head_voters)
+        } else {
+            (&diverging.head_voters, &diverging.longest_voters)
+        };
+        assert_eq!(diverging.base, c1);
+        assert_eq!(c2_voters, &vec![alice]);
+        assert_eq!(b2_voters, &vec![bob]);
+        assert!(err.to_string().contains(&alice.to_string()));
+        assert!(err.to_string().contains(&bob.to_string()));
+    }
+
     #[test]
     fn test_quorum_merges() {
         let tmp = tempfile::tempdir().unwrap();
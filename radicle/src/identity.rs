@@ -1,11 +1,15 @@
 #![warn(clippy::unwrap_used)]
+pub mod alias;
 pub mod did;
 pub mod doc;
 pub mod project;
 
 pub use crypto::PublicKey;
 pub use did::Did;
-pub use doc::{Doc, DocAt, DocError, IdError, PayloadError, RawDoc, RepoId, Visibility};
+pub use doc::{
+    Doc, DocAt, DocError, IdError, InvalidPayload, PayloadError, RawDoc, RepoId, Validator,
+    Validators, Visibility,
+};
 pub use project::Project;
 
 pub use crate::cob::identity::{Error, Identity, IdentityMut};
@@ -0,0 +1,321 @@
+//! Repository aliases, ie. the display names contributors go by in a repository.
+//!
+//! Each remote may publish an alias under its own namespace, in `refs/rad/alias`. The
+//! branch points to a commit with a single JSON file, binding the remote's DID to a
+//! display name and optional avatar URL. Since the branch is under the remote's
+//! namespace, it is covered by the remote's signed refs.
+use std::collections::BTreeMap;
+use std::env;
+use std::path::Path;
+
+use crypto::Signer;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::git;
+use crate::identity::doc::MAX_STRING_LENGTH;
+use crate::identity::Did;
+use crate::profile;
+use crate::storage::git::Repository;
+use crate::storage::{ReadRepository, RemoteId, RepositoryError, SignRepository, WriteRepository};
+
+pub use crate::git::refs::storage::ALIAS_BRANCH;
+
+/// Path to the alias in the alias branch.
+pub static PATH: Lazy<&Path> = Lazy::new(|| Path::new("alias.json"));
+
+#[derive(Error, Debug)]
+pub enum AliasError {
+    #[error("alias name cannot be empty")]
+    EmptyName,
+    #[error("alias name cannot exceed {MAX_STRING_LENGTH} bytes")]
+    NameTooLong,
+    #[error("alias avatar cannot exceed {MAX_STRING_LENGTH} bytes")]
+    AvatarTooLong,
+    #[error("alias belongs to {found}, expected {expected}")]
+    Mismatch { expected: Did, found: Did },
+}
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("invalid alias: {0}")]
+    Invalid(#[from] AliasError),
+    #[error("json: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("git: {0}")]
+    Git(#[from] git::raw::Error),
+    #[error("git: {0}")]
+    GitExt(#[from] git::ext::Error),
+    #[error(transparent)]
+    Repository(#[from] RepositoryError),
+    #[error(transparent)]
+    Refs(#[from] crate::storage::refs::Error),
+}
+
+/// A contributor's alias in a repository.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Alias {
+    /// The contributor publishing the alias.
+    pub did: Did,
+    /// Display name.
+    pub name: String,
+    /// Avatar URL.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avatar: Option<String>,
+}
+
+impl Alias {
+    /// Create a new alias for the given contributor.
+    pub fn new(
+        did: impl Into<Did>,
+        name: impl ToString,
+        avatar: Option<String>,
+    ) -> Result<Self, AliasError> {
+        let alias = Self {
+            did: did.into(),
+            name: name.to_string(),
+            avatar,
+        };
+        alias.validate()?;
+
+        Ok(alias)
+    }
+
+    /// Check that the alias is within bounds.
+    pub fn validate(&self) -> Result<(), AliasError> {
+        if self.name.trim().is_empty() {
+            return Err(AliasError::EmptyName);
+        }
+        if self.name.len() > MAX_STRING_LENGTH {
+            return Err(AliasError::NameTooLong);
+        }
+        if self
+            .avatar
+            .as_ref()
+            .is_some_and(|a| a.len() > MAX_STRING_LENGTH)
+        {
+            return Err(AliasError::AvatarTooLong);
+        }
+        Ok(())
+    }
+
+    /// Load the alias of the given remote, if any.
+    ///
+    /// Aliases that are out of bounds, or that were published on behalf of another
+    /// contributor, are rejected.
+    pub fn load<R: ReadRepository>(remote: &RemoteId, repo: &R) -> Result<Option<Self>, Error> {
+        let oid = match repo.reference_oid(remote, &ALIAS_BRANCH) {
+            Ok(oid) => oid,
+            Err(e) if git::is_not_found_err(&e) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let blob = repo.blob_at(oid, *PATH)?;
+        let alias: Self = serde_json::from_slice(blob.content())?;
+        let expected = Did::from(remote);
+
+        if alias.did != expected {
+            return Err(AliasError::Mismatch {
+                expected,
+                found: alias.did,
+            }
+            .into());
+        }
+        alias.validate()?;
+
+        Ok(Some(alias))
+    }
+
+    /// Publish the alias under the signer's namespace, and sign the updated refs.
+    pub fn publish<G: Signer>(&self, repo: &Repository, signer: &G) -> Result<git::Oid, Error> {
+        let remote = signer.public_key();
+        let expected = Did::from(remote);
+
+        if self.did != expected {
+            return Err(AliasError::Mismatch {
+                expected,
+                found: self.did,
+            }
+            .into());
+        }
+        self.validate()?;
+
+        let raw = repo.raw();
+        let json = serde_json::to_vec_pretty(self)?;
+        let tree = git::write_tree(*PATH, &json, raw)?;
+        let refname = git::refs::storage::alias(remote);
+        let parent = match repo.reference_oid(remote, &ALIAS_BRANCH) {
+            Ok(oid) => Some(raw.find_commit(*oid)?),
+            Err(e) if git::is_not_found_err(&e) => None,
+            Err(e) => return Err(e.into()),
+        };
+        let author = if let Ok(s) = env::var(profile::env::GIT_COMMITTER_DATE) {
+            let timestamp = s.trim().parse::<i64>().unwrap_or_default();
+            let time = git::raw::Time::new(timestamp, 0);
+
+            git::raw::Signature::new("radicle", remote.to_string().as_str(), &time)?
+        } else {
+            raw.signature()?
+        };
+        let oid = raw.commit(
+            Some(refname.as_str()),
+            &author,
+            &author,
+            "Update alias\n",
+            &tree,
+            &parent.iter().collect::<Vec<_>>(),
+        )?;
+        repo.sign_refs(signer)?;
+
+        Ok(oid.into())
+    }
+}
+
+/// Get the aliases published by the contributors of a repository.
+///
+/// Invalid aliases are skipped, such that a single misbehaving remote can't prevent
+/// the others from being displayed.
+pub fn aliases(repo: &Repository) -> Result<BTreeMap<RemoteId, Alias>, Error> {
+    let mut aliases = BTreeMap::new();
+
+    for remote in repo.remote_ids()? {
+        let remote = remote?;
+
+        match Alias::load(&remote, repo) {
+            Ok(Some(alias)) => {
+                aliases.insert(remote, alias);
+            }
+            Ok(None) => {}
+            Err(e) => {
+                log::warn!(target: "radicle", "Skipping invalid alias of {remote}: {e}");
+            }
+        }
+    }
+    Ok(aliases)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use crypto::test::signer::MockSigner;
+
+    use super::*;
+    use crate::storage::{ReadStorage as _, RemoteRepository as _, WriteStorage as _};
+    use crate::test::fixtures;
+
+    #[test]
+    fn test_alias_bounds() {
+        let signer = MockSigner::default();
+        let did = Did::from(signer.public_key());
+
+        assert!(Alias::new(did, "Alice", Some("https://example.com/a.png".to_owned())).is_ok());
+        assert!(matches!(
+            Alias::new(did, " ", None),
+            Err(AliasError::EmptyName)
+        ));
+        assert!(matches!(
+            Alias::new(did, "a".repeat(MAX_STRING_LENGTH + 1), None),
+            Err(AliasError::NameTooLong)
+        ));
+        assert!(matches!(
+            Alias::new(did, "Alice", Some("a".repeat(MAX_STRING_LENGTH + 1))),
+            Err(AliasError::AvatarTooLong)
+        ));
+    }
+
+    #[test]
+    fn test_alias_publish() {
+        let tmp = tempfile::tempdir().unwrap();
+        let alice = MockSigner::from_seed([0xa; 32]);
+        let bob = MockSigner::from_seed([0xb; 32]);
+        let storage = fixtures::storage(tmp.path(), &alice).unwrap();
+        let rid = storage.repositories().unwrap().first().unwrap().rid;
+        let repo = storage.repository_mut(rid).unwrap();
+
+        assert!(aliases(&repo).unwrap().is_empty());
+
+        let a = Alias::new(alice.public_key(), "Alice Liddell", None).unwrap();
+        let b = Alias::new(
+            bob.public_key(),
+            "Bob",
+            Some("https://example.com/bob.png".to_owned()),
+        )
+        .unwrap();
+        a.publish(&repo, &alice).unwrap();
+        b.publish(&repo, &bob).unwrap();
+
+        // The alias is covered by the signed refs.
+        let refs = repo.remote(bob.public_key()).unwrap().refs;
+        assert!(refs.get(&ALIAS_BRANCH).is_some());
+
+        let all = aliases(&repo).unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all.get(alice.public_key()), Some(&a));
+        assert_eq!(all.get(bob.public_key()), Some(&b));
+
+        // Publishing again updates the alias.
+        let a = Alias::new(alice.public_key(), "Alice", None).unwrap();
+        a.publish(&repo, &alice).unwrap();
+        assert_eq!(
+            Alias::load(alice.public_key(), &repo).unwrap(),
+            Some(a.clone())
+        );
+
+        // Can't publish on behalf of someone else.
+        assert!(matches!(
+            b.publish(&repo, &alice),
+            Err(Error::Invalid(AliasError::Mismatch { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_alias_rejected_on_read() {
+        let tmp = tempfile::tempdir().unwrap();
+        let alice = MockSigner::from_seed([0xa; 32]);
+        let bob = MockSigner::from_seed([0xb; 32]);
+        let storage = fixtures::storage(tmp.path(), &alice).unwrap();
+        let rid = storage.repositories().unwrap().first().unwrap().rid;
+        let repo = storage.repository_mut(rid).unwrap();
+        let raw = repo.raw();
+        let sig = git::raw::Signature::now("anonymous", "anonymous@radicle.xyz").unwrap();
+        let write = |remote: &RemoteId, alias: &Alias| {
+            let json = serde_json::to_vec(alias).unwrap();
+            let tree = git::write_tree(*PATH, &json, raw).unwrap();
+            let refname = git::refs::storage::alias(remote);
+
+            raw.commit(Some(refname.as_str()), &sig, &sig, "Alias", &tree, &[])
+                .unwrap();
+        };
+
+        // Oversized alias.
+        write(
+            alice.public_key(),
+            &Alias {
+                did: alice.public_key().into(),
+                name: "a".repeat(MAX_STRING_LENGTH + 1),
+                avatar: None,
+            },
+        );
+        // Alias claiming to be someone else.
+        write(
+            bob.public_key(),
+            &Alias {
+                did: alice.public_key().into(),
+                name: "Alice".to_owned(),
+                avatar: None,
+            },
+        );
+        repo.sign_refs(&bob).unwrap();
+
+        assert!(matches!(
+            Alias::load(alice.public_key(), &repo),
+            Err(Error::Invalid(AliasError::NameTooLong))
+        ));
+        assert!(matches!(
+            Alias::load(bob.public_key(), &repo),
+            Err(Error::Invalid(AliasError::Mismatch { .. }))
+        ));
+        assert!(aliases(&repo).unwrap().is_empty());
+    }
+}
@@ -48,6 +48,8 @@ pub enum DocError {
     GitExt(#[from] git::Error),
     #[error("git: {0}")]
     Git(#[from] git2::Error),
+    #[error(transparent)]
+    Payload(#[from] PayloadError),
     #[error("missing identity document")]
     Missing,
 }
@@ -222,6 +224,71 @@ pub enum PayloadError {
     Json(#[from] serde_json::Error),
     #[error("payload '{0}' not found in identity document")]
     NotFound(PayloadId),
+    #[error("invalid payload '{0}': {1}")]
+    Invalid(PayloadId, InvalidPayload),
+}
+
+/// Reason a payload failed validation.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{reason} (at '{pointer}')")]
+pub struct InvalidPayload {
+    /// JSON pointer to the invalid value, eg. `/name`. The empty string refers to the whole
+    /// payload.
+    pub pointer: String,
+    /// Why the value is invalid.
+    pub reason: String,
+}
+
+impl InvalidPayload {
+    /// Create a new payload validation error.
+    pub fn new(pointer: impl ToString, reason: impl ToString) -> Self {
+        Self {
+            pointer: pointer.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+/// A payload validator.
+pub type Validator = fn(&serde_json::Value) -> Result<(), InvalidPayload>;
+
+/// Payload validators, keyed by payload type.
+///
+/// Payloads are validated when an identity document is created or updated, see
+/// [`RawDoc::validated`]. Documents loaded from storage are not re-validated, so that
+/// adding or changing a validator never makes existing repositories unreadable.
+#[derive(Debug, Clone)]
+pub struct Validators(BTreeMap<PayloadId, Validator>);
+
+impl Default for Validators {
+    fn default() -> Self {
+        Self(BTreeMap::from_iter([(
+            PayloadId::project(),
+            Project::validate as Validator,
+        )]))
+    }
+}
+
+impl Validators {
+    /// Register a validator for payloads of the given type.
+    /// Returns the validator it replaced, if any.
+    pub fn register(&mut self, id: PayloadId, validator: Validator) -> Option<Validator> {
+        self.0.insert(id, validator)
+    }
+
+    /// Check whether payloads of the given type have a validator.
+    pub fn is_known(&self, id: &PayloadId) -> bool {
+        self.0.contains_key(id)
+    }
+
+    /// Validate a payload using the validator for its type.
+    /// Payloads of unknown types are always valid.
+    pub fn validate(&self, id: &PayloadId, payload: &Payload) -> Result<(), PayloadError> {
+        if let Some(validate) = self.0.get(id) {
+            validate(payload).map_err(|e| PayloadError::Invalid(id.clone(), e))?;
+        }
+        Ok(())
+    }
 }
 
 /// A `Payload` is a free-form JSON value that can be associated with an
@@ -453,6 +520,7 @@ impl RawDoc {
         } = self;
         let delegates = Delegates::new(delegates)?;
         let threshold = Threshold::new(threshold, &delegates)?;
+
         Ok(Doc {
             version,
             payload,
@@ -461,6 +529,16 @@ impl RawDoc {
             visibility,
         })
     }
+
+    /// Like [`RawDoc::verified`], but also ensure that payloads with a validator are valid.
+    ///
+    /// This should be used when creating or updating a document, rather than when loading one.
+    pub fn validated(self, validators: &Validators) -> Result<Doc, DocError> {
+        for (id, payload) in &self.payload {
+            validators.validate(id, payload)?;
+        }
+        self.verified()
+    }
 }
 
 /// A valid set of delegates for the identity [`Doc`].
@@ -908,6 +986,95 @@ mod test {
         assert_matches!(doc.verified(), Err(DocError::Delegates(DelegatesError(_))));
     }
 
+    #[test]
+    fn test_invalid_project_payload() {
+        let did = Did::from(MockSigner::from_seed([0xff; 32]).public_key());
+        let mut doc = RawDoc::new(gen::<Project>(1), vec![did], 1, Visibility::Public);
+        let validators = Validators::default();
+
+        let project = doc.payload.get_mut(&PayloadId::project()).unwrap();
+        project
+            .as_object_mut()
+            .unwrap()
+            .insert("name".into(), json!(""));
+
+        let Err(DocError::Payload(PayloadError::Invalid(id, err))) =
+            doc.clone().validated(&validators)
+        else {
+            panic!("expected payload to be invalid");
+        };
+        assert_eq!(id, PayloadId::project());
+        assert_eq!(err.pointer, "/name");
+
+        let project = doc.payload.get_mut(&PayloadId::project()).unwrap();
+        let obj = project.as_object_mut().unwrap();
+        obj.insert("name".into(), json!("heartwood"));
+        obj.insert(
+            "description".into(),
+            json!("x".repeat(MAX_STRING_LENGTH + 1)),
+        );
+
+        let Err(DocError::Payload(PayloadError::Invalid(_, err))) =
+            doc.clone().validated(&validators)
+        else {
+            panic!("expected payload to be invalid");
+        };
+        assert_eq!(err.pointer, "/description");
+
+        let project = doc.payload.get_mut(&PayloadId::project()).unwrap();
+        project.as_object_mut().unwrap().remove("defaultBranch");
+
+        let Err(DocError::Payload(PayloadError::Invalid(_, err))) =
+            doc.clone().validated(&validators)
+        else {
+            panic!("expected payload to be invalid");
+        };
+        assert_eq!(err.pointer, "");
+
+        // Documents are not validated when loaded, only when created or updated.
+        assert_matches!(doc.verified(), Ok(_));
+    }
+
+    #[test]
+    fn test_unknown_payload() {
+        let did = Did::from(MockSigner::from_seed([0xff; 32]).public_key());
+        let id = PayloadId::from_str("com.example.unknown").unwrap();
+        let mut doc = RawDoc::new(gen::<Project>(1), vec![did], 1, Visibility::Public);
+        doc.payload
+            .insert(id.clone(), Payload::from(json!({ "name": "" })));
+
+        assert!(!Validators::default().is_known(&id));
+        assert_matches!(doc.validated(&Validators::default()), Ok(_));
+    }
+
+    #[test]
+    fn test_register_validator() {
+        fn validate(value: &serde_json::Value) -> Result<(), InvalidPayload> {
+            if value.get("size").and_then(|v| v.as_u64()).is_some() {
+                Ok(())
+            } else {
+                Err(InvalidPayload::new("/size", "size must be a number"))
+            }
+        }
+        let did = Did::from(MockSigner::from_seed([0xff; 32]).public_key());
+        let id = PayloadId::from_str("com.example.registered").unwrap();
+        let mut doc = RawDoc::new(gen::<Project>(1), vec![did], 1, Visibility::Public);
+        let mut validators = Validators::default();
+
+        assert!(validators.register(id.clone(), validate).is_none());
+        assert!(validators.is_known(&id));
+
+        doc.payload
+            .insert(id.clone(), Payload::from(json!({ "size": "big" })));
+        assert_matches!(
+            doc.clone().validated(&validators),
+            Err(DocError::Payload(PayloadError::Invalid(_, _)))
+        );
+
+        doc.payload.insert(id, Payload::from(json!({ "size": 42 })));
+        assert_matches!(doc.validated(&validators), Ok(_));
+    }
+
     #[test]
     fn test_is_valid_version() {
         // 0 is not a valid version
@@ -234,6 +234,55 @@ impl Project {
         Self::new(name, description, default_branch)
     }
 
+    /// Validate a `xyz.radicle.project` payload, applying the same rules as [`Project::new`].
+    /// This is the validator registered for project payloads, see [`doc::Validators`].
+    pub fn validate(value: &serde_json::Value) -> Result<(), doc::InvalidPayload> {
+        let serde_json::Value::Object(obj) = value else {
+            return Err(doc::InvalidPayload::new("", "payload must be an object"));
+        };
+        let field = |key: &str| {
+            obj.get(key)
+                .ok_or_else(|| doc::InvalidPayload::new("", format!("missing field `{key}`")))
+        };
+        let name = field("name")?
+            .as_str()
+            .ok_or_else(|| doc::InvalidPayload::new("/name", "name must be a string"))
+            .and_then(|s| {
+                ProjectName::from_str(s).map_err(|e| doc::InvalidPayload::new("/name", e))
+            })?;
+        let description = field("description")?
+            .as_str()
+            .ok_or_else(|| {
+                doc::InvalidPayload::new("/description", "description must be a string")
+            })?
+            .to_owned();
+        let default_branch = field("defaultBranch")?
+            .as_str()
+            .ok_or_else(|| {
+                doc::InvalidPayload::new("/defaultBranch", "default branch must be a string")
+            })
+            .and_then(|s| {
+                BranchName::try_from(s).map_err(|e| doc::InvalidPayload::new("/defaultBranch", e))
+            })?;
+
+        match Self::new(name, description, default_branch) {
+            Ok(_) => Ok(()),
+            Err(errs) => {
+                // Report the first error, there is always at least one.
+                let err = errs
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| doc::InvalidPayload::new("", "invalid project payload"))?;
+                let pointer = match err {
+                    ProjectError::Name(_) => "/name",
+                    ProjectError::Description(_) => "/description",
+                    ProjectError::DefaultBranch(_) => "/defaultBranch",
+                };
+                Err(doc::InvalidPayload::new(pointer, err))
+            }
+        }
+    }
+
     #[inline]
     pub fn name(&self) -> &str {
         self.name.as_str()
@@ -3,12 +3,14 @@
 mod features;
 
 pub mod address;
+pub mod ban;
 pub mod config;
 pub mod db;
 pub mod events;
 pub mod notifications;
 pub mod policy;
 pub mod refs;
+pub mod reputation;
 pub mod routing;
 pub mod seed;
 pub mod timestamp;
@@ -40,13 +42,17 @@ pub use address::KnownAddress;
 pub use config::Config;
 pub use cyphernet::addr::{HostName, PeerAddr};
 pub use db::Database;
-pub use events::{Event, Events};
+pub use events::{Event, Events, FetchProgress};
 pub use features::Features;
 pub use seed::SyncedAt;
 pub use timestamp::Timestamp;
 
 /// Peer-to-peer protocol version.
 pub const PROTOCOL_VERSION: u8 = 1;
+/// Control socket protocol version.
+///
+/// Nodes that predate the [`Command::Hello`] exchange are considered to speak version `0`.
+pub const CONTROL_PROTOCOL_VERSION: u32 = 1;
 /// Default name for control socket file.
 pub const DEFAULT_SOCKET_NAME: &str = "control.sock";
 /// Default radicle protocol port.
@@ -417,9 +423,23 @@ pub enum CommandResult<T> {
         /// The reason for the error.
         #[serde(rename = "error")]
         reason: String,
+        /// The kind of error, if known.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        kind: Option<CommandErrorKind>,
     },
 }
 
+/// Kind of error returned on the node control socket.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CommandErrorKind {
+    /// The command, or one of its fields, was not understood by the node.
+    UnknownCommand,
+    /// An error kind unknown to this version of the protocol.
+    #[serde(untagged)]
+    Other(String),
+}
+
 impl<T, E> From<Result<T, E>> for CommandResult<T>
 where
     E: std::error::Error,
@@ -429,6 +449,7 @@ where
             Ok(t) => Self::Okay(t),
             Err(e) => Self::Error {
                 reason: e.to_string(),
+                kind: None,
             },
         }
     }
@@ -465,6 +486,15 @@ impl CommandResult<()> {
     pub fn error(err: impl std::error::Error) -> Self {
         Self::Error {
             reason: err.to_string(),
+            kind: None,
+        }
+    }
+
+    /// Create an error result for a command that wasn't understood.
+    pub fn unknown_command(err: impl std::error::Error) -> Self {
+        Self::Error {
+            reason: err.to_string(),
+            kind: Some(CommandErrorKind::UnknownCommand),
         }
     }
 }
@@ -477,6 +507,69 @@ impl<T: Serialize> CommandResult<T> {
     }
 }
 
+/// An optional feature of the control socket protocol, advertised in [`Hello`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Capability {
+    /// Streaming of [`FetchProgress`] events in response to [`Command::Fetch`].
+    FetchProgress,
+    /// Fetching a single [`RefClass`] with [`Command::Fetch`].
+    FetchRefs,
+    /// Streaming of [`ShutdownPhase`] updates in response to [`Command::Shutdown`].
+    ShutdownProgress,
+    /// A capability unknown to this version of the protocol.
+    #[serde(untagged)]
+    Other(String),
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FetchProgress => write!(f, "fetch-progress"),
+            Self::FetchRefs => write!(f, "fetch-refs"),
+            Self::ShutdownProgress => write!(f, "shutdown-progress"),
+            Self::Other(other) => write!(f, "{other}"),
+        }
+    }
+}
+
+/// Control socket protocol version and capabilities, exchanged by client and node
+/// at the start of a connection.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hello {
+    /// Protocol version.
+    pub version: u32,
+    /// Supported capabilities.
+    pub capabilities: Vec<Capability>,
+}
+
+impl Hello {
+    /// The hello of this version of the protocol.
+    pub fn local() -> Self {
+        Self {
+            version: CONTROL_PROTOCOL_VERSION,
+            capabilities: vec![
+                Capability::FetchProgress,
+                Capability::FetchRefs,
+                Capability::ShutdownProgress,
+            ],
+        }
+    }
+
+    /// The hello assumed for nodes that don't understand [`Command::Hello`].
+    pub fn legacy() -> Self {
+        Self {
+            version: 0,
+            capabilities: vec![],
+        }
+    }
+
+    /// Check whether the given capability is supported.
+    pub fn supports(&self, capability: &Capability) -> bool {
+        self.capabilities.contains(capability)
+    }
+}
+
 /// Peer public protocol address.
 #[derive(Wrapper, WrapperMut, Clone, Eq, PartialEq, Debug, Hash, From, Serialize, Deserialize)]
 #[wrapper(Deref, Display, FromStr)]
@@ -529,8 +622,11 @@ impl From<Address> for HostName {
 }
 
 /// Command name.
+///
+/// Fields that aren't known are rejected, so that the node doesn't silently ignore a part
+/// of the request that it doesn't understand.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase", tag = "command")]
+#[serde(rename_all = "camelCase", tag = "command", deny_unknown_fields)]
 pub enum Command {
     /// Announce repository references for given repository to peers.
     #[serde(rename_all = "camelCase")]
@@ -576,6 +672,12 @@ pub enum Command {
         rid: RepoId,
         nid: NodeId,
         timeout: time::Duration,
+        /// Stream [`FetchProgress`] events before the result.
+        #[serde(default, skip_serializing_if = "crate::serde_ext::is_default")]
+        progress: bool,
+        /// The classes of references to fetch.
+        #[serde(default, skip_serializing_if = "crate::serde_ext::is_default")]
+        refs: RefClass,
     },
 
     /// Seed the given repository.
@@ -594,20 +696,48 @@ pub enum Command {
     #[serde(rename_all = "camelCase")]
     Unfollow { nid: NodeId },
 
+    /// Ban a node or a range of IP addresses.
+    #[serde(rename_all = "camelCase")]
+    Ban { ban: ban::Ban },
+
+    /// Lift the ban of a node or a range of IP addresses.
+    #[serde(rename_all = "camelCase")]
+    Unban { target: ban::Target },
+
+    /// Get the active bans.
+    Bans,
+
     /// Get the node's status.
     Status,
 
     /// Get node debug information.
     Debug,
 
+    /// Get the time of the next scheduled sync.
+    NextSync,
+
+    /// Get the repositories being fetched.
+    Fetching,
+
     /// Get the node's NID.
     NodeId,
 
     /// Shutdown the node.
-    Shutdown,
+    #[serde(rename_all = "camelCase")]
+    Shutdown {
+        /// Stream [`ShutdownPhase`] updates before the result.
+        #[serde(default, skip_serializing_if = "crate::serde_ext::is_default")]
+        progress: bool,
+    },
 
     /// Subscribe to events.
     Subscribe,
+
+    /// Exchange protocol version and capabilities with the node.
+    ///
+    /// Unlike other commands, this one can be followed by another command on the same
+    /// connection.
+    Hello(Hello),
 }
 
 impl Command {
@@ -790,6 +920,52 @@ pub enum AnnounceEvent {
     Announced,
 }
 
+/// The classes of references to fetch from each remote.
+///
+/// The `rad/id` and `rad/sigrefs` references, as well as the identity COB, are always
+/// fetched, since they are needed to validate the remote's references.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RefClass {
+    /// Fetch all references.
+    #[default]
+    All,
+    /// Fetch code only, ie. branches and tags.
+    CodeOnly,
+    /// Fetch collaborative objects only.
+    CobsOnly,
+}
+
+impl RefClass {
+    /// Check whether `refname`, relative to a remote's namespace, is skipped when
+    /// fetching this class of references. `refs/rad` references and the identity COB are
+    /// never skipped.
+    pub fn is_skipped(&self, refname: &git::RefStr) -> bool {
+        let refname = refname.as_str();
+
+        if refname.starts_with("refs/rad/") || refname.starts_with("refs/cobs/xyz.radicle.id/") {
+            return false;
+        }
+        match self {
+            Self::All => false,
+            Self::CodeOnly => {
+                !(refname.starts_with("refs/heads/") || refname.starts_with("refs/tags/"))
+            }
+            Self::CobsOnly => !refname.starts_with("refs/cobs/"),
+        }
+    }
+}
+
+impl fmt::Display for RefClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::All => write!(f, "all"),
+            Self::CodeOnly => write!(f, "code-only"),
+            Self::CobsOnly => write!(f, "cobs-only"),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(tag = "status", rename_all = "camelCase")]
 pub enum FetchResult {
@@ -829,6 +1005,56 @@ impl FetchResult {
     }
 }
 
+/// A line of the response to a [`Command::Fetch`] with progress enabled.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FetchResponse {
+    /// The fetch made progress.
+    Progress(FetchProgress),
+    /// The fetch completed. This is always the last line of the response.
+    Result(FetchResult),
+}
+
+/// Phase of a graceful node shutdown.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "phase")]
+pub enum ShutdownPhase {
+    /// New work is refused, and ongoing fetches are given a chance to complete.
+    #[serde(rename_all = "camelCase")]
+    Draining {
+        /// Number of fetches still in progress.
+        fetches: usize,
+    },
+    /// Disconnecting from peers.
+    #[serde(rename_all = "camelCase")]
+    Disconnecting {
+        /// Number of peers being disconnected.
+        peers: usize,
+    },
+    /// The node is about to exit.
+    Exiting,
+}
+
+impl fmt::Display for ShutdownPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Draining { fetches } => write!(f, "waiting for {fetches} fetch(es) to complete"),
+            Self::Disconnecting { peers } => write!(f, "disconnecting from {peers} peer(s)"),
+            Self::Exiting => write!(f, "exiting"),
+        }
+    }
+}
+
+/// A line of the response to a [`Command::Shutdown`] with progress enabled.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ShutdownResponse {
+    /// The shutdown entered a new phase, or is still in the same phase.
+    Progress(ShutdownPhase),
+    /// The node is shutting down. This is always the last line of the response.
+    Result(Success),
+}
+
 impl<S: ToString> From<Result<(Vec<RefUpdate>, HashSet<NodeId>, bool), S>> for FetchResult {
     fn from(value: Result<(Vec<RefUpdate>, HashSet<NodeId>, bool), S>) -> Self {
         match value {
@@ -933,6 +1159,16 @@ pub enum Error {
     Connect(PathBuf, io::ErrorKind),
     #[error("command error: {reason}")]
     Command { reason: String },
+    #[error("command not understood by node: {reason}")]
+    UnknownCommand { reason: String },
+    #[error(
+        "node does not support `{capability}` (control protocol version {version}), \
+        try upgrading it"
+    )]
+    Unsupported {
+        capability: Capability,
+        version: u32,
+    },
     #[error("received invalid json `{response}` in response to command: {error}")]
     InvalidJson {
         response: String,
@@ -991,6 +1227,28 @@ pub trait Handle: Clone + Sync + Send {
         from: NodeId,
         timeout: time::Duration,
     ) -> Result<FetchResult, Self::Error>;
+    /// Fetch a repository from the network, calling `progress` as the fetch
+    /// makes progress. By default, no progress is reported.
+    fn fetch_with_progress(
+        &mut self,
+        id: RepoId,
+        from: NodeId,
+        timeout: time::Duration,
+        progress: &mut dyn FnMut(FetchProgress),
+    ) -> Result<FetchResult, Self::Error> {
+        let _ = progress;
+        self.fetch(id, from, timeout)
+    }
+    /// Fetch the given class of references of a repository from the network, calling
+    /// `progress` as the fetch makes progress.
+    fn fetch_refs(
+        &mut self,
+        id: RepoId,
+        from: NodeId,
+        timeout: time::Duration,
+        refs: RefClass,
+        progress: &mut dyn FnMut(FetchProgress),
+    ) -> Result<FetchResult, Self::Error>;
     /// Start seeding the given repo. May update the scope. Does nothing if the
     /// repo is already seeded.
     fn seed(&mut self, id: RepoId, scope: policy::Scope) -> Result<bool, Self::Error>;
@@ -1000,6 +1258,13 @@ pub trait Handle: Clone + Sync + Send {
     fn unseed(&mut self, id: RepoId) -> Result<bool, Self::Error>;
     /// Unfollow the given peer.
     fn unfollow(&mut self, id: NodeId) -> Result<bool, Self::Error>;
+    /// Ban a node or a range of IP addresses. Connections to and from the banned target
+    /// are refused, and existing connections are closed.
+    fn ban(&mut self, ban: ban::Ban) -> Result<bool, Self::Error>;
+    /// Lift the ban of a node or a range of IP addresses.
+    fn unban(&mut self, target: ban::Target) -> Result<bool, Self::Error>;
+    /// Get the active bans.
+    fn bans(&self) -> Result<Vec<ban::Ban>, Self::Error>;
     /// Notify the service that a project has been updated, and announce local refs.
     fn announce_refs(&mut self, id: RepoId) -> Result<RefsAt, Self::Error>;
     /// Announce local inventory.
@@ -1008,6 +1273,15 @@ pub trait Handle: Clone + Sync + Send {
     fn add_inventory(&mut self, rid: RepoId) -> Result<bool, Self::Error>;
     /// Ask the service to shutdown.
     fn shutdown(self) -> Result<(), Self::Error>;
+    /// Ask the service to shutdown, calling `progress` as the shutdown makes progress.
+    /// By default, no progress is reported.
+    fn shutdown_with_progress(
+        self,
+        progress: &mut dyn FnMut(ShutdownPhase),
+    ) -> Result<(), Self::Error> {
+        let _ = progress;
+        self.shutdown()
+    }
     /// Query the peer session state.
     fn sessions(&self) -> Result<Self::Sessions, Self::Error>;
     /// Query the state of a peer session. Returns [`None`] if no session was found.
@@ -1016,6 +1290,10 @@ pub trait Handle: Clone + Sync + Send {
     fn subscribe(&self, timeout: time::Duration) -> Result<Self::Events, Self::Error>;
     /// Return debug information as a JSON value.
     fn debug(&self) -> Result<json::Value, Self::Error>;
+    /// Get the time of the next scheduled sync, or [`None`] if scheduled syncs are disabled.
+    fn next_sync(&self) -> Result<Option<Timestamp>, Self::Error>;
+    /// Get the repositories currently being fetched.
+    fn fetching(&self) -> Result<Vec<RepoId>, Self::Error>;
 }
 
 /// Iterator of results `T` when passing a [`Command`] to [`Node::call`].
@@ -1053,7 +1331,17 @@ impl<T: DeserializeOwned> Iterator for LineIter<T> {
                 };
                 match result {
                     CommandResult::Okay(result) => Some(Ok(result)),
-                    CommandResult::Error { reason } => Some(Err(Error::Command { reason })),
+                    CommandResult::Error {
+                        reason,
+                        kind: Some(CommandErrorKind::UnknownCommand),
+                    } => Some(Err(Error::UnknownCommand { reason })),
+                    // Nodes that predate error kinds fail to parse commands they don't know.
+                    CommandResult::Error { reason, kind: None }
+                        if reason.contains("unknown variant") =>
+                    {
+                        Some(Err(Error::UnknownCommand { reason }))
+                    }
+                    CommandResult::Error { reason, .. } => Some(Err(Error::Command { reason })),
                 }
             }
             Err(e) => match e.kind() {
@@ -1087,8 +1375,7 @@ impl Node {
         cmd: Command,
         timeout: time::Duration,
     ) -> Result<LineIter<T>, Error> {
-        let stream = UnixStream::connect(&self.socket)
-            .map_err(|e| Error::Connect(self.socket.clone(), e.kind()))?;
+        let stream = self.connect()?;
         cmd.to_writer(&stream)?;
         Ok(LineIter {
             stream: BufReader::new(stream),
@@ -1097,6 +1384,65 @@ impl Node {
         })
     }
 
+    /// Call a command that requires the given capability on the node.
+    ///
+    /// Returns [`Error::Unsupported`] without sending the command if the node doesn't
+    /// advertise the capability.
+    pub fn call_with<T: DeserializeOwned + Send + 'static>(
+        &self,
+        cmd: Command,
+        capability: Capability,
+        timeout: time::Duration,
+    ) -> Result<LineIter<T>, Error> {
+        let stream = self.connect()?;
+        let mut lines = LineIter::<Hello> {
+            stream: BufReader::new(stream),
+            timeout: DEFAULT_TIMEOUT,
+            witness: PhantomData,
+        };
+        let hello = Self::handshake(&mut lines)?;
+        if !hello.supports(&capability) {
+            return Err(Error::Unsupported {
+                capability,
+                version: hello.version,
+            });
+        }
+        cmd.to_writer(lines.stream.get_ref())?;
+
+        Ok(LineIter {
+            stream: lines.stream,
+            timeout,
+            witness: PhantomData,
+        })
+    }
+
+    /// Get the node's control protocol version and capabilities.
+    pub fn hello(&self) -> Result<Hello, Error> {
+        let stream = self.connect()?;
+
+        Self::handshake(&mut LineIter {
+            stream: BufReader::new(stream),
+            timeout: DEFAULT_TIMEOUT,
+            witness: PhantomData,
+        })
+    }
+
+    fn connect(&self) -> Result<UnixStream, Error> {
+        UnixStream::connect(&self.socket).map_err(|e| Error::Connect(self.socket.clone(), e.kind()))
+    }
+
+    fn handshake(lines: &mut LineIter<Hello>) -> Result<Hello, Error> {
+        Command::Hello(Hello::local()).to_writer(lines.stream.get_ref())?;
+
+        match lines.next() {
+            Some(Ok(hello)) => Ok(hello),
+            // Nodes that predate the hello exchange don't know the command, and hang up.
+            Some(Err(Error::UnknownCommand { .. })) => Ok(Hello::legacy()),
+            Some(Err(e)) => Err(e),
+            None => Err(Error::EmptyResponse),
+        }
+    }
+
     /// Announce refs of the given `rid` to the given seeds.
     /// Waits for the seeds to acknowledge the refs or times out if no acknowledgments are received
     /// within the given time.
@@ -1254,6 +1600,8 @@ impl Handle for Node {
                     rid,
                     nid: from,
                     timeout,
+                    progress: false,
+                    refs: RefClass::All,
                 },
                 DEFAULT_TIMEOUT.max(timeout),
             )?
@@ -1263,6 +1611,61 @@ impl Handle for Node {
         Ok(result)
     }
 
+    fn fetch_with_progress(
+        &mut self,
+        rid: RepoId,
+        from: NodeId,
+        timeout: time::Duration,
+        progress: &mut dyn FnMut(FetchProgress),
+    ) -> Result<FetchResult, Error> {
+        let lines = self.call_with::<FetchResponse>(
+            Command::Fetch {
+                rid,
+                nid: from,
+                timeout,
+                progress: true,
+                refs: RefClass::All,
+            },
+            Capability::FetchProgress,
+            DEFAULT_TIMEOUT.max(timeout),
+        )?;
+        for line in lines {
+            match line? {
+                FetchResponse::Progress(p) => progress(p),
+                FetchResponse::Result(result) => return Ok(result),
+            }
+        }
+        Err(Error::EmptyResponse)
+    }
+
+    fn fetch_refs(
+        &mut self,
+        rid: RepoId,
+        from: NodeId,
+        timeout: time::Duration,
+        refs: RefClass,
+        progress: &mut dyn FnMut(FetchProgress),
+    ) -> Result<FetchResult, Error> {
+        let lines = self.call_with::<FetchResponse>(
+            Command::Fetch {
+                rid,
+                nid: from,
+                timeout,
+                progress: true,
+                refs,
+            },
+            Capability::FetchRefs,
+            DEFAULT_TIMEOUT.max(timeout),
+        )?;
+        for line in lines {
+            match line? {
+                FetchResponse::Progress(p) => progress(p),
+                FetchResponse::Result(result) => return Ok(result),
+            }
+        }
+        Err(Error::EmptyResponse)
+    }
+
     fn follow(&mut self, nid: NodeId, alias: Option<Alias>) -> Result<bool, Error> {
         let mut lines = self.call::<Success>(Command::Follow { nid, alias }, DEFAULT_TIMEOUT)?;
         let response = lines.next().ok_or(Error::EmptyResponse)??;
@@ -1291,6 +1694,29 @@ impl Handle for Node {
         Ok(response.updated)
     }
 
+    fn ban(&mut self, ban: ban::Ban) -> Result<bool, Error> {
+        let mut lines = self.call::<Success>(Command::Ban { ban }, DEFAULT_TIMEOUT)?;
+        let response = lines.next().ok_or(Error::EmptyResponse)??;
+
+        Ok(response.updated)
+    }
+
+    fn unban(&mut self, target: ban::Target) -> Result<bool, Error> {
+        let mut lines = self.call::<Success>(Command::Unban { target }, DEFAULT_TIMEOUT)?;
+        let response = lines.next().ok_or(Error::EmptyResponse)??;
+
+        Ok(response.updated)
+    }
+
+    fn bans(&self) -> Result<Vec<ban::Ban>, Error> {
+        let bans = self
+            .call::<Vec<ban::Ban>>(Command::Bans, DEFAULT_TIMEOUT)?
+            .next()
+            .ok_or(Error::EmptyResponse)??;
+
+        Ok(bans)
+    }
+
     fn announce_refs(&mut self, rid: RepoId) -> Result<RefsAt, Error> {
         let refs: RefsAt = self
             .call(Command::AnnounceRefs { rid }, DEFAULT_TIMEOUT)?
@@ -1345,9 +1771,40 @@ impl Handle for Node {
         Ok(debug)
     }
 
+    fn next_sync(&self) -> Result<Option<Timestamp>, Error> {
+        self.call::<Option<Timestamp>>(Command::NextSync, DEFAULT_TIMEOUT)?
+            .next()
+            .ok_or(Error::EmptyResponse)?
+            .map_err(Error::from)
+    }
+
+    fn fetching(&self) -> Result<Vec<RepoId>, Error> {
+        self.call::<Vec<RepoId>>(Command::Fetching, DEFAULT_TIMEOUT)?
+            .next()
+            .ok_or(Error::EmptyResponse)?
+            .map_err(Error::from)
+    }
+
     fn shutdown(self) -> Result<(), Error> {
-        for line in self.call::<Success>(Command::Shutdown, DEFAULT_TIMEOUT)? {
-            line?;
+        self.shutdown_with_progress(&mut |_| {})
+    }
+
+    fn shutdown_with_progress(self, progress: &mut dyn FnMut(ShutdownPhase)) -> Result<(), Error> {
+        let lines = match self.call_with::<ShutdownResponse>(
+            Command::Shutdown { progress: true },
+            Capability::ShutdownProgress,
+            DEFAULT_TIMEOUT,
+        ) {
+            // Older nodes reply as soon as the shutdown is initiated.
+            Err(Error::Unsupported { .. }) => self
+                .call::<ShutdownResponse>(Command::Shutdown { progress: false }, DEFAULT_TIMEOUT)?,
+            result => result?,
+        };
+        for line in lines {
+            match line? {
+                ShutdownResponse::Progress(phase) => progress(phase),
+                ShutdownResponse::Result(_) => break,
+            }
         }
         // Wait until the shutdown has completed.
         while self.is_running() {
@@ -1411,6 +1868,169 @@ mod test {
         assert!(Alias::from_str("cloudhead\n").is_err());
     }
 
+    #[test]
+    fn test_fetch_command_compat() {
+        let rid = crate::test::arbitrary::gen::<RepoId>(1);
+        let nid = crate::test::arbitrary::gen::<NodeId>(1);
+        let cmd = Command::Fetch {
+            rid,
+            nid,
+            timeout: DEFAULT_TIMEOUT,
+            progress: false,
+            refs: RefClass::All,
+        };
+        let value = json::to_value(&cmd).unwrap();
+
+        // Old nodes don't know about progress or ref classes, so we don't send them
+        // unless needed.
+        assert!(value.get("progress").is_none());
+        assert!(value.get("refs").is_none());
+        assert_matches!(
+            json::from_value(value).unwrap(),
+            Command::Fetch {
+                progress: false,
+                refs: RefClass::All,
+                ..
+            }
+        );
+        let cmd = Command::Fetch {
+            rid,
+            nid,
+            timeout: DEFAULT_TIMEOUT,
+            progress: true,
+            refs: RefClass::CobsOnly,
+        };
+        let value = json::to_value(&cmd).unwrap();
+
+        assert_eq!(value.get("refs"), Some(&json::json!("cobsOnly")));
+        assert_matches!(
+            json::from_value(value).unwrap(),
+            Command::Fetch {
+                refs: RefClass::CobsOnly,
+                ..
+            }
+        );
+        assert_matches!(
+            json::from_str::<FetchResponse>(
+                &json::to_string(&FetchProgress::negotiating(rid, nid)).unwrap()
+            ),
+            Ok(FetchResponse::Progress(_))
+        );
+        assert_matches!(
+            json::from_str::<FetchResponse>(
+                &json::to_string(&FetchResult::Failed {
+                    reason: String::from("oops")
+                })
+                .unwrap()
+            ),
+            Ok(FetchResponse::Result(_))
+        );
+    }
+
+    #[test]
+    fn test_shutdown_command_compat() {
+        // Old nodes only understand the plain shutdown command.
+        assert_eq!(
+            json::to_value(Command::Shutdown { progress: false }).unwrap(),
+            json::json!({ "command": "shutdown" })
+        );
+        assert_matches!(
+            json::from_str(r#"{"command":"shutdown"}"#).unwrap(),
+            Command::Shutdown { progress: false }
+        );
+        assert_eq!(
+            json::to_string(&ShutdownPhase::Draining { fetches: 2 }).unwrap(),
+            r#"{"phase":"draining","fetches":2}"#
+        );
+        assert_matches!(
+            json::from_str::<ShutdownResponse>(r#"{"phase":"exiting"}"#),
+            Ok(ShutdownResponse::Progress(ShutdownPhase::Exiting))
+        );
+        assert_matches!(
+            json::from_str::<ShutdownResponse>(&json::to_string(&CommandResult::ok()).unwrap()),
+            Ok(ShutdownResponse::Result(_))
+        );
+    }
+
+    #[test]
+    fn test_ref_class() {
+        let skipped = |class: RefClass, name: &str| {
+            class.is_skipped(&git::RefString::try_from(name).unwrap())
+        };
+
+        for name in [
+            "refs/heads/master",
+            "refs/tags/v1.0",
+            "refs/cobs/xyz.radicle.issue/d87dcfe8c2b3200e78b128d9b959cfdf7063fefe",
+            "refs/notes/commits",
+            "refs/rad/id",
+            "refs/rad/sigrefs",
+        ] {
+            assert!(!skipped(RefClass::All, name));
+        }
+        assert!(!skipped(RefClass::CodeOnly, "refs/heads/master"));
+        assert!(!skipped(RefClass::CodeOnly, "refs/tags/v1.0"));
+        assert!(!skipped(RefClass::CodeOnly, "refs/rad/id"));
+        assert!(!skipped(RefClass::CodeOnly, "refs/rad/sigrefs"));
+        assert!(!skipped(RefClass::CodeOnly, "refs/cobs/xyz.radicle.id/a"));
+        assert!(skipped(RefClass::CodeOnly, "refs/cobs/xyz.radicle.patch/a"));
+        assert!(skipped(RefClass::CodeOnly, "refs/notes/commits"));
+
+        assert!(!skipped(
+            RefClass::CobsOnly,
+            "refs/cobs/xyz.radicle.patch/a"
+        ));
+        assert!(!skipped(RefClass::CobsOnly, "refs/rad/id"));
+        assert!(!skipped(RefClass::CobsOnly, "refs/rad/sigrefs"));
+        assert!(skipped(RefClass::CobsOnly, "refs/heads/master"));
+        assert!(skipped(RefClass::CobsOnly, "refs/tags/v1.0"));
+    }
+
+    #[test]
+    fn test_hello_compat() {
+        let hello = Hello {
+            version: 1,
+            capabilities: vec![Capability::FetchProgress],
+        };
+        assert_eq!(
+            json::to_value(Command::Hello(hello.clone())).unwrap(),
+            json::json!({
+                "command": "hello",
+                "version": 1,
+                "capabilities": ["fetch-progress"],
+            })
+        );
+        assert_eq!(
+            json::to_string(&CommandResult::Okay(hello)).unwrap(),
+            r#"{"version":1,"capabilities":["fetch-progress"]}"#
+        );
+        assert_eq!(
+            json::from_str::<Hello>(
+                r#"{"version":2,"capabilities":["fetch-progress","teleport"]}"#
+            )
+            .unwrap()
+            .capabilities,
+            vec![
+                Capability::FetchProgress,
+                Capability::Other(String::from("teleport"))
+            ]
+        );
+        assert_eq!(
+            json::to_string(&CommandResult::unknown_command(io::Error::from(
+                io::ErrorKind::InvalidData
+            )))
+            .unwrap(),
+            r#"{"error":"invalid data","kind":"unknownCommand"}"#
+        );
+        // Nodes that predate the hello exchange fail to parse it.
+        assert_matches!(
+            json::from_str::<CommandResult<Hello>>(
+                r#"{"error":"unknown variant `hello`, expected one of `announceRefs`"}"#
+            ),
+            Ok(CommandResult::Error { kind: None, .. })
+        );
+    }
+
     #[test]
     fn test_command_result() {
         #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
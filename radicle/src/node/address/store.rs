@@ -13,6 +13,9 @@ use crate::node::{Address, Alias, AliasError, AliasStore, Database, NodeId, Pena
 use crate::prelude::Timestamp;
 use crate::sql::transaction;
 
+/// Maximum number of addresses kept per node. Older addresses are pruned.
+pub const MAX_NODE_ADDRESSES: usize = 16;
+
 #[derive(Error, Debug)]
 pub enum Error {
     /// An Internal error.
@@ -262,7 +265,25 @@ impl Store for Database {
                 stmt.bind((5, &timestamp))?;
                 stmt.next()?;
             }
-            Ok::<_, Error>(db.change_count() > 0)
+            let updated = db.change_count() > 0;
+
+            // Only keep the most recently announced addresses of a node, so that a peer can't
+            // fill our address book by announcing a different set of addresses every time.
+            // Addresses we didn't learn from peers, eg. bootstrap addresses, are always kept.
+            let mut stmt = db.prepare(
+                "DELETE FROM addresses
+                 WHERE node = ?1 AND banned = 0 AND source = 'peer' AND rowid NOT IN (
+                     SELECT rowid FROM addresses
+                     WHERE node = ?1 AND banned = 0 AND source = 'peer'
+                     ORDER BY timestamp DESC, last_success DESC
+                     LIMIT ?2
+                 )",
+            )?;
+            stmt.bind((1, node))?;
+            stmt.bind((2, MAX_NODE_ADDRESSES as i64))?;
+            stmt.next()?;
+
+            Ok::<_, Error>(updated)
         })
     }
 
@@ -495,7 +516,7 @@ mod test {
 
     use super::*;
     use crate::test::arbitrary;
-    use cyphernet::addr::NetAddr;
+    use cyphernet::addr::{Addr as _, NetAddr};
     use localtime::LocalTime;
 
     #[test]
@@ -708,6 +729,72 @@ mod test {
         assert_eq!(node.agent, ua2);
     }
 
+    #[test]
+    fn test_insert_prunes_old_addresses() {
+        let alice = arbitrary::gen::<NodeId>(1);
+        let mut cache = Database::memory().unwrap();
+        let timestamp: Timestamp = LocalTime::now().into();
+        let ua = UserAgent::default();
+        let alias = Alias::new("alice");
+        let bootstrap = KnownAddress {
+            addr: net::SocketAddr::from(([7, 7, 7, 7], 8776)).into(),
+            source: Source::Bootstrap,
+            last_success: None,
+            last_attempt: None,
+            banned: false,
+        };
+        cache
+            .insert(
+                &alice,
+                1,
+                node::Features::SEED,
+                &alias,
+                0,
+                &ua,
+                timestamp,
+                [bootstrap.clone()],
+            )
+            .unwrap();
+
+        for i in 0..MAX_NODE_ADDRESSES as u16 * 2 {
+            let ka = KnownAddress {
+                addr: net::SocketAddr::from(([4, 4, 4, 4], 8776 + i)).into(),
+                source: Source::Peer,
+                last_success: None,
+                last_attempt: None,
+                banned: false,
+            };
+            cache
+                .insert(
+                    &alice,
+                    1,
+                    node::Features::SEED,
+                    &alias,
+                    0,
+                    &ua,
+                    timestamp + i as u64,
+                    [ka],
+                )
+                .unwrap();
+        }
+        let (bootstraps, peers): (Vec<_>, Vec<_>) = cache
+            .addresses_of(&alice)
+            .unwrap()
+            .into_iter()
+            .partition(|ka| ka.source == Source::Bootstrap);
+        assert_eq!(peers.len(), MAX_NODE_ADDRESSES);
+
+        // Only the most recently announced addresses are kept.
+        for ka in peers {
+            assert!(ka.addr.port() >= 8776 + MAX_NODE_ADDRESSES as u16);
+        }
+        // The bootstrap address isn't pruned, even though it is the oldest.
+        assert_eq!(
+            bootstraps.into_iter().map(|ka| ka.addr).collect::<Vec<_>>(),
+            vec![bootstrap.addr]
+        );
+    }
+
     #[test]
     fn test_insert_and_remove() {
         let alice = arbitrary::gen::<NodeId>(1);
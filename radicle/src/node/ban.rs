@@ -0,0 +1,403 @@
+//! Operator bans.
+//!
+//! Bans are set by the node operator, and refuse connections to and from a node, or from
+//! a range of IP addresses. Unlike the bans applied to misbehaving peers, they are never
+//! lifted by the node itself, except when they expire.
+pub mod store;
+pub use store::{Error, Store};
+
+use std::collections::{BTreeMap, HashMap};
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::{fmt, net};
+
+use thiserror::Error;
+
+use crate::node::{NodeId, Timestamp};
+
+/// Error parsing a ban target.
+#[derive(Error, Debug)]
+pub enum ParseError {
+    /// The target is neither a Node ID nor an IP address.
+    #[error("invalid ban target '{0}': expected a Node ID or an IP address range")]
+    Target(String),
+    /// The prefix length is too large for the address family.
+    #[error("invalid prefix length `{prefix}` for address {addr}")]
+    Prefix { addr: IpAddr, prefix: u8 },
+}
+
+/// A range of IP addresses, in CIDR notation, eg. `192.168.0.0/16`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Subnet {
+    /// Network address. Bits beyond the prefix are always zero.
+    addr: IpAddr,
+    /// Prefix length, in bits.
+    prefix: u8,
+}
+
+impl Subnet {
+    /// Create a new subnet. Bits of `addr` beyond the prefix are ignored.
+    pub fn new(addr: IpAddr, prefix: u8) -> Result<Self, ParseError> {
+        if prefix > max_prefix(&addr) {
+            return Err(ParseError::Prefix { addr, prefix });
+        }
+        let network = Self::host(addr).network(prefix);
+        let addr = match addr {
+            IpAddr::V4(_) => IpAddr::V4(net::Ipv4Addr::from(network as u32)),
+            IpAddr::V6(_) => IpAddr::V6(net::Ipv6Addr::from(network)),
+        };
+        Ok(Self { addr, prefix })
+    }
+
+    /// A subnet containing a single address.
+    pub fn host(addr: IpAddr) -> Self {
+        Self {
+            addr,
+            prefix: max_prefix(&addr),
+        }
+    }
+
+    /// Network address.
+    pub fn addr(&self) -> IpAddr {
+        self.addr
+    }
+
+    /// Prefix length, in bits.
+    pub fn prefix(&self) -> u8 {
+        self.prefix
+    }
+
+    /// Check whether the given address is part of this subnet.
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        match (self.addr, ip) {
+            (IpAddr::V4(_), IpAddr::V4(_)) | (IpAddr::V6(_), IpAddr::V6(_)) => {
+                Self::host(*ip).network(self.prefix) == bits(&self.addr)
+            }
+            _ => false,
+        }
+    }
+
+    /// Network bits of this subnet's address, given a prefix length.
+    fn network(&self, prefix: u8) -> u128 {
+        mask(bits(&self.addr), prefix, max_prefix(&self.addr))
+    }
+}
+
+impl fmt::Display for Subnet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix)
+    }
+}
+
+impl FromStr for Subnet {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseError::Target(s.to_owned());
+
+        match s.split_once('/') {
+            Some((addr, prefix)) => {
+                let addr = addr.parse::<IpAddr>().map_err(|_| err())?;
+                let prefix = prefix.parse::<u8>().map_err(|_| err())?;
+
+                Self::new(addr, prefix)
+            }
+            None => s.parse::<IpAddr>().map(Self::host).map_err(|_| err()),
+        }
+    }
+}
+
+/// What is banned.
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+#[serde(try_from = "String", into = "String")]
+pub enum Target {
+    /// A node, whatever its address.
+    Node(NodeId),
+    /// A range of IP addresses.
+    Subnet(Subnet),
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Node(nid) => nid.fmt(f),
+            Self::Subnet(subnet) => subnet.fmt(f),
+        }
+    }
+}
+
+impl FromStr for Target {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(nid) = NodeId::from_str(s) {
+            return Ok(Self::Node(nid));
+        }
+        Subnet::from_str(s).map(Self::Subnet)
+    }
+}
+
+impl From<Target> for String {
+    fn from(target: Target) -> Self {
+        target.to_string()
+    }
+}
+
+impl TryFrom<String> for Target {
+    type Error = ParseError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::from_str(&s)
+    }
+}
+
+/// A ban set by the node operator.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Ban {
+    /// What is banned.
+    pub target: Target,
+    /// Why it was banned.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// When the ban expires. Bans without an expiry are permanent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires: Option<Timestamp>,
+}
+
+impl Ban {
+    /// Create a new permanent ban.
+    pub fn new(target: Target) -> Self {
+        Self {
+            target,
+            reason: None,
+            expires: None,
+        }
+    }
+
+    /// Check whether the ban has expired at the given time.
+    pub fn is_expired(&self, now: Timestamp) -> bool {
+        self.expires.is_some_and(|t| t <= now)
+    }
+}
+
+/// Address family of a subnet.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Family {
+    V4,
+    V6,
+}
+
+/// The set of active bans, indexed for lookups on every connection.
+///
+/// Subnet bans are indexed by address family and prefix length, and then by network
+/// address. Checking an IP address thus takes one hash lookup per distinct prefix length
+/// in use, regardless of how many subnets are banned.
+#[derive(Debug, Default, Clone)]
+pub struct Bans {
+    nodes: HashMap<NodeId, Ban>,
+    subnets: BTreeMap<(Family, u8), HashMap<u128, Ban>>,
+}
+
+impl Bans {
+    /// Add a ban, replacing any existing ban of the same target.
+    pub fn insert(&mut self, ban: Ban) -> Option<Ban> {
+        match ban.target {
+            Target::Node(nid) => self.nodes.insert(nid, ban),
+            Target::Subnet(subnet) => self
+                .subnets
+                .entry((family(&subnet.addr), subnet.prefix))
+                .or_default()
+                .insert(bits(&subnet.addr), ban),
+        }
+    }
+
+    /// Remove the ban of the given target.
+    pub fn remove(&mut self, target: &Target) -> Option<Ban> {
+        match target {
+            Target::Node(nid) => self.nodes.remove(nid),
+            Target::Subnet(subnet) => {
+                let key = (family(&subnet.addr), subnet.prefix);
+                let subnets = self.subnets.get_mut(&key)?;
+                let ban = subnets.remove(&bits(&subnet.addr));
+
+                if subnets.is_empty() {
+                    self.subnets.remove(&key);
+                }
+                ban
+            }
+        }
+    }
+
+    /// Get the ban of the given node, if any. The ban may have expired.
+    pub fn node(&self, nid: &NodeId) -> Option<&Ban> {
+        self.nodes.get(nid)
+    }
+
+    /// Get a ban of a subnet containing the given address, if any. The ban may have expired.
+    pub fn ip(&self, ip: &IpAddr) -> Option<&Ban> {
+        let host = Subnet::host(*ip);
+        let family = family(ip);
+
+        self.subnets
+            .range((family, 0)..=(family, u8::MAX))
+            .find_map(|((_, prefix), subnets)| subnets.get(&host.network(*prefix)))
+    }
+
+    /// Remove all bans that have expired at the given time, returning them.
+    pub fn purge(&mut self, now: Timestamp) -> Vec<Ban> {
+        let expired = self
+            .iter()
+            .filter(|b| b.is_expired(now))
+            .map(|b| b.target)
+            .collect::<Vec<_>>();
+
+        expired
+            .into_iter()
+            .filter_map(|target| self.remove(&target))
+            .collect()
+    }
+
+    /// Iterate over all bans.
+    pub fn iter(&self) -> impl Iterator<Item = &Ban> {
+        self.nodes
+            .values()
+            .chain(self.subnets.values().flat_map(|s| s.values()))
+    }
+
+    /// Number of bans.
+    pub fn len(&self) -> usize {
+        self.nodes.len() + self.subnets.values().map(|s| s.len()).sum::<usize>()
+    }
+
+    /// Check if there are no bans.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl FromIterator<Ban> for Bans {
+    fn from_iter<I: IntoIterator<Item = Ban>>(iter: I) -> Self {
+        let mut bans = Self::default();
+        for ban in iter {
+            bans.insert(ban);
+        }
+        bans
+    }
+}
+
+fn family(addr: &IpAddr) -> Family {
+    match addr {
+        IpAddr::V4(_) => Family::V4,
+        IpAddr::V6(_) => Family::V6,
+    }
+}
+
+fn max_prefix(addr: &IpAddr) -> u8 {
+    match addr {
+        IpAddr::V4(_) => 32,
+        IpAddr::V6(_) => 128,
+    }
+}
+
+fn bits(addr: &IpAddr) -> u128 {
+    match addr {
+        IpAddr::V4(ip) => u32::from(*ip) as u128,
+        IpAddr::V6(ip) => u128::from(*ip),
+    }
+}
+
+/// Keep the first `prefix` bits of a `width`-bit address.
+fn mask(bits: u128, prefix: u8, width: u8) -> u128 {
+    let host = width.saturating_sub(prefix) as u32;
+
+    bits.checked_shr(host)
+        .and_then(|b| b.checked_shl(host))
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+    use crate::test::arbitrary;
+    use localtime::LocalTime;
+
+    #[test]
+    fn test_target_parse() {
+        let nid = arbitrary::gen::<NodeId>(1);
+
+        assert_eq!(
+            Target::from_str(&nid.to_string()).unwrap(),
+            Target::Node(nid)
+        );
+        assert_eq!(
+            Target::from_str("10.1.2.3/8").unwrap().to_string(),
+            "10.0.0.0/8"
+        );
+        assert_eq!(
+            Target::from_str("10.1.2.3").unwrap().to_string(),
+            "10.1.2.3/32"
+        );
+        assert_eq!(
+            Target::from_str("2001:db8::1/32").unwrap().to_string(),
+            "2001:db8::/32"
+        );
+        assert!(Target::from_str("10.0.0.0/33").is_err());
+        assert!(Target::from_str("seed.radicle.xyz").is_err());
+    }
+
+    #[test]
+    fn test_subnet_contains() {
+        let subnet = Subnet::from_str("192.168.0.0/16").unwrap();
+
+        assert!(subnet.contains(&[192, 168, 4, 2].into()));
+        assert!(!subnet.contains(&[192, 169, 0, 1].into()));
+        assert!(!subnet.contains(&"::ffff:c0a8:402".parse().unwrap()));
+        assert!(Subnet::from_str("0.0.0.0/0")
+            .unwrap()
+            .contains(&[8, 8, 8, 8].into()));
+    }
+
+    #[test]
+    fn test_bans_lookup() {
+        let nid = arbitrary::gen::<NodeId>(1);
+        let mut bans = [
+            Ban::new(Target::Node(nid)),
+            Ban::new(Target::from_str("10.0.0.0/8").unwrap()),
+            Ban::new(Target::from_str("172.16.4.1").unwrap()),
+            Ban::new(Target::from_str("2001:db8::/32").unwrap()),
+        ]
+        .into_iter()
+        .collect::<Bans>();
+
+        assert_eq!(bans.len(), 4);
+        assert!(bans.node(&nid).is_some());
+        assert!(bans.ip(&[10, 9, 8, 7].into()).is_some());
+        assert!(bans.ip(&[172, 16, 4, 1].into()).is_some());
+        assert!(bans.ip(&[172, 16, 4, 2].into()).is_none());
+        assert!(bans.ip(&"2001:db8:1::1".parse().unwrap()).is_some());
+        assert!(bans.ip(&"2001:db9::1".parse().unwrap()).is_none());
+
+        bans.remove(&Target::from_str("10.0.0.0/8").unwrap());
+        assert!(bans.ip(&[10, 9, 8, 7].into()).is_none());
+        assert_eq!(bans.len(), 3);
+    }
+
+    #[test]
+    fn test_bans_purge() {
+        let now = Timestamp::from(LocalTime::from_secs(1000));
+        let mut expiring = Ban::new(Target::from_str("10.0.0.0/8").unwrap());
+        expiring.expires = Some(now + 1);
+        let permanent = Ban::new(Target::from_str("10.1.0.0/16").unwrap());
+        let mut bans = [expiring.clone(), permanent.clone()]
+            .into_iter()
+            .collect::<Bans>();
+
+        assert!(bans.purge(now).is_empty());
+        assert_eq!(bans.purge(now + 1), vec![expiring]);
+        assert_eq!(bans.iter().collect::<Vec<_>>(), vec![&permanent]);
+    }
+}
@@ -0,0 +1,134 @@
+use std::num::TryFromIntError;
+use std::str::FromStr;
+
+use sqlite as sql;
+use thiserror::Error;
+
+use crate::node::ban::{Ban, Target};
+use crate::node::{Database, Timestamp};
+
+#[derive(Error, Debug)]
+pub enum Error {
+    /// An Internal error.
+    #[error("internal error: {0}")]
+    Internal(#[from] sql::Error),
+    /// Invalid ban target.
+    #[error("invalid ban target: {0}")]
+    Target(#[from] super::ParseError),
+    /// Timestamp error.
+    #[error("invalid timestamp: {0}")]
+    Timestamp(#[from] TryFromIntError),
+}
+
+/// Ban store.
+///
+/// Used to persist the bans set by the node operator.
+pub trait Store {
+    /// Add a ban, replacing any existing ban of the same target.
+    /// Returns `true` if the ban was added or changed.
+    fn ban(&mut self, ban: &Ban) -> Result<bool, Error>;
+    /// Remove the ban of the given target. Returns `true` if there was such a ban.
+    fn unban(&mut self, target: &Target) -> Result<bool, Error>;
+    /// Get all bans, including expired ones.
+    fn bans(&self) -> Result<Vec<Ban>, Error>;
+    /// Remove all bans that have expired at the given time. Returns the number of bans removed.
+    fn purge_bans(&mut self, now: Timestamp) -> Result<usize, Error>;
+}
+
+impl Store for Database {
+    fn ban(&mut self, ban: &Ban) -> Result<bool, Error> {
+        let mut stmt = self.db.prepare(
+            "INSERT INTO `bans` (target, reason, expires)
+             VALUES (?1, ?2, ?3)
+             ON CONFLICT DO UPDATE
+             SET reason = ?2, expires = ?3
+             WHERE reason IS NOT ?2 OR expires IS NOT ?3",
+        )?;
+        stmt.bind((1, ban.target.to_string().as_str()))?;
+        stmt.bind((2, ban.reason.as_deref()))?;
+        stmt.bind((3, ban.expires.as_ref()))?;
+        stmt.next()?;
+
+        Ok(self.db.change_count() > 0)
+    }
+
+    fn unban(&mut self, target: &Target) -> Result<bool, Error> {
+        let mut stmt = self.db.prepare("DELETE FROM `bans` WHERE target = ?")?;
+        stmt.bind((1, target.to_string().as_str()))?;
+        stmt.next()?;
+
+        Ok(self.db.change_count() > 0)
+    }
+
+    fn bans(&self) -> Result<Vec<Ban>, Error> {
+        let stmt = self
+            .db
+            .prepare("SELECT target, reason, expires FROM `bans` ORDER BY target")?;
+        let mut bans = Vec::new();
+
+        for row in stmt.into_iter() {
+            let row = row?;
+            let target = Target::from_str(row.try_read::<&str, _>("target")?)?;
+            let reason = row.try_read::<Option<&str>, _>("reason")?;
+            let expires = row.try_read::<Option<i64>, _>("expires")?;
+
+            bans.push(Ban {
+                target,
+                reason: reason.map(ToOwned::to_owned),
+                expires: expires.map(Timestamp::try_from).transpose()?,
+            });
+        }
+        Ok(bans)
+    }
+
+    fn purge_bans(&mut self, now: Timestamp) -> Result<usize, Error> {
+        let mut stmt = self
+            .db
+            .prepare("DELETE FROM `bans` WHERE expires IS NOT NULL AND expires <= ?")?;
+        stmt.bind((1, &now))?;
+        stmt.next()?;
+
+        Ok(self.db.change_count())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+    use crate::node::NodeId;
+    use crate::test::arbitrary;
+
+    #[test]
+    fn test_bans() {
+        let mut db = Database::memory().unwrap();
+        let nid = arbitrary::gen::<NodeId>(1);
+        let node = Ban {
+            target: Target::Node(nid),
+            reason: Some(String::from("spam")),
+            expires: None,
+        };
+        let subnet = Ban {
+            target: Target::from_str("10.0.0.0/8").unwrap(),
+            reason: None,
+            expires: Some(Timestamp::EPOCH + 1000),
+        };
+
+        assert!(db.ban(&node).unwrap());
+        assert!(db.ban(&subnet).unwrap());
+        assert!(!db.ban(&subnet).unwrap());
+
+        let bans = db.bans().unwrap();
+        assert_eq!(bans.len(), 2);
+        assert!(bans.contains(&node));
+        assert!(bans.contains(&subnet));
+
+        assert_eq!(db.purge_bans(Timestamp::EPOCH + 999).unwrap(), 0);
+        assert_eq!(db.purge_bans(Timestamp::EPOCH + 1000).unwrap(), 1);
+        assert_eq!(db.bans().unwrap(), vec![node.clone()]);
+
+        assert!(db.unban(&node.target).unwrap());
+        assert!(!db.unban(&node.target).unwrap());
+        assert!(db.bans().unwrap().is_empty());
+    }
+}
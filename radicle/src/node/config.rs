@@ -1,14 +1,17 @@
 use std::collections::HashSet;
-use std::net;
 use std::ops::Deref;
+use std::path::PathBuf;
+use std::{fmt, net};
 
 use cyphernet::addr::PeerAddr;
 use localtime::LocalDuration;
 use serde_json as json;
 
+use crate::git::PatternString;
 use crate::node;
 use crate::node::policy::{Scope, SeedingPolicy};
 use crate::node::{Address, Alias, NodeId};
+use crate::prelude::RepoId;
 
 /// Peer-to-peer protocol version.
 pub type ProtocolVersion = u8;
@@ -104,6 +107,12 @@ pub struct Limits {
     /// How long to keep a gossip message entry before pruning it.
     #[serde(with = "crate::serde_ext::localtime::duration")]
     pub gossip_max_age: LocalDuration,
+    /// How long to keep the namespaces of unfollowed peers before pruning them.
+    #[serde(
+        default = "defaults::namespace_max_age",
+        with = "crate::serde_ext::localtime::duration"
+    )]
+    pub namespace_max_age: LocalDuration,
     /// Maximum number of concurrent fetches per peer connection.
     pub fetch_concurrency: usize,
     /// Maximum number of open files.
@@ -114,6 +123,12 @@ pub struct Limits {
     /// Connection limits.
     #[serde(default)]
     pub connection: ConnectionLimits,
+    /// How long to wait for ongoing fetches to complete when shutting down.
+    #[serde(
+        default = "defaults::shutdown_timeout",
+        with = "crate::serde_ext::localtime::duration"
+    )]
+    pub shutdown_timeout: LocalDuration,
 }
 
 impl Default for Limits {
@@ -122,10 +137,112 @@ impl Default for Limits {
             routing_max_size: 1000,
             routing_max_age: LocalDuration::from_mins(7 * 24 * 60), // One week
             gossip_max_age: LocalDuration::from_mins(2 * 7 * 24 * 60), // Two weeks
+            namespace_max_age: defaults::namespace_max_age(),
             fetch_concurrency: 1,
             max_open_files: 4096,
             rate: RateLimits::default(),
             connection: ConnectionLimits::default(),
+            shutdown_timeout: defaults::shutdown_timeout(),
+        }
+    }
+}
+
+/// Scheduled sync configuration.
+///
+/// When enabled, the node periodically fetches the repositories it seeds from connected
+/// seeds, so that it catches up on updates it missed while offline.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledSync {
+    /// Whether scheduled syncs are enabled.
+    pub enabled: bool,
+    /// Time between sync rounds. Repositories fetched more recently than this are
+    /// skipped.
+    #[serde(with = "crate::serde_ext::localtime::duration")]
+    pub interval: LocalDuration,
+    /// Maximum number of seeds to fetch each repository from, per round.
+    pub seeds: usize,
+}
+
+impl Default for ScheduledSync {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval: LocalDuration::from_mins(60),
+            seeds: 2,
+        }
+    }
+}
+
+/// Metrics configuration.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Metrics {
+    /// Address to serve metrics on, in the Prometheus text format.
+    pub listen: net::SocketAddr,
+}
+
+/// Repository event that can trigger a hook.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HookEvent {
+    /// A branch or tag was created or updated.
+    RefUpdated,
+    /// A patch was opened.
+    PatchOpened,
+    /// An issue was created.
+    IssueCreated,
+}
+
+impl fmt::Display for HookEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RefUpdated => write!(f, "ref-updated"),
+            Self::PatchOpened => write!(f, "patch-opened"),
+            Self::IssueCreated => write!(f, "issue-created"),
+        }
+    }
+}
+
+/// A command that is run when repository events occur.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Hook {
+    /// Events that trigger the hook.
+    pub events: Vec<HookEvent>,
+    /// Repositories the hook is restricted to. If empty, it runs for all repositories.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rids: Vec<RepoId>,
+    /// Path to the executable to run. The event is passed to it as JSON on its standard input.
+    pub command: PathBuf,
+}
+
+/// Repository event hooks.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Hooks {
+    /// Maximum number of hooks running at the same time. Events that would run more
+    /// hooks are dropped.
+    pub concurrency: usize,
+    /// Maximum number of times each hook is run per minute. Further events are dropped.
+    pub rate: usize,
+    /// Configured hooks.
+    pub commands: Vec<Hook>,
+}
+
+impl Hooks {
+    /// Whether no hooks are configured.
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+}
+
+impl Default for Hooks {
+    fn default() -> Self {
+        Self {
+            concurrency: 4,
+            rate: 60,
+            commands: vec![],
         }
     }
 }
@@ -312,10 +429,14 @@ pub struct Config {
     /// Connections to these peers will be maintained.
     #[serde(default)]
     pub connect: HashSet<ConnectAddress>,
+    /// Seeds to prefer when choosing where to fetch from. These are tried before any
+    /// other seed, regardless of how fetches from them went in the past.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub preferred_seeds: Vec<NodeId>,
     /// Specify the node's public addresses
     #[serde(default)]
     pub external_addresses: Vec<Address>,
-    /// Global proxy.
+    /// Global SOCKS5 proxy, used for outbound connections. Host names are resolved by the proxy.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub proxy: Option<net::SocketAddr>,
     /// Onion address config.
@@ -340,6 +461,19 @@ pub struct Config {
     /// Default seeding policy.
     #[serde(default)]
     pub seeding_policy: DefaultSeedingPolicy,
+    /// Scheduled sync of seeded repositories.
+    #[serde(default)]
+    pub sync: ScheduledSync,
+    /// Reference patterns, relative to a remote's namespace, that are never fetched,
+    /// unless allowed by a repository's reference policy, eg. `refs/heads/tmp/*`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blocked_refs: Vec<PatternString>,
+    /// Metrics listener. Metrics are not served unless this is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<Metrics>,
+    /// Commands to run when repositories change.
+    #[serde(default, skip_serializing_if = "Hooks::is_empty")]
+    pub hooks: Hooks,
     /// Extra fields that aren't supported.
     #[serde(flatten, skip_serializing)]
     pub extra: json::Map<String, json::Value>,
@@ -359,6 +493,7 @@ impl Config {
             peers: PeerConfig::default(),
             listen: vec![],
             connect: HashSet::default(),
+            preferred_seeds: vec![],
             external_addresses: vec![],
             network: Network::default(),
             proxy: None,
@@ -368,6 +503,10 @@ impl Config {
             workers: DEFAULT_WORKERS,
             log: defaults::log(),
             seeding_policy: DefaultSeedingPolicy::default(),
+            sync: ScheduledSync::default(),
+            blocked_refs: vec![],
+            metrics: None,
+            hooks: Hooks::default(),
             extra: json::Map::default(),
         }
     }
@@ -399,7 +538,7 @@ impl Config {
     }
 
     pub fn features(&self) -> node::Features {
-        node::Features::SEED
+        node::Features::SEED | node::Features::RENDEZVOUS
     }
 }
 
@@ -414,4 +553,14 @@ mod defaults {
     pub fn log() -> log::Level {
         log::Level::Info
     }
+
+    /// Namespace retention of unfollowed peers.
+    pub fn namespace_max_age() -> localtime::LocalDuration {
+        localtime::LocalDuration::from_mins(30 * 24 * 60) // One month
+    }
+
+    /// Time to wait for ongoing fetches on shutdown.
+    pub fn shutdown_timeout() -> localtime::LocalDuration {
+        localtime::LocalDuration::from_secs(30)
+    }
 }
@@ -0,0 +1,17 @@
+-- Fetch statistics of peers, used to rank seeds when choosing where to fetch from.
+create table if not exists "peer-stats" (
+  -- Node ID.
+  --
+  -- Nb. We don't use a foreign key constraint here, because we may fetch from
+  -- nodes we haven't received a node announcement from.
+  "node"               text      primary key not null,
+  -- Number of successful fetches from this node.
+  "fetches_ok"         integer   not null default 0,
+  -- Number of failed fetches from this node.
+  "fetches_failed"     integer   not null default 0,
+  -- Average duration of successful fetches, in milliseconds.
+  "latency"            integer   not null default 0,
+  -- Local time at which we last fetched from this node.
+  "last_seen"          integer   not null
+  --
+) strict;
@@ -0,0 +1,11 @@
+-- Bans set by the node operator.
+create table if not exists "bans" (
+  -- Banned Node ID, or IP address range in CIDR notation.
+  "target"             text      primary key not null,
+  -- Why the target was banned.
+  "reason"             text,
+  -- Time at which the ban expires, in milliseconds since epoch.
+  -- Bans without an expiry are permanent.
+  "expires"            integer
+  --
+) strict;
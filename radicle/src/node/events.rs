@@ -1,6 +1,9 @@
 //! Events for `upload-pack` processes.
 pub mod upload_pack;
 pub use upload_pack::UploadPack;
+/// Progress of fetches initiated by the local node.
+pub mod fetch;
+pub use fetch::FetchProgress;
 
 use std::ops::Deref;
 use std::sync::Arc;
@@ -69,6 +72,10 @@ pub enum Event {
         features: node::Features,
         addresses: Vec<node::Address>,
     },
+    SyncRound {
+        rids: Vec<RepoId>,
+        next: Timestamp,
+    },
     UploadPack(upload_pack::UploadPack),
 }
 
@@ -0,0 +1,59 @@
+use crate::node::NodeId;
+use crate::prelude::RepoId;
+
+/// Progress of a fetch initiated by the local node.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", tag = "progress")]
+pub enum FetchProgress {
+    /// Negotiating with the remote which objects need to be fetched.
+    #[serde(rename_all = "camelCase")]
+    Negotiating {
+        /// The repository being fetched.
+        rid: RepoId,
+        /// The node being fetched from.
+        remote: NodeId,
+    },
+    /// Receiving objects from the remote.
+    #[serde(rename_all = "camelCase")]
+    Receiving {
+        /// The repository being fetched.
+        rid: RepoId,
+        /// The node being fetched from.
+        remote: NodeId,
+        /// The number of objects received so far.
+        objects: usize,
+        /// The number of packfile bytes received so far.
+        bytes: usize,
+    },
+}
+
+impl FetchProgress {
+    /// Construct a `FetchProgress::Negotiating` event.
+    pub fn negotiating(rid: RepoId, remote: NodeId) -> Self {
+        Self::Negotiating { rid, remote }
+    }
+
+    /// Construct a `FetchProgress::Receiving` event.
+    pub fn receiving(rid: RepoId, remote: NodeId, objects: usize, bytes: usize) -> Self {
+        Self::Receiving {
+            rid,
+            remote,
+            objects,
+            bytes,
+        }
+    }
+
+    /// The repository being fetched.
+    pub fn rid(&self) -> &RepoId {
+        match self {
+            Self::Negotiating { rid, .. } | Self::Receiving { rid, .. } => rid,
+        }
+    }
+
+    /// The node being fetched from.
+    pub fn remote(&self) -> &NodeId {
+        match self {
+            Self::Negotiating { remote, .. } | Self::Receiving { remote, .. } => remote,
+        }
+    }
+}
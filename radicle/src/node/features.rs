@@ -14,6 +14,10 @@ impl Features {
     /// `SEED` is the base feature set all seed nodes must support.
     pub const SEED: Features = Features(0b00000001);
 
+    /// `RENDEZVOUS` means the node understands rendezvous messages, used to help
+    /// nodes behind NAT connect to each other via a common peer.
+    pub const RENDEZVOUS: Features = Features(0b00000010);
+
     /// Returns [`Features`] with the other features added.
     #[must_use]
     pub fn with(self, other: Features) -> Features {
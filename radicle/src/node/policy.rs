@@ -7,6 +7,7 @@ use std::str::FromStr;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::git::PatternString;
 use crate::prelude::RepoId;
 
 pub use super::{Alias, NodeId};
@@ -34,6 +35,17 @@ pub struct FollowPolicy {
     pub policy: Policy,
 }
 
+/// Reference policy of a seeded repository.
+///
+/// The `pattern` is matched against reference names relative to a remote's
+/// namespace, eg. `refs/heads/tmp/*`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RefsPolicy {
+    pub rid: RepoId,
+    pub pattern: PatternString,
+    pub policy: Policy,
+}
+
 /// Seeding policy of a node or repo.
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase", tag = "policy")]
@@ -12,7 +12,9 @@ use crate::storage::{Namespaces, ReadRepository as _, ReadStorage, RepositoryErr
 pub use crate::node::policy::store;
 pub use crate::node::policy::store::Error;
 pub use crate::node::policy::store::Store;
-pub use crate::node::policy::{Alias, FollowPolicy, Policy, Scope, SeedPolicy, SeedingPolicy};
+pub use crate::node::policy::{
+    Alias, FollowPolicy, Policy, RefsPolicy, Scope, SeedPolicy, SeedingPolicy,
+};
 
 #[derive(Debug, Error)]
 pub enum NamespacesError {
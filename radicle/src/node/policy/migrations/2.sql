@@ -0,0 +1,11 @@
+-- Repository reference policies.
+create table if not exists "refs" (
+  -- Repository ID.
+  "repo"               text      not null,
+  -- Reference pattern, relative to a remote's namespace, eg. `refs/heads/tmp/*`.
+  "pattern"            text      not null,
+  -- Policy for the references matching the pattern.
+  "policy"             text      default 'block',
+  --
+  primary key ("repo", "pattern")
+) strict;
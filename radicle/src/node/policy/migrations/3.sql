@@ -0,0 +1,8 @@
+-- Nodes that were unfollowed, and whose namespaces haven't been pruned yet.
+create table if not exists "unfollowed" (
+  -- Node ID.
+  "id"                 text      primary key not null,
+  -- When the node was unfollowed, in milliseconds since the epoch.
+  "timestamp"          integer   not null
+  --
+) strict;
@@ -6,10 +6,14 @@ use std::{fmt, io, ops::Not as _, str::FromStr, time};
 use sqlite as sql;
 use thiserror::Error;
 
+use localtime::LocalTime;
+
+use crate::git::{PatternStr, PatternString};
 use crate::node::{Alias, AliasStore};
-use crate::prelude::{NodeId, RepoId};
+use crate::prelude::{NodeId, RepoId, Timestamp};
+use crate::sql::transaction;
 
-use super::{FollowPolicy, Policy, Scope, SeedPolicy, SeedingPolicy};
+use super::{FollowPolicy, Policy, RefsPolicy, Scope, SeedPolicy, SeedingPolicy};
 
 /// How long to wait for the database lock to be released before failing a read.
 const DB_READ_TIMEOUT: time::Duration = time::Duration::from_secs(3);
@@ -26,6 +30,14 @@ pub enum Error {
     Internal(#[from] sql::Error),
 }
 
+/// Database migrations.
+/// The first migration is the initial schema, before the database was versioned.
+const MIGRATIONS: &[&str] = &[
+    include_str!("migrations/1.sql"),
+    include_str!("migrations/2.sql"),
+    include_str!("migrations/3.sql"),
+];
+
 /// Read-only type witness.
 pub struct Read;
 /// Read-write type witness.
@@ -49,15 +61,14 @@ impl<T> fmt::Debug for Store<T> {
 }
 
 impl Store<Read> {
-    const SCHEMA: &'static str = include_str!("schema.sql");
-
     /// Same as [`Self::open`], but in read-only mode. This is useful to have multiple
     /// open databases, as no locking is required.
+    ///
+    /// The database is expected to have been migrated when it was opened for writing.
     pub fn reader<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
         let mut db =
             sql::Connection::open_with_flags(path, sqlite::OpenFlags::new().with_read_only())?;
         db.set_busy_timeout(DB_READ_TIMEOUT.as_millis() as usize)?;
-        db.execute(Self::SCHEMA)?;
 
         Ok(Self {
             db,
@@ -67,28 +78,17 @@ impl Store<Read> {
 
     /// Create a new in-memory address book.
     pub fn memory() -> Result<Self, Error> {
-        let db = sql::Connection::open_with_flags(
-            ":memory:",
-            sqlite::OpenFlags::new().with_read_only(),
-        )?;
-        db.execute(Self::SCHEMA)?;
-
-        Ok(Self {
-            db,
-            _marker: PhantomData,
-        })
+        Store::<Write>::memory().map(Store::read_only)
     }
 }
 
 impl Store<Write> {
-    const SCHEMA: &'static str = include_str!("schema.sql");
-
     /// Open a policy store at the given path. Creates a new store if it
     /// doesn't exist.
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
         let mut db = sql::Connection::open(path)?;
         db.set_busy_timeout(DB_WRITE_TIMEOUT.as_millis() as usize)?;
-        db.execute(Self::SCHEMA)?;
+        migrate(&db)?;
 
         Ok(Self {
             db,
@@ -99,7 +99,7 @@ impl Store<Write> {
     /// Create a new in-memory address book.
     pub fn memory() -> Result<Self, Error> {
         let db = sql::Connection::open(":memory:")?;
-        db.execute(Self::SCHEMA)?;
+        migrate(&db)?;
 
         Ok(Self {
             db,
@@ -117,6 +117,8 @@ impl Store<Write> {
 
     /// Follow a node.
     pub fn follow(&mut self, id: &NodeId, alias: Option<&str>) -> Result<bool, Error> {
+        self.forget_unfollowed(id)?;
+
         let mut stmt = self.db.prepare(
             "INSERT INTO `following` (id, alias)
              VALUES (?1, ?2)
@@ -179,13 +181,76 @@ impl Store<Write> {
         Ok(self.db.change_count() > 0)
     }
 
+    /// Set a repository's policy for the references matching `pattern`.
+    pub fn set_refs_policy(
+        &mut self,
+        id: &RepoId,
+        pattern: &PatternStr,
+        policy: Policy,
+    ) -> Result<bool, Error> {
+        let mut stmt = self.db.prepare(
+            "INSERT INTO `refs` (repo, pattern, policy)
+             VALUES (?1, ?2, ?3)
+             ON CONFLICT DO UPDATE
+             SET policy = ?3 WHERE policy != ?3",
+        )?;
+
+        stmt.bind((1, id))?;
+        stmt.bind((2, pattern.as_str()))?;
+        stmt.bind((3, policy))?;
+        stmt.next()?;
+
+        Ok(self.db.change_count() > 0)
+    }
+
+    /// Remove a repository's policy for the references matching `pattern`.
+    pub fn remove_refs_policy(&mut self, id: &RepoId, pattern: &PatternStr) -> Result<bool, Error> {
+        let mut stmt = self
+            .db
+            .prepare("DELETE FROM `refs` WHERE repo = ? AND pattern = ?")?;
+
+        stmt.bind((1, id))?;
+        stmt.bind((2, pattern.as_str()))?;
+        stmt.next()?;
+
+        Ok(self.db.change_count() > 0)
+    }
+
     /// Unfollow a node.
+    ///
+    /// The time at which the node was unfollowed is recorded, so that its namespaces can be
+    /// pruned once they are no longer needed. See [`Store::unfollowed`].
     pub fn unfollow(&mut self, id: &NodeId) -> Result<bool, Error> {
         let mut stmt = self.db.prepare("DELETE FROM `following` WHERE id = ?")?;
 
         stmt.bind((1, id))?;
         stmt.next()?;
 
+        if self.db.change_count() == 0 {
+            return Ok(false);
+        }
+        let mut stmt = self.db.prepare(
+            "INSERT INTO `unfollowed` (id, timestamp)
+             VALUES (?1, ?2)
+             ON CONFLICT DO UPDATE
+             SET timestamp = ?2",
+        )?;
+
+        stmt.bind((1, id))?;
+        stmt.bind((2, &Timestamp::from(LocalTime::now())))?;
+        stmt.next()?;
+
+        Ok(true)
+    }
+
+    /// Forget that a node was unfollowed, eg. because its namespaces were pruned, or because
+    /// it was followed again.
+    pub fn forget_unfollowed(&mut self, id: &NodeId) -> Result<bool, Error> {
+        let mut stmt = self.db.prepare("DELETE FROM `unfollowed` WHERE id = ?")?;
+
+        stmt.bind((1, id))?;
+        stmt.next()?;
+
         Ok(self.db.change_count() > 0)
     }
 
@@ -341,6 +406,49 @@ impl<T> Store<T> {
         }
         Ok(Box::new(entries.into_iter()))
     }
+
+    /// Get the nodes that were unfollowed, and when, ordered from least to most recent.
+    pub fn unfollowed(&self) -> Result<Vec<(NodeId, Timestamp)>, Error> {
+        let stmt = self
+            .db
+            .prepare("SELECT id, timestamp FROM `unfollowed` ORDER BY timestamp")?;
+
+        let mut entries = Vec::new();
+        for row in stmt.into_iter() {
+            let row = row?;
+            let id = row.read::<NodeId, _>("id");
+            let timestamp = row.read::<Timestamp, _>("timestamp");
+
+            entries.push((id, timestamp));
+        }
+        Ok(entries)
+    }
+
+    /// Get a repository's reference policies.
+    pub fn refs_policies(&self, id: &RepoId) -> Result<Vec<RefsPolicy>, Error> {
+        let mut stmt = self
+            .db
+            .prepare("SELECT pattern, policy FROM `refs` WHERE repo = ?")?;
+        stmt.bind((1, id))?;
+
+        let mut entries = Vec::new();
+        for row in stmt.into_iter() {
+            let row = row?;
+            let pattern = row.read::<&str, _>("pattern");
+            let pattern = PatternString::try_from(pattern).map_err(|e| sql::Error {
+                code: None,
+                message: Some(format!("sql: invalid reference pattern '{pattern}': {e}")),
+            })?;
+            let policy = row.read::<Policy, _>("policy");
+
+            entries.push(RefsPolicy {
+                rid: *id,
+                pattern,
+                policy,
+            });
+        }
+        Ok(entries)
+    }
 }
 
 impl<T> AliasStore for Store<T> {
@@ -353,12 +461,44 @@ impl<T> AliasStore for Store<T> {
     }
 }
 
+/// Get the `user_version` value from the database header.
+fn version(db: &sql::Connection) -> Result<usize, Error> {
+    let version = db
+        .prepare("PRAGMA user_version")?
+        .into_iter()
+        .next()
+        .transpose()?
+        .map_or(0, |row| row.read::<i64, _>(0));
+
+    Ok(version as usize)
+}
+
+/// Migrate the database to the latest schema.
+///
+/// Since all migrations only create missing tables, databases that were created before they
+/// were versioned start from the first migration.
+fn migrate(db: &sql::Connection) -> Result<usize, Error> {
+    let mut version = version(db)?;
+
+    for (i, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        transaction(db, |db| {
+            db.execute(migration)?;
+            db.execute(format!("PRAGMA user_version = {}", i + 1))?;
+
+            Ok::<_, Error>(())
+        })?;
+        version = i + 1;
+    }
+    Ok(version)
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod test {
     use crate::assert_matches;
 
     use super::*;
+    use crate::git;
     use crate::test::arbitrary;
 
     #[test]
@@ -373,6 +513,45 @@ mod test {
         assert!(!db.is_following(&id).unwrap());
     }
 
+    #[test]
+    fn test_unfollowed() {
+        let alice = arbitrary::gen::<NodeId>(1);
+        let bob = arbitrary::gen::<NodeId>(1);
+        let mut db = Store::open(":memory:").unwrap();
+
+        assert!(!db.unfollow(&alice).unwrap());
+        assert!(db.unfollowed().unwrap().is_empty());
+
+        db.follow(&alice, None).unwrap();
+        db.follow(&bob, None).unwrap();
+        assert!(db.unfollow(&alice).unwrap());
+        assert!(db.unfollow(&bob).unwrap());
+
+        let unfollowed = db
+            .unfollowed()
+            .unwrap()
+            .into_iter()
+            .map(|(nid, _)| nid)
+            .collect::<Vec<_>>();
+        assert_eq!(unfollowed.len(), 2);
+        assert!(unfollowed.contains(&alice));
+        assert!(unfollowed.contains(&bob));
+
+        // Following a node again means its namespaces must be kept.
+        db.follow(&alice, None).unwrap();
+        assert_eq!(
+            db.unfollowed()
+                .unwrap()
+                .into_iter()
+                .map(|(nid, _)| nid)
+                .collect::<Vec<_>>(),
+            vec![bob]
+        );
+        assert!(db.forget_unfollowed(&bob).unwrap());
+        assert!(!db.forget_unfollowed(&bob).unwrap());
+        assert!(db.unfollowed().unwrap().is_empty());
+    }
+
     #[test]
     fn test_seed_and_unseed_repo() {
         let id = arbitrary::gen::<RepoId>(1);
@@ -462,6 +641,61 @@ mod test {
         assert_eq!(db.seed_policy(&id).unwrap().unwrap().scope(), None);
     }
 
+    #[test]
+    fn test_refs_policies() {
+        let id = arbitrary::gen::<RepoId>(1);
+        let tmp = git::refspec::pattern!("refs/heads/tmp/*");
+        let issues = git::refspec::pattern!("refs/cobs/xyz.radicle.issue/*");
+        let mut db = Store::open(":memory:").unwrap();
+
+        assert!(db.refs_policies(&id).unwrap().is_empty());
+        assert!(db.set_refs_policy(&id, &tmp, Policy::Block).unwrap());
+        assert!(!db.set_refs_policy(&id, &tmp, Policy::Block).unwrap());
+        assert!(db.set_refs_policy(&id, &issues, Policy::Allow).unwrap());
+
+        let policies = db.refs_policies(&id).unwrap();
+        assert_eq!(policies.len(), 2);
+        assert!(policies
+            .iter()
+            .any(|p| p.pattern == tmp && p.policy == Policy::Block));
+        assert!(policies
+            .iter()
+            .any(|p| p.pattern == issues && p.policy == Policy::Allow));
+
+        assert!(db.remove_refs_policy(&id, &tmp).unwrap());
+        assert!(!db.remove_refs_policy(&id, &tmp).unwrap());
+        assert_eq!(db.refs_policies(&id).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_migrate_unversioned() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("policies.db");
+        let id = arbitrary::gen::<NodeId>(1);
+        let rid = arbitrary::gen::<RepoId>(1);
+        let pattern = git::refspec::pattern!("refs/heads/tmp/*");
+
+        // A database created before it was versioned.
+        {
+            let db = sql::Connection::open(&path).unwrap();
+            db.execute(MIGRATIONS[0]).unwrap();
+            db.execute(format!("INSERT INTO `following` (id) VALUES ('{id}')"))
+                .unwrap();
+        }
+        // Reading doesn't require the database to be migrated.
+        assert!(Store::reader(&path).unwrap().is_following(&id).unwrap());
+
+        let mut db = Store::open(&path).unwrap();
+        assert_eq!(version(&db.db).unwrap(), MIGRATIONS.len());
+        assert!(db.is_following(&id).unwrap());
+        assert!(db.set_refs_policy(&rid, &pattern, Policy::Block).unwrap());
+        drop(db);
+
+        let db = Store::reader(&path).unwrap();
+        assert_eq!(db.refs_policies(&rid).unwrap().len(), 1);
+        assert!(db.unfollowed().unwrap().is_empty());
+    }
+
     #[test]
     fn test_node_policy() {
         let id = arbitrary::gen::<NodeId>(1);
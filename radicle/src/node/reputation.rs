@@ -0,0 +1,121 @@
+//! Peer reputation.
+//!
+//! We keep track of how fetches from each peer went, and use this information to rank
+//! seeds when choosing where to fetch from.
+pub mod store;
+pub use store::{Error, Store};
+
+use std::cmp::Reverse;
+
+use localtime::{LocalDuration, LocalTime};
+
+/// Number of fetches from a peer under which its statistics aren't trusted. Until then, the
+/// peer is ranked as if it had never failed, so that new peers get a chance to be fetched
+/// from, instead of being starved by peers with an established track record.
+pub const EXPLORATION_THRESHOLD: usize = 3;
+
+/// Fetch statistics of a peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeerStats {
+    /// Number of successful fetches.
+    pub fetches_ok: usize,
+    /// Number of failed fetches.
+    pub fetches_failed: usize,
+    /// Average duration of successful fetches.
+    pub latency: LocalDuration,
+    /// When we last fetched from this peer.
+    pub last_seen: LocalTime,
+}
+
+impl PeerStats {
+    /// Total number of fetches.
+    pub fn fetches(&self) -> usize {
+        self.fetches_ok + self.fetches_failed
+    }
+
+    /// Ratio of successful fetches, between `0.0` and `1.0`.
+    /// Returns `None` if we never fetched from this peer.
+    pub fn success_rate(&self) -> Option<f64> {
+        let fetches = self.fetches();
+        if fetches == 0 {
+            return None;
+        }
+        Some(self.fetches_ok as f64 / fetches as f64)
+    }
+
+    /// Whether we haven't fetched from this peer enough times to trust its statistics.
+    pub fn is_exploring(&self) -> bool {
+        self.fetches() < EXPLORATION_THRESHOLD
+    }
+}
+
+/// Rank of a peer as a fetch target.
+///
+/// Peers are ranked by whether they are preferred seeds, then by their fetch success rate,
+/// and finally by their fetch latency. Better peers compare as *lesser*, so that sorting
+/// a list of ranks in ascending order puts the best peer first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Rank {
+    /// Whether the peer is a preferred seed.
+    preferred: Reverse<bool>,
+    /// Fetch success rate, in thousandths.
+    reliability: Reverse<u16>,
+    /// Average fetch latency.
+    latency: LocalDuration,
+}
+
+impl Rank {
+    /// Rank a peer given its statistics, if any.
+    pub fn new(preferred: bool, stats: Option<&PeerStats>) -> Self {
+        let (reliability, latency) = match stats {
+            Some(s) if !s.is_exploring() => (
+                s.success_rate()
+                    .map_or(1000, |r| (r * 1000.).round() as u16),
+                s.latency,
+            ),
+            Some(s) => (1000, s.latency),
+            None => (1000, LocalDuration::from_millis(0)),
+        };
+
+        Self {
+            preferred: Reverse(preferred),
+            reliability: Reverse(reliability),
+            latency,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn stats(ok: usize, failed: usize, latency: u128) -> PeerStats {
+        PeerStats {
+            fetches_ok: ok,
+            fetches_failed: failed,
+            latency: LocalDuration::from_millis(latency),
+            last_seen: LocalTime::default(),
+        }
+    }
+
+    #[test]
+    fn test_rank() {
+        let healthy = stats(9, 1, 300);
+        let fast = stats(9, 1, 100);
+        let flaky = stats(2, 8, 100);
+
+        assert!(Rank::new(false, Some(&healthy)) < Rank::new(false, Some(&flaky)));
+        assert!(Rank::new(false, Some(&fast)) < Rank::new(false, Some(&healthy)));
+        assert!(Rank::new(true, Some(&flaky)) < Rank::new(false, Some(&fast)));
+    }
+
+    #[test]
+    fn test_rank_exploration() {
+        let flaky = stats(2, 8, 100);
+        let new = stats(0, 2, 0);
+
+        assert!(new.is_exploring());
+        assert!(Rank::new(false, None) < Rank::new(false, Some(&flaky)));
+        assert!(Rank::new(false, Some(&new)) < Rank::new(false, Some(&flaky)));
+    }
+}
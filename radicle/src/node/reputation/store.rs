@@ -0,0 +1,170 @@
+#![allow(clippy::type_complexity)]
+use std::num::TryFromIntError;
+
+use localtime::{LocalDuration, LocalTime};
+use sqlite as sql;
+use thiserror::Error;
+
+use crate::node::reputation::PeerStats;
+use crate::node::{Database, NodeId};
+
+#[derive(Error, Debug)]
+pub enum Error {
+    /// An Internal error.
+    #[error("internal error: {0}")]
+    Internal(#[from] sql::Error),
+    /// Timestamp error.
+    #[error("invalid timestamp: {0}")]
+    Timestamp(#[from] TryFromIntError),
+}
+
+/// Reputation store.
+///
+/// Used to store fetch statistics of peers.
+pub trait Store {
+    /// Record a successful fetch from the given node, which took `latency` to complete.
+    fn fetch_succeeded(
+        &mut self,
+        nid: &NodeId,
+        latency: LocalDuration,
+        time: LocalTime,
+    ) -> Result<(), Error>;
+    /// Record a failed fetch from the given node.
+    fn fetch_failed(&mut self, nid: &NodeId, time: LocalTime) -> Result<(), Error>;
+    /// Get the fetch statistics of the given node, if we ever fetched from it.
+    fn stats(&self, nid: &NodeId) -> Result<Option<PeerStats>, Error>;
+    /// Get the fetch statistics of all nodes.
+    fn peer_stats(
+        &self,
+    ) -> Result<Box<dyn Iterator<Item = Result<(NodeId, PeerStats), Error>> + '_>, Error>;
+}
+
+impl Store for Database {
+    fn fetch_succeeded(
+        &mut self,
+        nid: &NodeId,
+        latency: LocalDuration,
+        time: LocalTime,
+    ) -> Result<(), Error> {
+        let mut stmt = self.db.prepare(
+            "INSERT INTO `peer-stats` (node, fetches_ok, latency, last_seen)
+             VALUES (?1, 1, ?2, ?3)
+             ON CONFLICT DO UPDATE
+             SET latency = (latency * fetches_ok + ?2) / (fetches_ok + 1),
+                 fetches_ok = fetches_ok + 1,
+                 last_seen = ?3",
+        )?;
+        stmt.bind((1, nid))?;
+        stmt.bind((2, i64::try_from(latency.as_millis())?))?;
+        stmt.bind((3, i64::try_from(time.as_millis())?))?;
+        stmt.next()?;
+
+        Ok(())
+    }
+
+    fn fetch_failed(&mut self, nid: &NodeId, time: LocalTime) -> Result<(), Error> {
+        let mut stmt = self.db.prepare(
+            "INSERT INTO `peer-stats` (node, fetches_failed, last_seen)
+             VALUES (?1, 1, ?2)
+             ON CONFLICT DO UPDATE
+             SET fetches_failed = fetches_failed + 1,
+                 last_seen = ?2",
+        )?;
+        stmt.bind((1, nid))?;
+        stmt.bind((2, i64::try_from(time.as_millis())?))?;
+        stmt.next()?;
+
+        Ok(())
+    }
+
+    fn stats(&self, nid: &NodeId) -> Result<Option<PeerStats>, Error> {
+        let mut stmt = self.db.prepare(
+            "SELECT fetches_ok, fetches_failed, latency, last_seen
+             FROM `peer-stats`
+             WHERE node = ?",
+        )?;
+        stmt.bind((1, nid))?;
+
+        if let Some(row) = stmt.into_iter().next() {
+            Ok(Some(read(&row?)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn peer_stats(
+        &self,
+    ) -> Result<Box<dyn Iterator<Item = Result<(NodeId, PeerStats), Error>> + '_>, Error> {
+        let stmt = self.db.prepare(
+            "SELECT node, fetches_ok, fetches_failed, latency, last_seen
+             FROM `peer-stats`
+             ORDER BY last_seen DESC",
+        )?;
+
+        Ok(Box::new(stmt.into_iter().map(|row| {
+            let row = row?;
+            let nid = row.try_read::<NodeId, _>("node")?;
+
+            Ok((nid, read(&row)?))
+        })))
+    }
+}
+
+/// Read peer statistics from a database row.
+fn read(row: &sql::Row) -> Result<PeerStats, Error> {
+    let fetches_ok = row.try_read::<i64, _>("fetches_ok")?;
+    let fetches_failed = row.try_read::<i64, _>("fetches_failed")?;
+    let latency = row.try_read::<i64, _>("latency")?;
+    let last_seen = row.try_read::<i64, _>("last_seen")?;
+
+    Ok(PeerStats {
+        fetches_ok: usize::try_from(fetches_ok)?,
+        fetches_failed: usize::try_from(fetches_failed)?,
+        latency: LocalDuration::from_millis(u128::try_from(latency)?),
+        last_seen: LocalTime::from_millis(u128::try_from(last_seen)?),
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+    use crate::test::arbitrary;
+
+    #[test]
+    fn test_fetch_stats() {
+        let mut db = Database::memory().unwrap();
+        let alice = arbitrary::gen::<NodeId>(1);
+        let bob = arbitrary::gen::<NodeId>(1);
+        let t = LocalTime::from_millis(1000);
+
+        assert_eq!(db.stats(&alice).unwrap(), None);
+
+        db.fetch_succeeded(&alice, LocalDuration::from_millis(100), t)
+            .unwrap();
+        db.fetch_succeeded(&alice, LocalDuration::from_millis(300), t)
+            .unwrap();
+        db.fetch_failed(&alice, t + LocalDuration::from_secs(1))
+            .unwrap();
+        db.fetch_failed(&bob, t).unwrap();
+
+        assert_eq!(
+            db.stats(&alice).unwrap(),
+            Some(PeerStats {
+                fetches_ok: 2,
+                fetches_failed: 1,
+                latency: LocalDuration::from_millis(200),
+                last_seen: t + LocalDuration::from_secs(1),
+            })
+        );
+
+        let all = db
+            .peer_stats()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].0, alice);
+        assert_eq!(all[1].1.fetches_failed, 1);
+    }
+}
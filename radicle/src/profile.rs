@@ -9,13 +9,25 @@
 //!       radicle.pub                            # Public key (PKCS 8)
 //!     node/
 //!       control.sock                           # Node control socket
+//!     profiles/                                # Named profiles
+//!       seed/                                  # A profile home, laid out like the above
+//!     profile                                  # Name of the active profile
+//!
+//! The profile home is selected as follows, in order:
+//!
+//! 1. The profile named by `RAD_PROFILE`, if set.
+//! 2. The active profile, as recorded by `rad use`.
+//! 3. The default profile, ie. `$RAD_HOME` itself.
+//!
+//! `$RAD_HOME` defaults to `$HOME/.radicle`.
 //!
 
 pub mod config;
 pub use config::{Config, ConfigError, ConfigPath, RawConfig};
 
 use std::path::{Path, PathBuf};
-use std::{fs, io};
+use std::str::FromStr;
+use std::{fmt, fs, io};
 
 use localtime::LocalTime;
 use thiserror::Error;
@@ -40,6 +52,8 @@ pub mod env {
 
     /// Path to the radicle home folder.
     pub const RAD_HOME: &str = "RAD_HOME";
+    /// Name of the profile to use, overriding the active profile.
+    pub const RAD_PROFILE: &str = "RAD_PROFILE";
     /// Path to the radicle node socket file.
     pub const RAD_SOCKET: &str = "RAD_SOCKET";
     /// Passphrase for the encrypted radicle secret key.
@@ -246,7 +260,11 @@ impl Profile {
     }
 
     pub fn load() -> Result<Self, Error> {
-        let home = self::home()?;
+        Self::open(self::home()?)
+    }
+
+    /// Load the profile found in the given home.
+    pub fn open(home: Home) -> Result<Self, Error> {
         let keystore = Keystore::new(&home.keys());
         let public_key = keystore
             .public_key()?
@@ -429,12 +447,21 @@ impl AliasStore for Aliases {
     }
 }
 
-/// Get the path to the radicle home folder.
+/// Get the path to the radicle home folder of the selected profile.
 pub fn home() -> Result<Home, io::Error> {
+    let profiles = Profiles::open()?;
+    let name = profiles.current()?;
+
+    Home::new(profiles.path(&name))
+}
+
+/// Get the path to the base radicle home folder, which holds the default profile
+/// and all named profiles.
+pub fn base() -> Result<PathBuf, io::Error> {
     if let Some(home) = env::var_os(env::RAD_HOME) {
-        Ok(Home::new(PathBuf::from(home))?)
+        Ok(PathBuf::from(home))
     } else if let Some(home) = env::var_os("HOME") {
-        Ok(Home::new(PathBuf::from(home).join(".radicle"))?)
+        Ok(PathBuf::from(home).join(".radicle"))
     } else {
         Err(io::Error::new(
             io::ErrorKind::NotFound,
@@ -443,6 +470,189 @@ pub fn home() -> Result<Home, io::Error> {
     }
 }
 
+/// Error parsing a profile name.
+#[derive(Error, Debug)]
+pub enum ProfileNameError {
+    #[error("profile name cannot be empty")]
+    Empty,
+    #[error(
+        "profile name cannot be greater than {} bytes",
+        ProfileName::MAX_LENGTH
+    )]
+    MaxBytesExceeded,
+    #[error("profile name can only contain alphanumeric characters, '-' and '_'")]
+    InvalidCharacter,
+}
+
+/// Profile name.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct ProfileName(String);
+
+impl ProfileName {
+    /// Maximum length of a profile name, in bytes.
+    pub const MAX_LENGTH: usize = 32;
+
+    /// Check whether this is the default profile.
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Return a reference to the name string.
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl Default for ProfileName {
+    /// The default profile, which lives in the base radicle home.
+    fn default() -> Self {
+        Self(String::from("default"))
+    }
+}
+
+impl fmt::Display for ProfileName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl FromStr for ProfileName {
+    type Err = ProfileNameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ProfileNameError::Empty);
+        }
+        if s.len() > Self::MAX_LENGTH {
+            return Err(ProfileNameError::MaxBytesExceeded);
+        }
+        if !s
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(ProfileNameError::InvalidCharacter);
+        }
+        Ok(Self(s.to_owned()))
+    }
+}
+
+/// Profiles kept under a base radicle home.
+#[derive(Debug, Clone)]
+pub struct Profiles {
+    base: PathBuf,
+}
+
+impl Profiles {
+    /// Directory holding the named profiles, relative to the base home.
+    pub const PROFILES_DIR: &'static str = "profiles";
+    /// File recording the active profile, relative to the base home.
+    pub const ACTIVE_FILE: &'static str = "profile";
+
+    /// Create a new [`Profiles`] object, from the base radicle home.
+    pub fn new(base: impl Into<PathBuf>) -> Self {
+        Self { base: base.into() }
+    }
+
+    /// Open the profiles of the base radicle home set in the environment.
+    pub fn open() -> Result<Self, io::Error> {
+        base().map(Self::new)
+    }
+
+    /// Get the home path of the given profile.
+    pub fn path(&self, name: &ProfileName) -> PathBuf {
+        if name.is_default() {
+            self.base.clone()
+        } else {
+            self.base.join(Self::PROFILES_DIR).join(name.as_str())
+        }
+    }
+
+    /// Check whether the given profile was initialized.
+    pub fn exists(&self, name: &ProfileName) -> bool {
+        Keystore::new(&self.path(name).join("keys"))
+            .public_key()
+            .ok()
+            .flatten()
+            .is_some()
+    }
+
+    /// List the initialized profiles, with the default profile first.
+    pub fn list(&self) -> Result<Vec<ProfileName>, io::Error> {
+        let mut names = Vec::new();
+        let dir = self.base.join(Self::PROFILES_DIR);
+
+        if dir.is_dir() {
+            for entry in fs::read_dir(dir)? {
+                let entry = entry?;
+                let Some(name) = entry
+                    .file_name()
+                    .to_str()
+                    .and_then(|n| ProfileName::from_str(n).ok())
+                else {
+                    continue;
+                };
+                if !name.is_default() && self.exists(&name) {
+                    names.push(name);
+                }
+            }
+        }
+        names.sort();
+
+        let default = ProfileName::default();
+        if self.exists(&default) {
+            names.insert(0, default);
+        }
+        Ok(names)
+    }
+
+    /// Get the active profile, as recorded in the base home.
+    pub fn active(&self) -> Result<Option<ProfileName>, io::Error> {
+        let name = match fs::read_to_string(self.base.join(Self::ACTIVE_FILE)) {
+            Ok(name) => name,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let name = ProfileName::from_str(name.trim())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        Ok(Some(name))
+    }
+
+    /// Record the active profile. Switching to the default profile clears the record.
+    pub fn set_active(&self, name: &ProfileName) -> Result<(), io::Error> {
+        let path = self.base.join(Self::ACTIVE_FILE);
+
+        if name.is_default() {
+            match fs::remove_file(path) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            }
+        } else {
+            fs::create_dir_all(&self.base)?;
+            fs::write(path, format!("{name}\n"))
+        }
+    }
+
+    /// Get the selected profile: the one named by `RAD_PROFILE`, or else the active
+    /// profile, or else the default profile.
+    pub fn current(&self) -> Result<ProfileName, io::Error> {
+        self.select(env::var(env::RAD_PROFILE).ok())
+    }
+
+    /// Select a profile given the value of `RAD_PROFILE`.
+    fn select(&self, var: Option<String>) -> Result<ProfileName, io::Error> {
+        if let Some(name) = var {
+            return ProfileName::from_str(&name).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("invalid `{}` value: {e}", env::RAD_PROFILE),
+                )
+            });
+        }
+        Ok(self.active()?.unwrap_or_else(ProfileName::default))
+    }
+}
+
 /// Radicle home.
 #[derive(Debug, Clone)]
 pub struct Home {
@@ -692,6 +902,74 @@ mod test {
         assert_eq!(home.path, path);
     }
 
+    #[test]
+    fn test_profiles_switch() {
+        let tmp = tempfile::tempdir().unwrap();
+        let profiles = Profiles::new(tmp.path());
+        let seed = ProfileName::from_str("seed").unwrap();
+
+        assert_eq!(profiles.select(None).unwrap(), ProfileName::default());
+        assert_eq!(profiles.path(&ProfileName::default()), tmp.path());
+        assert_eq!(
+            profiles.path(&seed),
+            tmp.path().join("profiles").join("seed")
+        );
+
+        profiles.set_active(&seed).unwrap();
+        assert_eq!(profiles.active().unwrap(), Some(seed.clone()));
+        assert_eq!(profiles.select(None).unwrap(), seed);
+
+        profiles.set_active(&ProfileName::default()).unwrap();
+        assert_eq!(profiles.active().unwrap(), None);
+        assert_eq!(profiles.select(None).unwrap(), ProfileName::default());
+    }
+
+    #[test]
+    fn test_profiles_env_override() {
+        let tmp = tempfile::tempdir().unwrap();
+        let profiles = Profiles::new(tmp.path());
+        let seed = ProfileName::from_str("seed").unwrap();
+        let work = ProfileName::from_str("work").unwrap();
+
+        // The environment variable takes precedence over the active profile.
+        profiles.set_active(&seed).unwrap();
+        assert_eq!(profiles.select(Some("work".to_owned())).unwrap(), work);
+        assert_eq!(
+            profiles.select(Some("default".to_owned())).unwrap(),
+            ProfileName::default()
+        );
+        assert_eq!(profiles.select(None).unwrap(), seed);
+        assert!(profiles.select(Some("../seed".to_owned())).is_err());
+    }
+
+    #[test]
+    fn test_profiles_list() {
+        let tmp = tempfile::tempdir().unwrap();
+        let profiles = Profiles::new(tmp.path());
+        let alias = Alias::new("alice");
+
+        assert!(profiles.list().unwrap().is_empty());
+
+        for name in ["work", "default", "seed"] {
+            let name = ProfileName::from_str(name).unwrap();
+            let home = Home::new(profiles.path(&name)).unwrap();
+
+            Profile::init(home, alias.clone(), None, crypto::Seed::generate()).unwrap();
+        }
+        // Not initialized, so not listed.
+        Home::new(profiles.path(&ProfileName::from_str("empty").unwrap())).unwrap();
+
+        assert_eq!(
+            profiles
+                .list()
+                .unwrap()
+                .iter()
+                .map(|n| n.as_str())
+                .collect::<Vec<_>>(),
+            vec!["default", "seed", "work"]
+        );
+    }
+
     #[test]
     fn test_config() {
         let cfg = json::from_value::<Config>(json::json!({
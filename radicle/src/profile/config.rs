@@ -207,9 +207,18 @@ impl RawConfig {
         &mut self,
         config_path: &ConfigPath,
         value: ConfigValue,
+    ) -> Result<json::Value, ModifyError> {
+        self.set_json(config_path, value.into())
+    }
+
+    /// Set the element at the given path to an arbitrary JSON value, eg. an array.
+    pub fn set_json(
+        &mut self,
+        config_path: &ConfigPath,
+        value: json::Value,
     ) -> Result<json::Value, ModifyError> {
         if let Some(element) = self.get_mut(config_path) {
-            *element = value.into();
+            *element = value;
             Ok(element.clone())
         } else {
             self.upsert(config_path, value)
@@ -218,15 +227,25 @@ impl RawConfig {
 
     /// Writes the configuration, including extra values, to disk. Errors if the config is not
     /// valid.
+    ///
+    /// The configuration is first written to a temporary file next to `path`, which then
+    /// replaces it, so that the file is never left half-written.
     pub fn write(&self, path: &Path) -> Result<(), ConfigError> {
         let _valid_config: Config = self.clone().try_into()?;
+        let mut name = path.file_name().unwrap_or_default().to_owned();
+        name.push(".tmp");
+
+        let tmp = path.with_file_name(name);
         let file = fs::OpenOptions::new()
             .create(true)
             .write(true)
             .truncate(true)
-            .open(path)?;
+            .open(&tmp)?;
 
-        self.write_file(file)
+        self.write_file(file)?;
+        fs::rename(tmp, path)?;
+
+        Ok(())
     }
 
     /// Write to an open file.
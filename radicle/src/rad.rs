@@ -16,7 +16,7 @@ use crate::storage::git::transport;
 use crate::storage::git::Repository;
 use crate::storage::refs::SignedRefs;
 use crate::storage::RepositoryError;
-use crate::storage::{BranchName, ReadRepository as _, RemoteId, SignRepository as _};
+use crate::storage::{BranchName, ReadRepository, RemoteId, SignRepository as _};
 use crate::storage::{WriteRepository, WriteStorage};
 use crate::{identity, storage};
 
@@ -68,7 +68,9 @@ pub fn init<G: Signer, S: WriteStorage>(
                 .join(", "),
         )
     })?;
-    let doc = identity::Doc::initial(proj, delegate, visibility);
+    let doc = identity::Doc::initial(proj, delegate, visibility)
+        .edit()
+        .validated(&identity::Validators::default())?;
     let (project, identity) = Repository::init(&doc, &storage, signer)?;
     let url = git::Url::from(project.id);
 
@@ -132,6 +134,37 @@ pub enum ForkError {
     NotFound(RepoId),
     #[error("repository: {0}")]
     Repository(#[from] RepositoryError),
+    #[error(
+        "branch `{branch}` was not found, available branches are: {}",
+        available.iter().map(|r| r.as_str()).collect::<Vec<_>>().join(", ")
+    )]
+    BranchNotFound {
+        branch: BranchName,
+        available: Vec<BranchName>,
+    },
+}
+
+/// Options for [`fork_with`].
+#[derive(Debug, Clone)]
+pub struct ForkOptions {
+    /// Peer whose view of the repository is forked. If `None`, the canonical branches are
+    /// forked.
+    pub from: Option<RemoteId>,
+    /// Branches to fork. If empty, only the default branch is forked.
+    pub branches: Vec<BranchName>,
+    /// Whether to fork the COB refs of the peer we're forking from. Since COBs don't have
+    /// a canonical state, this only applies when forking from a peer.
+    pub cobs: bool,
+}
+
+impl Default for ForkOptions {
+    fn default() -> Self {
+        Self {
+            from: None,
+            branches: vec![],
+            cobs: true,
+        }
+    }
 }
 
 /// Create a local tree for an existing project, from an existing remote.
@@ -170,25 +203,117 @@ pub fn fork_remote<G: Signer, S: storage::WriteStorage>(
     Ok(())
 }
 
+/// Fork a repository, creating our namespace from the canonical default branch.
 pub fn fork<G: Signer, S: storage::WriteStorage>(
     rid: RepoId,
     signer: &G,
     storage: &S,
 ) -> Result<(), ForkError> {
+    fork_with(rid, ForkOptions::default(), signer, storage).map(|_| ())
+}
+
+/// Fork a repository, creating only the refs selected by the given options in our namespace.
+/// Returns the refs that were created.
+pub fn fork_with<G: Signer, S: storage::WriteStorage>(
+    rid: RepoId,
+    options: ForkOptions,
+    signer: &G,
+    storage: &S,
+) -> Result<Vec<(git::Qualified<'static>, git::Oid)>, ForkError> {
     let me = signer.public_key();
     let repository = storage.repository_mut(rid)?;
-    let (canonical_branch, canonical_head) = repository.head()?;
     let raw = repository.raw();
+    let mut refs = Vec::new();
+
+    if options.from.is_none() && options.branches.is_empty() {
+        let (name, oid) = repository.head()?;
+        refs.push((name.to_owned(), oid));
+    } else {
+        let doc = repository.identity_doc()?;
+        let branches = if options.branches.is_empty() {
+            vec![doc.project()?.default_branch().clone()]
+        } else {
+            options.branches
+        };
+        // The peers whose branches are available to fork.
+        let sources = match &options.from {
+            Some(remote) => vec![*remote],
+            None => doc.delegates().iter().map(|did| **did).collect(),
+        };
+
+        for branch in branches {
+            let name = git::refs::branch(&branch).to_owned();
+            let oid = match &options.from {
+                Some(remote) => match repository.reference_oid(remote, &name) {
+                    Ok(oid) => Some(oid),
+                    Err(e) if git::ext::is_not_found_err(&e) => None,
+                    Err(e) => return Err(e.into()),
+                },
+                None => {
+                    let canonical = git::canonical::Canonical::reference(
+                        &repository,
+                        doc.delegates().into(),
+                        &name,
+                    )?;
+                    if canonical.tips().next().is_none() {
+                        None
+                    } else {
+                        let oid = canonical
+                            .quorum(doc.threshold(), raw)
+                            .map_err(RepositoryError::from)?;
+                        Some(oid)
+                    }
+                }
+            };
+            let Some(oid) = oid else {
+                return Err(ForkError::BranchNotFound {
+                    branch,
+                    available: branches_of(&repository, &sources)?,
+                });
+            };
+            refs.push((name, oid));
+        }
+        if let (Some(remote), true) = (&options.from, options.cobs) {
+            for (name, oid) in repository.references_of(remote)?.iter() {
+                if name.as_str().starts_with("refs/cobs/") {
+                    if let Some(name) = git::Qualified::from_refstr(name) {
+                        refs.push((name.to_owned(), *oid));
+                    }
+                }
+            }
+        }
+    }
 
-    raw.reference(
-        &canonical_branch.with_namespace(me.into()),
-        *canonical_head,
-        true,
-        &format!("creating default branch for {me}"),
-    )?;
+    for (name, oid) in &refs {
+        raw.reference(
+            &name.with_namespace(me.into()),
+            **oid,
+            true,
+            &format!("creating {name} for {me}"),
+        )?;
+    }
     repository.sign_refs(signer)?;
 
-    Ok(())
+    Ok(refs)
+}
+
+/// Get the branches of the given remotes, without duplicates.
+fn branches_of<R: ReadRepository>(
+    repository: &R,
+    remotes: &[RemoteId],
+) -> Result<Vec<BranchName>, ForkError> {
+    let mut branches = std::collections::BTreeSet::new();
+
+    for remote in remotes {
+        for name in repository.references_of(remote)?.keys() {
+            if let Some(branch) = name.as_str().strip_prefix("refs/heads/") {
+                if let Ok(branch) = BranchName::try_from(branch) {
+                    branches.insert(branch);
+                }
+            }
+        }
+    }
+    Ok(branches.into_iter().collect())
 }
 
 #[derive(Error, Debug)]
@@ -398,7 +523,7 @@ mod tests {
     use crate::identity::Did;
     use crate::storage::git::transport;
     use crate::storage::git::Storage;
-    use crate::storage::{ReadStorage, RemoteRepository as _};
+    use crate::storage::{ReadStorage, RemoteRepository as _, SignRepository as _};
     use crate::test::fixtures;
 
     use super::*;
@@ -494,6 +619,117 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_fork_with() {
+        let mut rng = fastrand::Rng::new();
+        let tempdir = tempfile::tempdir().unwrap();
+        let alice = MockSigner::new(&mut rng);
+        let alice_id = alice.public_key();
+        let bob = MockSigner::new(&mut rng);
+        let eve = MockSigner::new(&mut rng);
+        let storage = Storage::open(tempdir.path().join("storage"), fixtures::user()).unwrap();
+
+        transport::local::register(storage.clone());
+
+        // Alice creates a project, with a `dev` branch and a COB.
+        let (original, head) = fixtures::repository(tempdir.path().join("original"));
+        let (id, _, _) = init(
+            &original,
+            "acme".try_into().unwrap(),
+            "Acme's repo",
+            git::refname!("master"),
+            Visibility::default(),
+            &alice,
+            &storage,
+        )
+        .unwrap();
+        let repo = storage.repository(id).unwrap();
+        let cob =
+            git::refname!("refs/cobs/xyz.radicle.issue/d96f425412c9f8ad5d9a9a05c9831d0728e2338d");
+        for name in [git::refname!("refs/heads/dev"), cob.clone()] {
+            repo.raw()
+                .reference(
+                    &format!("refs/namespaces/{alice_id}/{name}"),
+                    head,
+                    false,
+                    "",
+                )
+                .unwrap();
+        }
+        repo.sign_refs(&alice).unwrap();
+
+        // Returns the refs of a peer, except for the `rad` refs created by signing.
+        let refs_of = |remote: &RemoteId| {
+            repo.references_of(remote)
+                .unwrap()
+                .iter()
+                .filter(|(name, _)| !name.as_str().starts_with("refs/rad/"))
+                .map(|(name, oid)| (name.clone(), *oid))
+                .collect::<Vec<_>>()
+        };
+
+        // Bob forks only the canonical `dev` branch.
+        let created = fork_with(
+            id,
+            ForkOptions {
+                branches: vec![git::refname!("dev")],
+                ..ForkOptions::default()
+            },
+            &bob,
+            &storage,
+        )
+        .unwrap();
+        assert_eq!(created, vec![(qualified!("refs/heads/dev"), head.into())]);
+        assert_eq!(
+            refs_of(bob.public_key()),
+            vec![(git::refname!("refs/heads/dev"), head.into())]
+        );
+
+        // Eve forks Alice's default branch and COBs.
+        fork_with(
+            id,
+            ForkOptions {
+                from: Some(*alice_id),
+                ..ForkOptions::default()
+            },
+            &eve,
+            &storage,
+        )
+        .unwrap();
+        let identity = repo.identity().unwrap().head();
+        assert_eq!(
+            refs_of(eve.public_key()),
+            vec![
+                (
+                    git::RefString::try_from(format!("refs/cobs/xyz.radicle.id/{identity}"))
+                        .unwrap(),
+                    identity
+                ),
+                (cob, head.into()),
+                (git::refname!("refs/heads/master"), head.into())
+            ]
+        );
+
+        // Forking a branch that doesn't exist lists the available branches.
+        let err = fork_with(
+            id,
+            ForkOptions {
+                from: Some(*alice_id),
+                branches: vec![git::refname!("feature")],
+                cobs: false,
+            },
+            &bob,
+            &storage,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ForkError::BranchNotFound { branch, available }
+            if branch == git::refname!("feature")
+                && available == vec![git::refname!("dev"), git::refname!("master")]
+        ));
+    }
+
     #[test]
     fn test_checkout() {
         let tempdir = tempfile::tempdir().unwrap();
@@ -119,6 +119,8 @@ pub enum RepositoryError {
     Quorum(#[from] canonical::QuorumError),
     #[error(transparent)]
     Refs(#[from] refs::Error),
+    #[error("refusing to prune the namespace of delegate {0}")]
+    DelegateNamespace(RemoteId),
 }
 
 impl RepositoryError {
@@ -650,6 +652,15 @@ pub trait WriteRepository: ReadRepository + SignRepository {
     fn set_identity_head_to(&self, commit: Oid) -> Result<(), RepositoryError>;
     /// Set the user info of the Git repository.
     fn set_user(&self, info: &UserInfo) -> Result<(), Error>;
+    /// Remove all references under the namespace of the given remote, in a single
+    /// transaction. Returns the number of references removed.
+    ///
+    /// The namespaces of delegates are never pruned, and attempting to do so returns
+    /// [`RepositoryError::DelegateNamespace`].
+    ///
+    /// N.b. the objects referenced by the namespace are left in the object database
+    /// until the next garbage collection.
+    fn prune_namespace(&self, remote: &RemoteId) -> Result<usize, RepositoryError>;
     /// Get the underlying git repository.
     fn raw(&self) -> &git2::Repository;
 }
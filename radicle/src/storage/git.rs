@@ -1,5 +1,6 @@
 #![warn(clippy::unwrap_used)]
 pub mod cob;
+pub mod crypt;
 pub mod transport;
 
 use std::collections::{BTreeMap, BTreeSet, HashMap};
@@ -11,7 +12,7 @@ use crypto::{Signer, Verified};
 use once_cell::sync::Lazy;
 use tempfile::TempDir;
 
-use crate::git::canonical::Canonical;
+use crate::git::canonical::{Canonical, CanonicalBranch};
 use crate::identity::doc::DocError;
 use crate::identity::{Doc, DocAt, RepoId};
 use crate::identity::{Identity, Project};
@@ -380,6 +381,82 @@ impl Repository {
         Ok(())
     }
 
+    /// Open the vault holding this repository's objects encrypted at rest. See
+    /// [`crypt::Vault::seal`] to opt the repository into encryption.
+    pub fn vault(&self, key: crypt::RepoKey) -> crypt::Vault {
+        crypt::Vault::open(self.backend.path().join(crypt::VAULT_DIR), key)
+    }
+
+    /// Run `f` with the sealed objects of this repository readable. Objects written by `f`
+    /// are sealed once it returns. See [`crypt::Vault::mount`].
+    pub fn mount<T, F>(&self, key: crypt::RepoKey, f: F) -> Result<T, crypt::Error>
+    where
+        F: FnOnce(&Self) -> T,
+    {
+        self.vault(key).mount(&self.backend, |_| f(self))
+    }
+
+    /// Check whether this repository's objects are encrypted at rest.
+    pub fn is_sealed(&self) -> bool {
+        self.backend
+            .path()
+            .join(crypt::VAULT_DIR)
+            .join(crypt::SEALED_FILE)
+            .is_file()
+    }
+
+    /// Run the Git garbage collector, removing unreachable objects right away,
+    /// eg. after pruning a namespace.
+    pub fn gc(&self) -> Result<(), io::Error> {
+        git::run::<_, _, &str, &str>(self.backend.path(), ["gc", "--prune=now", "--quiet"], [])?;
+
+        Ok(())
+    }
+
+    /// Size on disk of the repository's object database, in bytes.
+    pub fn size(&self) -> Result<u64, io::Error> {
+        fn walk(dir: &Path) -> Result<u64, io::Error> {
+            let mut size = 0;
+            for entry in fs::read_dir(dir)? {
+                let entry = entry?;
+                let meta = entry.metadata()?;
+
+                if meta.is_dir() {
+                    size += walk(&entry.path())?;
+                } else {
+                    size += meta.len();
+                }
+            }
+            Ok(size)
+        }
+        walk(&self.backend.path().join("objects"))
+    }
+
+    /// Get the remotes of this repository that are neither the local peer, a delegate
+    /// of the repository, or one of the given followed peers.
+    pub fn untracked(
+        &self,
+        local: &RemoteId,
+        followed: &BTreeSet<RemoteId>,
+    ) -> Result<Vec<RemoteId>, RepositoryError> {
+        let delegates = self
+            .delegates()?
+            .into_iter()
+            .map(|did| *did)
+            .collect::<BTreeSet<_>>();
+        let mut untracked = Vec::new();
+
+        for id in self.remote_ids()? {
+            let id = id?;
+
+            if *local == id || delegates.contains(&id) || followed.contains(&id) {
+                continue;
+            }
+            untracked.push(id);
+        }
+        Ok(untracked)
+    }
+
     /// Remove all the remotes of a repository that are not the
     /// delegates of the repository or the local peer.
     ///
@@ -490,6 +567,26 @@ impl Repository {
         Ok(proj)
     }
 
+    /// Compute the canonical tip of the given branch from the delegates' tips and the
+    /// repository threshold, along with the position of each delegate's tip relative to it.
+    pub fn canonical_branch(
+        &self,
+        branch: &git::RefStr,
+    ) -> Result<CanonicalBranch, RepositoryError> {
+        let doc = self.identity_doc()?;
+        let name = git::refs::branch(branch);
+        let raw = self.raw();
+        let canonical = Canonical::reference(self, doc.delegates().into(), &name)?;
+        let head = canonical.quorum(doc.threshold(), raw)?;
+        let positions = canonical.positions(head, raw)?;
+
+        Ok(CanonicalBranch {
+            name,
+            head,
+            positions,
+        })
+    }
+
     pub fn identity_doc_of(&self, remote: &RemoteId) -> Result<Doc, DocError> {
         let oid = self.identity_head_of(remote)?;
         Doc::load_at(oid, self).map(|d| d.into())
@@ -879,6 +976,36 @@ impl WriteRepository for Repository {
         Ok(())
     }
 
+    fn prune_namespace(&self, remote: &RemoteId) -> Result<usize, RepositoryError> {
+        // N.b. it is fatal to delete delegates, whether they are followed or not.
+        if self.delegates()?.iter().any(|did| **did == *remote) {
+            return Err(RepositoryError::DelegateNamespace(*remote));
+        }
+        let mut refs = Vec::new();
+        for r in self
+            .backend
+            .references_glob(format!("refs/namespaces/{remote}/*").as_str())?
+        {
+            let r = r?;
+            let name = r.name().ok_or(Error::InvalidRef)?;
+
+            refs.push(name.to_owned());
+        }
+        if refs.is_empty() {
+            return Ok(0);
+        }
+        let mut tx = self.backend.transaction()?;
+        for name in &refs {
+            tx.lock_ref(name)?;
+            tx.remove(name)?;
+        }
+        tx.commit()?;
+
+        log::debug!(target: "storage", "Pruned {} reference(s) of {remote} in {}", refs.len(), self.id);
+
+        Ok(refs.len())
+    }
+
     fn raw(&self) -> &git2::Repository {
         &self.backend
     }
@@ -1027,6 +1154,122 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_prune_namespace() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut rng = fastrand::Rng::new();
+        let signer = MockSigner::new(&mut rng);
+        let storage = Storage::open(tmp.path().join("storage"), fixtures::user()).unwrap();
+        let alice = *signer.public_key();
+        let bob = *MockSigner::new(&mut rng).public_key();
+        let eve = *MockSigner::new(&mut rng).public_key();
+
+        transport::local::register(storage.clone());
+
+        let (rid, _, _, head) =
+            fixtures::project(tmp.path().join("project"), &storage, &signer).unwrap();
+        let repo = storage.repository(rid).unwrap();
+
+        for remote in [bob, eve] {
+            for name in ["refs/heads/master", "refs/rad/sigrefs"] {
+                repo.raw()
+                    .reference(
+                        &format!("refs/namespaces/{remote}/{name}"),
+                        head,
+                        false,
+                        "test",
+                    )
+                    .unwrap();
+            }
+        }
+        let canonical = repo
+            .raw()
+            .references_glob("refs/heads/*")
+            .unwrap()
+            .map(|r| r.unwrap().name().unwrap().to_owned())
+            .collect::<Vec<_>>();
+        let alices = repo.references_of(&alice).unwrap();
+
+        assert_eq!(repo.prune_namespace(&bob).unwrap(), 2);
+        assert_eq!(repo.prune_namespace(&bob).unwrap(), 0);
+        assert!(repo.references_of(&bob).unwrap().is_empty());
+        assert_eq!(repo.references_of(&eve).unwrap().len(), 2);
+        assert_eq!(repo.references_of(&alice).unwrap(), alices);
+        assert!(!canonical.is_empty());
+        for name in canonical {
+            assert!(repo.raw().find_reference(&name).is_ok());
+        }
+        assert!(repo.canonical_identity_head().is_ok());
+        assert!(matches!(
+            repo.prune_namespace(&alice),
+            Err(RepositoryError::DelegateNamespace(remote)) if remote == alice
+        ));
+        assert_eq!(repo.references_of(&alice).unwrap(), alices);
+    }
+
+    #[test]
+    fn test_prune_untracked_and_gc() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut rng = fastrand::Rng::new();
+        let signer = MockSigner::new(&mut rng);
+        let storage = Storage::open(tmp.path().join("storage"), fixtures::user()).unwrap();
+        let alice = *signer.public_key();
+        let bob = *MockSigner::new(&mut rng).public_key();
+        let eve = *MockSigner::new(&mut rng).public_key();
+
+        transport::local::register(storage.clone());
+
+        let (rid, _, _, head) =
+            fixtures::project(tmp.path().join("project"), &storage, &signer).unwrap();
+        let repo = storage.repository(rid).unwrap();
+        let raw = repo.raw();
+
+        // Give Bob a branch with lots of similar, loose objects, which pack well.
+        let base = std::iter::repeat_with(|| rng.alphanumeric())
+            .take(4096)
+            .collect::<String>();
+        let mut tree = raw.treebuilder(None).unwrap();
+        for i in 0..64 {
+            let blob = raw.blob(format!("{base}{i}").as_bytes()).unwrap();
+            tree.insert(format!("{i}.txt"), blob, 0o100644).unwrap();
+        }
+        let tree = raw.find_tree(tree.write().unwrap()).unwrap();
+        let sig = git::raw::Signature::now("bob", "bob@radicle.xyz").unwrap();
+        let parent = raw.find_commit(head).unwrap();
+        let commit = raw
+            .commit(None, &sig, &sig, "Loose objects", &tree, &[&parent])
+            .unwrap();
+
+        for (remote, oid) in [(bob, commit), (eve, head)] {
+            for name in ["refs/heads/master", "refs/rad/sigrefs"] {
+                raw.reference(
+                    &format!("refs/namespaces/{remote}/{name}"),
+                    oid,
+                    false,
+                    "test",
+                )
+                .unwrap();
+            }
+        }
+        let followed = BTreeSet::from([bob]);
+        let untracked = repo.untracked(&alice, &followed).unwrap();
+        assert_eq!(untracked, vec![eve]);
+
+        for remote in &untracked {
+            repo.prune_namespace(remote).unwrap();
+        }
+        let before = repo.size().unwrap();
+        repo.gc().unwrap();
+        let after = repo.size().unwrap();
+
+        assert!(after < before, "{after} should be less than {before}");
+        assert!(repo.untracked(&alice, &followed).unwrap().is_empty());
+        assert!(repo.references_of(&eve).unwrap().is_empty());
+        assert_eq!(repo.references_of(&bob).unwrap().len(), 2);
+        assert!(repo.raw().find_commit(commit).is_ok());
+        assert!(repo.canonical_identity_head().is_ok());
+    }
+
     #[test]
     fn test_sign_refs() {
         let tmp = tempfile::tempdir().unwrap();
@@ -1,5 +1,5 @@
 //! COB storage Git backend.
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::Path;
 
 use cob::object::Objects;
@@ -50,7 +50,25 @@ pub enum TypesError {
     RefFormat(#[from] git::fmt::Error),
 }
 
-impl cob::Store for Repository {}
+impl cob::Store for Repository {
+    fn object_count(&self, typename: &cob::TypeName) -> Result<usize, Self::TypesError> {
+        let mut objects = BTreeSet::new();
+        let refs = self
+            .backend
+            .references_glob(&format!("refs/namespaces/*/refs/cobs/{typename}/*"))?;
+
+        for r in refs {
+            let r = r?;
+            let Some(name) = r.name() else {
+                continue;
+            };
+            if let Some((_, object_id)) = cob::object::parse_refstr(&RefStr::try_from_str(name)?) {
+                objects.insert(object_id);
+            }
+        }
+        Ok(objects.len())
+    }
+}
 
 impl change::Storage for Repository {
     type StoreError = <git2::Repository as change::Storage>::StoreError;
@@ -0,0 +1,542 @@
+//! Encryption of repository objects at rest.
+//!
+//! A repository can opt into having its Git objects stored encrypted, in a [`Vault`] next to
+//! the regular object database, so that a scan of the storage directory does not reveal the
+//! contents of the repository. Objects are sealed one by one with a per-repository
+//! [`RepoKey`], and are loaded into an in-memory object database to be read, see
+//! [`Vault::mount`].
+//!
+//! References are not encrypted: ref names and the object ids they point to remain visible.
+//! Distributing the key to the other peers allowed to access the repository is left to the
+//! caller.
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::{fmt, fs, io};
+
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use crypto::Signer;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::git::Oid;
+use crate::identity::RepoId;
+
+/// Name of the vault directory, relative to the repository path.
+pub const VAULT_DIR: &str = "vault";
+/// Name of the file marking a vault as holding the repository objects, relative to the
+/// vault path.
+pub const SEALED_FILE: &str = "SEALED";
+/// Length of a repository key, in bytes.
+pub const KEY_LEN: usize = 32;
+/// Length of the nonce prepended to each sealed object, in bytes.
+pub const NONCE_LEN: usize = 12;
+/// Priority of the in-memory object database backend holding unsealed objects.
+pub const MEMPACK_PRIORITY: i32 = 1;
+/// Priority of the in-memory object database backend holding objects written while a
+/// vault is mounted. It must be higher than [`MEMPACK_PRIORITY`], so that new objects are
+/// written to it.
+pub const MEMPACK_WRITE_PRIORITY: i32 = 2;
+
+/// Domain separation for the key derivation.
+const KEY_DOMAIN: &[u8] = b"radicle:repo-key:";
+/// Domain separation for object file names.
+const NAME_DOMAIN: &[u8] = b"radicle:vault:name:";
+/// Domain separation for object nonces.
+const NONCE_DOMAIN: &[u8] = b"radicle:vault:nonce:";
+
+/// Vault error.
+#[derive(Error, Debug)]
+pub enum Error {
+    /// An I/O error.
+    #[error("i/o: {0}")]
+    Io(#[from] io::Error),
+    /// A Git error.
+    #[error("git: {0}")]
+    Git(#[from] git2::Error),
+    /// The object could not be encrypted.
+    #[error("failed to encrypt object {0}")]
+    Encrypt(Oid),
+    /// The object could not be decrypted, eg. because the key is wrong.
+    #[error("failed to decrypt object {0}")]
+    Decrypt(Oid),
+    /// A vault file could not be decrypted, eg. because the key is wrong.
+    #[error("failed to decrypt '{0}'")]
+    Unreadable(PathBuf),
+    /// The object decrypted fine, but its content doesn't match its id.
+    #[error("object {0} is corrupted")]
+    Corrupted(Oid),
+}
+
+/// Symmetric key used to seal the objects of a repository.
+#[derive(Clone, PartialEq, Eq)]
+pub struct RepoKey([u8; KEY_LEN]);
+
+impl RepoKey {
+    /// Derive the key of a repository from the repository id and the node's key.
+    ///
+    /// Since Ed25519 signatures are deterministic, signing the repository id yields a secret
+    /// that only the holder of the node's key can reproduce, including via an SSH agent.
+    pub fn derive<G: Signer>(rid: &RepoId, signer: &G) -> Self {
+        let msg = [KEY_DOMAIN, rid.urn().as_bytes()].concat();
+        let sig = signer.sign(&msg);
+
+        Self(
+            Sha256::new()
+                .chain_update(KEY_DOMAIN)
+                .chain_update(sig)
+                .finalize()
+                .into(),
+        )
+    }
+
+    /// Get the raw key bytes, eg. to share the key with other peers.
+    pub fn as_bytes(&self) -> &[u8; KEY_LEN] {
+        &self.0
+    }
+}
+
+impl From<[u8; KEY_LEN]> for RepoKey {
+    fn from(bytes: [u8; KEY_LEN]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl fmt::Debug for RepoKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("RepoKey").field(&"<redacted>").finish()
+    }
+}
+
+/// Encrypted object store of a repository.
+///
+/// Each object is stored in its own file, named after a keyed hash of the object id, so
+/// that object ids are not revealed either. A file holds a nonce followed by the
+/// encrypted object type and content.
+pub struct Vault {
+    path: PathBuf,
+    key: RepoKey,
+    cipher: ChaCha20Poly1305,
+}
+
+impl fmt::Debug for Vault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Vault")
+            .field("path", &self.path)
+            .field("key", &self.key)
+            .finish()
+    }
+}
+
+impl Vault {
+    /// Open a vault at the given path. The vault directory is only created once objects
+    /// are sealed, see [`Vault::seal`].
+    pub fn open<P: AsRef<Path>>(path: P, key: RepoKey) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(key.as_bytes()));
+
+        Self { path, key, cipher }
+    }
+
+    /// The vault path.
+    pub fn path(&self) -> &Path {
+        self.path.as_path()
+    }
+
+    /// Check whether the repository objects were sealed in this vault.
+    pub fn is_sealed(&self) -> bool {
+        self.path.join(SEALED_FILE).is_file()
+    }
+
+    /// Check whether the vault holds the given object.
+    pub fn contains(&self, oid: &Oid) -> bool {
+        self.object_path(oid).exists()
+    }
+
+    /// Seal an object, returning its id.
+    pub fn write(&self, kind: git2::ObjectType, data: &[u8]) -> Result<Oid, Error> {
+        let oid = Oid::from(git2::Oid::hash_object(kind, data)?);
+        let path = self.object_path(&oid);
+
+        if path.exists() {
+            return Ok(oid);
+        }
+        // Nb. Since objects are content-addressed, deriving the nonce from the object id
+        // never re-uses a nonce for a different plaintext.
+        let nonce = self.digest(NONCE_DOMAIN, &oid);
+        let nonce = &nonce[..NONCE_LEN];
+        let plaintext = [&[kind_to_byte(kind)], data].concat();
+        let ciphertext = self
+            .cipher
+            .encrypt(Nonce::from_slice(nonce), plaintext.as_slice())
+            .map_err(|_| Error::Encrypt(oid))?;
+
+        // Write atomically, so that a partially written object is never read.
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, [nonce, ciphertext.as_slice()].concat())?;
+        fs::rename(&tmp, &path)?;
+
+        Ok(oid)
+    }
+
+    /// Unseal an object, returning its type and content.
+    pub fn read(&self, oid: &Oid) -> Result<Option<(git2::ObjectType, Vec<u8>)>, Error> {
+        let bytes = match fs::read(self.object_path(oid)) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let (kind, data) = self.decrypt(&bytes).ok_or(Error::Decrypt(*oid))?;
+
+        // Make sure the object wasn't swapped with another one.
+        if Oid::from(git2::Oid::hash_object(kind, &data)?) != *oid {
+            return Err(Error::Corrupted(*oid));
+        }
+        Ok(Some((kind, data)))
+    }
+
+    /// Seal all objects of a repository, and remove them from its object database.
+    ///
+    /// Returns the number of objects sealed. Objects already in the vault are skipped, so
+    /// this can be called again to seal objects added to the repository since.
+    pub fn seal(&self, repo: &git2::Repository) -> Result<usize, Error> {
+        fs::create_dir_all(&self.path)?;
+
+        let odb = repo.odb()?;
+        let mut oids = Vec::new();
+
+        odb.foreach(|oid| {
+            oids.push(*oid);
+            true
+        })?;
+
+        let mut sealed = 0;
+        for oid in oids {
+            if self.contains(&oid.into()) {
+                continue;
+            }
+            let obj = odb.read(oid)?;
+            self.write(obj.kind(), obj.data())?;
+            sealed += 1;
+        }
+        // Mark the vault before removing the plaintext objects, so that a repository
+        // never looks unsealed while its objects are only in the vault.
+        fs::write(self.path.join(SEALED_FILE), [])?;
+        self.remove_plaintext(repo.path())?;
+
+        Ok(sealed)
+    }
+
+    /// Unseal all objects into the given object database, returning the number of objects.
+    pub fn unseal(&self, odb: &git2::Odb) -> Result<usize, Error> {
+        let mut unsealed = 0;
+
+        for entry in fs::read_dir(&self.path)? {
+            let path = entry?.path();
+            if path.extension().is_some() || path.ends_with(SEALED_FILE) {
+                // Skip temporary files and the marker file.
+                continue;
+            }
+            // The object id can't be recovered from the file name, so we check it against
+            // the file name once decrypted.
+            let bytes = fs::read(&path)?;
+            let (kind, data) = self
+                .decrypt(&bytes)
+                .ok_or_else(|| Error::Unreadable(path.clone()))?;
+            let oid = odb.write(kind, &data)?.into();
+
+            if self.object_path(&oid) != path {
+                return Err(Error::Corrupted(oid));
+            }
+            unsealed += 1;
+        }
+        Ok(unsealed)
+    }
+
+    /// Make the sealed objects readable through the given repository while running `f`,
+    /// without writing them to disk in plaintext. Returns the result of `f`.
+    ///
+    /// While mounted, the repository's object database is replaced with an in-memory one
+    /// holding the unsealed objects, backed by the repository's objects on disk. Commits
+    /// written to the repository by `f`, along with the trees and blobs they point to, are
+    /// sealed once it returns, and the object database is restored. Other objects written by
+    /// `f` are unreachable, and are discarded.
+    ///
+    /// Nb. To be sealed, new objects are briefly written to disk in plaintext, like any
+    /// object fetched into a sealed repository.
+    pub fn mount<T, F>(&self, repo: &git2::Repository, f: F) -> Result<T, Error>
+    where
+        F: FnOnce(&git2::Repository) -> T,
+    {
+        let disk = repo.odb()?;
+        let odb = git2::Odb::new()?;
+        odb.add_new_mempack_backend(MEMPACK_PRIORITY)?;
+
+        self.unseal(&odb)?;
+
+        // Objects already unsealed aren't written again, so this only holds new objects.
+        let written = odb.add_new_mempack_backend(MEMPACK_WRITE_PRIORITY)?;
+        odb.add_disk_alternate(&repo.path().join("objects").to_string_lossy())?;
+        repo.set_odb(&odb)?;
+
+        let result = f(repo);
+        let mut pack = git2::Buf::new();
+        let dumped = written.dump(repo, &mut pack);
+
+        repo.set_odb(&disk)?;
+        dumped?;
+
+        if pack_len(&pack) > 0 {
+            let mut writer = disk.packwriter()?;
+            writer.write_all(&pack)?;
+            writer.commit()?;
+        }
+        self.seal(repo)?;
+
+        Ok(result)
+    }
+
+    /// Decrypt the content of a vault file, returning the object type and data.
+    fn decrypt(&self, bytes: &[u8]) -> Option<(git2::ObjectType, Vec<u8>)> {
+        if bytes.len() < NONCE_LEN {
+            return None;
+        }
+        let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+        let mut plaintext = self
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .ok()?;
+        if plaintext.is_empty() {
+            return None;
+        }
+        let kind = byte_to_kind(plaintext.remove(0))?;
+
+        Some((kind, plaintext))
+    }
+
+    fn object_path(&self, oid: &Oid) -> PathBuf {
+        let name = self
+            .digest(NAME_DOMAIN, oid)
+            .iter()
+            .fold(String::new(), |mut name, b| {
+                // Writing to a string never fails.
+                let _ = write!(name, "{b:02x}");
+                name
+            });
+
+        self.path.join(name)
+    }
+
+    fn digest(&self, domain: &[u8], oid: &Oid) -> [u8; 32] {
+        Sha256::new()
+            .chain_update(domain)
+            .chain_update(self.key.as_bytes())
+            .chain_update(oid.as_bytes())
+            .finalize()
+            .into()
+    }
+
+    /// Remove the loose objects and packs of a repository.
+    fn remove_plaintext(&self, repo: &Path) -> Result<(), Error> {
+        let objects = repo.join("objects");
+
+        for entry in fs::read_dir(&objects)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+
+            if name == "pack" {
+                for pack in fs::read_dir(entry.path())? {
+                    fs::remove_file(pack?.path())?;
+                }
+            } else if name.len() == 2 && name.chars().all(|c| c.is_ascii_hexdigit()) {
+                fs::remove_dir_all(entry.path())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Get the number of objects in a packfile, from its header.
+fn pack_len(pack: &[u8]) -> u32 {
+    pack.get(8..12)
+        .and_then(|n| n.try_into().ok())
+        .map(u32::from_be_bytes)
+        .unwrap_or(0)
+}
+
+fn kind_to_byte(kind: git2::ObjectType) -> u8 {
+    match kind {
+        git2::ObjectType::Commit => 1,
+        git2::ObjectType::Tree => 2,
+        git2::ObjectType::Blob => 3,
+        git2::ObjectType::Tag => 4,
+        // Only the above types are ever stored in an object database.
+        git2::ObjectType::Any => 0,
+    }
+}
+
+fn byte_to_kind(byte: u8) -> Option<git2::ObjectType> {
+    match byte {
+        1 => Some(git2::ObjectType::Commit),
+        2 => Some(git2::ObjectType::Tree),
+        3 => Some(git2::ObjectType::Blob),
+        4 => Some(git2::ObjectType::Tag),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::io::Write as _;
+
+    use crypto::test::signer::MockSigner;
+
+    use super::*;
+    use crate::test::arbitrary;
+
+    const SECRET: &[u8] = b"The launch codes are 0000.";
+
+    #[test]
+    fn test_key_derivation() {
+        let alice = MockSigner::from_seed([1; 32]);
+        let bob = MockSigner::from_seed([2; 32]);
+        let rid = arbitrary::gen::<RepoId>(1);
+        let other = arbitrary::gen::<RepoId>(1);
+
+        assert_eq!(RepoKey::derive(&rid, &alice), RepoKey::derive(&rid, &alice));
+        assert_ne!(RepoKey::derive(&rid, &alice), RepoKey::derive(&rid, &bob));
+        assert_ne!(
+            RepoKey::derive(&rid, &alice),
+            RepoKey::derive(&other, &alice)
+        );
+    }
+
+    #[test]
+    fn test_vault_read_write() {
+        let tmp = tempfile::tempdir().unwrap();
+        let key = RepoKey::from([7; KEY_LEN]);
+        let vault = Vault::open(tmp.path(), key);
+        let oid = vault.write(git2::ObjectType::Blob, SECRET).unwrap();
+
+        assert!(vault.contains(&oid));
+        assert_eq!(
+            vault.read(&oid).unwrap(),
+            Some((git2::ObjectType::Blob, SECRET.to_vec()))
+        );
+        assert_eq!(vault.read(&arbitrary::oid()).unwrap(), None);
+
+        // The file name is derived from the key, so we have to swap the key on disk
+        // to try decrypting with the wrong key.
+        let wrong = Vault::open(tmp.path(), RepoKey::from([8; KEY_LEN]));
+        fs::rename(vault.object_path(&oid), wrong.object_path(&oid)).unwrap();
+        assert!(matches!(wrong.read(&oid), Err(Error::Decrypt(_))));
+    }
+
+    #[test]
+    fn test_vault_seal() {
+        let tmp = tempfile::tempdir().unwrap();
+        let signer = MockSigner::default();
+        let rid = arbitrary::gen::<RepoId>(1);
+        let repo = git2::Repository::init_bare(tmp.path().join("repo")).unwrap();
+
+        let blob = repo.blob(SECRET).unwrap();
+        let mut tree = repo.treebuilder(None).unwrap();
+        tree.insert("SECRET", blob, 0o100644).unwrap();
+        let tree = repo.find_tree(tree.write().unwrap()).unwrap();
+        let sig = git2::Signature::now("anonymous", "anonymous@radicle.xyz").unwrap();
+        let commit = repo
+            .commit(Some("refs/heads/master"), &sig, &sig, "Secret", &tree, &[])
+            .unwrap();
+        // Also pack the objects, so that packs are sealed too.
+        let odb = repo.odb().unwrap();
+        let mut writer = odb.packwriter().unwrap();
+        let mut builder = repo.packbuilder().unwrap();
+        builder.insert_commit(commit).unwrap();
+        builder
+            .foreach(|chunk| writer.write_all(chunk).is_ok())
+            .unwrap();
+        writer.commit().unwrap();
+        drop(writer);
+        drop(odb);
+
+        let vault = Vault::open(repo.path().join(VAULT_DIR), RepoKey::derive(&rid, &signer));
+        assert!(!vault.path().exists());
+        assert!(!vault.is_sealed());
+        assert_eq!(vault.seal(&repo).unwrap(), 3);
+        assert_eq!(vault.seal(&repo).unwrap(), 0);
+        assert!(vault.is_sealed());
+
+        // The objects are gone from the repository, and nothing on disk reveals the content.
+        let repo = git2::Repository::open_bare(repo.path()).unwrap();
+        assert!(repo.find_blob(blob).is_err());
+
+        for path in files(tmp.path()) {
+            let bytes = fs::read(&path).unwrap();
+            assert!(
+                !bytes.windows(SECRET.len()).any(|w| w == SECRET),
+                "{} contains plaintext",
+                path.display()
+            );
+        }
+
+        // Once mounted, the objects are readable again, and new objects can be written.
+        let written = vault
+            .mount(&repo, |repo| {
+                assert_eq!(repo.find_blob(blob).unwrap().content(), SECRET);
+                assert_eq!(
+                    repo.find_reference("refs/heads/master")
+                        .unwrap()
+                        .peel_to_commit()
+                        .unwrap()
+                        .id(),
+                    commit
+                );
+                let blob = repo.blob(b"Another secret").unwrap();
+                let mut tree = repo.treebuilder(None).unwrap();
+                tree.insert("SECRET", blob, 0o100644).unwrap();
+                let tree = repo.find_tree(tree.write().unwrap()).unwrap();
+                let parent = repo.find_commit(commit).unwrap();
+
+                repo.commit(None, &sig, &sig, "Another secret", &tree, &[&parent])
+                    .unwrap();
+                blob
+            })
+            .unwrap();
+
+        // Objects written while mounted were sealed, and nothing was left on disk in
+        // plaintext.
+        assert!(vault.contains(&written.into()));
+
+        let repo = git2::Repository::open_bare(repo.path()).unwrap();
+        assert!(repo.find_blob(written).is_err());
+        assert!(repo.find_blob(blob).is_err());
+        assert!(!files(repo.path())
+            .iter()
+            .any(|p| p.starts_with(repo.path().join("objects"))));
+
+        let content = vault
+            .mount(&repo, |repo| {
+                repo.find_blob(written).unwrap().content().to_vec()
+            })
+            .unwrap();
+        assert_eq!(content, b"Another secret");
+    }
+
+    /// All files under a directory, recursively.
+    fn files(dir: &Path) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                files.extend(self::files(&path));
+            } else {
+                files.push(path);
+            }
+        }
+        files
+    }
+}
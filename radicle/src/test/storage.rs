@@ -344,6 +344,10 @@ impl WriteRepository for MockRepository {
     fn set_user(&self, _info: &git::UserInfo) -> Result<(), Error> {
         todo!()
     }
+
+    fn prune_namespace(&self, _remote: &RemoteId) -> Result<usize, RepositoryError> {
+        todo!()
+    }
 }
 
 impl SignRepository for MockRepository {